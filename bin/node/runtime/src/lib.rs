@@ -416,6 +416,7 @@ impl contracts::Trait for Runtime {
 	type MaxDepth = contracts::DefaultMaxDepth;
 	type MaxValueSize = contracts::DefaultMaxValueSize;
 	type BlockGasLimit = contracts::DefaultBlockGasLimit;
+	type ChainExtension = ();
 }
 
 impl sudo::Trait for Runtime {
@@ -0,0 +1,104 @@
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use crate::*;
+	use super::*;
+
+	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use primitives::H256;
+	// The testing primitives are very useful for avoiding having to work with signatures
+	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
+	use sp_runtime::{
+		Perbill, testing::Header, traits::{BlakeTwo256, Hash, IdentityLookup},
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Call = ();
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const MaxAnchorsPerBlock: u32 = 2;
+		pub const MaxTagLength: usize = 8;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type MaxAnchorsPerBlock = MaxAnchorsPerBlock;
+		type MaxTagLength = MaxTagLength;
+	}
+
+	type System = system::Module<Test>;
+	type Anchor = Module<Test>;
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn anchor_hash_should_work() {
+		new_test_ext().execute_with(|| {
+			let hash = BlakeTwo256::hash(b"contract.pdf");
+			assert_noop!(Anchor::anchor_hash(Origin::signed(1), hash, "t".repeat(9).into()), "Tag too long");
+
+			System::set_block_number(3);
+			assert_ok!(Anchor::anchor_hash(Origin::signed(1), hash, "pdf".into()));
+			assert_noop!(Anchor::anchor_hash(Origin::signed(2), hash, "pdf".into()), "Hash already anchored");
+			assert_eq!(<Anchor as AnchorProvider<Test>>::anchor_of(hash), Some((1, 3)));
+		});
+	}
+
+	#[test]
+	fn rate_limit_should_work() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Anchor::anchor_hash(Origin::signed(1), BlakeTwo256::hash(b"1"), vec![]));
+			assert_ok!(Anchor::anchor_hash(Origin::signed(1), BlakeTwo256::hash(b"2"), vec![]));
+			assert_noop!(Anchor::anchor_hash(Origin::signed(1), BlakeTwo256::hash(b"3"), vec![]), "Too many anchors in this block");
+			assert_ok!(Anchor::anchor_hash(Origin::signed(2), BlakeTwo256::hash(b"3"), vec![]));
+
+			System::set_block_number(2);
+			assert_ok!(Anchor::anchor_hash(Origin::signed(1), BlakeTwo256::hash(b"4"), vec![]));
+		});
+	}
+
+	#[test]
+	fn note_anchor_should_work() {
+		new_test_ext().execute_with(|| {
+			let hash = BlakeTwo256::hash(b"product data");
+			System::set_block_number(5);
+			Anchor::note_anchor(hash, &7);
+			System::set_block_number(6);
+			Anchor::note_anchor(hash, &8);
+			assert_eq!(Anchor::anchor_of(hash), Some((7, 5)));
+		});
+	}
+}
@@ -0,0 +1,131 @@
+//! # Anchor Module
+//!
+//! - [`anchor::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module proves that a hash existed at a given block. Anyone can anchor a hash,
+//! limited to `MaxAnchorsPerBlock` anchors per account and block. Other modules anchor
+//! their own hashes through the [`AnchorProvider`] trait, so they share the same proofs.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `anchor_hash` - Record the sender and the current block for a hash.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::Result,
+	ensure,
+	traits::Get,
+	weights::SimpleDispatchInfo,
+};
+use system::ensure_signed;
+
+#[cfg(test)]
+mod anchor_test;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The maximum anchors an account may create per block.
+	type MaxAnchorsPerBlock: Get<u32>;
+
+	/// The maximum length a tag may be.
+	type MaxTagLength: Get<usize>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as AnchorModule {
+		/// The lookup table for anchors
+		Anchors get(anchor_of): map T::Hash => Option<(T::AccountId, T::BlockNumber)>;
+		/// The anchors created by an account in its last active block
+		AnchorCount get(anchor_count): map T::AccountId => (T::BlockNumber, u32);
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Hash = <T as system::Trait>::Hash,
+		AccountId = <T as system::Trait>::AccountId,
+	{
+		/// Hash anchored with a tag
+		Anchored(AccountId, Hash, Vec<u8>),
+	}
+);
+
+decl_module! {
+	// Simple declaration of the `Module` type. Lets the macro know what it's working on.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The maximum anchors an account may create per block.
+		const MaxAnchorsPerBlock: u32 = T::MaxAnchorsPerBlock::get();
+
+		/// The maximum length a tag may be.
+		const MaxTagLength: u32 = T::MaxTagLength::get() as u32;
+
+		/// Anchor a hash
+		///
+		/// @origin	the sender
+		/// @hash	the hash to be anchored
+		/// @tag	the tag describing the hash
+		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+		fn anchor_hash(origin, hash: T::Hash, tag: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(tag.len() <= T::MaxTagLength::get(), "Tag too long");
+			ensure!(!<Anchors<T>>::exists(hash), "Hash already anchored");
+
+			let now = <system::Module<T>>::block_number();
+			let (last, count) = Self::anchor_count(&sender);
+			let count = if last == now { count } else { 0 };
+			ensure!(count < T::MaxAnchorsPerBlock::get(), "Too many anchors in this block");
+
+			<AnchorCount<T>>::insert(&sender, (now, count + 1));
+			<Anchors<T>>::insert(hash, (sender.clone(), now));
+			Self::deposit_event(RawEvent::Anchored(sender, hash, tag));
+			Ok(())
+		}
+	}
+}
+
+/// Client module should use this trait to anchor and look up hashes
+pub trait AnchorProvider<T: system::Trait> {
+	/// Get the account and block which anchored the hash
+	fn anchor_of(_hash: T::Hash) -> Option<(T::AccountId, T::BlockNumber)> { None }
+	/// Anchor the hash on behalf of an account, if it is not anchored yet
+	fn note_anchor(_hash: T::Hash, _who: &T::AccountId) {}
+}
+
+impl<T: system::Trait> AnchorProvider<T> for () {}
+
+impl<T: Trait> AnchorProvider<T> for Module<T> {
+	/// Get the account and block which anchored the hash
+	///
+	/// @hash	the anchored hash
+	fn anchor_of(hash: T::Hash) -> Option<(T::AccountId, T::BlockNumber)> {
+		<Anchors<T>>::get(hash)
+	}
+
+	/// Anchor the hash on behalf of an account, if it is not anchored yet.
+	/// The rate limit does not apply, the calling module bounds its own writes.
+	///
+	/// @hash	the hash to be anchored
+	/// @who	the account the anchor is recorded for
+	fn note_anchor(hash: T::Hash, who: &T::AccountId) {
+		if !<Anchors<T>>::exists(hash) {
+			<Anchors<T>>::insert(hash, (who.clone(), <system::Module<T>>::block_number()));
+		}
+	}
+}
@@ -0,0 +1,177 @@
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use crate::*;
+	use super::*;
+
+	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use primitives::H256;
+	use name_service::NameServiceResolver;
+	// The testing primitives are very useful for avoiding having to work with signatures
+	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
+	use sp_runtime::{
+		Perbill, testing::Header, traits::{BlakeTwo256, Hash, IdentityLookup},
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Call = ();
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const MaxAttestationsPerSubject: usize = 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type MaxAttestationsPerSubject = MaxAttestationsPerSubject;
+		type NameServiceResolver = Self;
+	}
+
+	const CERTIFIER: &str = "certifier";
+	const ALICE: &str = "alice";
+	const STANDARDS: &str = "standards";
+
+	impl NameServiceResolver<Test> for Test {
+		fn resolve_addr(node_hash: <Test as system::Trait>::Hash) -> Option<<Test as system::Trait>::AccountId> {
+			if node_hash == Self::single_name_hash(CERTIFIER) {
+				Some(1)
+			} else if node_hash == Self::single_name_hash(ALICE) {
+				Some(2)
+			} else if node_hash == Self::single_name_hash(STANDARDS) {
+				Some(3)
+			} else {
+				None
+			}
+		}
+	}
+
+	impl Test {
+		pub fn single_name_hash(name: &str) -> <Test as system::Trait>::Hash {
+			(name).using_encoded(<Test as system::Trait>::Hashing::hash)
+		}
+	}
+
+	type System = system::Module<Test>;
+	type Attestation = Module<Test>;
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn issue_should_work() {
+		new_test_ext().execute_with(|| {
+			let certifier = Test::single_name_hash(CERTIFIER);
+			let alice = Test::single_name_hash(ALICE);
+			let schema = BlakeTwo256::hash(b"certified organic");
+			let credential = BlakeTwo256::hash(b"credential #1");
+
+			assert_noop!(Attestation::issue(Origin::signed(2), certifier, alice, schema, credential, None), "Not authorized");
+			assert_ok!(Attestation::add_trusted_issuer(Origin::ROOT, schema, certifier));
+			System::set_block_number(10);
+			assert_noop!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, Some(10)), "Expired");
+
+			assert!(!Attestation::has_valid_attestation(&alice, &schema));
+			assert_ok!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, Some(20)));
+			assert_noop!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, None), "Credential already exists");
+			assert!(Attestation::is_valid(credential));
+			assert!(Attestation::has_valid_attestation(&alice, &schema));
+
+			assert_ok!(Attestation::issue(Origin::signed(1), certifier, alice, schema, BlakeTwo256::hash(b"credential #2"), None));
+			assert_noop!(
+				Attestation::issue(Origin::signed(1), certifier, alice, schema, BlakeTwo256::hash(b"credential #3"), None),
+				"Too many attestations"
+			);
+
+			// Expired credentials stop counting
+			System::set_block_number(20);
+			assert!(!Attestation::is_valid(credential));
+			assert_ok!(Attestation::issue(Origin::signed(1), certifier, alice, schema, BlakeTwo256::hash(b"credential #3"), None));
+		});
+	}
+
+	#[test]
+	fn revoke_should_work() {
+		new_test_ext().execute_with(|| {
+			let certifier = Test::single_name_hash(CERTIFIER);
+			let alice = Test::single_name_hash(ALICE);
+			let schema = BlakeTwo256::hash(b"certified organic");
+			let credential = BlakeTwo256::hash(b"credential #1");
+
+			assert_noop!(Attestation::revoke(Origin::signed(1), credential), "Credential does not exist");
+			assert_ok!(Attestation::add_trusted_issuer(Origin::ROOT, schema, certifier));
+			assert_ok!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, None));
+			assert_noop!(Attestation::revoke(Origin::signed(2), credential), "Not authorized");
+			assert_ok!(Attestation::revoke(Origin::signed(1), credential));
+			assert_noop!(Attestation::revoke(Origin::signed(1), credential), "Credential already revoked");
+
+			assert!(!Attestation::is_valid(credential));
+			assert!(!Attestation::has_valid_attestation(&alice, &schema));
+		});
+	}
+
+	#[test]
+	fn untrusted_issuer_should_not_attest() {
+		new_test_ext().execute_with(|| {
+			let certifier = Test::single_name_hash(CERTIFIER);
+			let alice = Test::single_name_hash(ALICE);
+			let standards = Test::single_name_hash(STANDARDS);
+			let schema = BlakeTwo256::hash(b"certified organic");
+			let credential = BlakeTwo256::hash(b"credential #1");
+
+			assert_noop!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, None), "Untrusted issuer");
+
+			// Only root and the schema owner govern the trusted issuers
+			assert_noop!(Attestation::add_trusted_issuer(Origin::signed(3), schema, certifier), "Schema does not exist");
+			assert_noop!(Attestation::register_schema(Origin::signed(1), standards, schema), "Not authorized");
+			assert_ok!(Attestation::register_schema(Origin::signed(3), standards, schema));
+			assert_noop!(Attestation::register_schema(Origin::signed(2), alice, schema), "Schema already registered");
+			assert_noop!(Attestation::add_trusted_issuer(Origin::signed(1), schema, certifier), "Not authorized");
+			assert_ok!(Attestation::add_trusted_issuer(Origin::signed(3), schema, certifier));
+			assert_noop!(Attestation::add_trusted_issuer(Origin::signed(3), schema, certifier), "Issuer already trusted");
+			assert_ok!(Attestation::issue(Origin::signed(1), certifier, alice, schema, credential, None));
+			assert!(Attestation::has_valid_attestation(&alice, &schema));
+
+			// The credentials of an issuer no longer trusted stop counting
+			assert_ok!(Attestation::remove_trusted_issuer(Origin::ROOT, schema, certifier));
+			assert!(Attestation::is_valid(credential));
+			assert!(!Attestation::has_valid_attestation(&alice, &schema));
+			assert_noop!(Attestation::remove_trusted_issuer(Origin::signed(3), schema, certifier), "Issuer not trusted");
+
+			// The owner may hand the schema over
+			assert_ok!(Attestation::set_schema_owner(Origin::signed(3), schema, alice));
+			assert_noop!(Attestation::add_trusted_issuer(Origin::signed(3), schema, certifier), "Not authorized");
+			assert_ok!(Attestation::add_trusted_issuer(Origin::signed(2), schema, certifier));
+			assert!(Attestation::has_valid_attestation(&alice, &schema));
+		});
+	}
+}
@@ -0,0 +1,305 @@
+//! # Attestation Module
+//!
+//! - [`attestation::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module records verifiable credentials issued by one name hash identity about
+//! another. A credential is identified by its hash and states a schema (e.g. the hash of
+//! "certified organic"), an optional expiration and whether it has been revoked. Other
+//! modules query attestations through the [`AttestationVerifier`] trait.
+//!
+//! Only the issuers trusted for a schema may issue its credentials, and the credentials of
+//! an issuer count only while it stays trusted. The trusted issuers of a schema are
+//! governed by root and by the owner of the schema, the name which registered it.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `register_schema` - Register a schema under an owner name, by the account the name resolves to.
+//! * `set_schema_owner` - Hand a schema over to another owner, by root or the schema owner.
+//! * `add_trusted_issuer` - Trust an issuer for a schema, by root or the schema owner.
+//! * `remove_trusted_issuer` - Stop trusting an issuer for a schema, by root or the schema owner.
+//! * `issue` - Issue a credential about a subject, by the account the trusted issuer name resolves to.
+//! * `revoke` - Revoke a credential, by the account the issuer name resolves to.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sp_runtime::traits::EnsureOrigin;
+use support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::Result,
+	ensure,
+	traits::Get,
+	weights::SimpleDispatchInfo,
+};
+use system::{ensure_signed, EnsureRoot};
+use name_service::NameServiceResolver;
+
+#[cfg(test)]
+mod attestation_test;
+
+/// The attestation record
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct Attestation<NameHash, Hash, BlockNumber> {
+	/// The name hash of the issuer
+	pub issuer: NameHash,
+	/// The name hash of the subject
+	pub subject: NameHash,
+	/// The hash of the credential schema
+	pub schema: Hash,
+	/// Issuance time
+	pub issued_at: BlockNumber,
+	/// The height at which the credential expires, if any
+	pub expiration: Option<BlockNumber>,
+	/// Whether the credential is revoked
+	pub revoked: bool,
+}
+
+type NameHash<T> = <T as system::Trait>::Hash;
+type AttestationOf<T> = Attestation<NameHash<T>, <T as system::Trait>::Hash, <T as system::Trait>::BlockNumber>;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The maximum credentials a subject may hold for one schema.
+	type MaxAttestationsPerSubject: Get<usize>;
+
+	/// The name service resolver
+	type NameServiceResolver: NameServiceResolver<Self>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as AttestationModule {
+		/// The lookup table for credentials
+		Attestations get(attestation_of): map T::Hash => Option<AttestationOf<T>>;
+		/// The lookup table for credentials of a subject with a schema
+		SubjectAttestations get(subject_attestations): map (NameHash<T>, T::Hash) => Vec<T::Hash>;
+		/// The name hashes owning the credential schemas, governing their trusted issuers
+		SchemaOwners get(schema_owner): map T::Hash => Option<NameHash<T>>;
+		/// The issuers trusted to issue the credentials of a schema, by schema and issuer
+		TrustedIssuers get(is_trusted_issuer): double_map T::Hash, blake2_256(NameHash<T>) => bool;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Hash = <T as system::Trait>::Hash,
+		AccountId = <T as system::Trait>::AccountId,
+	{
+		/// Credential issued
+		AttestationIssued(AccountId, Hash, Hash),
+		/// Credential revoked
+		AttestationRevoked(AccountId, Hash),
+		/// Schema owner set, by schema
+		SchemaOwnerSet(Hash, Hash),
+		/// Issuer trusted for a schema
+		TrustedIssuerAdded(Hash, Hash),
+		/// Issuer no longer trusted for a schema
+		TrustedIssuerRemoved(Hash, Hash),
+	}
+);
+
+decl_module! {
+	// Simple declaration of the `Module` type. Lets the macro know what it's working on.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The maximum credentials a subject may hold for one schema.
+		const MaxAttestationsPerSubject: u32 = T::MaxAttestationsPerSubject::get() as u32;
+
+		/// Register a credential schema under an owner
+		///
+		/// @origin	the account the owner name resolves to
+		/// @owner	the name hash of the owner
+		/// @schema	the hash of the credential schema
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn register_schema(origin, owner: NameHash<T>, schema: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::validate_authorization(&sender, owner)?;
+			ensure!(!<SchemaOwners<T>>::exists(schema), "Schema already registered");
+
+			<SchemaOwners<T>>::insert(schema, owner);
+			Self::deposit_event(RawEvent::SchemaOwnerSet(schema, owner));
+			Ok(())
+		}
+
+		/// Hand a credential schema over to another owner
+		///
+		/// @origin	root, or the account the schema owner name resolves to
+		/// @schema	the hash of the credential schema
+		/// @owner	the name hash of the new owner
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn set_schema_owner(origin, schema: T::Hash, owner: NameHash<T>) -> Result {
+			Self::ensure_schema_governor(origin, schema)?;
+
+			<SchemaOwners<T>>::insert(schema, owner);
+			Self::deposit_event(RawEvent::SchemaOwnerSet(schema, owner));
+			Ok(())
+		}
+
+		/// Trust an issuer to issue the credentials of a schema
+		///
+		/// @origin	root, or the account the schema owner name resolves to
+		/// @schema	the hash of the credential schema
+		/// @issuer	the name hash of the issuer
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn add_trusted_issuer(origin, schema: T::Hash, issuer: NameHash<T>) -> Result {
+			Self::ensure_schema_governor(origin, schema)?;
+			ensure!(!Self::is_trusted_issuer(schema, issuer), "Issuer already trusted");
+
+			<TrustedIssuers<T>>::insert(schema, issuer, true);
+			Self::deposit_event(RawEvent::TrustedIssuerAdded(schema, issuer));
+			Ok(())
+		}
+
+		/// Stop trusting an issuer for a schema, its credentials no longer counting
+		///
+		/// @origin	root, or the account the schema owner name resolves to
+		/// @schema	the hash of the credential schema
+		/// @issuer	the name hash of the issuer
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn remove_trusted_issuer(origin, schema: T::Hash, issuer: NameHash<T>) -> Result {
+			Self::ensure_schema_governor(origin, schema)?;
+			ensure!(Self::is_trusted_issuer(schema, issuer), "Issuer not trusted");
+
+			<TrustedIssuers<T>>::remove(schema, issuer);
+			Self::deposit_event(RawEvent::TrustedIssuerRemoved(schema, issuer));
+			Ok(())
+		}
+
+		/// Issue a credential
+		///
+		/// @origin	the sender
+		/// @issuer	the name hash of the issuer
+		/// @subject	the name hash of the subject
+		/// @schema	the hash of the credential schema
+		/// @credential	the hash of the credential
+		/// @expiration	the expiration height, if any
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn issue(origin, issuer: NameHash<T>, subject: NameHash<T>, schema: T::Hash, credential: T::Hash, expiration: Option<T::BlockNumber>) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::validate_authorization(&sender, issuer)?;
+			ensure!(Self::is_trusted_issuer(schema, issuer), "Untrusted issuer");
+
+			ensure!(!<Attestations<T>>::exists(credential), "Credential already exists");
+			let now = <system::Module<T>>::block_number();
+			if let Some(expiration) = expiration {
+				ensure!(now < expiration, "Expired");
+			}
+
+			let mut list = Self::subject_attestations((subject, schema));
+			// Drop the credentials which can never become valid again
+			list.retain(|c| Self::is_valid(*c));
+			ensure!(list.len() < T::MaxAttestationsPerSubject::get(), "Too many attestations");
+			list.push(credential);
+
+			let attestation = AttestationOf::<T> {
+				issuer: issuer,
+				subject: subject,
+				schema: schema,
+				issued_at: now,
+				expiration: expiration,
+				revoked: false,
+			};
+
+			<Attestations<T>>::insert(credential, attestation);
+			<SubjectAttestations<T>>::insert((subject, schema), list);
+			Self::deposit_event(RawEvent::AttestationIssued(sender, subject, credential));
+			Ok(())
+		}
+
+		/// Revoke a credential
+		///
+		/// @origin	the sender
+		/// @credential	the hash of the credential
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn revoke(origin, credential: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut attestation = Self::attestation_of(credential).ok_or("Credential does not exist")?;
+			Self::validate_authorization(&sender, attestation.issuer)?;
+			ensure!(!attestation.revoked, "Credential already revoked");
+
+			attestation.revoked = true;
+			<Attestations<T>>::insert(credential, attestation);
+			Self::deposit_event(RawEvent::AttestationRevoked(sender, credential));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Validate authorization by checking if the name hash is resolved to the sender
+	///
+	/// @sender	the sender
+	/// @hash	the name hash
+	fn validate_authorization(sender: &T::AccountId, hash: NameHash<T>) -> Result {
+		ensure!(Some(sender.clone()) == T::NameServiceResolver::resolve_addr(hash), "Not authorized");
+		Ok(())
+	}
+
+	/// Ensure the origin is root or the account the owner name of a schema resolves to
+	///
+	/// @origin	the origin
+	/// @schema	the hash of the credential schema
+	fn ensure_schema_governor(origin: T::Origin, schema: T::Hash) -> Result {
+		if let Err(origin) = EnsureRoot::<T::AccountId>::try_origin(origin) {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::schema_owner(schema).ok_or("Schema does not exist")?;
+			Self::validate_authorization(&sender, owner)?;
+		}
+		Ok(())
+	}
+
+	/// Check if a credential was issued by an issuer still trusted for its schema
+	///
+	/// @credential	the hash of the credential
+	pub fn is_trusted(credential: T::Hash) -> bool {
+		Self::attestation_of(credential).map_or(false, |a| Self::is_trusted_issuer(a.schema, a.issuer))
+	}
+
+	/// Verify a credential: it exists, is not revoked and not expired
+	///
+	/// @credential	the hash of the credential
+	pub fn is_valid(credential: T::Hash) -> bool {
+		match Self::attestation_of(credential) {
+			Some(attestation) => {
+				let now = <system::Module<T>>::block_number();
+				!attestation.revoked && attestation.expiration.map_or(true, |e| now < e)
+			},
+			None => false,
+		}
+	}
+}
+
+/// Client module should use this trait to check attestations
+pub trait AttestationVerifier<NameHash, Hash> {
+	/// Check if the subject holds a valid credential of the schema
+	fn has_valid_attestation(subject: &NameHash, schema: &Hash) -> bool;
+}
+
+impl<NameHash, Hash> AttestationVerifier<NameHash, Hash> for () {
+	/// Without a registry nobody holds a credential
+	fn has_valid_attestation(_subject: &NameHash, _schema: &Hash) -> bool { false }
+}
+
+impl<T: Trait> AttestationVerifier<NameHash<T>, T::Hash> for Module<T> {
+	/// Check if the subject holds a valid credential of the schema, from an issuer still
+	/// trusted for it
+	///
+	/// @subject	the name hash of the subject
+	/// @schema	the hash of the credential schema
+	fn has_valid_attestation(subject: &NameHash<T>, schema: &T::Hash) -> bool {
+		Self::subject_attestations((*subject, *schema)).into_iter().any(|c| Self::is_valid(c) && Self::is_trusted(c))
+	}
+}
@@ -0,0 +1,236 @@
+//! Benchmarks of the business calls whose weights depend on their input sizes.
+//!
+//! Run with `cargo bench` and fit the `WeightInfo` figures to the measured times.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use business_module::{Call, DataHash, InfoKind, NameServiceAuthorization, ResolveChangeAction, Trait};
+use codec::{Decode, Encode};
+use name_service::NameServiceResolver;
+use support::{impl_outer_origin, parameter_types, dispatch::Dispatchable, weights::{DispatchClass, Weight}};
+use primitives::H256;
+use sp_runtime::{
+	Perbill,
+	testing::{Header, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Hash, IdentifyAccount, IdentityLookup, Lazy, Verify},
+};
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 4 * 1024 * 1024;
+	pub const MaximumBlockLength: u32 = 4 * 1024 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+#[derive(Clone, Eq, PartialEq)]
+pub struct Runtime;
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
+}
+impl balances::Trait for Runtime {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = ();
+	type TransferPayment = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl timestamp::Trait for Runtime {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+parameter_types! {
+	pub const MinNameLength: usize = 3;
+	pub const MaxNameLength: usize = 256;
+	pub const MaxSeqIDLength: usize = 64;
+	pub const MaxExtraLength: usize = 1024;
+	pub const MaxProductInfoCount: usize = 256;
+	pub const MaxWhitelistSize: usize = 256;
+	// Products take no deposit, so the operators need no funded pool
+	pub const ProductDeposit: u64 = 0;
+	pub const RenewalPricePerBlock: u64 = 0;
+	pub const VerificationFee: u64 = 1;
+	pub const ArchiveGracePeriod: u64 = 5;
+	pub const MaxArchiveScan: u32 = 16;
+	pub const MaxAuthorizationDepth: u32 = 4;
+	pub const OnOperatorResolveChanged: ResolveChangeAction = ResolveChangeAction::Warn;
+	// The info count bench writes up to 200 infos in a block
+	pub const MaxWritesPerOperatorPerBlock: u32 = 256;
+	pub const MaxVerificationsPerBlock: u32 = 1024;
+	pub const MaxVerificationsPerAccount: u32 = 1024;
+	pub const AdminDispatchClass: DispatchClass = DispatchClass::Operational;
+	pub const AdminPaysFee: bool = false;
+	pub const MaxSubBusinesses: u32 = 16;
+	pub const MaxBusinessesPerName: u32 = 1024;
+	pub const MaxLotsPerBusiness: u32 = 1024;
+	pub const RichEvents: bool = false;
+	pub const MaxDigestEntries: u32 = 1024;
+	pub const AppealPeriod: u64 = 100;
+}
+type SubmitTransaction = system::offchain::TransactionSubmitter<(), Call<Runtime>, TestXt<Call<Runtime>, ()>>;
+impl Trait for Runtime {
+	type Event = ();
+	type ForceOrigin = system::EnsureRoot<u64>;
+	type MinNameLength = MinNameLength;
+	type MaxNameLength = MaxNameLength;
+	type MaxSeqIDLength = MaxSeqIDLength;
+	type MaxExtraLength = MaxExtraLength;
+	type MaxProductInfoCount = MaxProductInfoCount;
+	type MaxWhitelistSize = MaxWhitelistSize;
+	type Currency = balances::Module<Runtime>;
+	type ProductDeposit = ProductDeposit;
+	type StorageFeePolicy = ();
+	type RenewalPricePerBlock = RenewalPricePerBlock;
+	type RenewalFees = ();
+	type VerificationFee = VerificationFee;
+	type VerificationFees = ();
+	type NameServiceResolver = Self;
+	type AuthorizationProvider = NameServiceAuthorization<Self>;
+	type ArchiverId = UintAuthorityId;
+	type Call = Call<Runtime>;
+	type SubmitTransaction = SubmitTransaction;
+	type ArchiveGracePeriod = ArchiveGracePeriod;
+	type MaxArchiveScan = MaxArchiveScan;
+	type OperatorSignature = BenchSig;
+	type OperatorSigner = BenchSigner;
+	type WeightInfo = ();
+	type AttestationVerifier = ();
+	type Anchors = ();
+	type MaxAuthorizationDepth = MaxAuthorizationDepth;
+	type OnOperatorResolveChanged = OnOperatorResolveChanged;
+	type MaxWritesPerOperatorPerBlock = MaxWritesPerOperatorPerBlock;
+	type MaxVerificationsPerBlock = MaxVerificationsPerBlock;
+	type MaxVerificationsPerAccount = MaxVerificationsPerAccount;
+	type AdminDispatchClass = AdminDispatchClass;
+	type AdminPaysFee = AdminPaysFee;
+	type MaxSubBusinesses = MaxSubBusinesses;
+	type MaxBusinessesPerName = MaxBusinessesPerName;
+	type MaxLotsPerBusiness = MaxLotsPerBusiness;
+	type RichEvents = RichEvents;
+	type MaxDigestEntries = MaxDigestEntries;
+	type AppealPeriod = AppealPeriod;
+}
+
+/// Every name resolves to `OPERATOR`, so one account drives all calls
+impl NameServiceResolver<Runtime> for Runtime {
+	fn resolve_addr(_node_hash: H256) -> Option<u64> {
+		Some(OPERATOR)
+	}
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct BenchSigner(u64);
+impl IdentifyAccount for BenchSigner {
+	type AccountId = u64;
+	fn into_account(self) -> u64 { self.0 }
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct BenchSig(u64);
+impl Verify for BenchSig {
+	type Signer = BenchSigner;
+	fn verify<L: Lazy<[u8]>>(&self, _msg: L, signer: &u64) -> bool {
+		*signer == self.0
+	}
+}
+
+const OPERATOR: u64 = 1;
+
+fn owner_hash() -> H256 {
+	BlakeTwo256::hash(b"owner")
+}
+
+/// Set up a business whose whitelist holds `w` operators
+fn new_test_ext(w: usize) -> (runtime_io::TestExternalities, H256) {
+	let scope = business_module::Module::<Runtime>::default_scope_hash();
+	let mut storage = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	business_module::GenesisConfig::<Runtime> { scopes: vec![(scope, scope)] }.assimilate_storage(&mut storage).unwrap();
+	let mut t: runtime_io::TestExternalities = storage.into();
+	let mut biz_hash = H256::default();
+	t.execute_with(|| {
+		system::Module::<Runtime>::set_block_number(1);
+		assert!(Call::<Runtime>::create_business(scope, owner_hash(), b"business".to_vec(), 100).dispatch(Origin::signed(OPERATOR)).is_ok());
+		biz_hash = business_module::Module::<Runtime>::businesses_by_owner(owner_hash())[0];
+		for i in 0..w {
+			let operator = BlakeTwo256::hash(&(i as u64).encode());
+			assert!(Call::<Runtime>::add_business_whitelist(biz_hash, operator, None).dispatch(Origin::signed(OPERATOR)).is_ok());
+		}
+	});
+	(t, biz_hash)
+}
+
+fn create_product_by_extra(c: &mut Criterion) {
+	c.bench_function_over_inputs("create_product extra", |b, &e| {
+		b.iter_with_setup(|| new_test_ext(1), |(mut t, biz_hash)| t.execute_with(|| {
+			let operator = BlakeTwo256::hash(&0u64.encode());
+			let call = Call::<Runtime>::create_product(operator, biz_hash, b"1".to_vec(), DataHash::from_runtime_hash(&H256::default()), vec![b'e'; e]);
+			assert!(call.dispatch(Origin::signed(OPERATOR)).is_ok());
+		}))
+	}, vec![0usize, 256, 1024]);
+}
+
+fn create_product_by_whitelist(c: &mut Criterion) {
+	c.bench_function_over_inputs("create_product whitelist", |b, &w| {
+		b.iter_with_setup(|| new_test_ext(w), |(mut t, biz_hash)| t.execute_with(|| {
+			// The last whitelisted operator is the worst case for the whitelist scan
+			let operator = BlakeTwo256::hash(&((w - 1) as u64).encode());
+			let call = Call::<Runtime>::create_product(operator, biz_hash, b"1".to_vec(), DataHash::from_runtime_hash(&H256::default()), vec![]);
+			assert!(call.dispatch(Origin::signed(OPERATOR)).is_ok());
+		}))
+	}, vec![1usize, 16, 64]);
+}
+
+fn add_product_info_by_count(c: &mut Criterion) {
+	c.bench_function_over_inputs("add_product_info infos", |b, &i| {
+		let setup = || {
+			let (mut t, biz_hash) = new_test_ext(1);
+			t.execute_with(|| {
+				let operator = BlakeTwo256::hash(&0u64.encode());
+				assert!(Call::<Runtime>::create_product(operator, biz_hash, b"1".to_vec(), DataHash::from_runtime_hash(&H256::default()), vec![]).dispatch(Origin::signed(OPERATOR)).is_ok());
+				for _ in 1..i {
+					assert!(Call::<Runtime>::add_product_info(operator, biz_hash, b"1".to_vec(), InfoKind::Shipped, DataHash::from_runtime_hash(&H256::default()), vec![]).dispatch(Origin::signed(OPERATOR)).is_ok());
+				}
+			});
+			(t, biz_hash)
+		};
+		b.iter_with_setup(setup, |(mut t, biz_hash)| t.execute_with(|| {
+			let operator = BlakeTwo256::hash(&0u64.encode());
+			let call = Call::<Runtime>::add_product_info(operator, biz_hash, b"1".to_vec(), InfoKind::Shipped, DataHash::from_runtime_hash(&H256::default()), vec![]);
+			assert!(call.dispatch(Origin::signed(OPERATOR)).is_ok());
+		}))
+	}, vec![1usize, 64, 200]);
+}
+
+criterion_group!(benches, create_product_by_extra, create_product_by_whitelist, add_product_info_by_count);
+criterion_main!(benches);
@@ -0,0 +1,343 @@
+//! The dry run of registry extrinsics.
+//!
+//! An integrator learns that an operator is not in the whitelist of a business, or that the
+//! business expired, only once the write is included and its fees charged. The dry run RPC
+//! applies a signed extrinsic in a block initialized on top of the best block, or the block
+//! given, and returns the error it would fail with, with its weight and fee. The runtime
+//! calls of the client run on a copy of the state which is discarded, so the extrinsic is
+//! neither broadcast nor stored.
+//!
+//! A dry run executes the extrinsic at the cost of the node alone, so the method is unsafe:
+//! the node refuses it when its RPC servers listen beyond localhost.
+
+use std::sync::Arc;
+
+use codec::{Codec, Decode};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pistis_primitives::{CallOutcome, CallValidation, RegistryDryRunApi as RegistryDryRunRuntimeApi};
+use primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, One, ProvideRuntimeApi},
+};
+
+use crate::{runtime_error, DenyUnsafe};
+
+pub use self::gen_client::Client as RegistryDryRunClient;
+
+const DRY_RUN_ERROR: i64 = 4;
+
+/// Convert an error preparing a dry run into an RPC error.
+fn dry_run_error(message: &str, e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(DRY_RUN_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+/// Apply an extrinsic on a copy of the state of a block
+pub trait DryRunProvider<Block: BlockT, Balance> {
+	/// Apply an extrinsic in a block initialized with a header, returning how it fared
+	///
+	/// @at	the parent block
+	/// @header	the header of the block initialized on top of it
+	/// @uxt	the extrinsic
+	fn dry_run(
+		&self,
+		at: &BlockId<Block>,
+		header: <Block as BlockT>::Header,
+		uxt: <Block as BlockT>::Extrinsic,
+	) -> std::result::Result<CallValidation<Balance>, String>;
+}
+
+impl<C, Block, Balance> DryRunProvider<Block, Balance> for C where
+	Block: BlockT,
+	C: ProvideRuntimeApi,
+	C::Api: RegistryDryRunRuntimeApi<Block, Balance>,
+	Balance: Codec,
+{
+	fn dry_run(
+		&self,
+		at: &BlockId<Block>,
+		header: <Block as BlockT>::Header,
+		uxt: <Block as BlockT>::Extrinsic,
+	) -> std::result::Result<CallValidation<Balance>, String> {
+		self.runtime_api()
+			.validate_call(at, header, uxt)
+			.map_err(|e| format!("{:?}", e))
+	}
+}
+
+/// The error an extrinsic would be refused or would fail with
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallError {
+	/// Whether the transaction pool would refuse the extrinsic, without charging it
+	pub invalid: bool,
+	/// The index of the module of the error, none for the errors given as messages
+	pub module: Option<u8>,
+	/// The index of the error in its module
+	pub error: Option<u8>,
+	/// The message of the error, such as `Not in the whitelist`
+	pub message: String,
+}
+
+/// The dry run of an extrinsic
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallReport<Balance> {
+	/// The error the extrinsic would be refused or would fail with, none if it would succeed
+	pub error: Option<CallError>,
+	/// The weight of the call
+	pub weight: u32,
+	/// The fee of the extrinsic, its tip excepted
+	pub partial_fee: Balance,
+}
+
+impl<Balance> From<CallValidation<Balance>> for CallReport<Balance> {
+	fn from(validation: CallValidation<Balance>) -> Self {
+		let error = match validation.outcome {
+			CallOutcome::Success => None,
+			CallOutcome::Invalid(reason) => Some(CallError {
+				invalid: true,
+				module: None,
+				error: None,
+				message: String::from_utf8_lossy(&reason).into_owned(),
+			}),
+			CallOutcome::Failed { module, error, message } => Some(CallError {
+				invalid: false,
+				module,
+				error: Some(error),
+				message: String::from_utf8_lossy(&message).into_owned(),
+			}),
+		};
+		CallReport {
+			error,
+			weight: validation.weight,
+			partial_fee: validation.partial_fee,
+		}
+	}
+}
+
+/// Registry dry run RPC methods.
+#[rpc]
+pub trait RegistryDryRunApi<BlockHash, Balance> {
+	/// Returns how a SCALE encoded signed extrinsic would fare if submitted on top of a
+	/// block, with its weight and fee, without submitting it.
+	#[rpc(name = "registry_validateCall")]
+	fn validate_call(
+		&self,
+		extrinsic: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<CallReport<Balance>>;
+}
+
+/// An implementation of the registry dry run RPC methods.
+pub struct RegistryDryRun<C, B, Balance> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<(B, Balance)>,
+}
+
+impl<C, B, Balance> RegistryDryRun<C, B, Balance> {
+	/// Create new `RegistryDryRun` with the given reference to the client, refusing the
+	/// dry runs if `deny_unsafe` says so.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		RegistryDryRun {
+			client,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Balance> RegistryDryRunApi<<Block as BlockT>::Hash, Balance>
+	for RegistryDryRun<C, Block, Balance>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: HeaderBackend<Block>,
+	C: DryRunProvider<Block, Balance>,
+	Balance: Send + Sync + 'static,
+{
+	fn validate_call(
+		&self,
+		extrinsic: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<CallReport<Balance>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		// If the block hash is not supplied assume the best block.
+		let parent_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let at = BlockId::hash(parent_hash);
+
+		let uxt: <Block as BlockT>::Extrinsic = Decode::decode(&mut &*extrinsic)
+			.map_err(|e| dry_run_error("Unable to decode the extrinsic.", e))?;
+		let parent = self.client.header(BlockId::hash(parent_hash))
+			.map_err(|e| dry_run_error("Unable to read the block.", e))?
+			.ok_or_else(|| dry_run_error("Unknown block.", parent_hash))?;
+		let header = <<Block as BlockT>::Header as HeaderT>::new(
+			*parent.number() + One::one(),
+			Default::default(),
+			Default::default(),
+			parent_hash,
+			Default::default(),
+		);
+
+		let validation = self.client
+			.dry_run(&at, header, uxt)
+			.map_err(runtime_error)?;
+
+		Ok(validation.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use primitives::H256;
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_runtime::testing::{Block as TestBlock, ExtrinsicWrapper, Header};
+
+	type Block = TestBlock<ExtrinsicWrapper<u64>>;
+
+	/// A chain of a single best block, dry running the extrinsics with a given outcome
+	struct TestClient {
+		best_hash: H256,
+		validation: CallValidation<u64>,
+	}
+
+	impl TestClient {
+		fn new(outcome: CallOutcome) -> Self {
+			TestClient {
+				best_hash: H256::repeat_byte(1),
+				validation: CallValidation { outcome, weight: 10_000, partial_fee: 42 },
+			}
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(&self, id: BlockId<Block>) -> sp_blockchain::Result<Option<Header>> {
+			Ok(match id {
+				BlockId::Hash(hash) if hash == self.best_hash => Some(Header::new_from_number(7)),
+				BlockId::Number(7) => Some(Header::new_from_number(7)),
+				_ => None,
+			})
+		}
+
+		fn info(&self) -> Info<Block> {
+			Info {
+				best_hash: self.best_hash,
+				best_number: 7,
+				genesis_hash: Default::default(),
+				finalized_hash: self.best_hash,
+				finalized_number: 7,
+			}
+		}
+
+		fn status(&self, id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+			Ok(match self.header(id)? {
+				Some(_) => BlockStatus::InChain,
+				None => BlockStatus::Unknown,
+			})
+		}
+
+		fn number(&self, hash: H256) -> sp_blockchain::Result<Option<u64>> {
+			Ok(if hash == self.best_hash { Some(7) } else { None })
+		}
+
+		fn hash(&self, number: u64) -> sp_blockchain::Result<Option<H256>> {
+			Ok(if number == 7 { Some(self.best_hash) } else { None })
+		}
+	}
+
+	impl DryRunProvider<Block, u64> for TestClient {
+		fn dry_run(
+			&self,
+			at: &BlockId<Block>,
+			header: Header,
+			_uxt: ExtrinsicWrapper<u64>,
+		) -> std::result::Result<CallValidation<u64>, String> {
+			// The extrinsic is applied in a block on top of the one given
+			assert_eq!(*at, BlockId::hash(self.best_hash));
+			assert_eq!(header.number, 8);
+			assert_eq!(header.parent_hash, self.best_hash);
+			Ok(self.validation.clone())
+		}
+	}
+
+	fn dry_run(outcome: CallOutcome, deny_unsafe: DenyUnsafe) -> RegistryDryRun<TestClient, Block, u64> {
+		RegistryDryRun::new(Arc::new(TestClient::new(outcome)), deny_unsafe)
+	}
+
+	fn extrinsic() -> Bytes {
+		5u64.encode().into()
+	}
+
+	#[test]
+	fn validate_call_should_report_success() {
+		let api = dry_run(CallOutcome::Success, DenyUnsafe::No);
+		assert_eq!(
+			api.validate_call(extrinsic(), None).unwrap(),
+			CallReport { error: None, weight: 10_000, partial_fee: 42 }
+		);
+		// The best block given explicitly
+		let best_hash = H256::repeat_byte(1);
+		assert_eq!(api.validate_call(extrinsic(), Some(best_hash)).unwrap().error, None);
+	}
+
+	#[test]
+	fn validate_call_should_report_failure() {
+		let api = dry_run(CallOutcome::Failed {
+			module: None,
+			error: 0,
+			message: b"Not in the whitelist".to_vec(),
+		}, DenyUnsafe::No);
+		assert_eq!(
+			api.validate_call(extrinsic(), None).unwrap(),
+			CallReport {
+				error: Some(CallError {
+					invalid: false,
+					module: None,
+					error: Some(0),
+					message: "Not in the whitelist".into(),
+				}),
+				weight: 10_000,
+				partial_fee: 42,
+			}
+		);
+
+		let api = dry_run(CallOutcome::Failed { module: Some(9), error: 2, message: vec![] }, DenyUnsafe::No);
+		let error = api.validate_call(extrinsic(), None).unwrap().error.unwrap();
+		assert_eq!((error.module, error.error), (Some(9), Some(2)));
+
+		let api = dry_run(CallOutcome::Invalid(b"Payment".to_vec()), DenyUnsafe::No);
+		assert_eq!(
+			api.validate_call(extrinsic(), None).unwrap().error,
+			Some(CallError { invalid: true, module: None, error: None, message: "Payment".into() })
+		);
+	}
+
+	#[test]
+	fn validate_call_should_fail_on_bad_input() {
+		let api = dry_run(CallOutcome::Success, DenyUnsafe::No);
+		let error = api.validate_call(vec![1, 2].into(), None).unwrap_err();
+		assert_eq!(error.code, ErrorCode::ServerError(DRY_RUN_ERROR));
+		assert_eq!(error.message, "Unable to decode the extrinsic.");
+
+		let error = api.validate_call(extrinsic(), Some(H256::repeat_byte(2))).unwrap_err();
+		assert_eq!(error.message, "Unknown block.");
+	}
+
+	#[test]
+	fn validate_call_should_be_unsafe() {
+		let api = dry_run(CallOutcome::Success, DenyUnsafe::Yes);
+		let error = api.validate_call(extrinsic(), None).unwrap_err();
+		assert_eq!(error.message, "RPC call is unsafe to be called externally.");
+	}
+}
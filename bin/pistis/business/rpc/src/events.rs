@@ -0,0 +1,253 @@
+//! The subscription to the product events of a business.
+//!
+//! The events of a block live in the `System` `Events` storage value, so the subscription
+//! follows the changes of that key and streams the `ProductCreated` and `ProductInfoAppended`
+//! events of the subscribed business.
+
+use std::{convert::TryInto, sync::Arc};
+
+use codec::{Decode, Encode};
+use futures::{future, StreamExt as _, TryStreamExt as _};
+use jsonrpc_core::{Error, ErrorCode, Result, futures::{Future, Sink}};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use log::warn;
+use primitives::{Bytes, storage::StorageKey, twox_128};
+use sc_client::BlockchainEvents;
+use sc_rpc::{Metadata, Subscriptions};
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::Block as BlockT;
+
+pub use self::gen_client::Client as ProductEventsClient;
+
+const STORAGE_ERROR: i64 = 2;
+
+/// A product event of the subscribed business
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductEvent<Hash> {
+	/// The name of the event, `ProductCreated` or `ProductInfoAppended`
+	pub name: String,
+	/// The sequence id of the product
+	pub seq_id: Bytes,
+	/// The product hash
+	pub product_hash: Hash,
+	/// The SCALE encoded business event
+	pub event: Bytes,
+}
+
+/// The product events a block deposited for the subscribed business
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductNotification<BlockHash, Hash> {
+	/// The block hash
+	pub block: BlockHash,
+	/// The product events in the order they were deposited
+	pub events: Vec<ProductEvent<Hash>>,
+}
+
+/// Product event subscription methods.
+#[rpc]
+pub trait ProductEventsApi<BlockHash, Hash> {
+	/// RPC Metadata
+	type Metadata;
+
+	/// Subscribe to the products created and updated by a business
+	#[pubsub(subscription = "business_products", subscribe, name = "business_subscribeProducts")]
+	fn subscribe_products(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ProductNotification<BlockHash, Hash>>,
+		biz_hash: Hash,
+	);
+
+	/// Unsubscribe from the products of a business
+	#[pubsub(subscription = "business_products", unsubscribe, name = "business_unsubscribeProducts")]
+	fn unsubscribe_products(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool>;
+}
+
+/// An implementation of the product event subscriptions.
+pub struct ProductEvents<C, B, R> {
+	client: Arc<C>,
+	subscriptions: Subscriptions,
+	_marker: std::marker::PhantomData<(B, R)>,
+}
+
+impl<C, B, R> ProductEvents<C, B, R> {
+	/// Create new `ProductEvents` with the given reference to the client.
+	pub fn new(client: Arc<C>, subscriptions: Subscriptions) -> Self {
+		ProductEvents {
+			client,
+			subscriptions,
+			_marker: Default::default(),
+		}
+	}
+}
+
+/// The storage key of the events deposited in a block
+fn events_key() -> StorageKey {
+	let mut key = twox_128(b"System").to_vec();
+	key.extend_from_slice(&twox_128(b"Events"));
+	StorageKey(key)
+}
+
+/// Collect the product events of a business from the encoded events of a block
+///
+/// @biz_hash	the business hash
+/// @events	the SCALE encoded event records
+fn product_events<R>(biz_hash: &R::Hash, mut events: &[u8]) -> Vec<ProductEvent<R::Hash>> where
+	R: business::Trait,
+	<R as system::Trait>::Event: TryInto<business::Event<R>>,
+{
+	let records = match Vec::<system::EventRecord<<R as system::Trait>::Event, R::Hash>>::decode(&mut events) {
+		Ok(records) => records,
+		Err(e) => {
+			warn!("Failed to decode the block events: {:?}", e);
+			return Vec::new();
+		},
+	};
+
+	records.into_iter().filter_map(|record| {
+		let event: business::Event<R> = record.event.try_into().ok()?;
+		let (name, seq_id, product_hash) = match &event {
+			business::RawEvent::ProductCreated(_, biz, seq_id, product_hash, _) if biz == biz_hash =>
+				("ProductCreated", seq_id, product_hash),
+			business::RawEvent::ProductInfoAppended(_, biz, seq_id, product_hash, _, _) if biz == biz_hash =>
+				("ProductInfoAppended", seq_id, product_hash),
+			_ => return None,
+		};
+		Some(ProductEvent {
+			name: name.into(),
+			seq_id: seq_id.to_vec().into(),
+			product_hash: *product_hash,
+			event: event.encode().into(),
+		})
+	}).collect()
+}
+
+impl<C, Block, R> ProductEventsApi<<Block as BlockT>::Hash, R::Hash> for ProductEvents<C, Block, R>
+where
+	Block: BlockT + 'static,
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
+	R: business::Trait + Send + Sync + 'static,
+	<R as system::Trait>::Event: TryInto<business::Event<R>>,
+	R::Hash: Serialize + for<'de> Deserialize<'de>,
+{
+	type Metadata = Metadata;
+
+	fn subscribe_products(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ProductNotification<<Block as BlockT>::Hash, R::Hash>>,
+		biz_hash: R::Hash,
+	) {
+		let key = events_key();
+		let stream = match self.client.storage_changes_notification_stream(Some(&[key.clone()]), None) {
+			Ok(stream) => stream,
+			Err(e) => {
+				// reject the subscriber (ignore errors - we don't care if subscriber is no longer there).
+				let _ = subscriber.reject(Error {
+					code: ErrorCode::ServerError(STORAGE_ERROR),
+					message: "Failed to follow the block events.".into(),
+					data: Some(format!("{:?}", e).into()),
+				});
+				return;
+			},
+		};
+
+		self.subscriptions.add(subscriber, move |sink| {
+			let stream = stream
+				.filter_map(move |(block, changes)| {
+					let events = changes.iter()
+						.find(|(child, k, _)| child.is_none() && **k == key)
+						.and_then(|(_, _, data)| data)
+						.map(|data| product_events::<R>(&biz_hash, &data.0))
+						.unwrap_or_default();
+					// blocks without events of the business are not notified
+					future::ready(if events.is_empty() {
+						None
+					} else {
+						Some(Ok::<_, ()>(Ok(ProductNotification { block, events })))
+					})
+				})
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_products(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::convert::TryFrom;
+	use pistis_primitives::{BoundedSeqId, InfoKind};
+	use runtime::{AccountId, Event, Hash, Runtime};
+	use system::{EventRecord, Phase};
+
+	fn seq_id(id: &[u8]) -> BoundedSeqId {
+		BoundedSeqId::try_from(id.to_vec()).unwrap()
+	}
+
+	fn record(event: Event) -> EventRecord<Event, Hash> {
+		EventRecord { phase: Phase::ApplyExtrinsic(0), event, topics: vec![] }
+	}
+
+	fn product_event(name: &str, seq_id: &[u8], product_hash: Hash, event: business::Event<Runtime>) -> ProductEvent<Hash> {
+		ProductEvent {
+			name: name.into(),
+			seq_id: seq_id.to_vec().into(),
+			product_hash,
+			event: event.encode().into(),
+		}
+	}
+
+	#[test]
+	fn product_events_should_filter_the_business() {
+		let (ours, theirs) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+		let (our_product, their_product) = (Hash::repeat_byte(11), Hash::repeat_byte(21));
+		let operator = AccountId::default();
+		let created = business::RawEvent::ProductCreated(operator.clone(), ours, seq_id(b"1"), our_product, 10);
+		let appended = business::RawEvent::ProductInfoAppended(
+			operator.clone(), ours, seq_id(b"1"), our_product, InfoKind::Shipped, 20,
+		);
+		let records = vec![
+			record(Event::system(system::Event::ExtrinsicSuccess(Default::default()))),
+			record(business::RawEvent::BusinessCreated(operator.clone(), ours).into()),
+			record(business::RawEvent::ProductCreated(operator.clone(), theirs, seq_id(b"1"), their_product, 10).into()),
+			record(created.clone().into()),
+			record(business::RawEvent::ProductInfoAppended(
+				operator.clone(), theirs, seq_id(b"1"), their_product, InfoKind::Shipped, 20,
+			).into()),
+			record(appended.clone().into()),
+		];
+
+		assert_eq!(product_events::<Runtime>(&ours, &records.encode()), vec![
+			product_event("ProductCreated", b"1", our_product, created),
+			product_event("ProductInfoAppended", b"1", our_product, appended),
+		]);
+		assert!(product_events::<Runtime>(&Hash::repeat_byte(3), &records.encode()).is_empty());
+	}
+
+	#[test]
+	fn product_events_should_skip_undecodable_events() {
+		assert!(product_events::<Runtime>(&Hash::repeat_byte(1), &[]).is_empty());
+		assert!(product_events::<Runtime>(&Hash::repeat_byte(1), &[4, 1, 2, 3]).is_empty());
+	}
+}
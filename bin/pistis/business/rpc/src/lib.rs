@@ -0,0 +1,371 @@
+//! Node-specific RPC methods for querying names, businesses and products.
+
+use std::sync::Arc;
+
+use sp_blockchain::HeaderBackend;
+use codec::{Codec, Encode};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use primitives::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub mod dry_run;
+pub mod events;
+pub mod proof;
+
+pub use self::gen_client::Client as BusinessClient;
+pub use self::dry_run::{CallError, CallReport, DryRunProvider, RegistryDryRun, RegistryDryRunApi, RegistryDryRunClient};
+pub use self::events::{ProductEvents, ProductEventsApi, ProductEventsClient};
+pub use self::proof::{ProductProof, ReadProofProvider, RegistryProof, RegistryProofApi, RegistryProofClient};
+pub use pistis_primitives::{RegistryApi as RegistryRuntimeApi, RegistryDryRunApi as RegistryDryRunRuntimeApi};
+use pistis_primitives::{RegistryDigest, merkle::InfoProof};
+
+const RUNTIME_ERROR: i64 = 1;
+const UNSAFE_ERROR: i64 = 5;
+
+/// Convert a runtime api error into an RPC error.
+fn runtime_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped while querying the registry.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+/// Whether the unsafe RPC methods are refused, as the node serves clients beyond localhost
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DenyUnsafe {
+	/// Refuse the unsafe methods
+	Yes,
+	/// Serve the unsafe methods
+	No,
+}
+
+impl DenyUnsafe {
+	/// Return an RPC error if the unsafe methods are refused.
+	pub fn check_if_safe(self) -> Result<()> {
+		match self {
+			DenyUnsafe::Yes => Err(Error {
+				code: ErrorCode::ServerError(UNSAFE_ERROR),
+				message: "RPC call is unsafe to be called externally.".into(),
+				data: None,
+			}),
+			DenyUnsafe::No => Ok(()),
+		}
+	}
+}
+
+/// Business RPC methods.
+#[rpc]
+pub trait BusinessApi<BlockHash, Hash, AccountId> {
+	/// Returns the SCALE encoded resolve record of a node, or `None` if it does not resolve.
+	#[rpc(name = "nameService_resolve")]
+	fn resolve(
+		&self,
+		node_hash: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Option<Bytes>>;
+
+	/// Returns the nodes an account owns.
+	#[rpc(name = "nameService_namesOf")]
+	fn names_of(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Hash>>;
+
+	/// Returns the SCALE encoded business with its owner and whitelist resolved through
+	/// the name service, or `None` if it does not exist.
+	#[rpc(name = "business_info")]
+	fn business_info(
+		&self,
+		biz_hash: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Option<Bytes>>;
+
+	/// Returns the SCALE encoded product a business created under a sequence id,
+	/// or `None` if it holds no such product.
+	#[rpc(name = "business_productBySeqId")]
+	fn product_by_seq_id(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Option<Bytes>>;
+
+	/// Returns a page of the product hashes of a business in creation order. The runtime
+	/// caps the page size.
+	#[rpc(name = "business_listProducts")]
+	fn list_products(
+		&self,
+		biz_hash: Hash,
+		start: u64,
+		limit: u64,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Hash>>;
+
+	/// Returns the SCALE encoded history of a product, the infos of its lot merged in.
+	/// The history is empty if the product does not exist.
+	#[rpc(name = "business_productHistory")]
+	fn product_history(
+		&self,
+		product_hash: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Bytes>;
+
+	/// Returns the hash of the business an account created at an index among its
+	/// businesses, or `None` if it did not create that many.
+	#[rpc(name = "business_hashFor")]
+	fn business_hash_for(
+		&self,
+		creator: AccountId,
+		index: u64,
+		at: Option<BlockHash>,
+	) -> Result<Option<Hash>>;
+
+	/// Returns the businesses, products and lots changed in a block.
+	#[rpc(name = "business_registryDigest")]
+	fn registry_digest(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<RegistryDigest<Hash>>;
+
+	/// Returns the proof of an info of a product against the Merkle root of its infos, or
+	/// `None` if the product or the info does not exist.
+	#[rpc(name = "business_productInfoProof")]
+	fn product_info_proof(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		index: u32,
+		at: Option<BlockHash>,
+	) -> Result<Option<InfoProof>>;
+
+	/// Returns the SCALE encoded versioned commitment to the provenance of a product a
+	/// business created under a sequence id, to relay to other chains, or `None` if it
+	/// holds no such product.
+	#[rpc(name = "business_provenanceCommitment")]
+	fn provenance_commitment(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Option<Bytes>>;
+
+	/// Returns the SCALE encoded counts of the products and infos an operator wrote to a
+	/// business, with the block of its last write.
+	#[rpc(name = "business_operatorStats")]
+	fn operator_stats(
+		&self,
+		biz_hash: Hash,
+		name_hash: Hash,
+		at: Option<BlockHash>,
+	) -> Result<Bytes>;
+}
+
+/// An implementation of business specific RPC methods.
+pub struct Business<C, B, AccountId, BlockNumber, Moment> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(B, AccountId, BlockNumber, Moment)>,
+}
+
+impl<C, B, AccountId, BlockNumber, Moment> Business<C, B, AccountId, BlockNumber, Moment> {
+	/// Create new `Business` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Business {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Hash, AccountId, BlockNumber, Moment> BusinessApi<<Block as BlockT>::Hash, Hash, AccountId>
+	for Business<C, Block, AccountId, BlockNumber, Moment>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: RegistryRuntimeApi<Block, Hash, AccountId, BlockNumber, Moment>,
+	Hash: Codec + Send + Sync + 'static,
+	AccountId: Codec + Send + Sync + 'static,
+	BlockNumber: Codec + Send + Sync + 'static,
+	Moment: Codec + Send + Sync + 'static,
+{
+	fn resolve(
+		&self,
+		node_hash: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let record = api
+			.resolve(&at, node_hash)
+			.map_err(runtime_error)?;
+
+		Ok(record.map(|record| record.encode().into()))
+	}
+
+	fn names_of(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.names_of(&at, account).map_err(runtime_error)
+	}
+
+	fn business_info(
+		&self,
+		biz_hash: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let details = api
+			.business_info(&at, biz_hash)
+			.map_err(runtime_error)?;
+
+		Ok(details.map(|details| details.encode().into()))
+	}
+
+	fn product_by_seq_id(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let product = api
+			.product_by_seq_id(&at, biz_hash, seq_id.to_vec())
+			.map_err(runtime_error)?;
+
+		Ok(product.map(|product| product.encode().into()))
+	}
+
+	fn list_products(
+		&self,
+		biz_hash: Hash,
+		start: u64,
+		limit: u64,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.list_products(&at, biz_hash, start, limit).map_err(runtime_error)
+	}
+
+	fn product_history(
+		&self,
+		product_hash: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let history = api
+			.product_history(&at, product_hash)
+			.map_err(runtime_error)?;
+
+		Ok(history.encode().into())
+	}
+
+	fn business_hash_for(
+		&self,
+		creator: AccountId,
+		index: u64,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.business_hash_for(&at, creator, index).map_err(runtime_error)
+	}
+
+	fn registry_digest(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RegistryDigest<Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.registry_digest(&at).map_err(runtime_error)
+	}
+
+	fn product_info_proof(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		index: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<InfoProof>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.product_info_proof(&at, biz_hash, seq_id.to_vec(), index).map_err(runtime_error)
+	}
+
+	fn provenance_commitment(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let commitment = api
+			.provenance_commitment(&at, biz_hash, seq_id.to_vec())
+			.map_err(runtime_error)?;
+
+		Ok(commitment.map(|commitment| commitment.encode().into()))
+	}
+
+	fn operator_stats(
+		&self,
+		biz_hash: Hash,
+		name_hash: Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let stats = api
+			.operator_stats(&at, biz_hash, name_hash)
+			.map_err(runtime_error)?;
+
+		Ok(stats.encode().into())
+	}
+}
@@ -0,0 +1,163 @@
+//! The read proofs of registry entries.
+//!
+//! A mobile app checking a product should not have to trust the node it asks. The proof RPC
+//! returns the storage entries of a product with the trie nodes proving them at a block, and
+//! the app checks them against the state root of a header it trusts with
+//! `pistis_primitives::proof::verify_product_proof`, which derives the keys itself.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use client_api::backend::Backend;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use primitives::{Blake2Hasher, Bytes, H256, storage::StorageKey};
+use sc_client::{CallExecutor, Client};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+use crate::{runtime_error, RegistryRuntimeApi};
+
+pub use self::gen_client::Client as RegistryProofClient;
+
+const PROOF_ERROR: i64 = 3;
+
+/// Convert a client error while proving into an RPC error.
+fn proof_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(PROOF_ERROR),
+		message: "Failed to prove the registry entry.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+/// Read the storage of a block with its proof
+pub trait ReadProofProvider<Block: BlockT> {
+	/// Read the value of a storage key at a block
+	///
+	/// @at	the block
+	/// @key	the storage key
+	fn read_storage(&self, at: &BlockId<Block>, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>>;
+
+	/// Prove the values of storage keys at a block, returning the trie nodes of the proof
+	///
+	/// @at	the block
+	/// @keys	the storage keys
+	fn prove_storage(&self, at: &BlockId<Block>, keys: &[Vec<u8>]) -> sp_blockchain::Result<Vec<Vec<u8>>>;
+}
+
+impl<B, E, Block, RA> ReadProofProvider<Block> for Client<B, E, Block, RA> where
+	B: Backend<Block, Blake2Hasher>,
+	E: CallExecutor<Block, Blake2Hasher>,
+	Block: BlockT<Hash=H256>,
+{
+	fn read_storage(&self, at: &BlockId<Block>, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		Ok(self.storage(at, &StorageKey(key.to_vec()))?.map(|data| data.0))
+	}
+
+	fn prove_storage(&self, at: &BlockId<Block>, keys: &[Vec<u8>]) -> sp_blockchain::Result<Vec<Vec<u8>>> {
+		Ok(self.read_proof(at, keys)?.iter_nodes().collect())
+	}
+}
+
+/// A storage entry covered by a read proof
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenEntry {
+	/// The storage key
+	pub key: Bytes,
+	/// The SCALE encoded value, `None` if the key is proven absent
+	pub value: Option<Bytes>,
+}
+
+/// The storage entries of a product with their read proof
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductProof<BlockHash> {
+	/// The hash of the block proven at, whose header holds the state root
+	pub at: BlockHash,
+	/// The product hash under the sequence id, then the product under the hash it records,
+	/// or under the legacy hash if none is recorded
+	pub entries: Vec<ProvenEntry>,
+	/// The trie nodes of the proof
+	pub proof: Vec<Bytes>,
+}
+
+/// Registry proof RPC methods.
+#[rpc]
+pub trait RegistryProofApi<BlockHash, Hash> {
+	/// Returns the storage entries of the product a business created under a sequence id,
+	/// with a read proof of them at a block. A missing product is proven absent.
+	#[rpc(name = "registry_proveProduct")]
+	fn prove_product(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<ProductProof<BlockHash>>;
+}
+
+/// An implementation of the registry proof RPC methods.
+pub struct RegistryProof<C, B, AccountId, BlockNumber, Moment> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(B, AccountId, BlockNumber, Moment)>,
+}
+
+impl<C, B, AccountId, BlockNumber, Moment> RegistryProof<C, B, AccountId, BlockNumber, Moment> {
+	/// Create new `RegistryProof` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		RegistryProof {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Hash, AccountId, BlockNumber, Moment> RegistryProofApi<<Block as BlockT>::Hash, Hash>
+	for RegistryProof<C, Block, AccountId, BlockNumber, Moment>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C: ReadProofProvider<Block>,
+	C::Api: RegistryRuntimeApi<Block, Hash, AccountId, BlockNumber, Moment>,
+	Hash: Codec + Send + Sync + 'static,
+	AccountId: Codec + Send + Sync + 'static,
+	BlockNumber: Codec + Send + Sync + 'static,
+	Moment: Codec + Send + Sync + 'static,
+{
+	fn prove_product(
+		&self,
+		biz_hash: Hash,
+		seq_id: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<ProductProof<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		// If the block hash is not supplied assume the best block.
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let at = BlockId::hash(hash);
+
+		let keys = api
+			.product_storage_keys(&at, biz_hash, seq_id.to_vec())
+			.map_err(runtime_error)?;
+
+		let entries = keys.iter()
+			.map(|key| Ok(ProvenEntry {
+				key: key.clone().into(),
+				value: self.client.read_storage(&at, key).map_err(proof_error)?.map(Into::into),
+			}))
+			.collect::<Result<Vec<_>>>()?;
+		let proof = self.client.prove_storage(&at, &keys).map_err(proof_error)?;
+
+		Ok(ProductProof {
+			at: hash,
+			entries,
+			proof: proof.into_iter().map(Into::into).collect(),
+		})
+	}
+}
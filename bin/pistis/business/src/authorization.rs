@@ -0,0 +1,37 @@
+//! # Authorization
+//!
+//! The calls on a business name the owner or operator they act for by a name hash. Whether
+//! the sender may act for the name is decided by the `AuthorizationProvider` of the runtime,
+//! so a deployment can authorize by account membership, DID documents or attestations
+//! instead of the name service. `NameServiceAuthorization` is the standard provider.
+
+use rstd::marker::PhantomData;
+use name_service::NameServiceResolver;
+
+/// Decide which accounts may act for a name hash
+pub trait AuthorizationProvider<T: system::Trait> {
+	/// Check if an account may act for a name hash
+	///
+	/// @hash	the name hash
+	/// @account	the account
+	/// @max_depth	the ancestor levels whose owners are also accepted, 0 for the name only
+	fn is_authorized(hash: T::Hash, account: &T::AccountId, max_depth: u32) -> bool;
+}
+
+/// The standard provider: a name hash authorizes the account it resolves to in the name
+/// service, and the owners of its ancestors up to the depth.
+pub struct NameServiceAuthorization<Resolver>(PhantomData<Resolver>);
+
+impl<T: system::Trait, Resolver: NameServiceResolver<T>> AuthorizationProvider<T> for NameServiceAuthorization<Resolver> {
+	/// Check the account against the name service
+	///
+	/// @hash	the name hash
+	/// @account	the account
+	/// @max_depth	the ancestor levels whose owners are also accepted, 0 for the name only
+	fn is_authorized(hash: T::Hash, account: &T::AccountId, max_depth: u32) -> bool {
+		if max_depth == 0 {
+			return Resolver::resolve_addr(hash).as_ref() == Some(account);
+		}
+		Resolver::is_authorized(hash, account, max_depth)
+	}
+}
@@ -4,20 +4,33 @@ mod tests {
     use crate::*;
 	use super::*;
 
-	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use std::cell::RefCell;
+	use support::{assert_ok, assert_noop, impl_outer_event, impl_outer_origin, parameter_types, storage::unhashed, traits::Get, weights::{DispatchClass, GetDispatchInfo, Weight}};
 	use primitives::H256;
 	use system::EnsureSignedBy;
 	use name_service::NameServiceResolver;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
 	use sp_runtime::{
-		Perbill, testing::Header, traits::{BlakeTwo256, IdentityLookup},
+		Perbill, testing::{Header, TestXt, UintAuthorityId}, traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Lazy, OnFinalize, OnInitialize, Verify},
 	};
+	use support::unsigned::ValidateUnsigned;
 
 	impl_outer_origin! {
 		pub enum Origin for Test {}
 	}
 
+	mod business {
+		pub use crate::Event;
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			balances<T>,
+			business<T>,
+		}
+	}
+
 	// For testing the module, we construct most of a mock runtime. This means
 	// first constructing a configuration type (`Test`) which `impl`s each of the
 	// configuration traits of modules we want to use.
@@ -39,7 +52,7 @@ mod tests {
 		type AccountId = u64;
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
-		type Event = ();
+		type Event = TestEvent;
 		type BlockHashCount = BlockHashCount;
 		type MaximumBlockWeight = MaximumBlockWeight;
 		type MaximumBlockLength = MaximumBlockLength;
@@ -55,18 +68,66 @@ mod tests {
 		type Balance = u64;
 		type OnFreeBalanceZero = ();
 		type OnNewAccount = ();
-		type Event = ();
+		type Event = TestEvent;
 		type TransferPayment = ();
 		type DustRemoval = ();
 		type ExistentialDeposit = ExistentialDeposit;
 		type TransferFee = TransferFee;
 		type CreationFee = CreationFee;
 	}
+	parameter_types! {
+		pub const MinimumPeriod: u64 = 1;
+	}
+	impl timestamp::Trait for Test {
+		type Moment = u64;
+		type OnTimestampSet = ();
+		type MinimumPeriod = MinimumPeriod;
+	}
 
 	const BISINESS_OWNER: &str = "longguhu";
 	const ALICE: &str = "alice";
 	const BOB: &str = "bob";
 	const RAY: &str = "ray";
+	const OPS: &str = "ops.longguhu";
+
+	thread_local! {
+		static PRODUCT_DEPOSIT: RefCell<u64> = RefCell::new(0);
+		static EXTRA_BYTE_DEPOSIT: RefCell<u64> = RefCell::new(0);
+		static RESOLVE_CHANGE_ACTION: RefCell<ResolveChangeAction> = RefCell::new(ResolveChangeAction::Warn);
+		static RICH_EVENTS: RefCell<bool> = RefCell::new(false);
+	}
+
+	/// Products take no deposit unless a test sets one
+	pub struct ProductDeposit;
+	impl Get<u64> for ProductDeposit {
+		fn get() -> u64 {
+			PRODUCT_DEPOSIT.with(|v| *v.borrow())
+		}
+	}
+
+	/// Extras take no deposit unless a test sets a byte price
+	pub struct ExtraByteDeposit;
+	impl Get<u64> for ExtraByteDeposit {
+		fn get() -> u64 {
+			EXTRA_BYTE_DEPOSIT.with(|v| *v.borrow())
+		}
+	}
+
+	/// Resolve changes only warn unless a test sets another action
+	pub struct OnOperatorResolveChanged;
+	impl Get<ResolveChangeAction> for OnOperatorResolveChanged {
+		fn get() -> ResolveChangeAction {
+			RESOLVE_CHANGE_ACTION.with(|v| *v.borrow())
+		}
+	}
+
+	/// Infos are not logged in full unless a test enables it
+	pub struct RichEvents;
+	impl Get<bool> for RichEvents {
+		fn get() -> bool {
+			RICH_EVENTS.with(|v| *v.borrow())
+		}
+	}
 
 	parameter_types! {
 		pub const MinNameLength: usize = 3;
@@ -75,22 +136,98 @@ mod tests {
 		pub const MaxSeqIDLength: usize = 64;
 		pub const MaxExtraLength: usize = 1024;
 		pub const MaxProductInfoCount: usize = 10;
+		pub const MaxWhitelistSize: usize = 3;
+		pub const ArchiveGracePeriod: u64 = 5;
+		pub const MaxArchiveScan: u32 = 10;
+		pub const MaxAuthorizationDepth: u32 = 2;
+		pub const MaxWritesPerOperatorPerBlock: u32 = 16;
+		pub const MaxVerificationsPerBlock: u32 = 8;
+		pub const MaxVerificationsPerAccount: u32 = 3;
+		pub const AdminDispatchClass: DispatchClass = DispatchClass::Operational;
+		pub const AdminPaysFee: bool = false;
+		pub const MaxSubBusinesses: u32 = 2;
+		pub const MaxBusinessesPerName: u32 = 8;
+		pub const MaxLotsPerBusiness: u32 = 2;
+		pub const MaxDigestEntries: u32 = 3;
+		pub const AppealPeriod: u64 = 10;
+		pub const RenewalPricePerBlock: u64 = 0;
+		pub const VerificationFee: u64 = 2;
+		pub const CheapExtraLength: u32 = 4;
 	}
+	type Extrinsic = TestXt<Call<Test>, ()>;
+	type SubmitTransaction = system::offchain::TransactionSubmitter<(), Call<Test>, Extrinsic>;
 	impl Trait for Test {
-		type Event = ();
+		type Event = TestEvent;
 		type ForceOrigin = EnsureSignedBy<One, u64>;
 		type MinNameLength = MinNameLength;
 		type MaxNameLength = MaxNameLength;
 		type MaxSeqIDLength = MaxSeqIDLength;
 		type MaxExtraLength = MaxExtraLength;
 		type MaxProductInfoCount = MaxProductInfoCount;
+		type MaxWhitelistSize = MaxWhitelistSize;
+		type Currency = Balances;
+		type ProductDeposit = ProductDeposit;
+		type StorageFeePolicy = TieredStorageFee<ExtraByteDeposit, CheapExtraLength>;
+		type RenewalPricePerBlock = RenewalPricePerBlock;
+		type RenewalFees = ();
+		type VerificationFee = VerificationFee;
+		type VerificationFees = ();
 		type NameServiceResolver = Self;
+		type AuthorizationProvider = NameServiceAuthorization<Self>;
+		type ArchiverId = UintAuthorityId;
+		type Call = Call<Test>;
+		type SubmitTransaction = SubmitTransaction;
+		type ArchiveGracePeriod = ArchiveGracePeriod;
+		type MaxArchiveScan = MaxArchiveScan;
+		type OperatorSignature = TestSig;
+		type OperatorSigner = TestSigner;
+		type WeightInfo = ();
+		type AttestationVerifier = Self;
+		type Anchors = ();
+		type MaxAuthorizationDepth = MaxAuthorizationDepth;
+		type OnOperatorResolveChanged = OnOperatorResolveChanged;
+		type MaxWritesPerOperatorPerBlock = MaxWritesPerOperatorPerBlock;
+		type MaxVerificationsPerBlock = MaxVerificationsPerBlock;
+		type MaxVerificationsPerAccount = MaxVerificationsPerAccount;
+		type AdminDispatchClass = AdminDispatchClass;
+		type AdminPaysFee = AdminPaysFee;
+		type MaxSubBusinesses = MaxSubBusinesses;
+		type MaxBusinessesPerName = MaxBusinessesPerName;
+		type MaxLotsPerBusiness = MaxLotsPerBusiness;
+		type RichEvents = RichEvents;
+		type MaxDigestEntries = MaxDigestEntries;
+		type AppealPeriod = AppealPeriod;
+	}
+
+	/// The signer of test signatures, which is the account itself
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+	pub struct TestSigner(u64);
+	impl IdentifyAccount for TestSigner {
+		type AccountId = u64;
+		fn into_account(self) -> u64 { self.0 }
+	}
+
+	/// A test signature: the signer account and the signed message
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+	pub struct TestSig(u64, Vec<u8>);
+	impl Verify for TestSig {
+		type Signer = TestSigner;
+		fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &u64) -> bool {
+			*signer == self.0 && msg.get() == &self.1[..]
+		}
+	}
+
+	impl attestation::AttestationVerifier<<Test as system::Trait>::Hash, <Test as system::Trait>::Hash> for Test {
+		fn has_valid_attestation(subject: &<Test as system::Trait>::Hash, schema: &<Test as system::Trait>::Hash) -> bool {
+			// Only alice is certified, for any schema except the default one
+			*subject == Self::single_name_hash(ALICE) && *schema != <Test as system::Trait>::Hash::default()
+		}
 	}
 
 	impl NameServiceResolver<Test> for Test {
 		fn resolve_addr(node_hash: <Test as system::Trait>::Hash) -> Option<<Test as system::Trait>::AccountId> {
 			// let scope = Self::single_name_hash(&<Test as Trait>::ScopeName::get());
-			let scope = Service::scope_name_hash();
+			let scope = Service::default_scope_hash();
 			let longguhu = Self::single_name_hash(BISINESS_OWNER);
 			let alice = Self::single_name_hash(ALICE);
 			let bob = Self::single_name_hash(BOB);
@@ -110,6 +247,16 @@ mod tests {
 			println!("resolved to addr {:#?}", addr);
 			addr
 		}
+
+		fn is_reserved(name: &[u8]) -> bool {
+			name == b"nike"
+		}
+
+		fn is_authorized(node_hash: <Test as system::Trait>::Hash, account: &<Test as system::Trait>::AccountId, max_depth: u32) -> bool {
+			// ops.longguhu is a child of longguhu, whose owner is account 2
+			Self::resolve_addr(node_hash).as_ref() == Some(account)
+				|| (node_hash == Self::single_name_hash(OPS) && max_depth >= 1 && *account == 2)
+		}
 	}
 
 	impl Test {
@@ -134,22 +281,42 @@ mod tests {
 			],
 			vesting: vec![],
 		}.assimilate_storage(&mut t).unwrap();
+		GenesisConfig::<Test> {
+			scopes: vec![(scope(), scope())],
+		}.assimilate_storage(&mut t).unwrap();
 		t.into()
 	}
 
+	/// The default scope, administered by account 1
+	fn scope() -> H256 {
+		Service::default_scope_hash()
+	}
+
 	#[test]
 	fn create_business_should_work() {
 		new_test_ext().execute_with(|| {
 			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
-			assert_noop!(Service::create_business(Origin::signed(2), owner_hash, "crab".into(), 10), "Not authorized");
-			assert_noop!(Service::create_business(Origin::signed(1), owner_hash, "c".into(), 10), "Name too short");
-			assert_noop!(Service::create_business(Origin::signed(1), owner_hash, "c".repeat(17).into(), 10), "Name too long");
+			assert_noop!(Service::create_business(Origin::signed(2), scope(), owner_hash, "crab".into(), 10), "Not authorized");
+			assert_noop!(Service::create_business(Origin::signed(1), scope(), owner_hash, "c".into(), 10), "Name too short");
+			assert_noop!(Service::create_business(Origin::signed(1), scope(), owner_hash, "c".repeat(17).into(), 10), "Name too long");
+			assert_noop!(Service::create_business(Origin::signed(1), scope(), owner_hash, "nike".into(), 10), "Name is reserved");
 			System::set_block_number(10);
-			assert_noop!(Service::create_business(Origin::signed(1), owner_hash, "crab".into(), 10), "Expired");
-			let biz_hash = Service::business_hash(1, owner_hash);
-			assert_ok!(Service::create_business(Origin::signed(1), owner_hash, "crab".into(), 20));
+			assert_noop!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 10), "Expired");
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
 			assert_eq!(Service::block_number(), 10);
 			assert_eq!(Service::business_of(biz_hash).creator, 1);
+
+			// The hash is derived from the creator index, the same name gives another hash
+			assert_eq!(biz_hash, Service::business_hash(1, owner_hash, "crab".into(), 0));
+			assert_eq!(Service::creator_business_count(1), 1);
+			let second_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ne!(second_hash, biz_hash);
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert!(<Businesses<Test>>::exists(second_hash));
+			assert_eq!(Service::business_hash_for(1, 0), Some(biz_hash));
+			assert_eq!(Service::business_hash_for(1, 1), Some(second_hash));
+			assert_eq!(Service::business_hash_for(1, 2), None);
 		});
 	}
 
@@ -158,12 +325,12 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
 			let biz_hash = <Test as system::Trait>::Hash::default();
-			assert_noop!(Service::set_business_expiration(Origin::signed(2), biz_hash,  10), "Not authorized");
 			assert_noop!(Service::set_business_expiration(Origin::signed(1), biz_hash,  10), "Business does not exist");
 
 			System::set_block_number(10);
-			let biz_hash = Service::business_hash(1, owner_hash);
-			assert_ok!(Service::create_business(Origin::signed(1), owner_hash, "crab".into(), 20));
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_noop!(Service::set_business_expiration(Origin::signed(2), biz_hash,  10), "Not authorized");
 
 			assert_noop!(Service::set_business_expiration(Origin::signed(1), biz_hash,  10), "Expired");
 			assert_noop!(Service::set_business_expiration(Origin::signed(1), biz_hash,  20), "Same value");
@@ -173,6 +340,49 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn sub_business_should_work() {
+		new_test_ext().execute_with(|| {
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let parent_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+
+			// Only the parent owner may create sub-businesses, expiring no later than the parent
+			assert_noop!(Service::create_sub_business(Origin::signed(3), parent_hash, alice, "factory".into(), 20), "Not authorized");
+			assert_noop!(Service::create_sub_business(Origin::signed(2), parent_hash, alice, "factory".into(), 21), "Expiration beyond parent");
+			let child_hash = Service::next_business_hash(2, alice, "factory".into());
+			assert_ok!(Service::create_sub_business(Origin::signed(2), parent_hash, alice, "factory".into(), 18));
+			assert_eq!(Service::business_of(child_hash).parent, Some(parent_hash));
+			assert_eq!(Service::business_of(child_hash).scope, scope());
+			assert_eq!(Service::sub_businesses(parent_hash), vec![child_hash]);
+			assert_noop!(Service::create_sub_business(Origin::signed(3), child_hash, alice, "line".into(), 18), "Sub-businesses can not be nested");
+
+			let second_hash = Service::next_business_hash(2, alice, "brand".into());
+			assert_ok!(Service::create_sub_business(Origin::signed(2), parent_hash, alice, "brand".into(), 20));
+			assert_noop!(Service::create_sub_business(Origin::signed(2), parent_hash, alice, "shop".into(), 20), "Too many sub-businesses");
+
+			// Shortening the parent caps the sub-businesses, extending it does not extend them
+			assert_noop!(Service::set_business_expiration(Origin::signed(1), child_hash, 21), "Expiration beyond parent");
+			assert_ok!(Service::set_business_expiration(Origin::signed(1), parent_hash, 15));
+			assert_eq!(Service::business_of(child_hash).expiration, 15);
+			assert_eq!(Service::business_of(second_hash).expiration, 15);
+			assert_ok!(Service::set_business_expiration(Origin::signed(1), parent_hash, 30));
+			assert_eq!(Service::business_of(child_hash).expiration, 15);
+
+			// Archived sub-businesses leave the list of their parent
+			Service::archive_business(child_hash);
+			assert_eq!(Service::sub_businesses(parent_hash), vec![second_hash]);
+
+			// An archived parent no longer caps the expiration of its sub-businesses
+			Service::archive_business(parent_hash);
+			assert_ok!(Service::set_business_expiration(Origin::signed(1), second_hash, 40));
+			assert_eq!(Service::business_of(second_hash).expiration, 40);
+		});
+	}
+
 	#[test]
 	fn business_whitelist_should_work() {
 		new_test_ext().execute_with(|| {
@@ -182,18 +392,21 @@ mod tests {
 
 			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
 			let biz_hash = <Test as system::Trait>::Hash::default();
-			assert_noop!(Service::add_business_whitelist(Origin::signed(1), biz_hash,  alice), "Business does not exist");
+			assert_noop!(Service::add_business_whitelist(Origin::signed(1), biz_hash,  alice, None), "Business does not exist");
 
 			System::set_block_number(10);
-			let biz_hash = Service::business_hash(1, owner_hash);
-			assert_ok!(Service::create_business(Origin::signed(1), owner_hash, "crab".into(), 20));
-			assert_noop!(Service::add_business_whitelist(Origin::signed(3), biz_hash,  alice), "Not authorized");
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_noop!(Service::add_business_whitelist(Origin::signed(3), biz_hash,  alice, None), "Not authorized");
 
-			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice));
-			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice), "Already in the whitelist");
-			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  bob));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None));
+			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None), "Already in the whitelist");
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  bob, None));
 
-			assert_eq!(Service::business_of(biz_hash).whitelist, [alice, bob]);
+			assert_eq!(Service::whitelist_names(&Service::business_of(biz_hash)), [alice, bob]);
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  ray, None));
+			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  owner_hash, None), "Exceeds max whitelist size");
+			assert_ok!(Service::remove_business_whitelist(Origin::signed(2), biz_hash,  ray));
 			assert_noop!(Service::remove_business_whitelist(Origin::signed(2), biz_hash,  ray), "Not in the whitelist");
 
 			assert_ok!(Service::remove_business_whitelist(Origin::signed(2), biz_hash,  alice));
@@ -202,6 +415,125 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn set_business_whitelist_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let ray = (RAY).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, Some(15)));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob, None));
+
+			assert_noop!(Service::set_business_whitelist(Origin::signed(3), biz_hash, vec![alice]), "Not authorized");
+			assert_noop!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![alice, bob, ray, owner_hash]), "Exceeds max whitelist size");
+			assert_noop!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![alice, ray, alice]), "Duplicate in the whitelist");
+			assert_noop!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![bob, alice]), "Same value");
+
+			assert_ok!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![ray, alice]));
+			assert_eq!(
+				Service::business_of(biz_hash).whitelist,
+				[WhitelistEntry { name_hash: ray, valid_until: None }, WhitelistEntry { name_hash: alice, valid_until: Some(15) }]
+			);
+		});
+	}
+
+	#[test]
+	fn required_attestation_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let schema = ("certified organic").using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+
+			assert_noop!(Service::set_required_attestation(Origin::signed(3), biz_hash, Some(schema)), "Not authorized");
+			assert_noop!(Service::set_required_attestation(Origin::signed(2), biz_hash, None), "Same value");
+			assert_ok!(Service::set_required_attestation(Origin::signed(2), biz_hash, Some(schema)));
+			assert_eq!(Service::required_attestation(biz_hash), Some(schema));
+
+			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob, None), "Missing required attestation");
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+
+			assert_ok!(Service::set_required_attestation(Origin::signed(2), biz_hash, None));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob, None));
+		});
+	}
+
+	#[test]
+	fn authorization_depth_should_work() {
+		new_test_ext().execute_with(|| {
+			let ops = (OPS).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("label printed").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, ops, None));
+
+			// the owner of longguhu may not act for ops.longguhu by default
+			assert_noop!(Service::create_product(Origin::signed(2), ops, biz_hash, "1".into(), data_hash.clone(), "".into()), "Not authorized");
+
+			assert_noop!(Service::set_authorization_depth(Origin::signed(3), biz_hash, 1), "Not authorized");
+			assert_noop!(Service::set_authorization_depth(Origin::signed(2), biz_hash, 3), "Exceeds max authorization depth");
+			assert_noop!(Service::set_authorization_depth(Origin::signed(2), biz_hash, 0), "Same value");
+			assert_ok!(Service::set_authorization_depth(Origin::signed(2), biz_hash, 1));
+			assert_eq!(Service::authorization_depth(biz_hash), 1);
+
+			assert_ok!(Service::create_product(Origin::signed(2), ops, biz_hash, "1".into(), data_hash.clone(), "".into()));
+			assert_noop!(Service::create_product(Origin::signed(3), ops, biz_hash, "2".into(), data_hash.clone(), "".into()), "Not authorized");
+
+			assert_ok!(Service::set_authorization_depth(Origin::signed(2), biz_hash, 0));
+			assert_noop!(Service::create_product(Origin::signed(2), ops, biz_hash, "2".into(), data_hash.clone(), "".into()), "Not authorized");
+		});
+	}
+
+	#[test]
+	fn resolve_change_should_suspend_operator() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("label printed").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::businesses_by_operator(alice), vec![biz_hash]);
+			assert!(<Service as name_service::NodeReferences<H256>>::is_referenced(&alice));
+
+			// warnings leave the entry usable
+			<Service as name_service::OnResolveChanged<Test>>::on_resolve_changed(alice, Some(3), Some(5));
+			assert!(!Service::is_suspended((biz_hash, alice)));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), "".into()));
+
+			RESOLVE_CHANGE_ACTION.with(|v| *v.borrow_mut() = ResolveChangeAction::Suspend);
+			<Service as name_service::OnResolveChanged<Test>>::on_resolve_changed(alice, Some(3), Some(5));
+			assert!(Service::is_suspended((biz_hash, alice)));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), "".into()), "Whitelist entry suspended");
+
+			assert_noop!(Service::confirm_whitelist_member(Origin::signed(3), biz_hash, alice), "Not authorized");
+			assert_ok!(Service::confirm_whitelist_member(Origin::signed(2), biz_hash, alice));
+			assert_noop!(Service::confirm_whitelist_member(Origin::signed(2), biz_hash, alice), "Whitelist entry not suspended");
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), "".into()));
+
+			// removed operators are not notified anymore
+			<Service as name_service::OnResolveChanged<Test>>::on_resolve_changed(alice, Some(3), Some(5));
+			assert_ok!(Service::remove_business_whitelist(Origin::signed(2), biz_hash, alice));
+			assert!(!Service::is_suspended((biz_hash, alice)));
+			assert!(Service::businesses_by_operator(alice).is_empty());
+			assert!(!<Service as name_service::NodeReferences<H256>>::is_referenced(&alice));
+		});
+	}
+
 	#[test]
 	fn product_should_work() {
 		new_test_ext().execute_with(|| {
@@ -211,56 +543,1809 @@ mod tests {
 
 			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
 			let biz_hash = <Test as system::Trait>::Hash::default();
-			let data_hash = ("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
 			let extra = r#"{"amount":10000,"type":"btc","public_key":"1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg"}"#;
 			let seq_id = &"1".repeat(64)[..];
 
-			assert_noop!(Service::create_product(Origin::signed(3), bob, biz_hash, seq_id.into(), data_hash, extra.into()), "Not authorized");
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, extra.into()), "Business does not exist");
+			assert_noop!(Service::create_product(Origin::signed(3), bob, biz_hash, seq_id.into(), data_hash.clone(), extra.into()), "Not authorized");
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), extra.into()), "Business does not exist");
 
 			System::set_block_number(10);
-			let biz_hash = Service::business_hash(1, owner_hash);
-			assert_ok!(Service::create_business(Origin::signed(1), owner_hash, "crab".into(), 20));
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
 
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, extra.into()), "Not in the whitelist");
-			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), extra.into()), "Not in the whitelist");
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None));
 
 			System::set_block_number(20);
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, extra.into()), "Expired");
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), extra.into()), "Expired");
 			System::set_block_number(15);
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".repeat(65).into(), data_hash, extra.into()), "Sequence ID too long");
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, "e".repeat(1025).into()), "Extra info too long");
+			timestamp::Module::<Test>::set_timestamp(1_577_836_800_000);
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".repeat(65).into(), data_hash.clone(), extra.into()), "Sequence ID too long");
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), "e".repeat(1025).into()), "Extra info too long");
+			let sha512 = DataHash { algo: HashAlgo::Sha2_512, digest: vec![7; 32] };
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), sha512, extra.into()), "Digest length does not match the hash algorithm");
+			// A digest of another algorithm is anchored apart from the equal runtime hash
+			let sha256 = DataHash { algo: HashAlgo::Sha2_256, digest: data_hash.digest.clone() };
+			assert_eq!(Service::anchor_key(&data_hash).as_ref(), &data_hash.digest[..]);
+			assert_eq!(Service::anchor_key(&sha256), <Test as system::Trait>::Hashing::hash_of(&sha256));
+			assert_ne!(Service::anchor_key(&sha256), Service::anchor_key(&data_hash));
 
-			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, extra.into()));
-			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash, extra.into()), "Product already exists");
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), extra.into()));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.into(), data_hash.clone(), extra.into()), "DuplicateSeqId");
 			let product_hash = Service::product_hash(biz_hash, seq_id.into());	
-			assert_eq!(Service::product_of(product_hash).seq_id, String::from(seq_id).as_bytes());
+			assert_eq!(Service::product_of(product_hash).seq_id.to_vec(), String::from(seq_id).as_bytes());
 			assert_eq!(Service::product_of(product_hash).infos.len(), 1);
 			assert_eq!(Service::product_of(product_hash).infos[0].data_hash, data_hash);
+			assert_eq!(Service::product_of(product_hash).infos[0].created_at, 15);
+			assert_eq!(Service::product_of(product_hash).infos[0].timestamp, 1_577_836_800_000);
 
-			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob));
-			assert_noop!(Service::add_product_info(Origin::signed(3), bob, biz_hash, seq_id.into(), data_hash, extra.into()), "Not authorized");
-			assert_ok!(Service::add_product_info(Origin::signed(4), bob, biz_hash, seq_id.into(), data_hash, extra.into()));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob, None));
+			assert_noop!(Service::add_product_info(Origin::signed(3), bob, biz_hash, seq_id.into(), InfoKind::Shipped, data_hash.clone(), extra.into()), "Not authorized");
+			assert_ok!(Service::add_product_info(Origin::signed(4), bob, biz_hash, seq_id.into(), InfoKind::Shipped, data_hash.clone(), extra.into()));
 
 			assert_eq!(Service::product_of(product_hash).infos.len(), 2);
+			assert_eq!(Service::product_of(product_hash).infos[0].kind, InfoKind::Created);
+			assert_eq!(Service::product_infos_of_kind(product_hash, InfoKind::Shipped).len(), 1);
 		});
 	}
 
 	#[test]
-	fn product_hash_should_work() {
-		let biz_hash = <Test as system::Trait>::Hash::default(); 
-		let seq_id = &"1".repeat(32)[..];
-		let product_hash = Service::product_hash(biz_hash, seq_id.into());
+	fn seq_mode_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("seq").using_encoded(<Test as system::Trait>::Hashing::hash));
 
-		println!("biz_hash = {:#?}", biz_hash);
-		println!("product_hash = {:#?}", product_hash);
-		let mut data = biz_hash.as_ref().to_vec();
-		let mut seq_vec:Vec<u8> = seq_id.into();
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
 
-		println!("seq_vec = {:#?}", seq_vec);
-		data.append(&mut seq_vec);
-		let hash = <Test as system::Trait>::Hashing::hash(&data);
-		assert_eq!(product_hash, hash);
+			assert_noop!(Service::set_seq_mode(Origin::signed(3), biz_hash, SeqMode::AutoIncrement), "Not authorized");
+			assert_noop!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::Provided), "Same value");
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::AutoIncrement));
+
+			// The chain numbers the products, skipping the ids provided before
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]), "Sequence ID is assigned");
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, vec![], data_hash.clone(), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, vec![], data_hash.clone(), vec![]));
+			assert!(Service::product_by_seq_id(biz_hash, "0".into()).is_some());
+			assert!(Service::product_by_seq_id(biz_hash, "2".into()).is_some());
+			assert_eq!(Service::next_seq_id(biz_hash), 3);
+
+			// Back to provided ids
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::Provided));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "x".into(), data_hash.clone(), vec![]));
+			assert_eq!(Service::product_count(biz_hash), 4);
+
+			// Switching the mode and each write skip a bounded number of taken ids, a write
+			// failing past them leaves the counter as it was
+			for n in 3..=(3 + 2 * MAX_SEQ_ID_SKIPS) {
+				System::set_block_number(11 + u64::from(n) / 10);
+				assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, n.to_string().into_bytes(), data_hash.clone(), vec![]));
+			}
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::AutoIncrement));
+			assert_eq!(Service::next_seq_id(biz_hash), u64::from(3 + MAX_SEQ_ID_SKIPS));
+			assert_noop!(
+				Service::create_product(Origin::signed(3), alice, biz_hash, vec![], data_hash.clone(), vec![]),
+				"Too many sequence IDs taken"
+			);
+			assert_eq!(Service::next_seq_id(biz_hash), u64::from(3 + MAX_SEQ_ID_SKIPS));
+
+			// Switching again moves the counter on
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::Provided));
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::AutoIncrement));
+			assert_eq!(Service::next_seq_id(biz_hash), u64::from(3 + 2 * MAX_SEQ_ID_SKIPS));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, vec![], data_hash, vec![]));
+			assert!(Service::product_by_seq_id(biz_hash, (4 + 2 * MAX_SEQ_ID_SKIPS).to_string().into_bytes()).is_some());
+		});
+	}
+
+	#[test]
+	fn extra_schema_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("extra").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), b"{}".to_vec()));
+
+			assert_noop!(Service::set_extra_schema(Origin::signed(3), biz_hash, vec![1, 2]), "Not authorized");
+			assert_noop!(Service::set_extra_schema(Origin::signed(2), biz_hash, vec![]), "Same value");
+			assert_ok!(Service::set_extra_schema(Origin::signed(2), biz_hash, vec![2, 1, 2]));
+			assert_eq!(Service::extra_schema(biz_hash), Some(vec![1, 2]));
+			assert_noop!(Service::set_extra_schema(Origin::signed(2), biz_hash, vec![1, 2]), "Same value");
+
+			// Only structured extra with allowed keys in order
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), b"{}".to_vec()), "Extra info not structured");
+			let unordered = ExtraPayload::Structured(vec![(2, b"b".to_vec()), (1, b"a".to_vec())]).into_extra();
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), unordered), "Extra keys not in order");
+			let unknown = ExtraPayload::Structured(vec![(3, b"c".to_vec())]).into_extra();
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), unknown), "Extra key not allowed");
+			let structured = ExtraPayload::Structured(vec![(1, b"a".to_vec()), (2, b"b".to_vec())]).into_extra();
+			assert_eq!(ExtraPayload::from_extra(&structured), ExtraPayload::Structured(vec![(1, b"a".to_vec()), (2, b"b".to_vec())]));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), structured));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Received, data_hash.clone(), vec![]));
+			let infos = Service::product_by_seq_id(biz_hash, "1".into()).unwrap().infos;
+			assert_eq!(infos[infos.len() - 2].extra_version, EXTRA_VERSION_STRUCTURED);
+			assert_eq!(infos[infos.len() - 1].extra_version, EXTRA_VERSION_RAW);
+
+			// No keys remove the schema
+			assert_ok!(Service::set_extra_schema(Origin::signed(2), biz_hash, vec![]));
+			assert_eq!(Service::extra_schema(biz_hash), None);
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::QualityCheck, data_hash, b"{}".to_vec()));
+		});
+	}
+
+	#[test]
+	fn dedup_policy_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("dedup").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+
+			// Duplicates are accepted by default
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+
+			assert_noop!(Service::set_dedup_policy(Origin::signed(3), biz_hash, DedupPolicy::Reject), "Not authorized");
+			assert_ok!(Service::set_dedup_policy(Origin::signed(2), biz_hash, DedupPolicy::Reject));
+			assert_noop!(Service::set_dedup_policy(Origin::signed(2), biz_hash, DedupPolicy::Reject), "Same value");
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Received, data_hash.clone(), vec![]), "DuplicateDataHash");
+			let other = DataHash::from_runtime_hash(&("other").using_encoded(<Test as system::Trait>::Hashing::hash));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Received, other, vec![]));
+
+			assert_ok!(Service::set_dedup_policy(Origin::signed(2), biz_hash, DedupPolicy::Warn));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Received, data_hash, vec![]));
+			assert_eq!(Service::product_of(product_hash).infos.len(), 4);
+		});
+	}
+
+	#[test]
+	fn owner_council_should_work() {
+		new_test_ext().execute_with(|| {
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let ray = (RAY).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			let members = vec![owner_hash, alice, bob];
+			assert_noop!(Service::set_owner_council(Origin::signed(3), biz_hash, members.clone(), 2), "Not authorized");
+			assert_noop!(Service::set_owner_council(Origin::signed(2), biz_hash, members.clone(), 4), "Invalid council threshold");
+			assert_noop!(Service::set_owner_council(Origin::signed(2), biz_hash, vec![alice, alice], 1), "Duplicate council member");
+			assert_ok!(Service::set_owner_council(Origin::signed(2), biz_hash, members.clone(), 2));
+
+			// The owner alone may no longer change the whitelist
+			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash, ray, None), "Business is council owned");
+			let action = CouncilAction::AddWhitelist(ray, None);
+			assert_noop!(Service::approve_council_action(Origin::signed(3), biz_hash, bob, action.clone()), "Not authorized");
+			assert_ok!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, action.clone()));
+			assert_noop!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, action.clone()), "Already approved");
+			assert!(Service::business_of(biz_hash).whitelist.is_empty());
+			assert_ok!(Service::approve_council_action(Origin::signed(4), biz_hash, bob, action.clone()));
+			assert_eq!(Service::business_of(biz_hash).whitelist, [WhitelistEntry { name_hash: ray, valid_until: None }]);
+			assert!(Service::council_approvals(&biz_hash, &<Test as system::Trait>::Hashing::hash_of(&action)).is_empty());
+
+			// A failing action keeps its approvals
+			assert_ok!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, action.clone()));
+			assert_noop!(Service::approve_council_action(Origin::signed(4), biz_hash, bob, action.clone()), "Already in the whitelist");
+			let action_hash = <Test as system::Trait>::Hashing::hash_of(&action);
+			assert_eq!(Service::council_approvals(&biz_hash, &action_hash), [alice]);
+
+			// Replacing the council drops the pending approvals
+			let replace = CouncilAction::SetCouncil(members.clone(), 3);
+			assert_ok!(Service::approve_council_action(Origin::signed(2), biz_hash, owner_hash, replace.clone()));
+			assert_ok!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, replace));
+			assert_eq!(Service::owner_council(biz_hash).map(|council| council.threshold), Some(3));
+			assert!(Service::council_approvals(&biz_hash, &action_hash).is_empty());
+
+			// Every owner call goes through the council
+			assert_noop!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::AutoIncrement), "Business is council owned");
+			assert_noop!(Service::set_authorization_depth(Origin::signed(2), biz_hash, 1), "Business is council owned");
+			assert_noop!(Service::appeal_suspension(Origin::signed(2), biz_hash, action_hash), "Business is council owned");
+			assert_noop!(Service::remove_lot(Origin::signed(2), biz_hash, "lot-1".into()), "Business is council owned");
+			let seq_mode = CouncilAction::SetSeqMode(SeqMode::AutoIncrement);
+			assert_ok!(Service::approve_council_action(Origin::signed(2), biz_hash, owner_hash, seq_mode.clone()));
+			assert_ok!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, seq_mode.clone()));
+			assert_eq!(Service::seq_mode(biz_hash), SeqMode::Provided);
+			assert_ok!(Service::approve_council_action(Origin::signed(4), biz_hash, bob, seq_mode));
+			assert_eq!(Service::seq_mode(biz_hash), SeqMode::AutoIncrement);
+
+			// Dissolving the council gives the business back to the owner
+			let dissolve = CouncilAction::SetCouncil(vec![], 0);
+			assert_ok!(Service::approve_council_action(Origin::signed(2), biz_hash, owner_hash, dissolve.clone()));
+			assert_ok!(Service::approve_council_action(Origin::signed(3), biz_hash, alice, dissolve.clone()));
+			assert_ok!(Service::approve_council_action(Origin::signed(4), biz_hash, bob, dissolve));
+			assert_eq!(Service::owner_council(biz_hash), None);
+			assert_ok!(Service::remove_business_whitelist(Origin::signed(2), biz_hash, ray));
+			assert_ok!(Service::set_seq_mode(Origin::signed(2), biz_hash, SeqMode::Provided));
+		});
+	}
+
+	#[test]
+	fn registry_digest_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("digest").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_eq!(Service::registry_digest(), RegistryDigest {
+				businesses: vec![biz_hash],
+				products: vec![product_hash],
+				lots: vec![],
+				truncated: false,
+			});
+
+			// The digest is cleared by the next block, and bounded by `MaxDigestEntries`
+			Service::on_initialize(11);
+			assert!(Service::registry_digest().is_empty());
+			for seq_id in &["2", "3", "4", "5"] {
+				assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, (*seq_id).into(), data_hash.clone(), vec![]));
+			}
+			assert_eq!(Service::registry_digest().products.len(), 3);
+			assert!(Service::registry_digest().truncated);
+		});
+	}
+
+	#[test]
+	fn lot_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("lot").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_noop!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash.clone(), vec![]), "Not in the whitelist");
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash.clone(), vec![]));
+			assert_noop!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash.clone(), vec![]), "Lot already exists");
+			let lot_hash = Service::lot_hash(biz_hash, "lot-1".into());
+			assert_eq!(Service::lot_of(lot_hash).map(|lot| lot.biz_hash), Some(biz_hash));
+
+			System::set_block_number(11);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_noop!(Service::set_product_lot(Origin::signed(3), alice, biz_hash, "1".into(), "lot-2".into()), "Lot does not exist");
+			assert_ok!(Service::set_product_lot(Origin::signed(3), alice, biz_hash, "1".into(), "lot-1".into()));
+			assert_noop!(Service::set_product_lot(Origin::signed(3), alice, biz_hash, "1".into(), "lot-1".into()), "Product already in a lot");
+
+			// The lot infos are merged into the product history by block
+			System::set_block_number(12);
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			assert!(!Service::is_recalled(biz_hash, product_hash));
+			System::set_block_number(13);
+			assert_ok!(Service::add_lot_info(Origin::signed(3), alice, biz_hash, "lot-1".into(), InfoKind::Recalled, data_hash.clone(), vec![]));
+			let kinds: Vec<_> = Service::product_history(product_hash).into_iter().map(|info| (info.created_at, info.kind)).collect();
+			assert_eq!(kinds, vec![
+				(10, InfoKind::Created),
+				(11, InfoKind::Created),
+				(12, InfoKind::Shipped),
+				(13, InfoKind::Recalled),
+			]);
+			assert!(Service::is_recalled(biz_hash, product_hash));
+			assert_eq!(Service::product_of(product_hash).infos.len(), 2);
+
+			// A lot takes the product deposit and a business has a bounded number of lots
+			PRODUCT_DEPOSIT.with(|v| *v.borrow_mut() = 3);
+			assert_ok!(Service::fund_business(Origin::signed(2), biz_hash, 10));
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-2".into(), data_hash.clone(), vec![]));
+			let other_lot = Service::lot_hash(biz_hash, "lot-2".into());
+			assert_eq!(Service::lot_deposit(other_lot), 3);
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| (pool.available, pool.held)), Some((7, 3)));
+			assert_eq!(Service::business_lots(biz_hash), vec![lot_hash, other_lot]);
+			assert_noop!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-3".into(), data_hash.clone(), vec![]), "Too many lots");
+
+			// Only the owner removes a lot, once no product belongs to it
+			assert_noop!(Service::remove_lot(Origin::signed(3), biz_hash, "lot-1".into()), "Not authorized");
+			assert_noop!(Service::remove_lot(Origin::signed(2), biz_hash, "lot-1".into()), "Lot has products");
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert_eq!(Service::lot_product_count(lot_hash), 0);
+			assert_ok!(Service::remove_lot(Origin::signed(2), biz_hash, "lot-1".into()));
+			assert!(Service::lot_of(lot_hash).is_none());
+			assert_noop!(Service::remove_lot(Origin::signed(2), biz_hash, "lot-1".into()), "Lot does not exist");
+
+			// Its deposit goes back to the pool
+			assert_ok!(Service::remove_lot(Origin::signed(2), biz_hash, "lot-2".into()));
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| (pool.available, pool.held)), Some((10, 0)));
+			assert_eq!(Service::lot_deposit(other_lot), 0);
+			assert!(Service::business_lots(biz_hash).is_empty());
+		});
+	}
+
+	#[test]
+	fn recall_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("recall").using_encoded(<Test as system::Trait>::Hashing::hash));
+			let reason_hash = ("notice").using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::set_product_lot(Origin::signed(3), alice, biz_hash, "2".into(), "lot-1".into()));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			let lot_hash = Service::lot_hash(biz_hash, "lot-1".into());
+
+			// Only the owner recalls
+			assert_noop!(Service::issue_recall(Origin::signed(3), biz_hash, RecallScope::Product("1".into()), reason_hash), "Not authorized");
+			assert_noop!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Product("3".into()), reason_hash), "Product does not exist");
+			assert_noop!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Lot("lot-2".into()), reason_hash), "Lot does not exist");
+
+			System::set_block_number(11);
+			assert_ok!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Product("1".into()), reason_hash));
+			assert_noop!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Product("1".into()), reason_hash), "Already recalled");
+			assert_eq!(Service::active_recall(product_hash).map(|recall| (recall.issuer, recall.issued_at)), Some((2, 11)));
+			assert!(Service::is_recalled(biz_hash, product_hash));
+			let info = Service::product_by_seq_id(biz_hash, "1".into()).unwrap().infos.pop().unwrap();
+			assert_eq!((info.kind, info.data_hash), (InfoKind::Recalled, DataHash::from_runtime_hash(&reason_hash)));
+			assert_eq!(Service::product_of(product_hash).infos.len(), 1);
+
+			// A lot recall covers its products
+			let lot_product = Service::product_hash(biz_hash, "2".into());
+			assert!(!Service::is_recalled(biz_hash, lot_product));
+			assert_ok!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Lot("lot-1".into()), reason_hash));
+			assert!(Service::active_recall(lot_hash).is_some());
+			assert!(Service::is_recalled(biz_hash, lot_product));
+			assert_ok!(Service::lift_recall(Origin::signed(2), biz_hash, RecallScope::Lot("lot-1".into())));
+			assert_noop!(Service::lift_recall(Origin::signed(2), biz_hash, RecallScope::Lot("lot-1".into())), "Not recalled");
+			assert_eq!(Service::product_by_seq_id(biz_hash, "2".into()).unwrap().infos.last().unwrap().kind, InfoKind::Created);
+
+			// A business recall covers all its products, after it expires too
+			System::set_block_number(20);
+			assert_ok!(Service::issue_recall(Origin::signed(2), biz_hash, RecallScope::Business, reason_hash));
+			assert!(Service::active_recall(biz_hash).is_some());
+			assert_eq!(Service::active_recall_of(biz_hash, lot_product).map(|recall| recall.issued_at), Some(20));
+			assert!(Service::is_recalled(biz_hash, lot_product));
+			assert_eq!(Service::product_by_seq_id(biz_hash, "2".into()).unwrap().infos.last().unwrap().kind, InfoKind::Recalled);
+			assert_ok!(Service::lift_recall(Origin::signed(2), biz_hash, RecallScope::Business));
+			assert_eq!(Service::active_recall_of(biz_hash, lot_product), None);
+			assert!(!Service::is_recalled(biz_hash, lot_product));
+		});
+	}
+
+	#[test]
+	fn suspension_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("suspend").using_encoded(<Test as system::Trait>::Hashing::hash));
+			let reason_hash = ("fraud").using_encoded(<Test as system::Trait>::Hashing::hash);
+			let appeal_hash = ("appeal").using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(2);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 30));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+
+			assert_noop!(Service::suspend_business(Origin::signed(2), biz_hash, reason_hash), "Bad origin");
+			assert_noop!(Service::appeal_suspension(Origin::signed(2), biz_hash, appeal_hash), "Business is not suspended");
+			assert_ok!(Service::suspend_business(Origin::signed(1), biz_hash, reason_hash));
+			assert_noop!(Service::suspend_business(Origin::signed(1), biz_hash, reason_hash), "Business is suspended");
+
+			// The writes are blocked, the reads kept
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]), "Business is suspended");
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]), "Business is suspended");
+			assert_noop!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()), "Business is suspended");
+			assert_eq!(Service::product_by_seq_id(biz_hash, "1".into()).unwrap().infos.len(), 1);
+
+			// The owner appeals once, within the appeal period
+			assert_noop!(Service::appeal_suspension(Origin::signed(3), biz_hash, appeal_hash), "Not authorized");
+			assert_ok!(Service::appeal_suspension(Origin::signed(2), biz_hash, appeal_hash));
+			assert_noop!(Service::appeal_suspension(Origin::signed(2), biz_hash, appeal_hash), "Suspension already appealed");
+			assert_eq!(Service::suspension_of(biz_hash).unwrap().appeal, Some(appeal_hash));
+
+			assert_noop!(Service::reinstate_business(Origin::signed(2), biz_hash, appeal_hash), "Bad origin");
+			assert_ok!(Service::reinstate_business(Origin::signed(1), biz_hash, appeal_hash));
+			assert_noop!(Service::reinstate_business(Origin::signed(1), biz_hash, appeal_hash), "Business is not suspended");
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+
+			// The appeal period runs from the suspension
+			assert_ok!(Service::suspend_business(Origin::signed(1), biz_hash, reason_hash));
+			System::set_block_number(12);
+			assert_noop!(Service::appeal_suspension(Origin::signed(2), biz_hash, appeal_hash), "Appeal period is over");
+		});
+	}
+
+	#[test]
+	fn one_time_codes_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("codes").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash, vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+
+			// A tree of four codes
+			let codes: Vec<Vec<u8>> = vec![b"a1".to_vec(), b"b2".to_vec(), b"c3".to_vec(), b"d4".to_vec()];
+			let leaves: Vec<H256> = codes.iter().map(|code| Service::code_leaf(code)).collect();
+			let left = Service::code_node(leaves[0], leaves[1]);
+			let right = Service::code_node(leaves[2], leaves[3]);
+			let root = Service::code_node(left, right);
+
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 2, codes[2].clone(), vec![leaves[3], left]), "No codes committed");
+			assert_noop!(Service::commit_codes(Origin::signed(3), alice, biz_hash, "2".into(), root, 4), "Product does not exist");
+			assert_ok!(Service::commit_codes(Origin::signed(3), alice, biz_hash, "1".into(), root, 4));
+			assert_noop!(Service::commit_codes(Origin::signed(3), alice, biz_hash, "1".into(), root, 4), "Codes already committed");
+
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 4, codes[2].clone(), vec![leaves[3], left]), "Code index out of range");
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 3, codes[2].clone(), vec![leaves[3], left]), "Invalid code proof");
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 2, b"x".to_vec(), vec![leaves[3], left]), "Invalid code proof");
+			// An inner node does not pass for a leaf, nor a shorter or longer proof
+			assert_ne!(Service::code_leaf(&(leaves[0], leaves[1]).encode()), left);
+			assert!(!Service::verify_code_proof(root, 4, 0, &(leaves[0], leaves[1]).encode(), &[right]));
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 0, codes[0].clone(), vec![leaves[1]]), "Invalid code proof");
+			assert_noop!(Service::redeem_code(Origin::signed(5), product_hash, 2, codes[2].clone(), vec![leaves[3], left, root]), "Invalid code proof");
+			assert_ok!(Service::redeem_code(Origin::signed(5), product_hash, 2, codes[2].clone(), vec![leaves[3], left]));
+			assert!(Service::is_code_spent(product_hash, 2));
+			assert!(!Service::is_code_spent(product_hash, 1));
+			assert_eq!(Service::replayed_codes(product_hash), 0);
+
+			// Redeeming a spent code reveals a clone
+			assert_ok!(Service::redeem_code(Origin::signed(6), product_hash, 2, codes[2].clone(), vec![leaves[3], left]));
+			assert_eq!(Service::replayed_codes(product_hash), 1);
+			assert_ok!(Service::redeem_code(Origin::signed(5), product_hash, 1, codes[1].clone(), vec![leaves[0], right]));
+			assert_eq!(Service::replayed_codes(product_hash), 1);
+
+			// A tree of three codes is two levels deep, its last leaf padded
+			let padded = Service::code_node(left, Service::code_node(leaves[2], H256::zero()));
+			assert!(Service::verify_code_proof(padded, 3, 2, &codes[2], &[H256::zero(), left]));
+			assert!(!Service::verify_code_proof(padded, 3, 3, &codes[2], &[H256::zero(), left]));
+			assert!(Service::verify_code_proof(leaves[0], 1, 0, &codes[0], &[]));
+		});
+	}
+
+	#[test]
+	fn verify_product_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("verify").using_encoded(<Test as system::Trait>::Hashing::hash));
+			let forged = DataHash::from_runtime_hash(&("forged").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+
+			assert!(!Call::<Test>::verify_product(biz_hash, "1".into(), data_hash.clone()).get_dispatch_info().pays_fee);
+			assert_noop!(Service::verify_product(Origin::signed(5), biz_hash, "2".into(), data_hash.clone()), "Product does not exist");
+
+			// A match is free, a mismatch costs the verification fee
+			assert_ok!(Service::verify_product(Origin::signed(5), biz_hash, "1".into(), data_hash.clone()));
+			assert_ok!(Service::validate_verification(&5, biz_hash, "1".into(), &data_hash));
+			assert_eq!(Service::validate_verification(&5, biz_hash, "1".into(), &forged), Err("Insufficient balance for verification fee"));
+			assert_noop!(Service::verify_product(Origin::signed(5), biz_hash, "1".into(), forged.clone()), "Insufficient balance for verification fee");
+			assert_ok!(Service::verify_product(Origin::signed(1), biz_hash, "1".into(), forged.clone()));
+			assert_eq!(Balances::free_balance(1), 8);
+		});
+	}
+
+	/// The infos logged in full by the business events
+	fn recorded_infos() -> Vec<(H256, ProductInfoOf<Test>)> {
+		System::events().into_iter().filter_map(|record| match record.event {
+			TestEvent::business(RawEvent::InfoRecorded(hash, info)) => Some((hash, info)),
+			_ => None,
+		}).collect()
+	}
+
+	/// The whitelist members added (true) or removed (false), in the order of the events
+	fn whitelist_events() -> Vec<(bool, H256)> {
+		System::events().into_iter().filter_map(|record| match record.event {
+			TestEvent::business(RawEvent::WhitelistMemberAdded(_, _, name_hash)) => Some((true, name_hash)),
+			TestEvent::business(RawEvent::WhitelistMemberRemoved(_, _, name_hash)) => Some((false, name_hash)),
+			_ => None,
+		}).collect()
+	}
+
+	#[test]
+	fn whitelist_events_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let ray = (RAY).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			let mut expected = vec![(true, alice)];
+			assert_eq!(whitelist_events(), expected);
+
+			// A replacement emits an event per changed operator only
+			assert_ok!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![alice, bob, ray]));
+			expected.extend(vec![(true, bob), (true, ray)]);
+			assert_eq!(whitelist_events(), expected);
+			assert_noop!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![alice, bob, ray]), "Same value");
+			assert_ok!(Service::set_business_whitelist(Origin::signed(2), biz_hash, vec![bob]));
+			expected.extend(vec![(false, alice), (false, ray)]);
+			assert_eq!(whitelist_events(), expected);
+
+			assert_ok!(Service::remove_business_whitelist(Origin::signed(2), biz_hash, bob));
+			expected.push((false, bob));
+			assert_eq!(whitelist_events(), expected);
+		});
+	}
+
+	#[test]
+	fn rich_events_should_log_infos() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("rich").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+
+			// Infos are only counted by default
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_eq!(recorded_infos(), vec![]);
+
+			RICH_EVENTS.with(|v| *v.borrow_mut() = true);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), "crab".into()));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "2".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "2".into());
+			let infos = Service::product_of(product_hash).infos;
+			assert_eq!(infos.len(), 2);
+			assert_eq!(infos[0].extra.to_vec(), b"crab".to_vec());
+			assert_eq!(infos[1].kind, InfoKind::Shipped);
+			let mut recorded = vec![(product_hash, infos[0].clone()), (product_hash, infos[1].clone())];
+			assert_eq!(recorded_infos(), recorded);
+
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::add_lot_info(Origin::signed(3), alice, biz_hash, "lot".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			let lot_hash = Service::lot_hash(biz_hash, "lot".into());
+			let infos = Service::lot_of(lot_hash).unwrap().infos;
+			assert_eq!(infos.len(), 2);
+			recorded.extend(infos.into_iter().map(|info| (lot_hash, info)));
+			assert_eq!(recorded_infos(), recorded);
+		});
+	}
+
+	#[test]
+	fn product_deposit_should_work() {
+		new_test_ext().execute_with(|| {
+			PRODUCT_DEPOSIT.with(|v| *v.borrow_mut() = 2);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("deposit").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]), "Insufficient pool funds");
+
+			assert_noop!(Service::fund_business(Origin::signed(3), biz_hash, 5), "Not authorized");
+			assert_ok!(Service::fund_business(Origin::signed(2), biz_hash, 5));
+			assert_eq!(Balances::reserved_balance(&2), 5);
+
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "3".into(), data_hash.clone(), vec![]), "Insufficient pool funds");
+			assert_eq!(Service::product_deposit(Service::product_hash(biz_hash, "1".into())), 2);
+
+			assert_noop!(Service::withdraw_business_funds(Origin::signed(2), biz_hash, 2), "Insufficient pool funds");
+			assert_ok!(Service::withdraw_business_funds(Origin::signed(2), biz_hash, 1));
+			assert_eq!(Balances::reserved_balance(&2), 4);
+
+			// Archiving refunds the deposits of the products
+			Service::archive_business(biz_hash);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Service::deposit_pool(biz_hash), None);
+			assert_eq!(Service::product_deposit(Service::product_hash(biz_hash, "1".into())), 0);
+		});
+	}
+
+	#[test]
+	fn extra_deposit_should_work() {
+		new_test_ext().execute_with(|| {
+			EXTRA_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = |data: &str| DataHash::from_runtime_hash(&data.using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			// 4 cheap bytes, then tiers of 4 and 8 bytes at twice and four times the price
+			assert_eq!(<Test as Trait>::StorageFeePolicy::extra_deposit(0), 0);
+			assert_eq!(<Test as Trait>::StorageFeePolicy::extra_deposit(6), 8);
+			assert_eq!(<Test as Trait>::StorageFeePolicy::extra_deposit(10), 20);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash("a"), b"ab".to_vec()), "Insufficient pool funds");
+			assert_ok!(Service::fund_business(Origin::signed(2), biz_hash, 10));
+
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash("a"), b"ab".to_vec()));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_eq!(Service::product_deposit(product_hash), 2);
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash("b"), b"abcdefgh".to_vec()), "Insufficient pool funds");
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash("b"), b"abcdef".to_vec()));
+			assert_eq!(Service::product_deposit(product_hash), 10);
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| (pool.available, pool.held)), Some((0, 10)));
+			assert_eq!(Service::product_of(product_hash).infos[1].extra_version, EXTRA_VERSION_RAW);
+
+			// Removing the product returns the deposits of its infos with it
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| (pool.available, pool.held)), Some((10, 0)));
+
+			// The infos of lots take the same deposit
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash("c"), b"ab".to_vec()));
+			let lot_hash = Service::lot_hash(biz_hash, "lot-1".into());
+			assert_eq!(Service::lot_deposit(lot_hash), 2);
+			assert_noop!(
+				Service::add_lot_info(Origin::signed(3), alice, biz_hash, "lot-1".into(), InfoKind::Shipped, data_hash("d"), b"abcdefgh".to_vec()),
+				"Insufficient pool funds"
+			);
+			assert_ok!(Service::add_lot_info(Origin::signed(3), alice, biz_hash, "lot-1".into(), InfoKind::Shipped, data_hash("d"), b"abcdef".to_vec()));
+			assert_eq!(Service::lot_deposit(lot_hash), 10);
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| (pool.available, pool.held)), Some((0, 10)));
+		});
+	}
+
+	#[test]
+	fn scope_config_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = |data: &str| DataHash::from_runtime_hash(&data.using_encoded(<Test as system::Trait>::Hashing::hash));
+			let config = ScopeConfig {
+				max_extra_length: Some(4),
+				max_product_info_count: Some(2),
+				product_deposit: Some(1),
+				renewal_price_per_block: Some(1),
+			};
+
+			System::set_block_number(10);
+			assert_noop!(Service::set_scope_config(Origin::signed(2), scope(), config.clone()), "Not authorized");
+			assert_noop!(
+				Service::set_scope_config(Origin::signed(1), scope(), ScopeConfig { max_extra_length: Some(1025), ..config.clone() }),
+				"Extra info limit too large"
+			);
+			assert_ok!(Service::set_scope_config(Origin::signed(1), scope(), config.clone()));
+			assert_noop!(Service::set_scope_config(Origin::signed(1), scope(), config.clone()), "Same value");
+			assert_eq!(Service::scope_of(scope()).map(|scope| scope.config), Some(config));
+
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::fund_business(Origin::signed(2), biz_hash, 5));
+
+			// The limits and the deposit of the scope apply instead of the runtime constants
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash("a"), "12345".into()), "Extra info too long");
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash("a"), "1234".into()));
+			assert_eq!(Service::product_deposit(Service::product_hash(biz_hash, "1".into())), 1);
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash("b"), vec![]));
+			assert_noop!(
+				Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash("c"), vec![]),
+				"Exceeds max product info limit"
+			);
+
+			// Renewing takes the blocks beyond the expiration from the pool, shortening is free
+			assert_ok!(Service::set_business_expiration(Origin::signed(1), biz_hash, 23));
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| pool.available), Some(1));
+			assert_eq!(Balances::reserved_balance(&2), 2);
+			assert_noop!(Service::set_business_expiration(Origin::signed(1), biz_hash, 30), "Insufficient pool funds");
+			assert_ok!(Service::set_business_expiration(Origin::signed(1), biz_hash, 21));
+			assert_eq!(Service::deposit_pool(biz_hash).map(|pool| pool.available), Some(1));
+
+			// Scopes stored before their settings read as the runtime constants
+			unhashed::put(&<Scopes<Test>>::hashed_key_for(scope()), &(scope(), false));
+			assert_eq!(Service::scope_of(scope()).map(|scope| scope.config), Some(ScopeConfig::default()));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash("c"), vec![]));
+		});
+	}
+
+	#[test]
+	fn remove_product_should_work() {
+		new_test_ext().execute_with(|| {
+			PRODUCT_DEPOSIT.with(|v| *v.borrow_mut() = 1);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("remove").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::fund_business(Origin::signed(2), biz_hash, 3));
+			for seq_id in ["1", "2", "3"].iter() {
+				assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, (*seq_id).into(), data_hash.clone(), vec![]));
+			}
+			let first = Service::product_hash(biz_hash, "1".into());
+			let last = Service::product_hash(biz_hash, "3".into());
+
+			assert_noop!(Service::remove_product(Origin::signed(3), biz_hash, "1".into()), "Not authorized");
+			assert_noop!(Service::remove_product(Origin::signed(2), biz_hash, "4".into()), "Product does not exist");
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert!(!<Products<Test>>::exists(first));
+
+			// The last product takes the place of the removed one
+			assert_eq!(Service::product_count(biz_hash), 2);
+			assert_eq!(Service::business_product_index(biz_hash, 0), last);
+			assert_eq!(Service::creator_product_count(3), 2);
+			assert_eq!(Service::products_by_creator((3, 0)), last);
+			assert_eq!(Service::product_positions(last), (0, 0));
+
+			let pool = Service::deposit_pool(biz_hash).unwrap();
+			assert_eq!((pool.available, pool.held), (1, 2));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+		});
+	}
+
+		#[test]
+	fn transfer_product_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("shipped").using_encoded(<Test as system::Trait>::Hashing::hash));
+			let seq_id = "sku-1";
+
+			System::set_block_number(10);
+			let maker = Service::next_business_hash(1, owner_hash, "maker".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "maker".into(), 20));
+			let retailer = Service::next_business_hash(1, owner_hash, "retailer".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "retailer".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), maker, alice, None));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), retailer, bob, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, maker, seq_id.into(), data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(maker, seq_id.into());
+
+			assert_noop!(Service::transfer_product(Origin::signed(4), bob, maker, retailer, seq_id.into()), "Not in the whitelist");
+			assert_noop!(Service::transfer_product(Origin::signed(3), alice, maker, maker, seq_id.into()), "Same business");
+			assert_noop!(Service::transfer_product(Origin::signed(3), alice, maker, retailer, "sku-2".into()), "Product does not exist");
+
+			// Hand-off waits for the retailer to accept
+			assert_ok!(Service::transfer_product(Origin::signed(3), alice, maker, retailer, seq_id.into()));
+			assert_noop!(Service::transfer_product(Origin::signed(3), alice, maker, retailer, seq_id.into()), "Transfer already pending");
+			assert_noop!(Service::accept_product_transfer(Origin::signed(3), alice, retailer, product_hash), "Not in the whitelist");
+			assert_ok!(Service::cancel_product_transfer(Origin::signed(3), alice, product_hash));
+			assert_noop!(Service::accept_product_transfer(Origin::signed(4), bob, retailer, product_hash), "No pending transfer");
+
+			assert_ok!(Service::transfer_product(Origin::signed(3), alice, maker, retailer, seq_id.into()));
+			assert_ok!(Service::accept_product_transfer(Origin::signed(4), bob, retailer, product_hash));
+			assert_eq!(Service::product_custodian(product_hash), Some(retailer));
+			assert_eq!(Service::held_product_hash(retailer, &seq_id.into()), Some(product_hash));
+
+			// Only the custodian appends infos
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, maker, seq_id.into(), InfoKind::Received, data_hash.clone(), vec![]), "Product does not exist");
+			assert_noop!(Service::create_product(Origin::signed(4), bob, retailer, seq_id.into(), data_hash.clone(), vec![]), "DuplicateSeqId");
+			assert_ok!(Service::add_product_info(Origin::signed(4), bob, retailer, seq_id.into(), InfoKind::Received, data_hash.clone(), vec![]));
+			assert_eq!(Service::product_of(product_hash).infos.len(), 2);
+
+			// Pre-approved transfers complete at once
+			assert_noop!(Service::set_transfer_approval(Origin::signed(3), maker, retailer, true), "Not authorized");
+			assert_ok!(Service::set_transfer_approval(Origin::signed(2), maker, retailer, true));
+			assert_noop!(Service::set_transfer_approval(Origin::signed(2), maker, retailer, true), "Same value");
+			assert_ok!(Service::transfer_product(Origin::signed(4), bob, retailer, maker, seq_id.into()));
+			assert_eq!(Service::product_custodian(product_hash), None);
+			assert_eq!(Service::held_product_hash(maker, &seq_id.into()), Some(product_hash));
+			assert_eq!(Service::held_product_hash(retailer, &seq_id.into()), None);
+
+			let history = Service::product_custody(product_hash);
+			assert_eq!(history.len(), 2);
+			assert_eq!((history[0].from_biz, history[0].to_biz, history[0].released_by, history[0].accepted_by), (maker, retailer, 3, 4));
+			assert_eq!((history[1].from_biz, history[1].to_biz, history[1].released_by, history[1].accepted_by), (retailer, maker, 4, 2));
+		});
+	}
+
+		#[test]
+	fn signed_product_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None));
+
+			let payload = OperatorPayload {
+				signer: 3,
+				name_hash: alice,
+				biz_hash: biz_hash,
+				seq_id: "1".into(),
+				kind: InfoKind::QualityCheck,
+				data_hash: data_hash,
+				extra: vec![],
+				nonce: 0,
+			};
+			let sign = |who: u64, context: &[u8], payload: &OperatorPayloadOf<Test>| TestSig(who, (context, System::block_hash(0), payload).encode());
+
+			// A payload signed for another chain is rejected
+			let foreign = TestSig(3, (CREATE_PRODUCT_CONTEXT, H256::repeat_byte(1), &payload).encode());
+			assert_noop!(Service::create_product_signed(Origin::signed(9), payload.clone(), foreign), "Invalid signature");
+
+			// Alice resolves to 3, the relayer is 9
+			assert_noop!(
+				Service::create_product_signed(Origin::signed(9), payload.clone(), sign(9, CREATE_PRODUCT_CONTEXT, &payload)),
+				"Invalid signature"
+			);
+			// A signer the provider does not authorize for the name is rejected
+			let unauthorized = OperatorPayload { signer: 9, ..payload.clone() };
+			assert_noop!(
+				Service::create_product_signed(Origin::signed(9), unauthorized.clone(), sign(9, CREATE_PRODUCT_CONTEXT, &unauthorized)),
+				"Not authorized"
+			);
+			assert_noop!(
+				Service::create_product_signed(Origin::signed(9), payload.clone(), sign(3, ADD_PRODUCT_INFO_CONTEXT, &payload)),
+				"Invalid signature"
+			);
+			assert_ok!(Service::create_product_signed(Origin::signed(9), payload.clone(), sign(3, CREATE_PRODUCT_CONTEXT, &payload)));
+			assert_eq!(Service::operator_nonce(alice), 1);
+
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_eq!(Service::product_of(product_hash).infos[0].creator, 3);
+
+			// The same payload can not be replayed
+			assert_noop!(
+				Service::add_product_info_signed(Origin::signed(9), payload.clone(), sign(3, ADD_PRODUCT_INFO_CONTEXT, &payload)),
+				"Invalid nonce"
+			);
+			let payload = OperatorPayload { nonce: 1, ..payload };
+			assert_ok!(Service::add_product_info_signed(Origin::signed(9), payload.clone(), sign(3, ADD_PRODUCT_INFO_CONTEXT, &payload)));
+			assert_eq!(Service::operator_nonce(alice), 2);
+			assert_eq!(Service::product_of(product_hash).infos.len(), 2);
+			assert_eq!(Service::product_of(product_hash).infos[1].kind, InfoKind::QualityCheck);
+
+			// An exhausted nonce fails before the write
+			<OperatorNonce<Test>>::insert(alice, u64::max_value());
+			let payload = OperatorPayload { nonce: u64::max_value(), ..payload };
+			assert_noop!(
+				Service::add_product_info_signed(Origin::signed(9), payload.clone(), sign(3, ADD_PRODUCT_INFO_CONTEXT, &payload)),
+				"Overflow advancing the operator nonce"
+			);
+			assert_eq!(Service::product_of(product_hash).infos.len(), 2);
+		});
+	}
+
+	#[test]
+	fn product_weight_should_follow_input_size() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+
+			let weight_of = |extra: Vec<u8>| Call::<Test>::create_product(alice, biz_hash, "1".into(), data_hash.clone(), extra).get_dispatch_info().weight;
+			assert_eq!(weight_of(vec![]), <() as WeightInfo>::create_product(1, 0, MaxWhitelistSize::get() as u32));
+			assert!(weight_of(vec![0; 1024]) > weight_of(vec![]));
+
+			// The whitelist is weighed at its bound, not read while weighing
+			let before = weight_of(vec![]);
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, bob, None));
+			assert_eq!(weight_of(vec![]), before);
+
+			// Appending info is weighed for a product holding the most infos
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			let info_weight = || Call::<Test>::add_product_info(alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]).get_dispatch_info().weight;
+			let bound = <() as WeightInfo>::add_product_info(1, 0, MaxWhitelistSize::get() as u32, MaxProductInfoCount::get() as u32);
+			assert_eq!(info_weight(), bound);
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			assert_eq!(info_weight(), bound);
+		});
+	}
+
+	#[test]
+	fn admin_calls_should_follow_dispatch_config() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+
+			let info = Call::<Test>::create_business(scope(), owner_hash, "crab".into(), 20).get_dispatch_info();
+			assert_eq!(info.class, DispatchClass::Operational);
+			assert!(!info.pays_fee);
+			let info = Call::<Test>::set_business_expiration(biz_hash, 30).get_dispatch_info();
+			assert_eq!(info.class, DispatchClass::Operational);
+			assert!(!info.pays_fee);
+
+			// Operator writes stay normal and pay fees
+			let info = Call::<Test>::create_product(alice, biz_hash, "1".into(), data_hash, vec![]).get_dispatch_info();
+			assert_eq!(info.class, DispatchClass::Normal);
+			assert!(info.pays_fee);
+		});
+	}
+
+	#[test]
+	fn admin_delegate_should_work() {
+		new_test_ext().execute_with(|| {
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			assert_noop!(Service::create_business(Origin::signed(5), scope(), owner_hash, "crab".into(), 30), "Not authorized");
+			assert_noop!(Service::set_admin_delegate(Origin::signed(2), scope(), 5, None), "Not authorized");
+			assert_noop!(Service::set_admin_delegate(Origin::signed(1), scope(), 5, Some(10)), "Expired");
+			assert_ok!(Service::set_admin_delegate(Origin::signed(1), scope(), 5, Some(20)));
+			assert_noop!(Service::set_admin_delegate(Origin::signed(1), scope(), 5, Some(20)), "Same value");
+
+			// The delegate creates businesses until its delegation ends, but may not administer them
+			let biz_hash = Service::next_business_hash(5, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(5), scope(), owner_hash, "crab".into(), 30));
+			assert_eq!(Service::business_of(biz_hash).creator, 5);
+			assert_noop!(Service::set_business_expiration(Origin::signed(5), biz_hash, 40), "Not authorized");
+			System::set_block_number(20);
+			assert_noop!(Service::create_business(Origin::signed(5), scope(), owner_hash, "clam".into(), 30), "Not authorized");
+
+			assert_ok!(Service::set_admin_delegate(Origin::signed(1), scope(), 5, None));
+			assert_ok!(Service::create_business(Origin::signed(5), scope(), owner_hash, "clam".into(), 30));
+			assert_ok!(Service::remove_admin_delegate(Origin::signed(1), scope(), 5));
+			assert_noop!(Service::remove_admin_delegate(Origin::signed(1), scope(), 5), "Not a delegate");
+			assert_noop!(Service::create_business(Origin::signed(5), scope(), owner_hash, "cod".into(), 30), "Not authorized");
+
+			// The delegates of a former admin lapse when the admin name changes hands
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let fish = ("fish").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(Service::register_scope(Origin::signed(1), fish, alice));
+			assert_ok!(Service::set_admin_delegate(Origin::signed(3), fish, 5, None));
+			assert_noop!(Service::create_business(Origin::signed(5), scope(), owner_hash, "cod".into(), 30), "Not authorized");
+			assert_ok!(Service::create_business(Origin::signed(5), fish, owner_hash, "cod".into(), 30));
+			<Scopes<Test>>::mutate(fish, |scope| scope.as_mut().unwrap().admin = bob);
+			assert_noop!(Service::create_business(Origin::signed(5), fish, owner_hash, "eel".into(), 30), "Not authorized");
+			assert_noop!(Service::remove_admin_delegate(Origin::signed(3), fish, 5), "Not authorized");
+			assert_ok!(Service::set_admin_delegate(Origin::signed(4), fish, 5, None));
+			assert_eq!(Service::scope_delegate(fish, 5), Some(AdminDelegation { admin: 4, valid_until: None }));
+			assert_ok!(Service::create_business(Origin::signed(5), fish, owner_hash, "eel".into(), 30));
+		});
+	}
+
+	#[test]
+	fn scopes_should_work() {
+		new_test_ext().execute_with(|| {
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let fish = ("fish").using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			assert_noop!(Service::create_business(Origin::signed(3), fish, owner_hash, "crab".into(), 20), "Scope does not exist");
+			assert_noop!(Service::register_scope(Origin::signed(3), fish, alice), "Bad origin");
+			assert_ok!(Service::register_scope(Origin::signed(1), fish, alice));
+			assert_noop!(Service::register_scope(Origin::signed(1), fish, alice), "Scope already exists");
+			assert_eq!(Service::scopes_by_admin(alice), vec![fish]);
+			assert!(<Service as name_service::NodeReferences<H256>>::is_referenced(&alice));
+
+			// Each scope is administered by the account its admin name resolves to
+			assert_noop!(Service::create_business(Origin::signed(1), fish, owner_hash, "crab".into(), 20), "Not authorized");
+			let biz_hash = Service::next_business_hash(3, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(3), fish, owner_hash, "crab".into(), 20));
+			assert_eq!(Service::business_of(biz_hash).scope, fish);
+			assert_eq!(Service::business_info(biz_hash).map(|details| details.scope), Some(fish));
+			assert_noop!(Service::set_business_expiration(Origin::signed(1), biz_hash, 30), "Not authorized");
+			assert_ok!(Service::set_business_expiration(Origin::signed(3), biz_hash, 30));
+
+			// A retired scope takes no new business, its businesses are still renewed
+			assert_noop!(Service::retire_scope(Origin::signed(3), fish), "Bad origin");
+			assert_ok!(Service::retire_scope(Origin::signed(1), fish));
+			assert_noop!(Service::retire_scope(Origin::signed(1), fish), "Scope is retired");
+			assert_noop!(Service::create_business(Origin::signed(3), fish, owner_hash, "clam".into(), 20), "Scope is retired");
+			assert_ok!(Service::set_business_expiration(Origin::signed(3), biz_hash, 40));
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "clam".into(), 20));
+			assert_eq!(Service::do_try_state(), Ok(()));
+		});
+	}
+
+	#[test]
+	fn whitelist_expiration_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("contractor").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 30));
+			assert_noop!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, Some(10)), "Expired");
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, Some(12)));
+
+			assert_noop!(Service::set_whitelist_expiration(Origin::signed(3), biz_hash, alice, Some(15)), "Not authorized");
+			assert_noop!(Service::set_whitelist_expiration(Origin::signed(2), biz_hash, bob, Some(15)), "Not in the whitelist");
+			assert_noop!(Service::set_whitelist_expiration(Origin::signed(2), biz_hash, alice, Some(12)), "Same value");
+
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			System::set_block_number(12);
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]), "Whitelist entry expired");
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]), "Whitelist entry expired");
+
+			// Extend the engagement
+			assert_ok!(Service::set_whitelist_expiration(Origin::signed(2), biz_hash, alice, Some(15)));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			assert_ok!(Service::set_whitelist_expiration(Origin::signed(2), biz_hash, alice, None));
+			System::set_block_number(20);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]));
+		});
+	}
+
+	#[test]
+	fn storage_should_migrate() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = ("legacy").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_eq!(Service::storage_version(), STORAGE_VERSION);
+
+			// A chain upgraded from the first layouts holds the businesses and products only, with
+			// the product index under `(business, index)` tuple keys and no other index
+			StorageVersion::kill();
+			let businesses = [(0u64, vec![alice], 2usize), (1u64, vec![alice, bob], 0usize)];
+			let mut products = vec![];
+			for (nonce, whitelist, count) in businesses.iter() {
+				let biz_hash = (1u64, owner_hash, *nonce).using_encoded(<Test as system::Trait>::Hashing::hash);
+				let business: (u64, H256, Vec<u8>, Vec<H256>, u64) = (1, owner_hash, b"crab".to_vec(), whitelist.clone(), 20);
+				unhashed::put(&<Businesses<Test>>::hashed_key_for(biz_hash), &business);
+				for index in 0..*count {
+					let seq_id = vec![b'1' + index as u8];
+					let product_hash = (biz_hash, seq_id.clone()).using_encoded(<Test as system::Trait>::Hashing::hash);
+					let legacy: (Vec<u8>, Vec<(u64, u64, H256, Vec<u8>)>) = (seq_id.clone(), vec![(3, 10, data_hash, b"{}".to_vec()); 2]);
+					unhashed::put(&<Products<Test>>::hashed_key_for(product_hash), &legacy);
+					unhashed::put(&Service::legacy_product_index_key(biz_hash, index as u64), &product_hash);
+					products.push((biz_hash, index as u64, seq_id, product_hash));
+				}
+				unhashed::put(&<ProductCount<Test>>::hashed_key_for(biz_hash), &(*count as u64));
+			}
+
+			// `on_initialize` registers the default scope they belong to
+			<Scopes<Test>>::remove(scope());
+			<ScopesByAdmin<Test>>::remove(scope());
+			Service::on_initialize(11);
+			assert_eq!(Service::storage_version(), STORAGE_VERSION);
+			assert_eq!(Service::scope_of(scope()).map(|s| s.admin), Some(scope()));
+			for (nonce, _, _) in businesses.iter() {
+				let biz_hash = (1u64, owner_hash, *nonce).using_encoded(<Test as system::Trait>::Hashing::hash);
+				assert_ok!(Service::migrate_legacy_business(Origin::signed(5), biz_hash, *nonce, MAX_LEGACY_PRODUCTS));
+			}
+
+			for (index, (nonce, whitelist, count)) in businesses.iter().enumerate() {
+				let biz_hash = (1u64, owner_hash, *nonce).using_encoded(<Test as system::Trait>::Hashing::hash);
+				let business = Service::business_of(biz_hash);
+				assert_eq!(business.creator, 1);
+				assert_eq!(business.owner, owner_hash);
+				assert_eq!(business.name.to_vec(), b"crab".to_vec());
+				assert_eq!(
+					business.whitelist,
+					whitelist.iter().map(|name_hash| WhitelistEntry { name_hash: *name_hash, valid_until: None }).collect::<Vec<_>>()
+				);
+				assert_eq!(business.expiration, 20);
+				assert_eq!(business.profile, None);
+				assert_eq!(business.category, 0);
+				assert_eq!(business.parent, None);
+				assert_eq!(business.scope, scope());
+				assert_eq!(Service::business_index(index as u64), biz_hash);
+				assert_eq!(Service::business_hash_for(1, index as u64), Some(biz_hash));
+				assert_eq!(Service::product_count(biz_hash), *count as u64);
+			}
+			assert_eq!(Service::business_count(), 2);
+			let biz_hashes = businesses.iter()
+				.map(|(nonce, _, _)| (1u64, owner_hash, *nonce).using_encoded(<Test as system::Trait>::Hashing::hash))
+				.collect::<Vec<_>>();
+			assert_eq!(Service::businesses_by_owner(owner_hash), biz_hashes);
+			assert_eq!(Service::businesses_by_operator(alice), biz_hashes);
+			assert_eq!(Service::businesses_by_operator(bob), vec![biz_hashes[1]]);
+			assert_eq!(Service::creator_business_count(1), 2);
+
+			assert_eq!(Service::creator_product_count(3), products.len() as u64);
+			for (creator_index, (biz_hash, index, seq_id, product_hash)) in products.iter().cloned().enumerate() {
+				let product = Service::product_of(product_hash);
+				assert_eq!(product.seq_id.to_vec(), seq_id);
+				assert_eq!(product.infos.len(), 2);
+				for info in product.infos.iter() {
+					assert_eq!(info.creator, 3);
+					assert_eq!(info.created_at, 10);
+					assert_eq!(info.timestamp, 0);
+					assert_eq!(info.kind, InfoKind::Custom(0));
+					assert_eq!(info.data_hash, DataHash::from_runtime_hash(&data_hash));
+					assert_eq!(info.extra.to_vec(), b"{}".to_vec());
+					assert_eq!(info.extra_version, EXTRA_VERSION_RAW);
+				}
+				let leaves = product.infos.iter().map(merkle::leaf_of).collect::<Vec<_>>();
+				assert_eq!(Service::info_peaks(product_hash), merkle::peaks_of(&leaves));
+				assert_eq!(product.infos_root, merkle::root_of_peaks(&merkle::peaks_of(&leaves)));
+				assert_eq!(Service::business_product_index(biz_hash, index), product_hash);
+				assert_eq!(unhashed::get::<H256>(&Service::legacy_product_index_key(biz_hash, index)), None);
+				assert_eq!(Service::product_hash_of((biz_hash, seq_id)), Some(product_hash));
+				assert_eq!(Service::products_by_creator((3, creator_index as u64)), product_hash);
+				assert_eq!(Service::product_positions(product_hash), (index, creator_index as u64));
+			}
+
+			// The migrated infos and the next ones prove against the same root
+			let (biz_hash, _, seq_id, product_hash) = products[0].clone();
+			let appended = DataHash::from_runtime_hash(&("appended").using_encoded(<Test as system::Trait>::Hashing::hash));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, seq_id.clone(), InfoKind::Shipped, appended, vec![]));
+			for (index, info) in Service::product_of(product_hash).infos.iter().enumerate() {
+				let proof = Service::product_info_proof(biz_hash, seq_id.clone(), index as u32).unwrap();
+				assert!(merkle::verify_proof(&proof.root, merkle::leaf_of(info), proof.index, proof.count, &proof.proof));
+			}
+		});
+	}
+
+	#[test]
+	fn legacy_business_should_migrate() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = ("legacy").using_encoded(<Test as system::Trait>::Hashing::hash);
+			System::set_block_number(10);
+
+			// A business and its products in the layouts before the enumeration indices, under
+			// the hash of the creator, the owner and the nonce
+			let biz_hash = (1u64, owner_hash, 0u64).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let business: (u64, H256, Vec<u8>, Vec<H256>, u64) = (1, owner_hash, b"crab".to_vec(), vec![alice], 20);
+			unhashed::put(&<Businesses<Test>>::hashed_key_for(biz_hash), &business);
+			let seq_ids = [b"1".to_vec(), b"2".to_vec()];
+			let products = seq_ids.iter()
+				.map(|seq_id| (biz_hash, seq_id).using_encoded(<Test as system::Trait>::Hashing::hash))
+				.collect::<Vec<H256>>();
+			for (index, product_hash) in products.iter().enumerate() {
+				let legacy: (Vec<u8>, Vec<(u64, u64, H256, Vec<u8>)>) = (seq_ids[index].clone(), vec![(3, 10, data_hash, b"{}".to_vec())]);
+				unhashed::put(&<Products<Test>>::hashed_key_for(*product_hash), &legacy);
+				unhashed::put(&Service::legacy_product_index_key(biz_hash, index as u64), product_hash);
+			}
+			<ProductCount<Test>>::insert(biz_hash, 2);
+
+			assert_noop!(Service::migrate_legacy_business(Origin::signed(5), biz_hash, 1, 1), "Nonce does not match");
+			assert_noop!(
+				Service::migrate_legacy_business(Origin::signed(5), biz_hash, 0, MAX_LEGACY_PRODUCTS + 1),
+				"Too many products"
+			);
+
+			// The products are migrated a page at a time, the business with the last page
+			assert_ok!(Service::migrate_legacy_business(Origin::signed(5), biz_hash, 0, 1));
+			assert_eq!(Service::legacy_product_cursor(biz_hash), 1);
+			assert_eq!(Service::business_count(), 0);
+			assert_ok!(Service::migrate_legacy_business(Origin::signed(5), biz_hash, 0, 1));
+			assert_noop!(Service::migrate_legacy_business(Origin::signed(5), biz_hash, 0, 1), "Not a legacy business");
+
+			let business = Service::business_of(biz_hash);
+			assert_eq!(business.whitelist, [WhitelistEntry { name_hash: alice, valid_until: None }]);
+			assert_eq!(business.expiration, 20);
+			assert_eq!(business.scope, scope());
+			assert_eq!(Service::business_count(), 1);
+			assert_eq!(Service::business_index(0), biz_hash);
+			assert_eq!(Service::businesses_by_owner(owner_hash), vec![biz_hash]);
+			assert_eq!(Service::businesses_by_operator(alice), vec![biz_hash]);
+			assert_eq!(Service::business_hash_for(1, 0), Some(biz_hash));
+			assert_eq!(Service::creator_product_count(3), 2);
+			for (index, product_hash) in products.iter().enumerate() {
+				let product = Service::product_of(*product_hash);
+				assert_eq!(product.infos[0].kind, InfoKind::Custom(0));
+				assert_eq!(product.infos[0].data_hash, DataHash::from_runtime_hash(&data_hash));
+				let leaves = product.infos.iter().map(merkle::leaf_of).collect::<Vec<_>>();
+				assert_eq!(product.infos_root, merkle::root_of_peaks(&merkle::peaks_of(&leaves)));
+				assert_eq!(Service::business_product_index(biz_hash, index as u64), *product_hash);
+				assert_eq!(Service::product_hash_of((biz_hash, seq_ids[index].clone())), Some(*product_hash));
+				assert_eq!(Service::products_by_creator((3, index as u64)), *product_hash);
+				assert_eq!(Service::product_positions(*product_hash), (index as u64, index as u64));
+				assert_eq!(unhashed::get::<H256>(&Service::legacy_product_index_key(biz_hash, index as u64)), None);
+			}
+		});
+	}
+
+	#[test]
+	fn set_business_profile_should_work() {
+		new_test_ext().execute_with(|| {
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let profile = ("profile").using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_noop!(Service::set_business_profile(Origin::signed(3), biz_hash, Some(profile), 2), "Not authorized");
+
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, Some(profile), 2));
+			assert_eq!(Service::business_of(biz_hash).profile, Some(profile));
+			assert_eq!(Service::businesses_by_category(2, 0, 10), vec![biz_hash]);
+			assert_noop!(Service::set_business_profile(Origin::signed(2), biz_hash, Some(profile), 2), "Same value");
+
+			// Moving to another category updates the index, uncategorized businesses are not listed
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, Some(profile), 5));
+			assert!(Service::businesses_by_category(2, 0, 10).is_empty());
+			assert_eq!(Service::businesses_by_category(5, 0, 10), vec![biz_hash]);
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, None, 0));
+			assert!(Service::businesses_by_category(5, 0, 10).is_empty());
+			assert!(Service::businesses_by_category(0, 0, 10).is_empty());
+
+			// The last business of a category takes the position of a removed one
+			let second = Service::next_business_hash(1, owner_hash, "lobster".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "lobster".into(), 20));
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, None, 5));
+			assert_ok!(Service::set_business_profile(Origin::signed(2), second, None, 5));
+			assert_eq!(Service::businesses_by_category(5, 0, 10), vec![biz_hash, second]);
+			assert_eq!(Service::businesses_by_category(5, 1, 10), vec![second]);
+			assert_eq!(Service::businesses_by_category(5, 0, 1), vec![biz_hash]);
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, None, 0));
+			assert_eq!(Service::businesses_by_category(5, 0, 10), vec![second]);
+			assert_eq!(Service::category_position(second), 0);
+
+			// Archived businesses leave the index
+			assert_ok!(Service::set_business_profile(Origin::signed(2), biz_hash, None, 5));
+			Service::archive_business(biz_hash);
+			assert_eq!(Service::businesses_by_category(5, 0, 10), vec![second]);
+			assert_eq!(Service::category_business_count(5), 1);
+			assert_eq!(Service::archived_business_of(biz_hash).map(|b| b.category), Some(5));
+		});
+	}
+
+	#[test]
+	fn enumeration_indices_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_eq!(Service::businesses_by_owner(owner_hash), vec![biz_hash]);
+
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]));
+
+			assert_eq!(Service::creator_product_count(3), 2);
+			assert_eq!(Service::products_by_creator((3, 0)), Service::product_hash(biz_hash, "1".into()));
+			assert_eq!(Service::products_by_creator((3, 1)), Service::product_hash(biz_hash, "2".into()));
+		});
+	}
+
+	#[test]
+	fn archive_businesses_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("I have a secret, haha~").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash,  alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::set_product_lot(Origin::signed(3), alice, biz_hash, "1".into(), "lot-1".into()));
+
+			assert_noop!(Service::set_archivers(Origin::signed(2), vec![UintAuthorityId(7)]), "Bad origin");
+			assert_ok!(Service::set_archivers(Origin::signed(1), vec![UintAuthorityId(7)]));
+
+			let payload = ArchivePayload {
+				block_number: 25,
+				biz_hashes: vec![biz_hash],
+				products: 1,
+				lots: 1,
+				public: UintAuthorityId(7),
+			};
+			let signature = UintAuthorityId(7).sign(&payload.encode()).unwrap();
+			let call = Call::archive_businesses(payload.clone(), signature);
+
+			// Still within the grace period
+			System::set_block_number(24);
+			assert!(!Service::is_archivable(biz_hash, 24));
+			assert!(Service::validate_unsigned(&call).is_err());
+
+			System::set_block_number(25);
+			assert!(Service::validate_unsigned(&call).is_ok());
+			let bad_call = Call::archive_businesses(ArchivePayload { public: UintAuthorityId(8), ..payload.clone() }, signature);
+			assert!(Service::validate_unsigned(&bad_call).is_err());
+
+			// The weight of the call must cover the products deleted with the business
+			let light = ArchivePayload { products: 0, ..payload.clone() };
+			let light_signature = UintAuthorityId(7).sign(&light.encode()).unwrap();
+			assert_noop!(Service::archive_businesses(Origin::NONE, light, light_signature), "Too many products to archive");
+			let light = ArchivePayload { lots: 0, ..payload.clone() };
+			let light_signature = UintAuthorityId(7).sign(&light.encode()).unwrap();
+			assert_noop!(Service::archive_businesses(Origin::NONE, light, light_signature), "Too many lots to archive");
+
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_ok!(Service::archive_businesses(Origin::NONE, payload, signature));
+			assert!(!<Businesses<Test>>::exists(biz_hash));
+			assert_eq!(Service::archived_business_of(biz_hash).unwrap().name, b"crab".to_vec());
+			assert_eq!(Service::businesses_by_owner(owner_hash), vec![]);
+			assert_eq!(Service::product_count(biz_hash), 0);
+			assert!(!<BusinessProductIndex<Test>>::exists(biz_hash, 0));
+			assert!(!<Products<Test>>::exists(product_hash));
+			assert_eq!(Service::product_hash_of((biz_hash, b"1".to_vec())), None);
+			assert_eq!(Service::creator_product_count(3), 0);
+			assert!(!<ProductsByCreator<Test>>::exists((3, 0)));
+
+			// The lots are deleted with the business
+			let lot_hash = Service::lot_hash(biz_hash, "lot-1".into());
+			assert!(Service::lot_of(lot_hash).is_none());
+			assert!(Service::business_lots(biz_hash).is_empty());
+			assert_eq!(Service::lot_product_count(lot_hash), 0);
+		});
+	}
+
+	#[test]
+	fn product_hash_should_work() {
+		let biz_hash = <Test as system::Trait>::Hash::default(); 
+		let seq_id = &"1".repeat(32)[..];
+		let product_hash = Service::product_hash(biz_hash, seq_id.into());
+
+		println!("biz_hash = {:#?}", biz_hash);
+		println!("product_hash = {:#?}", product_hash);
+		let mut data = PRODUCT_HASH_DOMAIN.encode();
+		data.extend_from_slice(biz_hash.as_ref());
+		data.extend_from_slice(&runtime_io::hashing::blake2_256(seq_id.as_bytes()));
+		let hash = <Test as system::Trait>::Hashing::hash(&data);
+		assert_eq!(product_hash, hash);
+	}
+
+	#[test]
+	fn product_hash_collision_should_be_distinguished() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let data_hash = DataHash::from_runtime_hash(&("collision").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::product_hash_of((biz_hash, b"1".to_vec())), None);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_eq!(Service::product_hash_of((biz_hash, b"1".to_vec())), Some(Service::product_hash(biz_hash, "1".into())));
+
+			// Another product already stored under the hash of a new sequence id
+			<Products<Test>>::insert(Service::product_hash(biz_hash, "2".into()), ProductOf::<Test>::default());
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash.clone(), vec![]), "ProductHashCollision");
+		});
+	}
+
+	#[test]
+	fn product_by_seq_id_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("label").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::product_by_seq_id(biz_hash, "1".into()), None);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+
+			let product = Service::product_by_seq_id(biz_hash, "1".into()).unwrap();
+			assert_eq!(product.seq_id, b"1".to_vec());
+			assert_eq!(product.infos[0].data_hash, data_hash);
+
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert_eq!(Service::product_by_seq_id(biz_hash, "1".into()), None);
+		});
+	}
+
+	#[test]
+	fn product_info_proof_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("merkle").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			assert_eq!(Service::product_of(product_hash).infos_root, merkle::leaf_of(&Service::product_of(product_hash).infos[0]));
+
+			for kind in [InfoKind::QualityCheck, InfoKind::Shipped, InfoKind::Received, InfoKind::Custom(7)].iter() {
+				System::set_block_number(System::block_number() + 1);
+				assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), *kind, data_hash.clone(), vec![]));
+			}
+
+			// The root updated on each append is the root of all the infos
+			let product = Service::product_of(product_hash);
+			let leaves = product.infos.iter().map(merkle::leaf_of).collect::<Vec<_>>();
+			assert_eq!(product.infos_root, merkle::root_of_peaks(&merkle::peaks_of(&leaves)));
+
+			// Each info verifies alone against the root
+			for (index, info) in product.infos.iter().enumerate() {
+				let proof = Service::product_info_proof(biz_hash, "1".into(), index as u32).unwrap();
+				assert_eq!(proof.root, product.infos_root);
+				assert_eq!(proof.count, 5);
+				assert!(merkle::verify_proof(&proof.root, merkle::leaf_of(info), proof.index, proof.count, &proof.proof));
+			}
+			assert_eq!(Service::product_info_proof(biz_hash, "1".into(), 5), None);
+			assert_eq!(Service::product_info_proof(biz_hash, "2".into(), 0), None);
+
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert!(Service::info_peaks(product_hash).is_empty());
+		});
+	}
+
+	#[test]
+	fn product_storage_keys_should_match_proof_keys() {
+		new_test_ext().execute_with(|| {
+			use pistis_primitives::proof;
+
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("proof").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash, vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+
+			// The keys a client derives are the keys the product is stored under
+			assert_eq!(Service::product_storage_keys(biz_hash, "1".into()), vec![
+				proof::product_hash_key(&biz_hash, b"1"),
+				proof::product_key(&product_hash),
+			]);
+			assert_eq!(unhashed::get::<H256>(&proof::product_hash_key(&biz_hash, b"1")), Some(product_hash));
+
+			// A sequence id not recorded is looked up under its legacy hash
+			let legacy_hash = Service::legacy_product_hash(biz_hash, b"2");
+			assert_eq!(legacy_hash, proof::legacy_product_hash::<<Test as system::Trait>::Hashing>(&biz_hash, b"2"));
+			assert_eq!(Service::product_storage_keys(biz_hash, "2".into()), vec![
+				proof::product_hash_key(&biz_hash, b"2"),
+				proof::product_key(&legacy_hash),
+			]);
+		});
+	}
+
+	#[test]
+	fn provenance_commitment_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("provenance").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::provenance_commitment(biz_hash, "1".into()), None);
+
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash, vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+
+			let commitment = Service::provenance_commitment(biz_hash, "1".into()).unwrap();
+			assert_eq!(commitment, VersionedProvenanceCommitment::V1(ProvenanceCommitmentV1 {
+				business: biz_hash,
+				business_name: "crab".into(),
+				owner: owner_hash,
+				seq_id: "1".into(),
+				product: product_hash,
+				infos_root: Service::product_of(product_hash).infos_root,
+				info_count: 2,
+				block_number: 10,
+				parent_hash: System::parent_hash(),
+			}));
+
+			// The layout index leads the encoding
+			let encoded = commitment.encode();
+			assert_eq!(encoded[0], 0);
+			assert_eq!(VersionedProvenanceCommitment::decode(&mut &encoded[..]).ok(), Some(commitment));
+			assert_eq!(Service::provenance_commitment(biz_hash, "2".into()), None);
+		});
+	}
+
+	#[test]
+	fn operator_stats_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = |n: u8| DataHash::from_runtime_hash(&(n).using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::operator_stats(biz_hash, alice), OperatorActivity::default());
+
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash(1), vec![]));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "2".into(), data_hash(2), vec![]));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash(3), vec![]));
+
+			System::set_block_number(12);
+			assert_ok!(Service::create_lot(Origin::signed(3), alice, biz_hash, "lot-1".into(), data_hash(4), vec![]));
+			assert_ok!(Service::add_lot_info(Origin::signed(3), alice, biz_hash, "lot-1".into(), InfoKind::Shipped, data_hash(5), vec![]));
+			// Failed writes are not counted
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "3".into(), InfoKind::Shipped, data_hash(6), vec![]), "Product does not exist");
+
+			assert_eq!(Service::operator_stats(biz_hash, alice), OperatorActivity {
+				products_created: 2,
+				infos_appended: 3,
+				last_active: 12,
+			});
+			assert_eq!(Service::operator_stats(biz_hash, owner_hash), OperatorActivity::default());
+		});
+	}
+
+	#[test]
+	fn redact_product_info_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("redact").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), b"alice@example.com".to_vec()));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Shipped, data_hash.clone(), b"+1 555".to_vec()));
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "1".into(), InfoKind::Received, data_hash.clone(), vec![]));
+			let product_hash = Service::product_hash(biz_hash, "1".into());
+			let original = Service::product_of(product_hash);
+
+			assert_noop!(Service::redact_product_info(Origin::signed(3), biz_hash, "1".into(), 0), "Not authorized");
+			assert_noop!(Service::redact_product_info(Origin::signed(2), biz_hash, "1".into(), 3), "Info does not exist");
+			assert_noop!(Service::redact_product_info(Origin::signed(2), biz_hash, "1".into(), 2), "Extra info is empty");
+			assert_noop!(Service::redact_product_info(Origin::signed(2), biz_hash, "2".into(), 0), "Product does not exist");
+
+			// By the owner, then by the force origin
+			assert_ok!(Service::redact_product_info(Origin::signed(2), biz_hash, "1".into(), 0));
+			assert_noop!(Service::redact_product_info(Origin::signed(2), biz_hash, "1".into(), 0), "Info already redacted");
+			assert_ok!(Service::redact_product_info(Origin::signed(1), biz_hash, "1".into(), 1));
+
+			let product = Service::product_of(product_hash);
+			assert!(product.infos.iter().all(|info| info.extra.is_empty()));
+			assert_eq!(Service::redacted_info(product_hash, 0), Some(merkle::leaf_of(&original.infos[0])));
+			assert_eq!(Service::redacted_info(product_hash, 1), Some(merkle::leaf_of(&original.infos[1])));
+
+			// The root and the proofs still hold for the original infos
+			assert_eq!(product.infos_root, original.infos_root);
+			for (index, info) in original.infos.iter().enumerate() {
+				let proof = Service::product_info_proof(biz_hash, "1".into(), index as u32).unwrap();
+				assert!(merkle::verify_proof(&proof.root, merkle::leaf_of(info), proof.index, proof.count, &proof.proof));
+			}
+			assert_eq!(Service::do_try_state(), Ok(()));
+
+			assert_ok!(Service::remove_product(Origin::signed(2), biz_hash, "1".into()));
+			assert_eq!(Service::redacted_info(product_hash, 0), None);
+		});
+	}
+
+	#[test]
+	fn rotate_operator_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let bob = (BOB).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let crab = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			let lobster = Service::next_business_hash(1, owner_hash, "lobster".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "lobster".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), crab, alice, Some(15)));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), lobster, alice, None));
+			RESOLVE_CHANGE_ACTION.with(|v| *v.borrow_mut() = ResolveChangeAction::Suspend);
+			<Service as name_service::OnResolveChanged<Test>>::on_resolve_changed(alice, Some(3), Some(5));
+			assert_ok!(Service::confirm_whitelist_member(Origin::signed(2), crab, alice));
+
+			assert_noop!(Service::rotate_operator(Origin::signed(4), alice, bob, vec![crab]), "Not authorized");
+			assert_noop!(Service::rotate_operator(Origin::signed(3), alice, alice, vec![crab]), "Same value");
+			assert_noop!(Service::rotate_operator(Origin::signed(3), alice, bob, vec![crab, crab]), "Duplicate business");
+			assert_noop!(Service::rotate_operator(Origin::signed(3), bob, alice, vec![crab]), "Not authorized");
+
+			// A failing business leaves the others untouched
+			let unknown = (b"unknown").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_noop!(Service::rotate_operator(Origin::signed(3), alice, bob, vec![crab, unknown]), "Business does not exist");
+
+			assert_ok!(Service::rotate_operator(Origin::signed(3), alice, bob, vec![crab, lobster]));
+			assert_eq!(Service::business_of(crab).whitelist, vec![WhitelistEntryOf::<Test> { name_hash: bob, valid_until: Some(15) }]);
+			assert_eq!(Service::business_of(lobster).whitelist, vec![WhitelistEntryOf::<Test> { name_hash: bob, valid_until: None }]);
+			assert!(Service::businesses_by_operator(alice).is_empty());
+			assert_eq!(Service::businesses_by_operator(bob), vec![crab, lobster]);
+			// The rotated entries wait for the owners to confirm them, the old ones are gone
+			assert!(Service::is_suspended((crab, bob)));
+			assert!(Service::is_suspended((lobster, bob)));
+			assert!(!Service::is_suspended((crab, alice)));
+			assert!(!Service::is_suspended((lobster, alice)));
+			assert_eq!(Service::do_try_state(), Ok(()));
+			assert_ok!(Service::confirm_whitelist_member(Origin::signed(2), crab, bob));
+
+			// Back by the force origin, which keeps the suspension as it was
+			assert_noop!(Service::rotate_operator(Origin::signed(1), alice, bob, vec![crab]), "Not in the whitelist");
+			assert_ok!(Service::rotate_operator(Origin::signed(1), bob, alice, vec![crab]));
+			assert_eq!(Service::businesses_by_operator(alice), vec![crab]);
+			assert!(!Service::is_suspended((crab, alice)));
+
+			// Suspended businesses can not be rotated
+			assert_ok!(Service::suspend_business(Origin::signed(1), lobster, H256::zero()));
+			assert_noop!(Service::rotate_operator(Origin::signed(1), bob, alice, vec![lobster]), "Business is suspended");
+		});
+	}
+
+	#[test]
+	fn operator_writes_should_be_rate_limited() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("flood").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			for seq_id in 0..MaxWritesPerOperatorPerBlock::get() - 1 {
+				assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, seq_id.to_string().into(), data_hash.clone(), vec![]));
+			}
+			assert_ok!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "0".into(), InfoKind::Shipped, data_hash.clone(), vec![]));
+			assert_eq!(Service::operator_writes(alice, biz_hash), MaxWritesPerOperatorPerBlock::get());
+			assert_noop!(Service::create_product(Origin::signed(3), alice, biz_hash, "a".into(), data_hash.clone(), vec![]), "RateLimited");
+			assert_noop!(Service::add_product_info(Origin::signed(3), alice, biz_hash, "0".into(), InfoKind::Shipped, data_hash.clone(), vec![]), "RateLimited");
+
+			// The counts are cleared at the end of the block
+			Service::on_finalize(10);
+			System::set_block_number(11);
+			assert_eq!(Service::operator_writes(alice, biz_hash), 0);
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "a".into(), data_hash, vec![]));
+		});
+	}
+
+	#[test]
+	fn businesses_per_name_should_be_bounded() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let mut biz_hashes = vec![];
+			for i in 0..MaxBusinessesPerName::get() {
+				let name: Vec<u8> = format!("shop{}", i).into();
+				biz_hashes.push(Service::next_business_hash(1, owner_hash, name.clone()));
+				assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, name, 20));
+			}
+			assert_noop!(Service::create_business(Origin::signed(1), scope(), owner_hash, "shopx".into(), 20), "Too many businesses for the name");
+
+			for biz_hash in biz_hashes.iter() {
+				assert_ok!(Service::add_business_whitelist(Origin::signed(2), *biz_hash, alice, None));
+			}
+			let whale = Service::next_business_hash(1, alice, "whale".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), alice, "whale".into(), 20));
+			assert_noop!(Service::add_business_whitelist(Origin::signed(3), whale, alice, None), "Too many businesses for the name");
+			assert_noop!(Service::set_business_whitelist(Origin::signed(3), whale, vec![alice]), "Too many businesses for the name");
+
+			// The notification of a name covers at most the bound, and the name service weighs it so
+			let weight = <Service as name_service::OnResolveChanged<Test>>::max_weight();
+			assert_eq!(weight, <() as WeightInfo>::on_resolve_changed(MaxBusinessesPerName::get()));
+		});
+	}
+
+	#[test]
+	fn verifications_should_be_rate_limited() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("scan").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, "1".into(), data_hash.clone(), vec![]));
+
+			// An account may send `MaxVerificationsPerAccount` verifications per block
+			for _ in 0..MaxVerificationsPerAccount::get() {
+				assert_ok!(Service::verify_product(Origin::signed(5), biz_hash, "1".into(), data_hash.clone()));
+			}
+			assert_eq!(Service::account_verifications(5), MaxVerificationsPerAccount::get());
+			assert_eq!(Service::validate_verification(&5, biz_hash, "1".into(), &data_hash), Err("RateLimited"));
+			assert_noop!(Service::verify_product(Origin::signed(5), biz_hash, "1".into(), data_hash.clone()), "RateLimited");
+
+			// A block holds `MaxVerificationsPerBlock` verifications of all accounts
+			let mut who = 6;
+			while Service::block_verifications() < MaxVerificationsPerBlock::get() {
+				if Service::account_verifications(who) == MaxVerificationsPerAccount::get() {
+					who += 1;
+				}
+				assert_ok!(Service::verify_product(Origin::signed(who), biz_hash, "1".into(), data_hash.clone()));
+			}
+			assert_eq!(Service::validate_verification(&9, biz_hash, "1".into(), &data_hash), Err("Block verification limit reached"));
+			assert_noop!(Service::verify_product(Origin::signed(9), biz_hash, "1".into(), data_hash.clone()), "Block verification limit reached");
+
+			// The counts are cleared at the end of the block
+			Service::on_finalize(10);
+			System::set_block_number(11);
+			assert_eq!(Service::block_verifications(), 0);
+			assert_eq!(Service::account_verifications(5), 0);
+			assert_ok!(Service::verify_product(Origin::signed(5), biz_hash, "1".into(), data_hash));
+		});
+	}
+
+	#[test]
+	fn list_products_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let data_hash = DataHash::from_runtime_hash(&("label").using_encoded(<Test as system::Trait>::Hashing::hash));
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, None));
+			assert_eq!(Service::list_products(biz_hash, 0, 10), vec![]);
+			for seq_id in &["1", "2", "3"] {
+				assert_ok!(Service::create_product(Origin::signed(3), alice, biz_hash, (*seq_id).into(), data_hash.clone(), vec![]));
+			}
+
+			let product_hash = |seq_id: &str| Service::product_hash_of((biz_hash, seq_id.as_bytes().to_vec())).unwrap();
+			assert_eq!(Service::list_products(biz_hash, 0, 2), vec![product_hash("1"), product_hash("2")]);
+			assert_eq!(Service::list_products(biz_hash, 2, 2), vec![product_hash("3")]);
+			assert_eq!(Service::list_products(biz_hash, 5, 2), vec![]);
+			assert_eq!(Service::list_products(biz_hash, 0, u64::max_value()).len(), 3);
+		});
+	}
+
+	#[test]
+	fn business_info_should_work() {
+		new_test_ext().execute_with(|| {
+			let alice = (ALICE).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let owner_hash = (BISINESS_OWNER).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			System::set_block_number(10);
+			let biz_hash = Service::next_business_hash(1, owner_hash, "crab".into());
+			assert_eq!(Service::business_info(biz_hash), None);
+			assert_ok!(Service::create_business(Origin::signed(1), scope(), owner_hash, "crab".into(), 20));
+			assert_ok!(Service::add_business_whitelist(Origin::signed(2), biz_hash, alice, Some(15)));
+
+			let details = Service::business_info(biz_hash).unwrap();
+			assert_eq!(details.owner, owner_hash);
+			assert_eq!(details.owner_account, Some(2));
+			assert_eq!(details.name, b"crab".to_vec());
+			assert_eq!(details.expiration, 20);
+			assert_eq!(details.whitelist, vec![ResolvedWhitelistEntry {
+				name_hash: alice,
+				name: None,
+				account: Some(3),
+				valid_until: Some(15),
+			}]);
+		});
 	}
 
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+//! # Storage fees
+//!
+//! The deposit a product write takes from the pool of its business grows with the `extra` it
+//! stores, as decided by the `StorageFeePolicy` of the runtime. The deposit of an info is
+//! held with the product deposit and returned with it when the product is removed.
+//! `TieredStorageFee` is the standard policy: the first bytes of an `extra` are cheap, and
+//! the price of a byte doubles with each tier beyond, so a large JSON document costs its
+//! business far more than a few short fields without being refused.
+
+use rstd::marker::PhantomData;
+use sp_runtime::traits::{SimpleArithmetic, Zero};
+use support::traits::Get;
+
+/// Decide the deposit the `extra` of a product write takes
+pub trait StorageFeePolicy<Balance> {
+	/// Get the deposit of an `extra`, on top of the product deposit when the write creates
+	/// the product
+	///
+	/// @extra_len	the length of the extra information
+	fn extra_deposit(extra_len: usize) -> Balance;
+}
+
+/// No deposit for the `extra`, the product deposit only
+impl<Balance: Zero> StorageFeePolicy<Balance> for () {
+	fn extra_deposit(_extra_len: usize) -> Balance {
+		Zero::zero()
+	}
+}
+
+/// The standard policy: the first `CheapLength` bytes cost `ByteDeposit` each, and each
+/// following tier, as long as all the tiers before it, costs twice the byte price of the
+/// previous one. With 128 cheap bytes, bytes 128 to 255 cost twice as much, bytes 256 to 511
+/// four times as much, and so on.
+pub struct TieredStorageFee<ByteDeposit, CheapLength>(PhantomData<(ByteDeposit, CheapLength)>);
+
+impl<Balance, ByteDeposit, CheapLength> StorageFeePolicy<Balance> for TieredStorageFee<ByteDeposit, CheapLength> where
+	Balance: SimpleArithmetic + Copy,
+	ByteDeposit: Get<Balance>,
+	CheapLength: Get<u32>,
+{
+	/// Sum the byte prices of the tiers the `extra` spans
+	///
+	/// @extra_len	the length of the extra information
+	fn extra_deposit(extra_len: usize) -> Balance {
+		let mut price = ByteDeposit::get();
+		let mut deposit = Balance::zero();
+		let (mut start, mut end) = (0usize, (CheapLength::get() as usize).max(1));
+		while start < extra_len {
+			let bytes = extra_len.min(end) - start;
+			deposit = deposit.saturating_add(price.saturating_mul((bytes as u32).into()));
+			price = price.saturating_mul(2u32.into());
+			start = end;
+			end = end.saturating_mul(2);
+		}
+		deposit
+	}
+}
@@ -6,92 +6,846 @@
 //! ## Overview
 //!
 //! This module is for business registration and product records
+//!
+//! ### Business hashes
+//!
+//! A business is stored under a hash of its creator, owner, normalized name and the number
+//! of businesses the creator created before, so clients derive it with `business_hash`
+//! instead of reading it from the `BusinessCreated` event. `BusinessesByCreator` maps the
+//! creator and index back to the hash, also through the `business_hash_for` runtime API.
+//!
+//! ### Archiving
+//!
+//! Businesses which stay expired for longer than `ArchiveGracePeriod` blocks are moved
+//! into `ArchivedBusinesses` by an offchain worker. The worker scans at most
+//! `MaxArchiveScan` businesses per block and submits an unsigned `archive_businesses`
+//! call signed with a local archiver key, which must be one of the `Archivers`.
+//! The products of an archived business are deleted with it, the ones held in custody of
+//! other businesses included. The payload carries the number of products, which the
+//! weight of the call follows.
+//!
+//! ### Relayed writes
+//!
+//! Operators without funded accounts sign an `OperatorPayload` off-chain and let any
+//! account relay it through `create_product_signed` or `add_product_info_signed`. The
+//! signature is checked against the signer the payload names, who must be authorized for
+//! the operator name by the `AuthorizationProvider` like the sender of a direct write, and
+//! `OperatorNonce` prevents replaying a payload. The signed message binds the payload to
+//! the genesis hash of the chain, so it can not be replayed on another chain.
+//!
+//! ### Storage deposits
+//!
+//! Every product takes `ProductDeposit` from the deposit pool of its business, which the
+//! owner funds with `fund_business`. The deposit goes back to the pool when the owner
+//! removes the product with `remove_product`. The pool stays reserved on the account of
+//! the owner and is returned to it by `withdraw_business_funds` and when the business is
+//! archived.
+//!
+//! The `StorageFeePolicy` of the runtime adds a deposit for the `extra` of the product and
+//! of the infos its business appends, growing with its length in tiers, see
+//! [`fees`](./fees/index.html). It is held and returned with the product deposit. The infos
+//! a custodian appends to a product transferred to it take no such deposit, since the
+//! deposits of a product go back to the pool of the business which created it. A lot takes
+//! the product deposit and its infos the same deposit for their `extra`, held in
+//! `LotDeposits` and returned when the owner removes the lot with `remove_lot`.
+//!
+//! ### Custody transfers
+//!
+//! A product stays keyed by the business and sequence id it was created with, while its
+//! custody moves along the supply chain. An operator of the current custodian starts a
+//! hand-off with `transfer_product`, which completes at once if the destination owner
+//! pre-approved the source with `set_transfer_approval`, and otherwise waits for an
+//! operator of the destination to `accept_product_transfer`. The destination then refers
+//! to the product by the same sequence id, and every hand-off is recorded in `ProductCustody`.
+//!
+//! ### Hierarchical authorization
+//!
+//! A name hash authorizes the account it resolves to, or whichever accounts the
+//! `AuthorizationProvider` of the runtime accepts for it. The owner of a business may let
+//! `set_authorization_depth` extend this to the owners of the ancestor names, up to
+//! `MaxAuthorizationDepth` levels, so the owner of `acme` may act for `ops.acme` in the
+//! calls on that business.
+//!
+//! The standard provider resolves the name hashes through the resolve cache of the name
+//! service, so the resolve record of an operator writing many times in a block is decoded
+//! at its first write only.
+//!
+//! ### Resolve changes
+//!
+//! The name service notifies the module when a name resolves to a new address. The
+//! businesses owned by the name log `OwnerResolveChanged`. The businesses whitelisting it
+//! log `OperatorResolveChanged` or, if `OnOperatorResolveChanged` is `Suspend`, suspend the
+//! entry until the owner confirms it with `confirm_whitelist_member`. A name may own at most
+//! `MaxBusinessesPerName` businesses and operate for as many, so the notification has a
+//! bounded weight, which the name service adds to the calls changing the address.
+//!
+//! ### Operator rotation
+//!
+//! An operator whose name must be replaced, as its key is compromised, moves its entries to
+//! the new name with `rotate_operator`, signed by the account the old name resolves to or
+//! by the force origin. The entries of all the businesses listed are swapped or none is:
+//! each keeps its expiration, and each business logs `OperatorRotated`. Suspended businesses
+//! can not be rotated. As whoever holds the old key may rotate, the entries of a rotation
+//! signed by the old name stay suspended until each owner confirms them with
+//! `confirm_whitelist_member`; the force origin keeps their suspension as it was.
+//!
+//! ### Data hashes
+//!
+//! The data hash of a product info is a `DataHash`, tagged with the algorithm the off-chain
+//! document was hashed with, so verifiers can reproduce the digest. Its digest must have
+//! the length of the algorithm. Infos are anchored under their digest when it is a hash of
+//! the runtime hashing, and under the runtime hash of the encoded `DataHash`, algorithm
+//! included, otherwise.
+//!
+//! Product infos record the `timestamp` of the block next to its number, so auditors need
+//! no external data to date them.
+//!
+//! Each product commits its infos in `infos_root`, a binary blake2 Merkle root updated from
+//! the `InfoPeaks` of the product on each append and logged with `ProductInfosRootChanged`.
+//! A verifier checks a single info with the proof from `product_info_proof` instead of
+//! downloading the whole history, see `pistis_primitives::merkle`.
+//!
+//! An info repeating a data hash already in the history of its product usually reveals an
+//! integration bug or a replay. The owner of a business chooses with `set_dedup_policy`
+//! whether such infos are accepted, accepted with a `DuplicateDataHashAppended` event, or
+//! rejected with `DuplicateDataHash`.
+//!
+//! ### Structured extra
+//!
+//! The `extra` of an info is free-form, usually JSON, which the chain can not check. The
+//! owner of a business may register the key ids its infos use with `set_extra_schema`.
+//! The non-empty `extra` of the product and lot infos of the business must then be an
+//! `ExtraPayload::Structured`, its keys strictly increasing and allowed by the schema. The
+//! businesses without a schema keep accepting raw bytes.
+//!
+//! Each info records the format of its `extra` in `extra_version`, `EXTRA_VERSION_RAW` or
+//! `EXTRA_VERSION_STRUCTURED`, so readers decode it without looking for the prefix. The
+//! `LatestExtraVersion` constant tells clients the latest format the runtime records.
+//!
+//! ### Redaction
+//!
+//! Personal data recorded in the `extra` of a product info by mistake is removed with
+//! `redact_product_info`, by the business owner or the force origin. The `extra` is emptied
+//! and the hash of the original info, its Merkle leaf, kept as a tombstone in
+//! `RedactedInfos`: the `infos_root` and the proofs of the other infos stay valid, and an
+//! auditor holding the original info still checks it against the tombstone.
+//!
+//! ### Provenance commitments
+//!
+//! `provenance_commitment` returns a `VersionedProvenanceCommitment` of a product: the
+//! business name, the name hash of the owner, the Merkle root of its infos and the block it
+//! was read at. The reverse name of the owner is left out, as any account may claim one. Its
+//! SCALE encoding starts with the index of its layout, so that a bridge or a partner chain
+//! attesting Pistis provenance decodes the layouts it knows as new ones are added.
+//!
+//! ### Sequence ids
+//!
+//! The operators of a business provide the sequence ids of its products by default. The
+//! owner may let the chain number them with `set_seq_mode`: in `AutoIncrement` mode
+//! `create_product` takes an empty sequence id and assigns the decimal of a counter of the
+//! business, skipping the ids already taken, so integrators need not coordinate them. The
+//! assigned id is logged with `ProductCreated`. A write skips at most `MAX_SEQ_ID_SKIPS`
+//! taken ids and fails past them, leaving the counter as it was. Switching to
+//! `AutoIncrement` also moves the counter past as many taken ids, so the owner gets past a
+//! longer run of ids provided before by switching the mode again.
+//!
+//! ### Rate limiting
+//!
+//! An operator may write at most `MaxWritesPerOperatorPerBlock` products and infos to a
+//! business per block, so a compromised operator key can not flood it. The write reaching
+//! the cap logs `OperatorWriteLimitReached`, the further writes of the block fail with
+//! `RateLimited`. The counts are cleared in `on_finalize`.
+//!
+//! `OperatorStats` counts the products and infos each operator wrote to a business with the
+//! block of its last write, for the owner to audit through the `operator_stats` runtime
+//! API; a key writing far more than usual, or while the operator is idle, is likely stolen.
+//! The stats outlive whitelist removals and archiving.
+//!
+//! ### Registry digest
+//!
+//! `Digest` lists the businesses, products and lots changed in the block, up to
+//! `MaxDigestEntries` hashes, and is cleared by `on_initialize` of the next block. Light
+//! indexers read it at each block through the `registry_digest` runtime API instead of
+//! decoding all the events, and fall back to the events when it is `truncated`.
+//!
+//! ### Rich events
+//!
+//! The product and lot events are slim, logging the hashes and the kind only. With
+//! `RichEvents` set each recorded info is also logged in full with `InfoRecorded`, so
+//! indexers can rebuild the histories from events. `MaxExtraLength` bounds the events too.
+//!
+//! ### Bounded fields
+//!
+//! The names, sequence ids, lot ids and extras of the stored records and events are
+//! `BoundedName`, `BoundedSeqId` and `BoundedExtra`, which refuse to be built or decoded
+//! past fixed ceilings. The calls take plain bytes and check the configurable limits, which
+//! may only be lower than the ceilings, before bounding them.
+//!
+//! ### Lots
+//!
+//! Goods of a production lot share most of their provenance. An operator records it once
+//! on a lot with `create_lot` and `add_lot_info`, and adds the products held by the
+//! business to the lot with `set_product_lot`. `product_history` merges the lot infos into
+//! the infos of a product by block, so a `Recalled` info on a lot recalls all its products.
+//! Lot writes count against the write limit of the operator like product writes. A
+//! business has at most `MaxLotsPerBusiness` lots, deleted with it when it is archived, and
+//! the owner removes a lot no product belongs to with `remove_lot`.
+//!
+//! ### Recalls
+//!
+//! The owner recalls a product, a lot or the whole business with `issue_recall`, naming
+//! the recall notice by its hash. The recall is kept in `ActiveRecalls` under the product,
+//! lot or business hash, so a verification app checks it in one read, until the owner
+//! lifts it with `lift_recall`. `product_by_seq_id` appends a `Recalled` info to the
+//! products covered by a recall in force.
+//!
+//! ### Suspensions
+//!
+//! A regulator of the consortium suspends a misbehaving business with `suspend_business`
+//! through the force origin, naming the suspension notice by its hash. A suspended
+//! business accepts no product or lot write and no product removal, while its records stay
+//! readable. Its owner may answer with the hash of an appeal through `appeal_suspension`
+//! within `AppealPeriod` blocks, and the force origin lifts the suspension with
+//! `reinstate_business`.
+//!
+//! ### One-time codes
+//!
+//! Against counterfeits, an operator prints a one-time code on each item of a product and
+//! commits the Merkle root of their hashes with `commit_codes`. Leaves and inner nodes are
+//! hashed under distinct domain separators, see `code_leaf` and `code_node`, and the tree of
+//! `count` codes is `ceil(log2(count))` levels deep, so every proof has that length. A consumer or a verifier
+//! service scanning an item submits its code with the Merkle proof to `redeem_code`, which
+//! marks it spent in the `SpentCodes` bitmaps. A spent code submitted again logs
+//! `CodeReplayed` and counts in `ReplayedCodes`, revealing a cloned item.
+//!
+//! ### Verifications
+//!
+//! A field inspector checking a document against a product records the check with
+//! `verify_product`, which logs `ProductVerified` with whether the data hash is among the
+//! infos of the product and changes nothing else. The call is feeless, a mismatch costs the
+//! inspector `VerificationFee`, handed to `VerificationFees`, so the checks leave an
+//! on-chain trail without a cost per scan. A runtime should reject the verifications of
+//! unknown products and the mismatches the sender can not pay for before dispatch, see
+//! `validate_verification`. Since a rejected feeless call still takes block space, a block
+//! holds at most `MaxVerificationsPerBlock` verifications and an account may send at most
+//! `MaxVerificationsPerAccount` of them per block; `validate_verification` rejects the
+//! verifications over the limits before they reach a block. The counts are cleared in
+//! `on_finalize`.
+//!
+//! ### Profiles and categories
+//!
+//! The owner of a business may record the hash of its off-chain profile document and a
+//! `u16` category with `set_business_profile`. The categories are assigned by the
+//! consortium, zero standing for none. `CategoryBusinesses` lists the businesses not
+//! archived under each category by position, so marketplaces can group them without
+//! off-chain metadata, a page at a time with `businesses_by_category`.
+//!
+//! ### Sub-businesses
+//!
+//! The owner of a business may register sub-businesses under it, one per factory or brand,
+//! with `create_sub_business`. A sub-business records its `parent`, is listed in
+//! `SubBusinesses` and expires no later than its parent: shortening the expiration of the
+//! parent caps the expiration of its sub-businesses, and sub-businesses can not be nested.
+//! The sub-businesses of an archived parent are no longer capped.
+//!
+//! ### Owner councils
+//!
+//! The owner may hand a business to a council of owner names with `set_owner_council`. The
+//! owner calls are then rejected; each change is a `CouncilAction` the members approve with
+//! `approve_council_action`, applied by the approval reaching the threshold. The council
+//! replaces or dissolves itself the same way, dropping the pending approvals. The business
+//! expiration stays with the scope admin, and `fund_business` with the owner, as it reserves
+//! the balance of the sender.
+//!
+//! ### Scopes
+//!
+//! Several consortia may keep independent registries on one chain. Each is a scope in
+//! `Scopes`, registered by the force origin with `register_scope` under a hash and an
+//! admin name, and retired with `retire_scope`, after which no business is created in it
+//! while its businesses live on. A business records the scope it was created in, its
+//! sub-businesses inherit it. The businesses created before scopes belong to the `pistis`
+//! scope of `default_scope_hash`, administered by the name of the same hash.
+//!
+//! The scope admin tunes its scope with `set_scope_config`: the maximum extra info length
+//! and info count within `MaxExtraLength` and `MaxProductInfoCount`, the product deposit and
+//! the renewal price per block. The settings left unset fall back to the runtime constants.
+//! Extending the expiration of a business takes the renewal price of each block beyond the
+//! later of its expiration and the current block from its deposit pool, handed to
+//! `RenewalFees`; a scope without renewal price is renewed for free.
+//!
+//! ### Scope admin calls
+//!
+//! `create_business` and `set_business_expiration` may only be called by the admin of the
+//! scope, the account its admin name resolves to, which is controlled by governance. The
+//! runtime chooses their dispatch class and whether they pay fees with
+//! `AdminDispatchClass` and `AdminPaysFee`, so the administration of the consortium does
+//! not depend on the balance of the admin account. A runtime making them feeless should
+//! reject them from other senders before dispatch, see `validate_scope_admin`.
+//!
+//! The scope admin may let operational accounts create businesses in the scope with
+//! `set_admin_delegate`, optionally until a block, without moving the address the admin
+//! name resolves to. `validate_business_creator` accepts the admin and its delegates. A
+//! delegation records the admin account which set it and lapses once the admin name no
+//! longer authorizes that account, so the delegates of a former admin do not outlive it.
+//!
+//! ### Storage migration
+//!
+//! A chain upgraded from the first layout holds businesses and products without any index,
+//! so they can not be enumerated on-chain. `on_initialize` registers the default scope they
+//! belong to and bumps the `StorageVersion` once, and anyone migrates each business with
+//! `migrate_legacy_business`, given the business hash and the nonce it was derived from,
+//! which are those of the `BusinessCreated` events before the upgrade in creation order.
+//! Its products are migrated a page at a time, and the business is migrated and indexed
+//! with its last page.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use app_crypto::RuntimeAppPublic;
 use codec::{Decode, Encode};
-use primitives::H256;
-use rstd::prelude::*;
-use sp_runtime::traits::{EnsureOrigin, Hash, StaticLookup, Zero};
+use primitives::{H256, offchain::StorageKind};
+use rstd::{convert::TryFrom, prelude::*};
+use sp_runtime::{
+	RuntimeDebug,
+	traits::{CheckedAdd, EnsureOrigin, Hash, IdentifyAccount, Member, SaturatedConversion, Saturating, StaticLookup, Verify, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, ValidTransaction,
+	},
+};
 use support::{
-	decl_event, decl_module, decl_storage,
+	debug, decl_error, decl_event, decl_module, decl_storage,
 	dispatch::Result,
 	ensure,
-	traits::{Get, Randomness},
-	weights::SimpleDispatchInfo,
+	storage::unhashed,
+	traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, Randomness, ReservableCurrency, WithdrawReason},
+	weights::{DispatchClass, SimpleDispatchInfo, Weight},
+	Parameter,
 };
-use system::{ensure_root, ensure_signed};
+use system::{ensure_none, ensure_signed, offchain::SubmitUnsignedTransaction};
 use name_service::NameServiceResolver;
+use attestation::AttestationVerifier;
+use anchor::AnchorProvider;
+
+pub mod authorization;
+pub mod fees;
+pub mod weights;
+pub use authorization::{AuthorizationProvider, NameServiceAuthorization};
+pub use fees::{StorageFeePolicy, TieredStorageFee};
+pub use weights::WeightInfo;
+pub use pistis_primitives::{
+	BoundedExtra, BoundedName, BoundedSeqId, Business, BusinessDetails, DataHash, EXTRA_VERSION_RAW,
+	EXTRA_VERSION_STRUCTURED, ExtraPayload, HashAlgo, InfoKind, LATEST_EXTRA_VERSION, Lot, OperatorActivity,
+	Product, ProductInfo, ProvenanceCommitmentV1, RegistryDigest, ResolvedWhitelistEntry,
+	VersionedProvenanceCommitment, WhitelistEntry, merkle::{self, InfoProof},
+};
 
 #[cfg(test)]
 mod business_test;
 
-/// The business struct 
-#[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct Business<NameHash, AccountId, BlockNumber> {
-	/// The creator
-	pub creator: AccountId,
-	/// The name hash of the owner
-	pub owner: NameHash,
-	/// The name of business
-	pub name: Vec<u8>,
-	/// The whitelist account
-	pub whitelist: Vec<NameHash>,
-	/// The expiration of business 
-	pub expiration: BlockNumber,
-}
-
-/// The information of a product
-#[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct ProductInfo<Hash, AccountId, BlockNumber> {
-	/// Creator account
-	pub creator: AccountId,
-	/// Creation time
-	pub created_at: BlockNumber,
-	/// Hash of data
-	pub data_hash: Hash,
-	/// Extra information
-	pub extra: Vec<u8>, // JSON info for details
-}
-
-/// The product information
-#[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct Product<Hash, AccountId, BlockNumber> {
-	/// Sequence ID of the record
-	pub seq_id: Vec<u8>, 
-	/// Product info array
-	pub infos: Vec<ProductInfo<Hash, AccountId, BlockNumber>>,
+/// The key type of the archiver keys
+pub const KEY_TYPE: app_crypto::KeyTypeId = app_crypto::KeyTypeId(*b"pbiz");
+
+pub mod sr25519 {
+	mod app_sr25519 {
+		use app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, super::super::KEY_TYPE);
+	}
+
+	/// An archiver keypair using sr25519 as its crypto.
+	#[cfg(feature = "std")]
+	pub type ArchiverPair = app_sr25519::Pair;
+
+	/// An archiver signature using sr25519 as its crypto.
+	pub type ArchiverSignature = app_sr25519::Signature;
+
+	/// An archiver identifier using sr25519 as its crypto.
+	pub type ArchiverId = app_sr25519::Public;
+}
+
+/// The signing context of operator payloads relayed to `create_product_signed`
+pub const CREATE_PRODUCT_CONTEXT: &[u8] = b"pistis/create_product";
+
+/// The signing context of operator payloads relayed to `add_product_info_signed`
+pub const ADD_PRODUCT_INFO_CONTEXT: &[u8] = b"pistis/add_product_info";
+
+/// The domain separator of product hashes
+pub const PRODUCT_HASH_DOMAIN: &[u8] = b"pistis/product";
+
+/// The domain separator of lot hashes
+pub const LOT_HASH_DOMAIN: &[u8] = b"pistis/lot";
+
+/// The domain separator of business hashes
+pub const BUSINESS_HASH_DOMAIN: &[u8] = b"pistis/business";
+
+/// The domain separator of the leaves of code trees
+pub const CODE_LEAF_DOMAIN: &[u8] = b"pistis/code-leaf";
+
+/// The domain separator of the inner nodes of code trees
+pub const CODE_NODE_DOMAIN: &[u8] = b"pistis/code-node";
+
+/// The maximum length of a one-time code
+pub const MAX_CODE_LENGTH: usize = 64;
+
+/// The maximum depth of a code proof, enough for any `u32` code index
+pub const MAX_CODE_PROOF_DEPTH: usize = 32;
+
+/// The maximum members of an owner council
+pub const MAX_COUNCIL_SIZE: usize = 16;
+
+/// The maximum businesses `rotate_operator` swaps an operator in at once
+pub const MAX_ROTATED_BUSINESSES: usize = 64;
+
+/// The maximum product hashes `list_products` returns at once
+pub const MAX_PRODUCT_PAGE: u64 = 256;
+
+/// The maximum business hashes `businesses_by_category` returns at once
+pub const MAX_BUSINESS_PAGE: u64 = 256;
+
+/// The maximum keys the extra schema of a business may allow
+pub const MAX_EXTRA_SCHEMA_KEYS: usize = 64;
+
+/// The maximum products `migrate_legacy_business` migrates at once
+pub const MAX_LEGACY_PRODUCTS: u32 = 64;
+
+/// The maximum taken sequence ids `create_product` skips at once in `AutoIncrement` mode
+pub const MAX_SEQ_ID_SKIPS: u32 = 16;
+
+/// The local storage key under which the offchain worker keeps its scan cursor
+const ARCHIVE_CURSOR_KEY: &[u8] = b"pistis/business-archive-cursor";
+
+/// What a business does when the name of one of its operators resolves to a new address
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ResolveChangeAction {
+	/// Log a warning event only
+	Warn,
+	/// Suspend the whitelist entry until the owner confirms it
+	Suspend,
+}
+
+/// What a business does when an info repeats a data hash in the history of its product
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DedupPolicy {
+	/// Accept the info
+	Allow,
+	/// Accept the info and log a warning event
+	Warn,
+	/// Reject the info
+	Reject,
+}
+
+impl Default for DedupPolicy {
+	fn default() -> Self {
+		DedupPolicy::Allow
+	}
+}
+
+/// How the sequence ids of the products of a business are chosen
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SeqMode {
+	/// The operators provide the sequence ids
+	Provided,
+	/// The chain assigns the sequence ids from a counter of the business
+	AutoIncrement,
+}
+
+impl Default for SeqMode {
+	fn default() -> Self {
+		SeqMode::Provided
+	}
+}
+
+/// The owner names sharing the ownership of a business, approving the owner calls together
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct OwnerCouncil<NameHash> {
+	/// The name hashes of the members
+	pub members: Vec<NameHash>,
+	/// The number of member approvals an action needs
+	pub threshold: u32,
+}
+
+/// A change to a council owned business, applied once enough members approve it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum CouncilAction<Hash, BlockNumber> {
+	/// Add an operator to the whitelist, like `add_business_whitelist`
+	AddWhitelist(Hash, Option<BlockNumber>),
+	/// Remove an operator from the whitelist, like `remove_business_whitelist`
+	RemoveWhitelist(Hash),
+	/// Set the expiration of a whitelist entry, like `set_whitelist_expiration`
+	SetWhitelistExpiration(Hash, Option<BlockNumber>),
+	/// Replace the council, or give the business back to its owner with no members
+	SetCouncil(Vec<Hash>, u32),
+	/// Create a sub-business, like `create_sub_business` (owner, name, expiration)
+	CreateSubBusiness(Hash, Vec<u8>, BlockNumber),
+	/// Confirm a suspended whitelist entry, like `confirm_whitelist_member`
+	ConfirmWhitelistMember(Hash),
+	/// Set the credential schema of the operators, like `set_required_attestation`
+	SetRequiredAttestation(Option<Hash>),
+	/// Set the profile and the category, like `set_business_profile`
+	SetBusinessProfile(Option<Hash>, u16),
+	/// Pre-approve or revoke the transfers from a source business, like `set_transfer_approval`
+	SetTransferApproval(Hash, bool),
+	/// Set the authorization depth, like `set_authorization_depth`
+	SetAuthorizationDepth(u32),
+	/// Set the deduplication policy, like `set_dedup_policy`
+	SetDedupPolicy(DedupPolicy),
+	/// Set the sequence id mode, like `set_seq_mode`
+	SetSeqMode(SeqMode),
+	/// Set the allowed extra keys, like `set_extra_schema`
+	SetExtraSchema(Vec<u16>),
+	/// Redact the extra of a product info, like `redact_product_info` (sequence id, index)
+	RedactProductInfo(Vec<u8>, u32),
+	/// Issue a recall, like `issue_recall` (scope, reason hash)
+	IssueRecall(RecallScope, Hash),
+	/// Lift a recall, like `lift_recall`
+	LiftRecall(RecallScope),
+	/// Appeal the suspension of the business, like `appeal_suspension`
+	AppealSuspension(Hash),
+	/// Remove a lot without products, like `remove_lot`
+	RemoveLot(Vec<u8>),
+	/// Remove a product, like `remove_product`
+	RemoveProduct(Vec<u8>),
+}
+
+/// What a recall covers
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum RecallScope {
+	/// The product the business holds under a sequence id
+	Product(Vec<u8>),
+	/// All the products of a lot of the business
+	Lot(Vec<u8>),
+	/// All the products of the business
+	Business,
+}
+
+impl RecallScope {
+	/// Get the length of the sequence or lot id
+	pub fn id_len(&self) -> u32 {
+		match self {
+			RecallScope::Product(id) | RecallScope::Lot(id) => id.len() as u32,
+			RecallScope::Business => 0,
+		}
+	}
+}
+
+/// A recall in force on a product, a lot or a business
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Recall<Hash, AccountId, BlockNumber, Moment> {
+	/// The business which issued the recall
+	pub biz_hash: Hash,
+	/// The account which issued the recall
+	pub issuer: AccountId,
+	/// The block the recall was issued at
+	pub issued_at: BlockNumber,
+	/// The timestamp the recall was issued at
+	pub timestamp: Moment,
+	/// The hash of the recall notice
+	pub reason_hash: Hash,
+}
+
+/// The suspension of a business by the force origin
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Suspension<Hash, BlockNumber> {
+	/// The hash of the suspension notice
+	pub reason_hash: Hash,
+	/// The block the business was suspended at
+	pub suspended_at: BlockNumber,
+	/// The hash of the appeal of the owner, if any
+	pub appeal: Option<Hash>,
+}
+
+/// A delegation of business creation in a scope
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct AdminDelegation<AccountId, BlockNumber> {
+	/// The admin account which set the delegation, honoured while the admin name authorizes it
+	pub admin: AccountId,
+	/// The block from which the delegate may no longer create businesses, none for no limit
+	pub valid_until: Option<BlockNumber>,
+}
+
+/// A scope of businesses, the registry of one consortium
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct ScopeInfo<NameHash, Balance> {
+	/// The name whose resolved address administers the scope
+	pub admin: NameHash,
+	/// Whether the scope is retired, no business being created in it
+	pub retired: bool,
+	/// The settings of the scope overriding the runtime constants
+	pub config: ScopeConfig<Balance>,
+}
+
+/// The settings of a scope, none falling back to the runtime constant
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct ScopeConfig<Balance> {
+	/// The maximum length an extra info may be, at most `MaxExtraLength`
+	pub max_extra_length: Option<u32>,
+	/// The maximum info entries a product or lot may have, at most `MaxProductInfoCount`
+	pub max_product_info_count: Option<u32>,
+	/// The deposit a product takes from the pool of its business
+	pub product_deposit: Option<Balance>,
+	/// The fee for each block a business is renewed for
+	pub renewal_price_per_block: Option<Balance>,
+}
+
+/// The storage deposit pool of a business
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct DepositPool<AccountId, Balance> {
+	/// The account the pool is reserved from
+	pub funder: AccountId,
+	/// The reserved balance not taken by products yet
+	pub available: Balance,
+	/// The reserved balance taken by products
+	pub held: Balance,
+}
+
+/// A custody change of a product
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct CustodyRecord<Hash, AccountId, BlockNumber> {
+	/// The business which released the product
+	pub from_biz: Hash,
+	/// The business which took over the product
+	pub to_biz: Hash,
+	/// The operator account which released the product
+	pub released_by: AccountId,
+	/// The account which accepted or pre-approved the transfer
+	pub accepted_by: AccountId,
+	/// Transfer time
+	pub transferred_at: BlockNumber,
+}
+
+/// The one-time codes printed on the items of a product, committed as a Merkle root
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct CodeCommitment<Hash> {
+	/// The Merkle root of the hashed codes, in code index order
+	pub root: Hash,
+	/// The number of codes
+	pub count: u32,
 }
 
+/// A custody transfer waiting for the destination to accept
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct PendingTransfer<Hash, AccountId> {
+	/// The business releasing the product
+	pub from_biz: Hash,
+	/// The business to take over the product
+	pub to_biz: Hash,
+	/// The operator account which started the transfer
+	pub released_by: AccountId,
+}
+
+/// The product write an operator signs off-chain, to be relayed by another account.
+/// The signed message is the SCALE encoding of `(context, genesis_hash, payload)`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct OperatorPayload<AccountId, Hash> {
+	/// The account signing for the operator
+	pub signer: AccountId,
+	/// The name hash of the operator
+	pub name_hash: Hash,
+	/// The business hash
+	pub biz_hash: Hash,
+	/// The sequence id
+	pub seq_id: Vec<u8>,
+	/// The kind of the info, ignored on creation which always records `Created`
+	pub kind: InfoKind,
+	/// The data hash
+	pub data_hash: DataHash,
+	/// The extra information
+	pub extra: Vec<u8>,
+	/// The nonce of the operator, preventing replays
+	pub nonce: u64,
+}
+
+/// The payload of an archive transaction submitted by the offchain worker
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ArchivePayload<Hash, BlockNumber, Public> {
+	/// The block number at which the scan happened
+	pub block_number: BlockNumber,
+	/// The businesses to be archived
+	pub biz_hashes: Vec<Hash>,
+	/// The products of the businesses, which are deleted with them
+	pub products: u32,
+	/// The lots of the businesses, which are deleted with them
+	pub lots: u32,
+	/// The archiver key which signed the payload
+	pub public: Public,
+}
+
+/// The business layout of the first version, decoded by the storage migration only
+#[derive(Decode)]
+struct BusinessV1<NameHash, AccountId, BlockNumber> {
+	creator: AccountId,
+	owner: NameHash,
+	name: Vec<u8>,
+	whitelist: Vec<NameHash>,
+	expiration: BlockNumber,
+}
+
+/// The product info layout of the first version, decoded by the storage migration only
+#[derive(Decode)]
+struct ProductInfoV0<Hash, AccountId, BlockNumber> {
+	creator: AccountId,
+	created_at: BlockNumber,
+	data_hash: Hash,
+	extra: Vec<u8>,
+}
+
+/// The product layout of the first version, decoded by the storage migration only
+#[derive(Decode)]
+struct ProductV0<Hash, AccountId, BlockNumber> {
+	seq_id: Vec<u8>,
+	infos: Vec<ProductInfoV0<Hash, AccountId, BlockNumber>>,
+}
+
+/// The storage version the module migrates to
+const STORAGE_VERSION: u32 = 1;
+
 type NameHash<T> = <T as system::Trait>::Hash;
 type BusinessOf<T> = Business<NameHash<T>, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
-type ProductOf<T> = Product<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
-type ProductInfoOf<T> = ProductInfo<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+pub type BusinessDetailsOf<T> = BusinessDetails<NameHash<T>, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+pub type ProductOf<T> = Product<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, MomentOf<T>>;
+pub type ProductInfoOf<T> = ProductInfo<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, MomentOf<T>>;
+pub type ProvenanceCommitmentOf<T> = VersionedProvenanceCommitment<<T as system::Trait>::Hash, <T as system::Trait>::BlockNumber>;
+pub type LotOf<T> = Lot<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, MomentOf<T>>;
+type MomentOf<T> = <T as timestamp::Trait>::Moment;
+type BusinessV1Of<T> = BusinessV1<NameHash<T>, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type ScopeInfoOf<T> = ScopeInfo<NameHash<T>, BalanceOf<T>>;
+type ScopeConfigOf<T> = ScopeConfig<BalanceOf<T>>;
+type AdminDelegationOf<T> = AdminDelegation<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type WhitelistEntryOf<T> = WhitelistEntry<NameHash<T>, <T as system::Trait>::BlockNumber>;
+type ProductV0Of<T> = ProductV0<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+type DepositPoolOf<T> = DepositPool<<T as system::Trait>::AccountId, BalanceOf<T>>;
+type CustodyRecordOf<T> = CustodyRecord<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type PendingTransferOf<T> = PendingTransfer<<T as system::Trait>::Hash, <T as system::Trait>::AccountId>;
+type CodeCommitmentOf<T> = CodeCommitment<<T as system::Trait>::Hash>;
+type OwnerCouncilOf<T> = OwnerCouncil<NameHash<T>>;
+pub type CouncilActionOf<T> = CouncilAction<NameHash<T>, <T as system::Trait>::BlockNumber>;
+type SuspensionOf<T> = Suspension<<T as system::Trait>::Hash, <T as system::Trait>::BlockNumber>;
+pub type RecallOf<T> = Recall<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, MomentOf<T>>;
+type OperatorPayloadOf<T> = OperatorPayload<<T as system::Trait>::AccountId, <T as system::Trait>::Hash>;
+type ArchivePayloadOf<T> = ArchivePayload<<T as system::Trait>::Hash, <T as system::Trait>::BlockNumber, <T as Trait>::ArchiverId>;
 
-pub trait Trait: system::Trait {
+pub trait Trait: system::Trait + timestamp::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	
-	/// The origin which may forcibly set or remove a name. Root can always do this.
+	/// The origin which may forcibly set or remove a name. Root is not accepted
+	/// unless this origin accepts it.
 	type ForceOrigin: EnsureOrigin<Self::Origin>;
 
 	/// The minimum length a name may be.
 	type MinNameLength: Get<usize>;
 
-	/// The maximum length a name may be.
+	/// The maximum length a name may be, at most the bound of `BoundedName`.
 	type MaxNameLength: Get<usize>;
 
-	/// The maximum length a sequence id may be
+	/// The maximum length a sequence id may be, at most the bound of `BoundedSeqId`
 	type MaxSeqIDLength: Get<usize>;
 	
-	/// The maximum length an extra info may be
+	/// The maximum length an extra info may be, at most the bound of `BoundedExtra`
 	type MaxExtraLength: Get<usize>;
 
 	/// The maximum info entries a product may have
 	type MaxProductInfoCount: Get<usize>;
 
+	/// The maximum operators the whitelist of a business may hold
+	type MaxWhitelistSize: Get<usize>;
+
+	/// The currency the storage deposits are reserved in
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The deposit a product takes from the pool of its business
+	type ProductDeposit: Get<BalanceOf<Self>>;
+
+	/// The policy of the deposit the `extra` of a product write takes on top, `()` for none
+	type StorageFeePolicy: StorageFeePolicy<BalanceOf<Self>>;
+
+	/// The fee for each block a business is renewed for, taken from its deposit pool
+	type RenewalPricePerBlock: Get<BalanceOf<Self>>;
+
+	/// The handler of the renewal fees, usually the treasury
+	type RenewalFees: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The fee a verification which does not match costs its sender
+	type VerificationFee: Get<BalanceOf<Self>>;
+
+	/// The handler of the verification fees, usually the treasury
+	type VerificationFees: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
 	/// The name service resolver
 	type NameServiceResolver: NameServiceResolver<Self>; 
+
+	/// The provider deciding which accounts may act for a name hash, usually
+	/// `NameServiceAuthorization` over the name service resolver
+	type AuthorizationProvider: AuthorizationProvider<Self>;
+
+	/// The identifier type of an archiver key
+	type ArchiverId: Member + Parameter + RuntimeAppPublic + Default + Ord;
+
+	/// A dispatchable call type
+	type Call: From<Call<Self>>;
+
+	/// A transaction submitter for the archive transactions
+	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+
+	/// The number of blocks an expired business is kept before being archived
+	type ArchiveGracePeriod: Get<Self::BlockNumber>;
+
+	/// The maximum businesses the offchain worker may inspect per block
+	type MaxArchiveScan: Get<u32>;
+
+	/// The signature of operator payloads relayed by another account
+	type OperatorSignature: Verify<Signer = Self::OperatorSigner> + Parameter;
+
+	/// The signer of operator payloads, identifying the operator account
+	type OperatorSigner: IdentifyAccount<AccountId = Self::AccountId>;
+
+	/// The weight functions of the calls
+	type WeightInfo: WeightInfo;
+
+	/// The attestation verifier for the operators to be whitelisted
+	type AttestationVerifier: AttestationVerifier<NameHash<Self>, Self::Hash>;
+
+	/// The anchor registry the data hashes of product infos are recorded in
+	type Anchors: AnchorProvider<Self>;
+
+	/// The maximum ancestor levels a business may accept authorization from
+	type MaxAuthorizationDepth: Get<u32>;
+
+	/// What a business does when the name of one of its operators resolves to a new address
+	type OnOperatorResolveChanged: Get<ResolveChangeAction>;
+
+	/// The maximum products and infos an operator may write to a business per block
+	type MaxWritesPerOperatorPerBlock: Get<u32>;
+
+	/// The maximum feeless verifications a block may hold
+	type MaxVerificationsPerBlock: Get<u32>;
+
+	/// The maximum feeless verifications an account may send per block
+	type MaxVerificationsPerAccount: Get<u32>;
+
+	/// The dispatch class of the scope admin calls, `create_business` and `set_business_expiration`
+	type AdminDispatchClass: Get<DispatchClass>;
+
+	/// Whether the scope admin calls pay transaction fees
+	type AdminPaysFee: Get<bool>;
+
+	/// The maximum sub-businesses a business may have
+	type MaxSubBusinesses: Get<u32>;
+
+	/// The maximum businesses a name may own, and the maximum it may operate for
+	type MaxBusinessesPerName: Get<u32>;
+
+	/// The maximum lots a business may have
+	type MaxLotsPerBusiness: Get<u32>;
+
+	/// Whether the product and lot infos are also logged in full with `InfoRecorded`
+	type RichEvents: Get<bool>;
+
+	/// The maximum hashes the registry digest of a block holds
+	type MaxDigestEntries: Get<u32>;
+
+	/// The blocks the owner of a suspended business has to appeal
+	type AppealPeriod: Get<Self::BlockNumber>;
 }
 
 decl_storage! {
@@ -102,10 +856,137 @@ decl_storage! {
 		Products get(product_of):  map T::Hash => ProductOf<T>;
 		/// The counting table for business
 		ProductCount get(product_count): map T::Hash => u64;
-		/// The lookup table for querying hash of product info with business and index
-		BusinessProductIndex get(business_product_index): map (T::Hash, u64) => T::Hash;
-		/// The nonce for hashing
-		Nonce: u64;
+		/// The lookup table for querying hash of product info with business and index, keyed by
+		/// business first so the index of a business can be removed by prefix
+		BusinessProductIndex get(business_product_index): double_map T::Hash, twox_64_concat(u64) => T::Hash;
+		/// The lookup table for querying the hash of a product with its business and sequence id
+		ProductHashes get(product_hash_of): map (T::Hash, Vec<u8>) => Option<T::Hash>;
+		/// The positions of a product in the business and the creator product indices
+		ProductPositions get(product_positions): map T::Hash => (u64, u64);
+		/// The peaks of the Merkle tree of the infos of a product, updating its root on append
+		InfoPeaks get(info_peaks): map T::Hash => Vec<H256>;
+		/// The lookup table for businesses owned by a name hash
+		BusinessesByOwner get(businesses_by_owner): map NameHash<T> => Vec<T::Hash>;
+		/// The lookup table for businesses not archived whose whitelist holds a name hash
+		BusinessesByOperator get(businesses_by_operator): map NameHash<T> => Vec<T::Hash>;
+		/// The lookup table for businesses not archived by category and position, uncategorized
+		/// ones left out
+		CategoryBusinesses get(category_business): double_map u16, twox_64_concat(u64) => T::Hash;
+		/// The number of businesses indexed under a category
+		CategoryBusinessCount get(category_business_count): map u16 => u64;
+		/// The position of a business in the index of its category
+		CategoryPositions get(category_position): map T::Hash => u64;
+		/// The lookup table for the sub-businesses not archived of a business
+		SubBusinesses get(sub_businesses): map T::Hash => Vec<T::Hash>;
+		/// The production lots, whose infos are shared by their products
+		Lots get(lot_of): map T::Hash => Option<LotOf<T>>;
+		/// The deposits taken by a lot and its infos from the pool of its business
+		LotDeposits get(lot_deposit): map T::Hash => BalanceOf<T>;
+		/// The lots of a business, at most `MaxLotsPerBusiness`
+		BusinessLots get(business_lots): map T::Hash => Vec<T::Hash>;
+		/// The number of products belonging to a lot
+		LotProductCount get(lot_product_count): map T::Hash => u32;
+		/// The lot a product belongs to
+		ProductLot get(product_lot): map T::Hash => Option<T::Hash>;
+		/// The one-time codes committed for a product
+		ProductCodes get(product_codes): map T::Hash => Option<CodeCommitmentOf<T>>;
+		/// The bitmaps of the redeemed codes of a product, 64 code indices per word
+		SpentCodes get(spent_codes): double_map T::Hash, blake2_256(u32) => u64;
+		/// The number of redemptions of already redeemed codes of a product
+		ReplayedCodes get(replayed_codes): map T::Hash => u32;
+		/// What a business does when an info repeats a data hash of its product
+		DedupPolicies get(dedup_policy): map T::Hash => DedupPolicy;
+		/// How the businesses choose the sequence ids of their products
+		SeqModes get(seq_mode): map T::Hash => SeqMode;
+		/// The key ids the structured extra of the infos of a business may use, in order
+		ExtraSchemas get(extra_schema): map T::Hash => Option<Vec<u16>>;
+		/// The hashes of the product infos before their extra was redacted, by product and index
+		RedactedInfos get(redacted_info): double_map T::Hash, twox_64_concat(u32) => Option<H256>;
+		/// The counter the next sequence id of a business in `AutoIncrement` mode is assigned from
+		NextSeqIds get(next_seq_id): map T::Hash => u64;
+		/// The whitelist entries suspended until the owner of the business confirms them
+		SuspendedOperators get(is_suspended): map (T::Hash, NameHash<T>) => bool;
+		/// The counting table for products created by an account
+		CreatorProductCount get(creator_product_count): map T::AccountId => u64;
+		/// The lookup table for querying hash of product with creator and index
+		ProductsByCreator get(products_by_creator): map (T::AccountId, u64) => T::Hash;
+		/// The counting table for all the businesses ever created
+		BusinessCount get(business_count): u64;
+		/// The lookup table for querying business hash with index
+		BusinessIndex get(business_index): map u64 => T::Hash;
+		/// The lookup table for the archived businesses
+		ArchivedBusinesses get(archived_business_of): map T::Hash => Option<BusinessOf<T>>;
+		/// The storage deposit pools of the businesses
+		DepositPools get(deposit_pool): map T::Hash => Option<DepositPoolOf<T>>;
+		/// The deposit taken by a product from the pool of its business, with the extra deposits of its infos
+		ProductDeposits get(product_deposit): map T::Hash => BalanceOf<T>;
+		/// The current custodian of the products transferred away from their creating business
+		ProductCustodian get(product_custodian): map T::Hash => Option<T::Hash>;
+		/// The lookup table for products held by a business under a sequence id, by transfer
+		HeldProducts get(held_products): map (T::Hash, Vec<u8>) => Option<T::Hash>;
+		/// The custody changes of a product
+		ProductCustody get(product_custody): map T::Hash => Vec<CustodyRecordOf<T>>;
+		/// The transfers waiting for the destination to accept
+		PendingTransfers get(pending_transfer): map T::Hash => Option<PendingTransferOf<T>>;
+		/// The account of the destination owner who pre-approved transfers from a source business
+		TransferApprovals get(transfer_approval): map (T::Hash, T::Hash) => Option<T::AccountId>;
+		/// The nonce of the next payload an operator may sign off-chain
+		OperatorNonce get(operator_nonce): map NameHash<T> => u64;
+		/// The credential schema an operator must hold to be whitelisted by a business
+		RequiredAttestation get(required_attestation): map T::Hash => Option<T::Hash>;
+		/// The ancestor levels of a name whose owners may act for it in the calls on a business
+		AuthorizationDepth get(authorization_depth): map T::Hash => u32;
+		/// The keys allowed to sign archive transactions
+		Archivers get(archivers): Vec<T::ArchiverId>;
+		/// The number of businesses an account created, the index of its next business hash
+		CreatorBusinessCount get(creator_business_count): map T::AccountId => u64;
+		/// The hashes of the businesses an account created, by their index among them
+		BusinessesByCreator get(business_hash_for): double_map T::AccountId, twox_64_concat(u64) => Option<T::Hash>;
+		/// The version of the storage layout, bumped by the migrations in `on_initialize`. Chains
+		/// built from a genesis start at the current version.
+		StorageVersion get(storage_version) build(|_| STORAGE_VERSION): u32;
+		/// The position of the next product `migrate_legacy_business` migrates, by business
+		LegacyProductCursor get(legacy_product_cursor): map T::Hash => u64;
+		/// The products and infos an operator wrote to a business in the current block
+		OperatorWrites get(operator_writes): double_map NameHash<T>, blake2_256(T::Hash) => u32;
+		/// The operators which wrote in the current block, whose counts `on_finalize` clears
+		WritingOperators: Vec<NameHash<T>>;
+		/// The verifications recorded in the current block
+		BlockVerifications get(block_verifications): u32;
+		/// The verifications an account sent in the current block
+		AccountVerifications get(account_verifications): map T::AccountId => u32;
+		/// The accounts which verified in the current block, whose counts `on_finalize` clears
+		VerifyingAccounts: Vec<T::AccountId>;
+		/// The products and infos the operators wrote to a business, with the block of their last write
+		OperatorStats get(operator_stats): double_map T::Hash, blake2_256(NameHash<T>) => OperatorActivity<T::BlockNumber>;
+		/// The accounts the admin of a scope let create businesses in it, with the admin account
+		/// which let them
+		ScopeDelegates get(scope_delegate): double_map T::Hash, blake2_256(T::AccountId) => Option<AdminDelegationOf<T>>;
+		/// The scopes of businesses
+		Scopes get(scope_of): map T::Hash => Option<ScopeInfoOf<T>>;
+		/// The lookup table for the scopes administered by a name hash
+		ScopesByAdmin get(scopes_by_admin): map NameHash<T> => Vec<T::Hash>;
+		/// The owner councils of the businesses whose whitelist is managed by several owners
+		OwnerCouncils get(owner_council): map T::Hash => Option<OwnerCouncilOf<T>>;
+		/// The members which approved a pending council action, by business and action hash
+		CouncilApprovals get(council_approvals): double_map T::Hash, blake2_256(T::Hash) => Vec<NameHash<T>>;
+		/// The recalls in force, by the hash of the recalled product, lot or business
+		ActiveRecalls get(active_recall): map T::Hash => Option<RecallOf<T>>;
+		/// The suspensions in force, by business
+		Suspensions get(suspension_of): map T::Hash => Option<SuspensionOf<T>>;
+		/// The businesses, products and lots changed in the block, cleared by the next block
+		Digest get(registry_digest): RegistryDigest<T::Hash>;
+	}
+	add_extra_genesis {
+		/// The scopes set up at genesis: the scope hash and its admin name hash. The default
+		/// scope is always registered, administered by the name of its hash unless listed.
+		config(scopes): Vec<(T::Hash, NameHash<T>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (scope_hash, admin) in config.scopes.iter() {
+				<Module<T>>::insert_scope(*scope_hash, *admin);
+			}
+			<Module<T>>::ensure_default_scope();
+		});
 	}
 }
 
@@ -115,20 +996,145 @@ decl_event!(
 		BlockNumber = <T as system::Trait>::BlockNumber,
 		Hash = <T as system::Trait>::Hash,
 		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+		Moment = MomentOf<T>,
+		ProductInfo = ProductInfoOf<T>,
 	{
 		/// Business created
 		BusinessCreated(AccountId, Hash),
 		/// Business expiration changed
 		BusinessExpirationChanged(AccountId, Hash, BlockNumber),
-		/// Bisiness whitelist changed
+		/// Bisiness whitelist changed, deprecated in favour of `WhitelistMemberAdded` and
+		/// `WhitelistMemberRemoved`
 		BusinessWhitelistChanged(AccountId, Hash, Vec<Hash>),
-		/// Product info created
-		ProductCreated(AccountId, Hash, Vec<u8>, Hash),
-		/// Product info appended
-		ProductInfoAppended(AccountId, Hash, Vec<u8>, Hash),
+		/// Product info created (operator, business, sequence id, product, timestamp)
+		ProductCreated(AccountId, Hash, BoundedSeqId, Hash, Moment),
+		/// Product info appended (operator, business, sequence id, product, kind, timestamp)
+		ProductInfoAppended(AccountId, Hash, BoundedSeqId, Hash, InfoKind, Moment),
+		/// Business archived
+		BusinessArchived(Hash),
+		/// Business whitelist replaced (sender, business, added, removed)
+		BusinessWhitelistReplaced(AccountId, Hash, Vec<Hash>, Vec<Hash>),
+		/// Business whitelist entry expiration changed (sender, business, operator, valid until)
+		BusinessWhitelistExpirationChanged(AccountId, Hash, Hash, Option<BlockNumber>),
+		/// Business required attestation changed
+		BusinessAttestationChanged(AccountId, Hash, Option<Hash>),
+		/// Business profile changed (sender, business, profile, category)
+		BusinessProfileChanged(AccountId, Hash, Option<Hash>, u16),
+		/// Sub-business created (sender, parent, sub-business)
+		SubBusinessCreated(AccountId, Hash, Hash),
+		/// Product or lot info recorded in full, with `RichEvents` only (product or lot hash, info)
+		InfoRecorded(Hash, ProductInfo),
+		/// Lot created (sender, business, lot id, lot hash, timestamp)
+		LotCreated(AccountId, Hash, BoundedSeqId, Hash, Moment),
+		/// Lot info appended (sender, business, lot id, lot hash, kind, timestamp)
+		LotInfoAppended(AccountId, Hash, BoundedSeqId, Hash, InfoKind, Moment),
+		/// Product added to a lot (sender, product hash, lot hash)
+		ProductLotSet(AccountId, Hash, Hash),
+		/// Lot removed (sender, business, lot id, lot hash)
+		LotRemoved(AccountId, Hash, Vec<u8>, Hash),
+		/// One-time codes committed (sender, product hash, root, count)
+		CodesCommitted(AccountId, Hash, Hash, u32),
+		/// One-time code redeemed (sender, product hash, code index)
+		CodeRedeemed(AccountId, Hash, u32),
+		/// Redeemed code submitted again, revealing a cloned item (sender, product hash, code index)
+		CodeReplayed(AccountId, Hash, u32),
+		/// Product checked against a data hash (sender, product hash, data hash, matched)
+		ProductVerified(AccountId, Hash, DataHash, bool),
+		/// Scope admin delegate set (admin, scope, delegate, valid until)
+		AdminDelegateSet(AccountId, Hash, AccountId, Option<BlockNumber>),
+		/// Scope admin delegate removed (admin, scope, delegate)
+		AdminDelegateRemoved(AccountId, Hash, AccountId),
+		/// Scope registered (scope, admin)
+		ScopeRegistered(Hash, Hash),
+		/// Scope retired
+		ScopeRetired(Hash),
+		/// Business suspended by the force origin (business, reason hash)
+		BusinessSuspended(Hash, Hash),
+		/// Suspension appealed by the owner (sender, business, appeal hash)
+		SuspensionAppealed(AccountId, Hash, Hash),
+		/// Business reinstated by the force origin (business, reason hash)
+		BusinessReinstated(Hash, Hash),
+		/// Scope settings changed (admin, scope)
+		ScopeConfigChanged(AccountId, Hash),
+		/// Business renewal charged to its deposit pool (business, fee)
+		RenewalCharged(Hash, Balance),
+		/// Recall issued (sender, business, scope, recalled hash, reason hash)
+		RecallIssued(AccountId, Hash, RecallScope, Hash, Hash),
+		/// Recall lifted (sender, business, scope, recalled hash)
+		RecallLifted(AccountId, Hash, RecallScope, Hash),
+		/// Owner council of a business set, no members for none (sender, business, members, threshold)
+		OwnerCouncilSet(AccountId, Hash, Vec<Hash>, u32),
+		/// Council action approved by a member (sender, business, member, action hash, approvals)
+		CouncilActionApproved(AccountId, Hash, Hash, Hash, u32),
+		/// Council action reached the threshold and was applied (business, action hash)
+		CouncilActionExecuted(Hash, Hash),
+		/// Business data hash deduplication policy changed (sender, business, policy)
+		DedupPolicyChanged(AccountId, Hash, DedupPolicy),
+		/// Business sequence id mode changed (sender, business, mode)
+		SeqModeChanged(AccountId, Hash, SeqMode),
+		/// Business extra schema changed, no keys removing it (sender, business, allowed keys)
+		ExtraSchemaChanged(AccountId, Hash, Vec<u16>),
+		/// Product info extra redacted (business, sequence id, index, hash of the original info)
+		ProductInfoRedacted(Hash, BoundedSeqId, u32, H256),
+		/// Product info repeating a data hash of the product appended (sender, business, product hash)
+		DuplicateDataHashAppended(AccountId, Hash, Hash),
+		/// Product transfer waiting for acceptance (sender, product hash, from, to)
+		ProductTransferRequested(AccountId, Hash, Hash, Hash),
+		/// Product custody transferred (accepting account, product hash, from, to)
+		ProductTransferred(AccountId, Hash, Hash, Hash),
+		/// Pending product transfer cancelled
+		ProductTransferCancelled(AccountId, Hash),
+		/// Transfer pre-approval changed (sender, destination, source, approved)
+		TransferApprovalChanged(AccountId, Hash, Hash, bool),
+		/// Operator added to a business whitelist (sender, business, operator)
+		WhitelistMemberAdded(AccountId, Hash, Hash),
+		/// Operator removed from a business whitelist (sender, business, operator)
+		WhitelistMemberRemoved(AccountId, Hash, Hash),
+		/// Business deposit pool funded (funder, business, amount)
+		BusinessFunded(AccountId, Hash, Balance),
+		/// Business deposit pool withdrawn (funder, business, amount)
+		BusinessFundsWithdrawn(AccountId, Hash, Balance),
+		/// Product removed (sender, business, sequence id, product hash)
+		ProductRemoved(AccountId, Hash, Vec<u8>, Hash),
+		/// Business authorization depth changed (sender, business, depth)
+		BusinessAuthorizationDepthChanged(AccountId, Hash, u32),
+		/// The owner name of a business resolves to a new address (business, owner)
+		OwnerResolveChanged(Hash, Hash),
+		/// An operator name of a business resolves to a new address (business, operator)
+		OperatorResolveChanged(Hash, Hash),
+		/// Whitelist entry suspended as its operator resolves to a new address (business, operator)
+		WhitelistMemberSuspended(Hash, Hash),
+		/// Suspended whitelist entry confirmed (sender, business, operator)
+		WhitelistMemberConfirmed(AccountId, Hash, Hash),
+		/// An operator reached its write limit on a business for the block (business, operator)
+		OperatorWriteLimitReached(Hash, Hash),
+		/// The Merkle root of the infos of a product changed (product hash, root)
+		ProductInfosRootChanged(Hash, H256),
+		/// An operator moved to a new name in the whitelist of a business (business, old name hash, new name hash)
+		OperatorRotated(Hash, Hash, Hash),
+		/// The storage migration is over (storage version)
+		StorageMigrated(u32),
+		/// A business created before the enumeration indices migrated and indexed (business)
+		LegacyBusinessMigrated(Hash),
 	}
 );
 
+decl_error! {
+	/// Errors of product creation
+	pub enum Error {
+		/// The business already used the sequence id
+		DuplicateSeqId,
+		/// The product hash of a new sequence id is taken, which should not happen
+		ProductHashCollision,
+		/// The operator reached its write limit on the business for the block, or the sender
+		/// its verification limit
+		RateLimited,
+		/// The data hash is already in the history of the product
+		DuplicateDataHash,
+	}
+}
+
 decl_module! {
 	// Simple declaration of the `Module` type. Lets the macro know what it's working on.
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
@@ -149,59 +1155,127 @@ decl_module! {
 		/// The maximum info entries a product may have
 		const MaxProductInfoCount: u32 = T::MaxProductInfoCount::get() as u32;
 
+		/// The maximum operators the whitelist of a business may hold
+		const MaxWhitelistSize: u32 = T::MaxWhitelistSize::get() as u32;
+
+		/// The deposit a product takes from the pool of its business
+		const ProductDeposit: BalanceOf<T> = T::ProductDeposit::get();
+
+		/// The latest format of the `extra` of the infos the runtime records.
+		const LatestExtraVersion: u8 = LATEST_EXTRA_VERSION;
+
+		/// The fee for each block a business is renewed for
+		const RenewalPricePerBlock: BalanceOf<T> = T::RenewalPricePerBlock::get();
+
+		/// The fee a verification which does not match costs its sender
+		const VerificationFee: BalanceOf<T> = T::VerificationFee::get();
+
+		/// The maximum ancestor levels a business may accept authorization from
+		const MaxAuthorizationDepth: u32 = T::MaxAuthorizationDepth::get();
+
+		/// The blocks the owner of a suspended business has to appeal.
+		const AppealPeriod: T::BlockNumber = T::AppealPeriod::get();
+
+		/// The maximum products and infos an operator may write to a business per block
+		const MaxWritesPerOperatorPerBlock: u32 = T::MaxWritesPerOperatorPerBlock::get();
+
+		/// The maximum feeless verifications a block may hold
+		const MaxVerificationsPerBlock: u32 = T::MaxVerificationsPerBlock::get();
+
+		/// The maximum feeless verifications an account may send per block
+		const MaxVerificationsPerAccount: u32 = T::MaxVerificationsPerAccount::get();
+
+		/// The maximum sub-businesses a business may have
+		const MaxSubBusinesses: u32 = T::MaxSubBusinesses::get();
+
+		/// The maximum businesses a name may own, and the maximum it may operate for
+		const MaxBusinessesPerName: u32 = T::MaxBusinessesPerName::get();
+
+		/// The maximum lots a business may have
+		const MaxLotsPerBusiness: u32 = T::MaxLotsPerBusiness::get();
+
 		/// Create business 
 		/// 
 		/// @origin	the sender
+		/// @scope_hash	the scope to create the business in
 		/// @owner	the hash of the owner name
 		/// @name	the business name in utf8
 		/// @expiration	the expiration height
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn create_business(origin, owner: NameHash<T>, name: Vec<u8>, expiration: T::BlockNumber) {
+		#[weight = FunctionOf(|(_, _, name, _): (&T::Hash, &NameHash<T>, &Vec<u8>, &T::BlockNumber)| T::WeightInfo::create_business(name.len() as u32), T::AdminDispatchClass::get(), T::AdminPaysFee::get())]
+		fn create_business(origin, scope_hash: T::Hash, owner: NameHash<T>, name: Vec<u8>, expiration: T::BlockNumber) {
 			let sender = ensure_signed(origin)?;
 			// Check if sender has previledge
-			Self::validate_authorization(&sender, Self::scope_name_hash())?;
+			Self::validate_business_creator(&sender, scope_hash)?;
 
-			ensure!(name.len() >= T::MinNameLength::get(), "Name too short");
-			ensure!(name.len() <= T::MaxNameLength::get(), "Name too long");
+			Self::do_create_business(sender, scope_hash, owner, name, expiration, None)?;
+		}
 
-			Self::validate_expiration(expiration)?;
+		/// Create a sub-business under a business, expiring no later than its parent
+		///
+		/// @origin	the sender
+		/// @parent_biz_hash	the hash of the parent business
+		/// @owner	the hash of the owner name
+		/// @name	the business name in utf8
+		/// @expiration	the expiration height
+		#[weight = FunctionOf(
+			|(_, _, name, _): (&T::Hash, &NameHash<T>, &Vec<u8>, &T::BlockNumber)|
+				T::WeightInfo::create_sub_business(name.len() as u32, T::MaxSubBusinesses::get()),
+			DispatchClass::Normal,
+			true
+		)]
+		fn create_sub_business(origin, parent_biz_hash: T::Hash, owner: NameHash<T>, name: Vec<u8>, expiration: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
 
-			// Generate hash for business
-			let nonce = Nonce::get();
-			let biz_hash = Self::business_hash(sender.clone(), owner);
-			let business = BusinessOf::<T> {
-				creator: sender.clone(),
-				owner: owner, 
-				name: name.clone(),
-				whitelist: Vec::new(),
-				expiration: expiration,
-			};
+			ensure!(<Businesses<T>>::exists(parent_biz_hash), "Business does not exist");
+			let parent = Self::business_of(parent_biz_hash);
+			Self::validate_owner(&sender, parent_biz_hash, &parent)?;
 
-			Self::insert_business(biz_hash, &business)?;
-			Self::deposit_event(RawEvent::BusinessCreated(sender.clone(), biz_hash));
-			// Change nonce value to introduce random value
-			Nonce::mutate(|n| *n += 1);
+			Self::do_create_sub_business(sender, parent_biz_hash, parent, owner, name, expiration)?;
 		}
 
-		/// Set expiration of business
+		/// Set expiration of business, capping the expiration of its sub-businesses
 		/// 
 		/// @origin 	the sender
 		/// @biz_hash	the business hash
 		/// @expiration	the expiration height 
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		#[weight = FunctionOf(
+			|_: (&T::Hash, &T::BlockNumber)| T::WeightInfo::set_business_expiration(
+				T::MaxWhitelistSize::get() as u32,
+				T::MaxSubBusinesses::get(),
+			),
+			T::AdminDispatchClass::get(),
+			T::AdminPaysFee::get()
+		)]
 		fn set_business_expiration(origin, biz_hash: T::Hash, expiration: T::BlockNumber) {
 			let sender = ensure_signed(origin)?;
-			Self::validate_authorization(&sender, Self::scope_name_hash())?;
 
 			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
 			let mut business = Self::business_of(biz_hash);
+			Self::validate_scope_admin(&sender, business.scope)?;
 			// FIXME: unnecessary? 
 			Self::validate_expiration(expiration)?;
 			ensure!(business.expiration != expiration, "Same value");
+			// An archived parent no longer caps its sub-businesses
+			if let Some(parent) = business.parent.filter(|parent| <Businesses<T>>::exists(parent)) {
+				ensure!(expiration <= Self::business_of(parent).expiration, "Expiration beyond parent");
+			}
+			Self::charge_renewal(biz_hash, &business, expiration)?;
 			business.expiration = expiration;
 			<Businesses<T>>::insert(biz_hash, business);
+			Self::touch_business(biz_hash);
 
 			Self::deposit_event(RawEvent::BusinessExpirationChanged(sender.clone(), biz_hash, expiration));	
+
+			// Sub-businesses may not outlive their parent
+			for child_hash in Self::sub_businesses(biz_hash) {
+				let mut child = Self::business_of(child_hash);
+				if child.expiration > expiration {
+					child.expiration = expiration;
+					<Businesses<T>>::insert(child_hash, child);
+					Self::touch_business(child_hash);
+					Self::deposit_event(RawEvent::BusinessExpirationChanged(sender.clone(), child_hash, expiration));
+				}
+			}
 		}
 
 		/// Add a name hash to the whitelist for a business
@@ -209,130 +1283,2718 @@ decl_module! {
 		/// @origin	the sender
 		/// @biz_hash	the business hash
 		/// @name_hash	the name hash of operator 
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn add_business_whitelist(origin, biz_hash: T::Hash, name_hash: NameHash<T>) {
+		/// @valid_until	the block from which the operator may no longer write, none for no limit
+		#[weight = FunctionOf(|_: (&T::Hash, &NameHash<T>, &Option<T::BlockNumber>)| T::WeightInfo::add_business_whitelist(T::MaxWhitelistSize::get() as u32), DispatchClass::Normal, true)]
+		fn add_business_whitelist(origin, biz_hash: T::Hash, name_hash: NameHash<T>, valid_until: Option<T::BlockNumber>) {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
-			let mut business = Self::business_of(biz_hash);
-			Self::validate_authorization(&sender, business.owner)?;
-
-			ensure!(!business.whitelist.contains(&name_hash), "Already in the whitelist");
-			business.whitelist.push(name_hash);
-			let new_list = business.whitelist.clone();
-			<Businesses<T>>::insert(biz_hash, business);
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
 
-			Self::deposit_event(RawEvent::BusinessWhitelistChanged(sender.clone(), biz_hash, new_list));	
+			Self::do_add_whitelist(sender, biz_hash, business, name_hash, valid_until)?;
 		}
 
-		/// Remove a namehash from the whitelist for a business
+		/// Replace the whole whitelist of a business. The operators kept keep their expiration,
+		/// the operators added have none.
 		///
 		/// @origin	the sender
 		/// @biz_hash	the business hash
-		/// @name_hash	the name hash of operator to be removed
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn remove_business_whitelist(origin, biz_hash: T::Hash, name_hash: NameHash<T>) {
+		/// @list	the name hashes of the operators
+		#[weight = FunctionOf(
+			|(_, list): (&T::Hash, &Vec<NameHash<T>>)| T::WeightInfo::set_business_whitelist(T::MaxWhitelistSize::get() as u32, list.len() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_business_whitelist(origin, biz_hash: T::Hash, list: Vec<NameHash<T>>) {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
 			let mut business = Self::business_of(biz_hash);
-			Self::validate_authorization(&sender, business.owner)?;
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			ensure!(list.len() <= T::MaxWhitelistSize::get(), "Exceeds max whitelist size");
+			let mut sorted = list.clone();
+			sorted.sort();
+			sorted.dedup();
+			ensure!(sorted.len() == list.len(), "Duplicate in the whitelist");
 
-			ensure!(business.whitelist.contains(&name_hash), "Not in the whitelist");
-			business.whitelist.retain(|o| o != &name_hash);
-			let new_list = business.whitelist.clone();
+			let old_list = Self::whitelist_names(&business);
+			let added: Vec<NameHash<T>> = list.iter().filter(|h| !old_list.contains(h)).cloned().collect();
+			let removed: Vec<NameHash<T>> = old_list.iter().filter(|h| !list.contains(h)).cloned().collect();
+			ensure!(!added.is_empty() || !removed.is_empty(), "Same value");
+			if let Some(schema) = Self::required_attestation(biz_hash) {
+				for name_hash in added.iter() {
+					ensure!(T::AttestationVerifier::has_valid_attestation(name_hash, &schema), "Missing required attestation");
+				}
+			}
+			for name_hash in added.iter() {
+				Self::validate_operator_capacity(name_hash, 1)?;
+			}
+
+			let old_entries = business.whitelist;
+			business.whitelist = list.into_iter().map(|name_hash| {
+				old_entries.iter()
+					.find(|e| e.name_hash == name_hash)
+					.cloned()
+					.unwrap_or(WhitelistEntryOf::<T> { name_hash: name_hash, valid_until: None })
+			}).collect();
 			<Businesses<T>>::insert(biz_hash, business);
+			Self::touch_business(biz_hash);
+			for name_hash in removed.iter() {
+				Self::unindex_operator(biz_hash, *name_hash);
+			}
+			for name_hash in added.iter() {
+				Self::index_operator(biz_hash, *name_hash);
+			}
 
-			Self::deposit_event(RawEvent::BusinessWhitelistChanged(sender.clone(), biz_hash, new_list));	
+			for name_hash in removed.iter() {
+				Self::deposit_event(RawEvent::WhitelistMemberRemoved(sender.clone(), biz_hash, *name_hash));
+			}
+			for name_hash in added.iter() {
+				Self::deposit_event(RawEvent::WhitelistMemberAdded(sender.clone(), biz_hash, *name_hash));
+			}
+			Self::deposit_event(RawEvent::BusinessWhitelistReplaced(sender, biz_hash, added, removed));
 		}
 
-		/// Create product for a business
-		/// 
+		/// Set the expiration of a whitelist entry, to extend or end the engagement of an operator
+		///
 		/// @origin	the sender
-		/// @name_hash	the name hash of the operator
 		/// @biz_hash	the business hash
-		/// @seq_id	the sequence id, should be unique within the business scope
-		/// @data_hash	the data hash to be stored with the product
-		/// @extra	the extra information, can be json string 
-		#[weight = SimpleDispatchInfo::FixedNormal(150_000)]
-		fn create_product(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: T::Hash, extra: Vec<u8>) {
+		/// @name_hash	the name hash of operator
+		/// @valid_until	the block from which the operator may no longer write, none for no limit
+		#[weight = FunctionOf(|_: (&T::Hash, &NameHash<T>, &Option<T::BlockNumber>)| T::WeightInfo::set_whitelist_expiration(T::MaxWhitelistSize::get() as u32), DispatchClass::Normal, true)]
+		fn set_whitelist_expiration(origin, biz_hash: T::Hash, name_hash: NameHash<T>, valid_until: Option<T::BlockNumber>) {
 			let sender = ensure_signed(origin)?;
 
-			Self::validate_authorization(&sender, name_hash)?;
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_whitelist_expiration(sender, biz_hash, business, name_hash, valid_until)?;
+		}
+
+		/// Confirm a whitelist entry suspended as its operator resolves to a new address
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @name_hash	the name hash of operator
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::confirm_whitelist_member())]
+		fn confirm_whitelist_member(origin, biz_hash: T::Hash, name_hash: NameHash<T>) {
+			let sender = ensure_signed(origin)?;
 
 			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
 			let business = Self::business_of(biz_hash);
-			ensure!(business.whitelist.contains(&name_hash), "Not in the whitelist");
-			
-			Self::validate_expiration(business.expiration)?;
-			ensure!(seq_id.len() <= T::MaxSeqIDLength::get(), "Sequence ID too long");
-			ensure!(extra.len() <= T::MaxExtraLength::get(), "Extra info too long");
-			// FIXME: what if the product hash collides?
-			let product_hash = Self::product_hash(biz_hash, seq_id.clone());
-			let info = ProductInfoOf::<T> {
-				creator: sender.clone(),
-				created_at: Self::block_number(),
-				data_hash: data_hash,
-				extra: extra.clone(),
-			};
+			Self::validate_owner(&sender, biz_hash, &business)?;
 
-			let product = ProductOf::<T> {
-				seq_id: seq_id.clone(),
-				infos: vec![info],
+			Self::do_confirm_whitelist_member(sender, biz_hash, name_hash)?;
+		}
+
+		/// Move an operator to a new name in the whitelists of businesses, when the key of its
+		/// name is compromised. Either every business is updated or none is.
+		///
+		/// @origin	the account the old name resolves to, or the force origin
+		/// @old_name_hash	the name hash the operator is whitelisted under
+		/// @new_name_hash	the name hash to whitelist the operator under
+		/// @businesses	the businesses whitelisting the old name hash
+		#[weight = FunctionOf(
+			|(_, _, businesses): (&NameHash<T>, &NameHash<T>, &Vec<T::Hash>)| T::WeightInfo::rotate_operator(businesses.len() as u32, T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn rotate_operator(origin, old_name_hash: NameHash<T>, new_name_hash: NameHash<T>, businesses: Vec<T::Hash>) {
+			let sender = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
 			};
+			if let Some(sender) = &sender {
+				Self::validate_authorization(sender, old_name_hash)?;
+			}
+
+			ensure!(old_name_hash != new_name_hash, "Same value");
+			ensure!(!businesses.is_empty(), "No business to rotate");
+			ensure!(businesses.len() <= MAX_ROTATED_BUSINESSES, "Too many businesses");
+			let mut sorted = businesses.clone();
+			sorted.sort();
+			sorted.dedup();
+			ensure!(sorted.len() == businesses.len(), "Duplicate business");
+			Self::validate_operator_capacity(&new_name_hash, businesses.len())?;
+
+			// Check every business before writing any
+			let mut rotated = Vec::with_capacity(businesses.len());
+			for biz_hash in businesses {
+				ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+				Self::validate_not_suspended(biz_hash)?;
+				let mut business = Self::business_of(biz_hash);
+				ensure!(!business.whitelist.iter().any(|e| e.name_hash == new_name_hash), "Already in the whitelist");
+				if let Some(schema) = Self::required_attestation(biz_hash) {
+					ensure!(T::AttestationVerifier::has_valid_attestation(&new_name_hash, &schema), "Missing required attestation");
+				}
+				let entry = business.whitelist.iter_mut()
+					.find(|e| e.name_hash == old_name_hash)
+					.ok_or("Not in the whitelist")?;
+				entry.name_hash = new_name_hash;
+				rotated.push((biz_hash, business));
+			}
 
-			Self::insert_product(biz_hash, product_hash, &product)?;
-			Self::deposit_event(RawEvent::ProductCreated(sender.clone(), biz_hash, seq_id.clone(), product_hash));	
+			for (biz_hash, business) in rotated {
+				// The old key may be the compromised one, so the owner confirms the new name
+				let suspended = sender.is_some() || Self::is_suspended((biz_hash, old_name_hash));
+				<Businesses<T>>::insert(biz_hash, business);
+				Self::touch_business(biz_hash);
+				// Drops the old name with its suspension
+				Self::unindex_operator(biz_hash, old_name_hash);
+				Self::index_operator(biz_hash, new_name_hash);
+				if suspended {
+					<SuspendedOperators<T>>::insert((biz_hash, new_name_hash), true);
+				}
+				Self::deposit_event(RawEvent::OperatorRotated(biz_hash, old_name_hash, new_name_hash));
+			}
 		}
 
-		/// Add product info for a business
-		/// 
+		/// Remove a namehash from the whitelist for a business
+		///
 		/// @origin	the sender
-		/// @name_hash	the name hash of the operator
 		/// @biz_hash	the business hash
-		/// @seq_id	the sequence id, should be unique within the business scope
-		/// @data_hash	the data hash to be stored with the product
-		/// @extra	the extra information, can be json string 
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn add_product_info(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: T::Hash, extra: Vec<u8>) {
+		/// @name_hash	the name hash of operator to be removed
+		#[weight = FunctionOf(|_: (&T::Hash, &NameHash<T>)| T::WeightInfo::remove_business_whitelist(T::MaxWhitelistSize::get() as u32), DispatchClass::Normal, true)]
+		fn remove_business_whitelist(origin, biz_hash: T::Hash, name_hash: NameHash<T>) {
 			let sender = ensure_signed(origin)?;
 
-			Self::validate_authorization(&sender, name_hash)?;
-
 			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
 			let business = Self::business_of(biz_hash);
-			ensure!(business.whitelist.contains(&name_hash), "Not in the whitelist");
-			
-			Self::validate_expiration(business.expiration)?;
-			ensure!(seq_id.len() <= T::MaxSeqIDLength::get(), "Sequence ID too long");
-			ensure!(extra.len() <= T::MaxExtraLength::get(), "Extra info too long");
-			// FIXME: what if the info hash collides?
-			let product_hash = Self::product_hash(biz_hash, seq_id.clone());
-			let info = ProductInfoOf::<T> {
-				creator: sender.clone(),
-				created_at: Self::block_number(),
-				data_hash: data_hash,
-				extra: extra.clone(),
-			};
+			Self::validate_owner(&sender, biz_hash, &business)?;
 
-			Self::append_product_info(product_hash, &seq_id, info)?;
-			Self::deposit_event(RawEvent::ProductInfoAppended(sender.clone(), biz_hash, seq_id.clone(), product_hash));	
+			Self::do_remove_whitelist(sender, biz_hash, business, name_hash)?;
 		}
-		
-	}
-}
 
-impl<T: Trait> Module<T> {
-	/// Validate authorization by checking if the name hash is resolved to the sender
-	/// 
-	/// @sender	the sender
+		/// Share the ownership of a business with a council of owner names. The whitelist
+		/// calls of the owner are then replaced by council actions.
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @members	the name hashes of the council members
+		/// @threshold	the number of member approvals an action needs
+		#[weight = FunctionOf(
+			|(_, members, _): (&T::Hash, &Vec<NameHash<T>>, &u32)| T::WeightInfo::set_owner_council(members.len() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_owner_council(origin, biz_hash: T::Hash, members: Vec<NameHash<T>>, threshold: u32) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+			ensure!(!members.is_empty(), "Council has no members");
+
+			Self::do_set_owner_council(sender, biz_hash, members, threshold)?;
+		}
+
+		/// Approve a council action on behalf of a council member, applying it when the
+		/// approvals reach the threshold
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @member	the name hash of the council member
+		/// @action	the council action
+		#[weight = FunctionOf(
+			|(_, _, action): (&T::Hash, &NameHash<T>, &CouncilActionOf<T>)| <Module<T>>::approve_council_action_weight(action),
+			DispatchClass::Normal,
+			true
+		)]
+		fn approve_council_action(origin, biz_hash: T::Hash, member: NameHash<T>, action: CouncilActionOf<T>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_authorization(&sender, member)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let council = Self::owner_council(biz_hash).ok_or("Business has no owner council")?;
+			ensure!(council.members.contains(&member), "Not a council member");
+
+			let action_hash = T::Hashing::hash_of(&action);
+			let mut approvals = Self::council_approvals(&biz_hash, &action_hash);
+			ensure!(!approvals.contains(&member), "Already approved");
+			approvals.push(member);
+			let count = approvals.len() as u32;
+			if count < council.threshold {
+				<CouncilApprovals<T>>::insert(&biz_hash, &action_hash, approvals);
+				Self::deposit_event(RawEvent::CouncilActionApproved(sender, biz_hash, member, action_hash, count));
+				return Ok(());
+			}
+
+			// The last approval applies the action, which fails before writing anything
+			let business = Self::business_of(biz_hash);
+			match action {
+				CouncilAction::AddWhitelist(name_hash, valid_until) =>
+					Self::do_add_whitelist(sender.clone(), biz_hash, business, name_hash, valid_until)?,
+				CouncilAction::RemoveWhitelist(name_hash) =>
+					Self::do_remove_whitelist(sender.clone(), biz_hash, business, name_hash)?,
+				CouncilAction::SetWhitelistExpiration(name_hash, valid_until) =>
+					Self::do_set_whitelist_expiration(sender.clone(), biz_hash, business, name_hash, valid_until)?,
+				CouncilAction::SetCouncil(members, threshold) =>
+					Self::do_set_owner_council(sender.clone(), biz_hash, members, threshold)?,
+				CouncilAction::CreateSubBusiness(owner, name, expiration) =>
+					Self::do_create_sub_business(sender.clone(), biz_hash, business, owner, name, expiration)?,
+				CouncilAction::ConfirmWhitelistMember(name_hash) =>
+					Self::do_confirm_whitelist_member(sender.clone(), biz_hash, name_hash)?,
+				CouncilAction::SetRequiredAttestation(schema) =>
+					Self::do_set_required_attestation(sender.clone(), biz_hash, schema)?,
+				CouncilAction::SetBusinessProfile(profile, category) =>
+					Self::do_set_business_profile(sender.clone(), biz_hash, business, profile, category)?,
+				CouncilAction::SetTransferApproval(from_biz, approved) =>
+					Self::do_set_transfer_approval(sender.clone(), biz_hash, from_biz, approved)?,
+				CouncilAction::SetAuthorizationDepth(depth) =>
+					Self::do_set_authorization_depth(sender.clone(), biz_hash, depth)?,
+				CouncilAction::SetDedupPolicy(policy) =>
+					Self::do_set_dedup_policy(sender.clone(), biz_hash, policy)?,
+				CouncilAction::SetSeqMode(mode) =>
+					Self::do_set_seq_mode(sender.clone(), biz_hash, mode)?,
+				CouncilAction::SetExtraSchema(allowed_keys) =>
+					Self::do_set_extra_schema(sender.clone(), biz_hash, allowed_keys)?,
+				CouncilAction::RedactProductInfo(seq_id, index) =>
+					Self::do_redact_product_info(biz_hash, seq_id, index)?,
+				CouncilAction::IssueRecall(scope, reason_hash) =>
+					Self::do_issue_recall(sender.clone(), biz_hash, scope, reason_hash)?,
+				CouncilAction::LiftRecall(scope) =>
+					Self::do_lift_recall(sender.clone(), biz_hash, scope)?,
+				CouncilAction::AppealSuspension(appeal_hash) =>
+					Self::do_appeal_suspension(sender.clone(), biz_hash, appeal_hash)?,
+				CouncilAction::RemoveLot(lot_id) =>
+					Self::do_remove_lot(sender.clone(), biz_hash, lot_id)?,
+				CouncilAction::RemoveProduct(seq_id) =>
+					Self::do_remove_product(sender.clone(), biz_hash, seq_id)?,
+			}
+			<CouncilApprovals<T>>::remove(&biz_hash, &action_hash);
+			Self::deposit_event(RawEvent::CouncilActionApproved(sender, biz_hash, member, action_hash, count));
+			Self::deposit_event(RawEvent::CouncilActionExecuted(biz_hash, action_hash));
+		}
+
+		/// Reserve funds into the deposit pool of a business, also open to the owner of a council
+		/// owned business as it reserves the balance of the sender
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @amount	the amount to reserve
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::fund_business())]
+		fn fund_business(origin, biz_hash: T::Hash, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_business_authorization(&sender, biz_hash, business.owner)?;
+
+			ensure!(!amount.is_zero(), "Zero amount");
+			let mut pool = Self::deposit_pool(biz_hash).unwrap_or(DepositPoolOf::<T> {
+				funder: sender.clone(),
+				available: Zero::zero(),
+				held: Zero::zero(),
+			});
+			ensure!(pool.funder == sender, "Pool funded by another account");
+			pool.available = pool.available.checked_add(&amount).ok_or("Overflow funding business")?;
+
+			T::Currency::reserve(&sender, amount)?;
+			<DepositPools<T>>::insert(biz_hash, pool);
+			Self::deposit_event(RawEvent::BusinessFunded(sender, biz_hash, amount));
+		}
+
+		/// Return funds not taken by products from the deposit pool of a business
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @amount	the amount to return
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::withdraw_business_funds())]
+		fn withdraw_business_funds(origin, biz_hash: T::Hash, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let mut pool = Self::deposit_pool(biz_hash).ok_or("Business is not funded")?;
+			ensure!(pool.funder == sender, "Pool funded by another account");
+			ensure!(amount <= pool.available, "Insufficient pool funds");
+
+			pool.available -= amount;
+			T::Currency::unreserve(&sender, amount);
+			if pool.available.is_zero() && pool.held.is_zero() {
+				<DepositPools<T>>::remove(biz_hash);
+			} else {
+				<DepositPools<T>>::insert(biz_hash, pool);
+			}
+			Self::deposit_event(RawEvent::BusinessFundsWithdrawn(sender, biz_hash, amount));
+		}
+
+		/// Set the credential schema operators must hold to be whitelisted
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @schema	the hash of the credential schema, none to drop the requirement
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_required_attestation())]
+		fn set_required_attestation(origin, biz_hash: T::Hash, schema: Option<T::Hash>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_required_attestation(sender, biz_hash, schema)?;
+		}
+
+		/// Set the profile and the category of a business
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @profile	the hash of the off-chain profile document, none to drop it
+		/// @category	the category of the business, zero for none
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_business_profile())]
+		fn set_business_profile(origin, biz_hash: T::Hash, profile: Option<T::Hash>, category: u16) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_business_profile(sender, biz_hash, business, profile, category)?;
+		}
+
+		/// Create product for a business
+		/// 
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id, should be unique within the business scope
+		/// @data_hash	the data hash to be stored with the product, with its algorithm
+		/// @extra	the extra information, can be json string 
+		#[weight = FunctionOf(
+			|(_, _, seq_id, _, extra): (&NameHash<T>, &T::Hash, &Vec<u8>, &DataHash, &Vec<u8>)| <Module<T>>::create_product_weight(seq_id, extra),
+			DispatchClass::Normal,
+			true
+		)]
+		fn create_product(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: DataHash, extra: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+			Self::do_create_product(sender, name_hash, biz_hash, seq_id, data_hash, extra)?;
+		}
+
+		/// Add product info for a business
+		/// 
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id, should be unique within the business scope
+		/// @kind	the kind of the info
+		/// @data_hash	the data hash to be stored with the product, with its algorithm
+		/// @extra	the extra information, can be json string 
+		#[weight = FunctionOf(
+			|(_, _, seq_id, _, _, extra): (&NameHash<T>, &T::Hash, &Vec<u8>, &InfoKind, &DataHash, &Vec<u8>)| <Module<T>>::add_product_info_weight(seq_id, extra),
+			DispatchClass::Normal,
+			true
+		)]
+		fn add_product_info(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, kind: InfoKind, data_hash: DataHash, extra: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+			Self::do_add_product_info(sender, name_hash, biz_hash, seq_id, kind, data_hash, extra)?;
+		}
+
+		/// Create product for a business with a payload signed by the operator off-chain.
+		/// The sender only relays the payload and pays the fees.
+		///
+		/// @origin	the relayer
+		/// @payload	the operator payload
+		/// @signature	the signature of the signer of the payload
+		#[weight = FunctionOf(
+			|(payload, _): (&OperatorPayloadOf<T>, &T::OperatorSignature)| <Module<T>>::create_product_weight(&payload.seq_id, &payload.extra)
+				.saturating_add(T::WeightInfo::verify_operator_payload(payload.encode().len() as u32)),
+			DispatchClass::Normal,
+			true
+		)]
+		fn create_product_signed(origin, payload: OperatorPayloadOf<T>, signature: T::OperatorSignature) {
+			let _relayer = ensure_signed(origin)?;
+
+			let operator = Self::verify_operator_payload(CREATE_PRODUCT_CONTEXT, &payload, &signature)?;
+			let name_hash = payload.name_hash;
+			let next_nonce = payload.nonce
+				.checked_add(1)
+				.ok_or("Overflow advancing the operator nonce")?;
+			Self::do_create_product(operator, name_hash, payload.biz_hash, payload.seq_id, payload.data_hash, payload.extra)?;
+			<OperatorNonce<T>>::insert(name_hash, next_nonce);
+		}
+
+		/// Add product info for a business with a payload signed by the operator off-chain.
+		/// The sender only relays the payload and pays the fees.
+		///
+		/// @origin	the relayer
+		/// @payload	the operator payload
+		/// @signature	the signature of the signer of the payload
+		#[weight = FunctionOf(
+			|(payload, _): (&OperatorPayloadOf<T>, &T::OperatorSignature)| <Module<T>>::add_product_info_weight(&payload.seq_id, &payload.extra)
+				.saturating_add(T::WeightInfo::verify_operator_payload(payload.encode().len() as u32)),
+			DispatchClass::Normal,
+			true
+		)]
+		fn add_product_info_signed(origin, payload: OperatorPayloadOf<T>, signature: T::OperatorSignature) {
+			let _relayer = ensure_signed(origin)?;
+
+			let operator = Self::verify_operator_payload(ADD_PRODUCT_INFO_CONTEXT, &payload, &signature)?;
+			let name_hash = payload.name_hash;
+			let next_nonce = payload.nonce
+				.checked_add(1)
+				.ok_or("Overflow advancing the operator nonce")?;
+			Self::do_add_product_info(operator, name_hash, payload.biz_hash, payload.seq_id, payload.kind, payload.data_hash, payload.extra)?;
+			<OperatorNonce<T>>::insert(name_hash, next_nonce);
+		}
+
+		/// Create a lot whose infos are shared by the products added to it, taking the product
+		/// deposit from the pool of the business
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @lot_id	the lot id, should be unique within the business scope
+		/// @data_hash	the data hash to be stored with the lot, with its algorithm
+		/// @extra	the extra information, can be json string
+		#[weight = FunctionOf(
+			|(_, _, lot_id, _, extra): (&NameHash<T>, &T::Hash, &Vec<u8>, &DataHash, &Vec<u8>)|
+				T::WeightInfo::create_lot(lot_id.len() as u32, extra.len() as u32, T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn create_lot(origin, name_hash: NameHash<T>, biz_hash: T::Hash, lot_id: Vec<u8>, data_hash: DataHash, extra: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+			Self::validate_operator_write(biz_hash, &name_hash, &lot_id, &data_hash, &extra)?;
+			let (lot_id, extra) = (BoundedSeqId::try_from(lot_id)?, BoundedExtra::try_from(extra)?);
+
+			let lot_hash = Self::lot_hash(biz_hash, lot_id.to_vec());
+			ensure!(!<Lots<T>>::exists(lot_hash), "Lot already exists");
+			let mut lots = Self::business_lots(biz_hash);
+			ensure!(lots.len() < T::MaxLotsPerBusiness::get() as usize, "Too many lots");
+			let deposit = Self::scope_config(biz_hash).product_deposit.unwrap_or_else(T::ProductDeposit::get)
+				.saturating_add(T::StorageFeePolicy::extra_deposit(extra.len()));
+			let pool = Self::take_product_deposit(biz_hash, deposit)?;
+			let anchor = Self::anchor_key(&data_hash);
+			let info = Self::new_info(sender.clone(), InfoKind::Created, data_hash, extra);
+			let timestamp = info.timestamp;
+			let lot = LotOf::<T> {
+				biz_hash: biz_hash,
+				lot_id: lot_id.clone(),
+				infos: vec![info],
+			};
+
+			lots.push(lot_hash);
+
+			<Lots<T>>::insert(lot_hash, &lot);
+			<BusinessLots<T>>::insert(biz_hash, lots);
+			Self::hold_lot_deposit(biz_hash, lot_hash, pool, deposit);
+			Self::touch_lot(lot_hash);
+			T::Anchors::note_anchor(anchor, &sender);
+			Self::note_operator_write(biz_hash, name_hash, false);
+			Self::deposit_event(RawEvent::LotCreated(sender, biz_hash, lot_id, lot_hash, timestamp));
+			Self::deposit_info_recorded(lot_hash, lot.infos.last());
+		}
+
+		/// Add an info to a lot, shared by all its products. A `Recalled` info recalls them all.
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @lot_id	the lot id
+		/// @kind	the kind of the info
+		/// @data_hash	the data hash to be stored with the info, with its algorithm
+		/// @extra	the extra information, can be json string
+		#[weight = FunctionOf(
+			|(_, _, lot_id, _, _, extra): (&NameHash<T>, &T::Hash, &Vec<u8>, &InfoKind, &DataHash, &Vec<u8>)|
+				T::WeightInfo::add_lot_info(
+					lot_id.len() as u32,
+					extra.len() as u32,
+					T::MaxWhitelistSize::get() as u32,
+					T::MaxProductInfoCount::get() as u32,
+				),
+			DispatchClass::Normal,
+			true
+		)]
+		fn add_lot_info(origin, name_hash: NameHash<T>, biz_hash: T::Hash, lot_id: Vec<u8>, kind: InfoKind, data_hash: DataHash, extra: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+			Self::validate_operator_write(biz_hash, &name_hash, &lot_id, &data_hash, &extra)?;
+			let (lot_id, extra) = (BoundedSeqId::try_from(lot_id)?, BoundedExtra::try_from(extra)?);
+
+			let lot_hash = Self::lot_hash(biz_hash, lot_id.to_vec());
+			let mut lot = Self::lot_of(lot_hash).ok_or("Lot does not exist")?;
+			ensure!(lot.infos.len() < Self::max_product_info_count(biz_hash), "Exceeds max product info limit");
+			let deposit = T::StorageFeePolicy::extra_deposit(extra.len());
+			let pool = Self::take_product_deposit(biz_hash, deposit)?;
+			let anchor = Self::anchor_key(&data_hash);
+			let info = Self::new_info(sender.clone(), kind, data_hash, extra);
+			let timestamp = info.timestamp;
+			lot.infos.push(info);
+
+			<Lots<T>>::insert(lot_hash, &lot);
+			Self::hold_lot_deposit(biz_hash, lot_hash, pool, deposit);
+			Self::touch_lot(lot_hash);
+			T::Anchors::note_anchor(anchor, &sender);
+			Self::note_operator_write(biz_hash, name_hash, false);
+			Self::deposit_event(RawEvent::LotInfoAppended(sender, biz_hash, lot_id, lot_hash, kind, timestamp));
+			Self::deposit_info_recorded(lot_hash, lot.infos.last());
+		}
+
+		/// Add a product held by a business to one of its lots, once
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id the business holds the product under
+		/// @lot_id	the lot id
+		#[weight = FunctionOf(
+			|(_, _, seq_id, lot_id): (&NameHash<T>, &T::Hash, &Vec<u8>, &Vec<u8>)|
+				T::WeightInfo::set_product_lot(seq_id.len().saturating_add(lot_id.len()) as u32, T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_product_lot(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, lot_id: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_whitelist(biz_hash, &business, &name_hash)?;
+			Self::validate_expiration(business.expiration)?;
+			Self::validate_not_suspended(biz_hash)?;
+
+			let product_hash = Self::held_product_hash(biz_hash, &seq_id).ok_or("Product does not exist")?;
+			let lot_hash = Self::lot_hash(biz_hash, lot_id);
+			ensure!(<Lots<T>>::exists(lot_hash), "Lot does not exist");
+			ensure!(!<ProductLot<T>>::exists(product_hash), "Product already in a lot");
+
+			<ProductLot<T>>::insert(product_hash, lot_hash);
+			<LotProductCount<T>>::mutate(lot_hash, |count| *count += 1);
+			Self::touch_product(product_hash);
+			Self::deposit_event(RawEvent::ProductLotSet(sender, product_hash, lot_hash));
+		}
+
+		/// Remove a lot no product belongs to and return its deposits to the pool of the
+		/// business
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @lot_id	the lot id
+		#[weight = FunctionOf(
+			|(_, lot_id): (&T::Hash, &Vec<u8>)|
+				T::WeightInfo::remove_lot(lot_id.len() as u32, T::MaxLotsPerBusiness::get()),
+			DispatchClass::Normal,
+			true
+		)]
+		fn remove_lot(origin, biz_hash: T::Hash, lot_id: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_remove_lot(sender, biz_hash, lot_id)?;
+		}
+
+		/// Commit the Merkle root of the one-time codes printed on the items of a product, once
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id the business holds the product under
+		/// @root	the Merkle root of the hashed codes
+		/// @count	the number of codes
+		#[weight = FunctionOf(
+			|(_, _, seq_id, _, _): (&NameHash<T>, &T::Hash, &Vec<u8>, &T::Hash, &u32)|
+				T::WeightInfo::commit_codes(seq_id.len() as u32, T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn commit_codes(origin, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, root: T::Hash, count: u32) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, biz_hash, name_hash)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_whitelist(biz_hash, &business, &name_hash)?;
+			Self::validate_expiration(business.expiration)?;
+			Self::validate_not_suspended(biz_hash)?;
+
+			let product_hash = Self::held_product_hash(biz_hash, &seq_id).ok_or("Product does not exist")?;
+			ensure!(count > 0, "No codes");
+			ensure!(!<ProductCodes<T>>::exists(product_hash), "Codes already committed");
+
+			<ProductCodes<T>>::insert(product_hash, CodeCommitmentOf::<T> { root, count });
+			Self::touch_product(product_hash);
+			Self::deposit_event(RawEvent::CodesCommitted(sender, product_hash, root, count));
+		}
+
+		/// Redeem a one-time code of a product. A code redeemed before is recorded as a replay,
+		/// revealing a cloned item.
+		///
+		/// @origin	the sender
+		/// @product_hash	the product hash
+		/// @index	the index of the code
+		/// @code	the code
+		/// @proof	the sibling hashes from the leaf of the code up to the root
+		#[weight = FunctionOf(
+			|(_, _, code, proof): (&T::Hash, &u32, &Vec<u8>, &Vec<T::Hash>)|
+				T::WeightInfo::redeem_code(code.len() as u32, proof.len() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn redeem_code(origin, product_hash: T::Hash, index: u32, code: Vec<u8>, proof: Vec<T::Hash>) {
+			let sender = ensure_signed(origin)?;
+
+			let codes = Self::product_codes(product_hash).ok_or("No codes committed")?;
+			ensure!(index < codes.count, "Code index out of range");
+			ensure!(code.len() <= MAX_CODE_LENGTH, "Code too long");
+			ensure!(proof.len() <= MAX_CODE_PROOF_DEPTH, "Proof too long");
+			ensure!(Self::verify_code_proof(codes.root, codes.count, index, &code, &proof), "Invalid code proof");
+
+			let (word, bit) = (index / 64, 1u64 << (index % 64));
+			let spent = Self::spent_codes(&product_hash, &word);
+			Self::touch_product(product_hash);
+			if spent & bit != 0 {
+				<ReplayedCodes<T>>::mutate(product_hash, |n| *n = n.saturating_add(1));
+				Self::deposit_event(RawEvent::CodeReplayed(sender, product_hash, index));
+			} else {
+				<SpentCodes<T>>::insert(&product_hash, &word, spent | bit);
+				Self::deposit_event(RawEvent::CodeRedeemed(sender, product_hash, index));
+			}
+		}
+
+		/// Record the check of a product against a data hash. Feeless, a mismatch costs the
+		/// sender the verification fee.
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id of the product
+		/// @data_hash	the data hash of the checked document
+		#[weight = FunctionOf(
+			|(_, seq_id, _): (&T::Hash, &Vec<u8>, &DataHash)|
+				T::WeightInfo::verify_product(seq_id.len() as u32, T::MaxProductInfoCount::get() as u32),
+			DispatchClass::Normal,
+			false
+		)]
+		fn verify_product(origin, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: DataHash) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_verification_rate(&sender)?;
+			let (product_hash, matched) = Self::verification_of(biz_hash, seq_id, &data_hash)?;
+
+			if !matched {
+				let imbalance = T::Currency::withdraw(
+					&sender,
+					T::VerificationFee::get(),
+					WithdrawReason::Fee.into(),
+					ExistenceRequirement::KeepAlive,
+				).map_err(|_| "Insufficient balance for verification fee")?;
+				T::VerificationFees::on_unbalanced(imbalance);
+			}
+			Self::note_verification(sender.clone());
+			Self::deposit_event(RawEvent::ProductVerified(sender, product_hash, data_hash, matched));
+		}
+
+		/// Remove a product with its infos and return its deposit to the pool of the business
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id of the product
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::remove_product())]
+		fn remove_product(origin, biz_hash: T::Hash, seq_id: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_remove_product(sender, biz_hash, seq_id)?;
+		}
+
+		/// Transfer the custody of a product to another business. The transfer completes
+		/// if the destination pre-approved the source, otherwise it waits for acceptance.
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the operator of the source
+		/// @from_biz	the business holding the product
+		/// @to_biz	the business to take over the product
+		/// @seq_id	the sequence id the source holds the product under
+		#[weight = FunctionOf(
+			|(_, _, _, seq_id): (&NameHash<T>, &T::Hash, &T::Hash, &Vec<u8>)| T::WeightInfo::transfer_product(seq_id.len() as u32, T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn transfer_product(origin, name_hash: NameHash<T>, from_biz: T::Hash, to_biz: T::Hash, seq_id: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, from_biz, name_hash)?;
+
+			ensure!(<Businesses<T>>::exists(from_biz), "Business does not exist");
+			let source = Self::business_of(from_biz);
+			Self::validate_whitelist(from_biz, &source, &name_hash)?;
+			Self::validate_expiration(source.expiration)?;
+			Self::validate_not_suspended(from_biz)?;
+
+			ensure!(from_biz != to_biz, "Same business");
+			ensure!(<Businesses<T>>::exists(to_biz), "Destination does not exist");
+			Self::validate_expiration(Self::business_of(to_biz).expiration)?;
+			Self::validate_not_suspended(to_biz)?;
+
+			let product_hash = Self::held_product_hash(from_biz, &seq_id).ok_or("Product does not exist")?;
+			ensure!(!<PendingTransfers<T>>::exists(product_hash), "Transfer already pending");
+			Self::validate_custody_slot(to_biz, &seq_id, product_hash)?;
+
+			let pending = PendingTransferOf::<T> {
+				from_biz: from_biz,
+				to_biz: to_biz,
+				released_by: sender.clone(),
+			};
+			match Self::transfer_approval((to_biz, from_biz)) {
+				Some(approver) => Self::complete_transfer(product_hash, seq_id, pending, approver),
+				None => {
+					<PendingTransfers<T>>::insert(product_hash, pending);
+					Self::deposit_event(RawEvent::ProductTransferRequested(sender, product_hash, from_biz, to_biz));
+				}
+			}
+		}
+
+		/// Accept a pending product transfer on behalf of the destination
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the owner or an operator of the destination
+		/// @to_biz	the destination business
+		/// @product_hash	the product hash
+		#[weight = FunctionOf(
+			|_: (&NameHash<T>, &T::Hash, &T::Hash)| T::WeightInfo::accept_product_transfer(T::MaxWhitelistSize::get() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn accept_product_transfer(origin, name_hash: NameHash<T>, to_biz: T::Hash, product_hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_business_authorization(&sender, to_biz, name_hash)?;
+
+			let pending = Self::pending_transfer(product_hash).ok_or("No pending transfer")?;
+			ensure!(pending.to_biz == to_biz, "Not the destination");
+			ensure!(<Businesses<T>>::exists(to_biz), "Destination does not exist");
+			let destination = Self::business_of(to_biz);
+			if destination.owner != name_hash {
+				Self::validate_whitelist(to_biz, &destination, &name_hash)?;
+			}
+			Self::validate_expiration(destination.expiration)?;
+			Self::validate_not_suspended(to_biz)?;
+
+			let seq_id = Self::product_of(product_hash).seq_id.into_inner();
+			// The sequence id may have been taken since the transfer started
+			Self::validate_custody_slot(to_biz, &seq_id, product_hash)?;
+			Self::complete_transfer(product_hash, seq_id, pending, sender);
+		}
+
+		/// Cancel a pending product transfer on behalf of the source
+		///
+		/// @origin	the sender
+		/// @name_hash	the name hash of the owner or an operator of the source
+		/// @product_hash	the product hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::cancel_product_transfer())]
+		fn cancel_product_transfer(origin, name_hash: NameHash<T>, product_hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_authorization(&sender, name_hash)?;
+
+			let pending = Self::pending_transfer(product_hash).ok_or("No pending transfer")?;
+			let source = Self::business_of(pending.from_biz);
+			if source.owner != name_hash {
+				Self::validate_whitelist(pending.from_biz, &source, &name_hash)?;
+			}
+
+			<PendingTransfers<T>>::remove(product_hash);
+			Self::deposit_event(RawEvent::ProductTransferCancelled(sender, product_hash));
+		}
+
+		/// Pre-approve or revoke the transfers from a source business to a business
+		///
+		/// @origin	the sender
+		/// @to_biz	the business hash
+		/// @from_biz	the source business hash
+		/// @approved	whether transfers from the source complete without acceptance
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_transfer_approval())]
+		fn set_transfer_approval(origin, to_biz: T::Hash, from_biz: T::Hash, approved: bool) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(to_biz), "Business does not exist");
+			let business = Self::business_of(to_biz);
+			Self::validate_owner(&sender, to_biz, &business)?;
+
+			Self::do_set_transfer_approval(sender, to_biz, from_biz, approved)?;
+		}
+
+		/// Set the ancestor levels whose owners may act for a name in the calls on a business,
+		/// zero to accept the resolved address of the name only
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @depth	the number of ancestor levels
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_authorization_depth())]
+		fn set_authorization_depth(origin, biz_hash: T::Hash, depth: u32) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			// Only the owner itself, not its ancestors, may change the depth
+			Self::validate_authorization(&sender, business.owner)?;
+			Self::validate_not_council_owned(biz_hash)?;
+
+			Self::do_set_authorization_depth(sender, biz_hash, depth)?;
+		}
+
+		/// Set what the business does when an info repeats a data hash of its product
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @policy	the deduplication policy
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_dedup_policy())]
+		fn set_dedup_policy(origin, biz_hash: T::Hash, policy: DedupPolicy) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_dedup_policy(sender, biz_hash, policy)?;
+		}
+
+		/// Set whether the operators provide the sequence ids of the products of the
+		/// business or the chain assigns them
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @mode	the sequence id mode
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_seq_mode())]
+		fn set_seq_mode(origin, biz_hash: T::Hash, mode: SeqMode) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_seq_mode(sender, biz_hash, mode)?;
+		}
+
+		/// Set the key ids the structured extra of the infos of the business may use, no keys
+		/// removing the schema
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @allowed_keys	the allowed key ids
+		#[weight = FunctionOf(
+			|(_, allowed_keys): (&T::Hash, &Vec<u16>)| T::WeightInfo::set_extra_schema(allowed_keys.len() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_extra_schema(origin, biz_hash: T::Hash, allowed_keys: Vec<u16>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_set_extra_schema(sender, biz_hash, allowed_keys)?;
+		}
+
+		/// Redact the extra of a product info, keeping the hash of the original info as a
+		/// tombstone
+		///
+		/// @origin	the business owner or the force origin
+		/// @biz_hash	the business hash
+		/// @seq_id	the sequence id
+		/// @index	the index of the info
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::redact_product_info())]
+		fn redact_product_info(origin, biz_hash: T::Hash, seq_id: Vec<u8>, index: u32) {
+			let sender = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			if let Some(sender) = sender {
+				let business = Self::business_of(biz_hash);
+				Self::validate_owner(&sender, biz_hash, &business)?;
+			}
+
+			Self::do_redact_product_info(biz_hash, seq_id, index)?;
+		}
+
+		/// Recall a product, a lot or all the products of a business. Recalls stay in force
+		/// after the business expires, until the owner lifts them.
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @scope	what the recall covers
+		/// @reason_hash	the hash of the recall notice
+		#[weight = FunctionOf(
+			|(_, scope, _): (&T::Hash, &RecallScope, &T::Hash)| T::WeightInfo::issue_recall(scope.id_len()),
+			DispatchClass::Normal,
+			true
+		)]
+		fn issue_recall(origin, biz_hash: T::Hash, scope: RecallScope, reason_hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_issue_recall(sender, biz_hash, scope, reason_hash)?;
+		}
+
+		/// Lift a recall of the business
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @scope	what the recall covers
+		#[weight = FunctionOf(
+			|(_, scope): (&T::Hash, &RecallScope)| T::WeightInfo::lift_recall(scope.id_len()),
+			DispatchClass::Normal,
+			true
+		)]
+		fn lift_recall(origin, biz_hash: T::Hash, scope: RecallScope) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_lift_recall(sender, biz_hash, scope)?;
+		}
+
+		/// Suspend a business, blocking its product writes until it is reinstated
+		///
+		/// @origin	the force origin
+		/// @biz_hash	the business hash
+		/// @reason_hash	the hash of the suspension notice
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::suspend_business())]
+		fn suspend_business(origin, biz_hash: T::Hash, reason_hash: T::Hash) {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			ensure!(!<Suspensions<T>>::exists(biz_hash), "Business is suspended");
+
+			<Suspensions<T>>::insert(biz_hash, SuspensionOf::<T> {
+				reason_hash: reason_hash,
+				suspended_at: Self::block_number(),
+				appeal: None,
+			});
+			Self::touch_business(biz_hash);
+			Self::deposit_event(RawEvent::BusinessSuspended(biz_hash, reason_hash));
+		}
+
+		/// Appeal the suspension of a business, once and within the appeal period
+		///
+		/// @origin	the sender
+		/// @biz_hash	the business hash
+		/// @appeal_hash	the hash of the appeal
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::appeal_suspension())]
+		fn appeal_suspension(origin, biz_hash: T::Hash, appeal_hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+			let business = Self::business_of(biz_hash);
+			Self::validate_owner(&sender, biz_hash, &business)?;
+
+			Self::do_appeal_suspension(sender, biz_hash, appeal_hash)?;
+		}
+
+		/// Reinstate a suspended business
+		///
+		/// @origin	the force origin
+		/// @biz_hash	the business hash
+		/// @reason_hash	the hash of the reinstatement notice
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::reinstate_business())]
+		fn reinstate_business(origin, biz_hash: T::Hash, reason_hash: T::Hash) {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(<Suspensions<T>>::exists(biz_hash), "Business is not suspended");
+
+			<Suspensions<T>>::remove(biz_hash);
+			Self::touch_business(biz_hash);
+			Self::deposit_event(RawEvent::BusinessReinstated(biz_hash, reason_hash));
+		}
+
+		/// Let an account create businesses in a scope on behalf of its admin
+		///
+		/// @origin	the scope admin
+		/// @scope_hash	the scope
+		/// @delegate	the delegate account
+		/// @valid_until	the block from which the delegate may no longer create businesses, none for no limit
+		#[weight = FunctionOf(
+			|_: (&T::Hash, &T::AccountId, &Option<T::BlockNumber>)| T::WeightInfo::set_admin_delegate(),
+			T::AdminDispatchClass::get(),
+			T::AdminPaysFee::get()
+		)]
+		fn set_admin_delegate(origin, scope_hash: T::Hash, delegate: T::AccountId, valid_until: Option<T::BlockNumber>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_scope_admin(&sender, scope_hash)?;
+
+			if let Some(until) = valid_until {
+				Self::validate_expiration(until)?;
+			}
+			let delegation = AdminDelegation { admin: sender.clone(), valid_until };
+			ensure!(Self::scope_delegate(scope_hash, &delegate) != Some(delegation.clone()), "Same value");
+			<ScopeDelegates<T>>::insert(scope_hash, &delegate, delegation);
+
+			Self::deposit_event(RawEvent::AdminDelegateSet(sender, scope_hash, delegate, valid_until));
+		}
+
+		/// Stop an account creating businesses in a scope on behalf of its admin
+		///
+		/// @origin	the scope admin
+		/// @scope_hash	the scope
+		/// @delegate	the delegate account
+		#[weight = FunctionOf(
+			|_: (&T::Hash, &T::AccountId)| T::WeightInfo::remove_admin_delegate(),
+			T::AdminDispatchClass::get(),
+			T::AdminPaysFee::get()
+		)]
+		fn remove_admin_delegate(origin, scope_hash: T::Hash, delegate: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_scope_admin(&sender, scope_hash)?;
+
+			ensure!(<ScopeDelegates<T>>::exists(scope_hash, &delegate), "Not a delegate");
+			<ScopeDelegates<T>>::remove(scope_hash, &delegate);
+
+			Self::deposit_event(RawEvent::AdminDelegateRemoved(sender, scope_hash, delegate));
+		}
+
+		/// Register a scope of businesses
+		///
+		/// @origin	the force origin
+		/// @scope_hash	the scope hash
+		/// @admin	the name hash whose resolved address administers the scope
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::register_scope())]
+		fn register_scope(origin, scope_hash: T::Hash, admin: NameHash<T>) {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(!<Scopes<T>>::exists(scope_hash), "Scope already exists");
+			Self::insert_scope(scope_hash, admin);
+
+			Self::deposit_event(RawEvent::ScopeRegistered(scope_hash, admin));
+		}
+
+		/// Retire a scope, no business being created in it any more. Its businesses live on,
+		/// their expiration still managed by its admin.
+		///
+		/// @origin	the force origin
+		/// @scope_hash	the scope hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::retire_scope())]
+		fn retire_scope(origin, scope_hash: T::Hash) {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			let mut scope = Self::scope_of(scope_hash).ok_or("Scope does not exist")?;
+			ensure!(!scope.retired, "Scope is retired");
+			scope.retired = true;
+			<Scopes<T>>::insert(scope_hash, scope);
+
+			Self::deposit_event(RawEvent::ScopeRetired(scope_hash));
+		}
+
+		/// Set the settings of a scope
+		///
+		/// @origin	the scope admin
+		/// @scope_hash	the scope
+		/// @config	the settings, none falling back to the runtime constants
+		#[weight = FunctionOf(
+			|_: (&T::Hash, &ScopeConfigOf<T>)| T::WeightInfo::set_scope_config(),
+			T::AdminDispatchClass::get(),
+			T::AdminPaysFee::get()
+		)]
+		fn set_scope_config(origin, scope_hash: T::Hash, config: ScopeConfigOf<T>) {
+			let sender = ensure_signed(origin)?;
+			Self::validate_scope_admin(&sender, scope_hash)?;
+
+			if let Some(len) = config.max_extra_length {
+				ensure!(len as usize <= T::MaxExtraLength::get(), "Extra info limit too large");
+			}
+			if let Some(count) = config.max_product_info_count {
+				ensure!(count as usize <= T::MaxProductInfoCount::get(), "Product info limit too large");
+			}
+			let mut scope = Self::scope_of(scope_hash).ok_or("Scope does not exist")?;
+			ensure!(scope.config != config, "Same value");
+			scope.config = config;
+			<Scopes<T>>::insert(scope_hash, scope);
+
+			Self::deposit_event(RawEvent::ScopeConfigChanged(sender, scope_hash));
+		}
+
+		/// Set the keys allowed to sign archive transactions
+		///
+		/// @origin	the force origin
+		/// @archivers	the archiver keys
+		#[weight = FunctionOf(|(archivers,): (&Vec<T::ArchiverId>,)| T::WeightInfo::set_archivers(archivers.len() as u32), DispatchClass::Normal, true)]
+		fn set_archivers(origin, archivers: Vec<T::ArchiverId>) {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			let mut archivers = archivers;
+			archivers.sort();
+			archivers.dedup();
+			<Archivers<T>>::put(archivers);
+		}
+
+		/// Archive expired businesses, submitted by the offchain worker
+		///
+		/// @origin	the none origin
+		/// @payload	the archive payload
+		/// @_signature	the signature of the payload, checked in `validate_unsigned`
+		#[weight = FunctionOf(
+			|(payload, _): (&ArchivePayloadOf<T>, &<T::ArchiverId as RuntimeAppPublic>::Signature)|
+				T::WeightInfo::archive_businesses(payload.biz_hashes.len() as u32, payload.products, payload.lots),
+			DispatchClass::Normal,
+			true
+		)]
+		fn archive_businesses(origin, payload: ArchivePayloadOf<T>, _signature: <T::ArchiverId as RuntimeAppPublic>::Signature) {
+			ensure_none(origin)?;
+
+			let now = Self::block_number();
+			let archivable = payload.biz_hashes.iter().filter(|h| Self::is_archivable(**h, now));
+			let products = archivable.clone().fold(0u64, |n, h| n.saturating_add(Self::product_count(*h)));
+			ensure!(products <= payload.products as u64, "Too many products to archive");
+			let lots = archivable.fold(0usize, |n, h| n.saturating_add(Self::business_lots(*h).len()));
+			ensure!(lots <= payload.lots as usize, "Too many lots to archive");
+			for biz_hash in payload.biz_hashes.iter() {
+				if Self::is_archivable(*biz_hash, now) {
+					Self::archive_business(*biz_hash);
+					Self::deposit_event(RawEvent::BusinessArchived(*biz_hash));
+				}
+			}
+		}
+
+		/// Migrate a business created before the enumeration indices, which the storage
+		/// migration can not find, with up to `max_products` of its products. The business
+		/// itself is migrated and indexed with its last products.
+		///
+		/// @origin	any account
+		/// @biz_hash	the business hash
+		/// @nonce	the nonce the business hash was derived from
+		/// @max_products	the maximum products to migrate, at most `MAX_LEGACY_PRODUCTS`
+		#[weight = FunctionOf(
+			|(_, _, max_products): (&T::Hash, &u64, &u32)| T::WeightInfo::migrate_legacy_business(*max_products),
+			DispatchClass::Normal,
+			true
+		)]
+		fn migrate_legacy_business(origin, biz_hash: T::Hash, nonce: u64, max_products: u32) {
+			ensure_signed(origin)?;
+			ensure!(max_products <= MAX_LEGACY_PRODUCTS, "Too many products");
+			let old = Self::legacy_get::<BusinessV1Of<T>>(&<Businesses<T>>::hashed_key_for(biz_hash))
+				.ok_or("Not a legacy business")?;
+			ensure!(
+				(&old.creator, &old.owner, nonce).using_encoded(T::Hashing::hash) == biz_hash,
+				"Nonce does not match"
+			);
+
+			let count = Self::product_count(biz_hash);
+			let start = Self::legacy_product_cursor(biz_hash);
+			let end = count.min(start.saturating_add(max_products as u64));
+			for position in start..end {
+				Self::migrate_legacy_product(biz_hash, position);
+			}
+			if end < count {
+				<LegacyProductCursor<T>>::insert(biz_hash, end);
+				return Ok(());
+			}
+			<LegacyProductCursor<T>>::remove(biz_hash);
+
+			let business = Self::migrate_legacy_business_record(biz_hash, old);
+			<BusinessesByOwner<T>>::mutate(business.owner, |list| list.push(biz_hash));
+			let index = Self::business_count();
+			<BusinessIndex<T>>::insert(index, biz_hash);
+			BusinessCount::put(index + 1);
+			Self::deposit_event(RawEvent::LegacyBusinessMigrated(biz_hash));
+		}
+
+		fn on_initialize(_now: T::BlockNumber) {
+			<Digest<T>>::kill();
+			if Self::storage_version() < STORAGE_VERSION {
+				Self::ensure_default_scope();
+				StorageVersion::put(STORAGE_VERSION);
+				Self::deposit_event(RawEvent::StorageMigrated(STORAGE_VERSION));
+			}
+		}
+
+		fn on_finalize(_now: T::BlockNumber) {
+			for name_hash in <WritingOperators<T>>::take() {
+				<OperatorWrites<T>>::remove_prefix(&name_hash);
+			}
+			for who in <VerifyingAccounts<T>>::take() {
+				<AccountVerifications<T>>::remove(&who);
+			}
+			<BlockVerifications>::kill();
+		}
+
+		// Runs after every block.
+		fn offchain_worker(now: T::BlockNumber) {
+			debug::RuntimeLogger::init();
+			Self::offchain_archive(now);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Validate authorization by checking if the sender may act for the name hash
+	/// 
+	/// @sender	the sender
 	/// @hash	the name hash 
 	pub fn validate_authorization(sender: &T::AccountId, hash: NameHash<T>) -> Result {
-		// Check the resolved address against sender
-		ensure!(Some(sender.clone()) == T::NameServiceResolver::resolve_addr(hash), "Not authorized");
+		ensure!(T::AuthorizationProvider::is_authorized(hash, sender, 0), "Not authorized");
+		Ok(())
+	}
+
+	/// Check a product against a data hash
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id of the product
+	/// @data_hash	the data hash of the checked document
+	fn verification_of(biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: &DataHash) -> rstd::result::Result<(T::Hash, bool), &'static str> {
+		let product_hash = Self::product_hash_of((biz_hash, seq_id)).ok_or("Product does not exist")?;
+		ensure!(<Products<T>>::exists(product_hash), "Product does not exist");
+		let matched = Self::product_of(product_hash).infos.iter().any(|info| info.data_hash == *data_hash);
+		Ok((product_hash, matched))
+	}
+
+	/// Validate a verification would be recorded: neither the block nor the sender reached
+	/// the verification limits, the product exists and the sender can pay the fee if the
+	/// data hash does not match
+	///
+	/// @sender	the sender
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id of the product
+	/// @data_hash	the data hash of the checked document
+	pub fn validate_verification(sender: &T::AccountId, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: &DataHash) -> Result {
+		Self::validate_verification_rate(sender)?;
+		let (_, matched) = Self::verification_of(biz_hash, seq_id, data_hash)?;
+		if !matched {
+			let fee = T::VerificationFee::get();
+			ensure!(
+				T::Currency::free_balance(sender) >= fee.saturating_add(T::Currency::minimum_balance()),
+				"Insufficient balance for verification fee"
+			);
+		}
+		Ok(())
+	}
+
+	/// Validate the sender is the admin of a scope
+	///
+	/// @sender	the sender
+	/// @scope_hash	the scope
+	pub fn validate_scope_admin(sender: &T::AccountId, scope_hash: T::Hash) -> Result {
+		let scope = Self::scope_of(scope_hash).ok_or("Scope does not exist")?;
+		Self::validate_authorization(sender, scope.admin)
+	}
+
+	/// Validate the sender may create businesses in a scope not retired, as its admin or a
+	/// delegate of an account the admin name still authorizes
+	///
+	/// @sender	the sender
+	/// @scope_hash	the scope
+	pub fn validate_business_creator(sender: &T::AccountId, scope_hash: T::Hash) -> Result {
+		let scope = Self::scope_of(scope_hash).ok_or("Scope does not exist")?;
+		ensure!(!scope.retired, "Scope is retired");
+		if Self::validate_authorization(sender, scope.admin).is_ok() {
+			return Ok(());
+		}
+		match Self::scope_delegate(scope_hash, sender) {
+			Some(delegation) if delegation.valid_until.map_or(true, |until| Self::block_number() < until)
+				&& Self::validate_authorization(&delegation.admin, scope.admin).is_ok() => Ok(()),
+			_ => Err("Not authorized"),
+		}
+	}
+
+	/// Get the settings of the scope of a business
+	///
+	/// @biz_hash	the business hash
+	pub fn scope_config(biz_hash: T::Hash) -> ScopeConfigOf<T> {
+		Self::scope_of(Self::business_of(biz_hash).scope).map(|scope| scope.config).unwrap_or_default()
+	}
+
+	/// Get the maximum length an extra info written to a business may be
+	///
+	/// @biz_hash	the business hash
+	pub fn max_extra_length(biz_hash: T::Hash) -> usize {
+		Self::scope_config(biz_hash).max_extra_length.map_or_else(T::MaxExtraLength::get, |len| len as usize)
+	}
+
+	/// Validate an extra info against the schema of the business, if any
+	///
+	/// @biz_hash	the business hash
+	/// @extra	the extra information
+	pub fn validate_extra(biz_hash: T::Hash, extra: &[u8]) -> Result {
+		let schema = match Self::extra_schema(biz_hash) {
+			Some(schema) if !extra.is_empty() => schema,
+			_ => return Ok(()),
+		};
+		let fields = match ExtraPayload::from_extra(extra) {
+			ExtraPayload::Structured(fields) => fields,
+			ExtraPayload::Raw(_) => return Err("Extra info not structured"),
+		};
+		ensure!(fields.windows(2).all(|pair| pair[0].0 < pair[1].0), "Extra keys not in order");
+		ensure!(fields.iter().all(|(key, _)| schema.binary_search(key).is_ok()), "Extra key not allowed");
+		Ok(())
+	}
+
+	/// Get the maximum info entries a product or lot of a business may have
+	///
+	/// @biz_hash	the business hash
+	pub fn max_product_info_count(biz_hash: T::Hash) -> usize {
+		Self::scope_config(biz_hash).max_product_info_count.map_or_else(T::MaxProductInfoCount::get, |count| count as usize)
+	}
+
+	/// Take the renewal fee of a new expiration from the deposit pool of a business, for
+	/// each block beyond the later of its expiration and the current block
+	///
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @expiration	the new expiration
+	fn charge_renewal(biz_hash: T::Hash, business: &BusinessOf<T>, expiration: T::BlockNumber) -> Result {
+		let price = Self::scope_of(business.scope)
+			.and_then(|scope| scope.config.renewal_price_per_block)
+			.unwrap_or_else(T::RenewalPricePerBlock::get);
+		let blocks: u32 = expiration.saturating_sub(business.expiration.max(Self::block_number())).saturated_into();
+		let fee = price.saturating_mul(blocks.into());
+		if fee.is_zero() {
+			return Ok(());
+		}
+		let mut pool = Self::deposit_pool(biz_hash).ok_or("Insufficient pool funds")?;
+		ensure!(fee <= pool.available, "Insufficient pool funds");
+		let (imbalance, _) = T::Currency::slash_reserved(&pool.funder, fee);
+		T::RenewalFees::on_unbalanced(imbalance);
+		pool.available -= fee;
+		<DepositPools<T>>::insert(biz_hash, pool);
+
+		Self::deposit_event(RawEvent::RenewalCharged(biz_hash, fee));
+		Ok(())
+	}
+
+	/// Register a scope
+	///
+	/// @scope_hash	the scope hash
+	/// @admin	the admin name hash
+	fn insert_scope(scope_hash: T::Hash, admin: NameHash<T>) {
+		<Scopes<T>>::insert(scope_hash, ScopeInfoOf::<T> { admin: admin, retired: false, config: Default::default() });
+		<ScopesByAdmin<T>>::mutate(admin, |list| list.push(scope_hash));
+	}
+
+	/// Validate authorization in a call on a business, accepting the owners of the ancestor
+	/// names up to the authorization depth of the business
+	///
+	/// @sender	the sender
+	/// @biz_hash	the business hash
+	/// @hash	the name hash
+	pub fn validate_business_authorization(sender: &T::AccountId, biz_hash: T::Hash, hash: NameHash<T>) -> Result {
+		let depth = Self::authorization_depth(biz_hash).min(T::MaxAuthorizationDepth::get());
+		ensure!(T::AuthorizationProvider::is_authorized(hash, sender, depth), "Not authorized");
+		Ok(())
+	}
+
+	/// Validate the sender may manage a business as its owner, which a business owned by a
+	/// council only allows through council actions
+	///
+	/// @sender	the sender
+	/// @biz_hash	the business hash
+	/// @business	the business
+	fn validate_owner(sender: &T::AccountId, biz_hash: T::Hash, business: &BusinessOf<T>) -> Result {
+		Self::validate_business_authorization(sender, biz_hash, business.owner)?;
+		Self::validate_not_council_owned(biz_hash)
+	}
+
+	/// Validate the owner calls of a business are not handed to a council
+	///
+	/// @biz_hash	the business hash
+	fn validate_not_council_owned(biz_hash: T::Hash) -> Result {
+		ensure!(!<OwnerCouncils<T>>::exists(biz_hash), "Business is council owned");
+		Ok(())
+	}
+
+	/// Add an operator to the whitelist of a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @name_hash	the name hash of operator
+	/// @valid_until	the block from which the operator may no longer write, none for no limit
+	fn do_add_whitelist(
+		sender: T::AccountId,
+		biz_hash: T::Hash,
+		mut business: BusinessOf<T>,
+		name_hash: NameHash<T>,
+		valid_until: Option<T::BlockNumber>,
+	) -> Result {
+		ensure!(!business.whitelist.iter().any(|e| e.name_hash == name_hash), "Already in the whitelist");
+		ensure!(business.whitelist.len() < T::MaxWhitelistSize::get(), "Exceeds max whitelist size");
+		if let Some(until) = valid_until {
+			Self::validate_expiration(until)?;
+		}
+		if let Some(schema) = Self::required_attestation(biz_hash) {
+			ensure!(T::AttestationVerifier::has_valid_attestation(&name_hash, &schema), "Missing required attestation");
+		}
+		Self::validate_operator_capacity(&name_hash, 1)?;
+		business.whitelist.push(WhitelistEntryOf::<T> {
+			name_hash: name_hash,
+			valid_until: valid_until,
+		});
+		let new_list = Self::whitelist_names(&business);
+		<Businesses<T>>::insert(biz_hash, business);
+		Self::touch_business(biz_hash);
+		Self::index_operator(biz_hash, name_hash);
+
+		Self::deposit_event(RawEvent::WhitelistMemberAdded(sender.clone(), biz_hash, name_hash));
+		Self::deposit_event(RawEvent::BusinessWhitelistChanged(sender, biz_hash, new_list));
+		Ok(())
+	}
+
+	/// Remove an operator from the whitelist of a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @name_hash	the name hash of operator to be removed
+	fn do_remove_whitelist(sender: T::AccountId, biz_hash: T::Hash, mut business: BusinessOf<T>, name_hash: NameHash<T>) -> Result {
+		ensure!(business.whitelist.iter().any(|e| e.name_hash == name_hash), "Not in the whitelist");
+		business.whitelist.retain(|e| e.name_hash != name_hash);
+		let new_list = Self::whitelist_names(&business);
+		<Businesses<T>>::insert(biz_hash, business);
+		Self::touch_business(biz_hash);
+		Self::unindex_operator(biz_hash, name_hash);
+
+		Self::deposit_event(RawEvent::WhitelistMemberRemoved(sender.clone(), biz_hash, name_hash));
+		Self::deposit_event(RawEvent::BusinessWhitelistChanged(sender, biz_hash, new_list));
+		Ok(())
+	}
+
+	/// Set the expiration of a whitelist entry
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @name_hash	the name hash of operator
+	/// @valid_until	the block from which the operator may no longer write, none for no limit
+	fn do_set_whitelist_expiration(
+		sender: T::AccountId,
+		biz_hash: T::Hash,
+		mut business: BusinessOf<T>,
+		name_hash: NameHash<T>,
+		valid_until: Option<T::BlockNumber>,
+	) -> Result {
+		if let Some(until) = valid_until {
+			Self::validate_expiration(until)?;
+		}
+		let entry = business.whitelist.iter_mut().find(|e| e.name_hash == name_hash).ok_or("Not in the whitelist")?;
+		ensure!(entry.valid_until != valid_until, "Same value");
+		entry.valid_until = valid_until;
+		<Businesses<T>>::insert(biz_hash, business);
+		Self::touch_business(biz_hash);
+
+		Self::deposit_event(RawEvent::BusinessWhitelistExpirationChanged(sender, biz_hash, name_hash, valid_until));
+		Ok(())
+	}
+
+	/// Set or dissolve the owner council of a business, dropping the pending actions
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @members	the name hashes of the council members, none to dissolve it
+	/// @threshold	the number of member approvals an action needs
+	fn do_set_owner_council(sender: T::AccountId, biz_hash: T::Hash, members: Vec<NameHash<T>>, threshold: u32) -> Result {
+		if members.is_empty() {
+			ensure!(<OwnerCouncils<T>>::exists(biz_hash), "Business has no owner council");
+			<OwnerCouncils<T>>::remove(biz_hash);
+		} else {
+			ensure!(members.len() <= MAX_COUNCIL_SIZE, "Too many council members");
+			let mut sorted = members.clone();
+			sorted.sort();
+			sorted.dedup();
+			ensure!(sorted.len() == members.len(), "Duplicate council member");
+			ensure!(threshold > 0 && threshold as usize <= members.len(), "Invalid council threshold");
+			<OwnerCouncils<T>>::insert(biz_hash, OwnerCouncilOf::<T> {
+				members: members.clone(),
+				threshold: threshold,
+			});
+		}
+		<CouncilApprovals<T>>::remove_prefix(&biz_hash);
+		Self::touch_business(biz_hash);
+
+		Self::deposit_event(RawEvent::OwnerCouncilSet(sender, biz_hash, members, threshold));
+		Ok(())
+	}
+
+	/// Get the weight of the approval of a council action, with the action applied by the
+	/// approval reaching the threshold
+	///
+	/// @action	the council action
+	pub fn approve_council_action_weight(action: &CouncilActionOf<T>) -> Weight {
+		let applied = match action {
+			CouncilAction::AddWhitelist(..)
+				| CouncilAction::RemoveWhitelist(..)
+				| CouncilAction::SetWhitelistExpiration(..)
+				| CouncilAction::SetCouncil(..) => 0,
+			CouncilAction::CreateSubBusiness(_, name, _) =>
+				T::WeightInfo::create_sub_business(name.len() as u32, T::MaxSubBusinesses::get()),
+			CouncilAction::ConfirmWhitelistMember(..) => T::WeightInfo::confirm_whitelist_member(),
+			CouncilAction::SetRequiredAttestation(..) => T::WeightInfo::set_required_attestation(),
+			CouncilAction::SetBusinessProfile(..) => T::WeightInfo::set_business_profile(),
+			CouncilAction::SetTransferApproval(..) => T::WeightInfo::set_transfer_approval(),
+			CouncilAction::SetAuthorizationDepth(..) => T::WeightInfo::set_authorization_depth(),
+			CouncilAction::SetDedupPolicy(..) => T::WeightInfo::set_dedup_policy(),
+			CouncilAction::SetSeqMode(..) => T::WeightInfo::set_seq_mode(),
+			CouncilAction::SetExtraSchema(keys) => T::WeightInfo::set_extra_schema(keys.len() as u32),
+			CouncilAction::RedactProductInfo(..) => T::WeightInfo::redact_product_info(),
+			CouncilAction::IssueRecall(scope, _) => T::WeightInfo::issue_recall(scope.id_len()),
+			CouncilAction::LiftRecall(scope) => T::WeightInfo::lift_recall(scope.id_len()),
+			CouncilAction::AppealSuspension(..) => T::WeightInfo::appeal_suspension(),
+			CouncilAction::RemoveLot(lot_id) =>
+				T::WeightInfo::remove_lot(lot_id.len() as u32, T::MaxLotsPerBusiness::get()),
+			CouncilAction::RemoveProduct(..) => T::WeightInfo::remove_product(),
+		};
+		T::WeightInfo::approve_council_action(MAX_COUNCIL_SIZE as u32, T::MaxWhitelistSize::get() as u32)
+			.saturating_add(applied)
+	}
+
+	/// Create a sub-business under a business, expiring no later than its parent
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @parent_biz_hash	the hash of the parent business
+	/// @parent	the parent business
+	/// @owner	the hash of the owner name
+	/// @name	the business name in utf8
+	/// @expiration	the expiration height
+	fn do_create_sub_business(
+		sender: T::AccountId,
+		parent_biz_hash: T::Hash,
+		parent: BusinessOf<T>,
+		owner: NameHash<T>,
+		name: Vec<u8>,
+		expiration: T::BlockNumber,
+	) -> Result {
+		Self::validate_expiration(parent.expiration)?;
+		ensure!(parent.parent.is_none(), "Sub-businesses can not be nested");
+		ensure!(expiration <= parent.expiration, "Expiration beyond parent");
+		ensure!(
+			(Self::sub_businesses(parent_biz_hash).len() as u32) < T::MaxSubBusinesses::get(),
+			"Too many sub-businesses"
+		);
+
+		let biz_hash = Self::do_create_business(sender.clone(), parent.scope, owner, name, expiration, Some(parent_biz_hash))?;
+		<SubBusinesses<T>>::mutate(parent_biz_hash, |list| list.push(biz_hash));
+		Self::deposit_event(RawEvent::SubBusinessCreated(sender, parent_biz_hash, biz_hash));
+		Ok(())
+	}
+
+	/// Confirm a whitelist entry suspended as its operator resolves to a new address
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of operator
+	fn do_confirm_whitelist_member(sender: T::AccountId, biz_hash: T::Hash, name_hash: NameHash<T>) -> Result {
+		ensure!(Self::is_suspended((biz_hash, name_hash)), "Whitelist entry not suspended");
+		<SuspendedOperators<T>>::remove((biz_hash, name_hash));
+
+		Self::deposit_event(RawEvent::WhitelistMemberConfirmed(sender, biz_hash, name_hash));
+		Ok(())
+	}
+
+	/// Set the credential schema operators must hold to be whitelisted
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @schema	the hash of the credential schema, none to drop the requirement
+	fn do_set_required_attestation(sender: T::AccountId, biz_hash: T::Hash, schema: Option<T::Hash>) -> Result {
+		ensure!(Self::required_attestation(biz_hash) != schema, "Same value");
+		match schema {
+			Some(schema) => <RequiredAttestation<T>>::insert(biz_hash, schema),
+			None => <RequiredAttestation<T>>::remove(biz_hash),
+		}
+
+		Self::deposit_event(RawEvent::BusinessAttestationChanged(sender, biz_hash, schema));
+		Ok(())
+	}
+
+	/// Set the profile and the category of a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @profile	the hash of the off-chain profile document, none to drop it
+	/// @category	the category of the business, zero for none
+	fn do_set_business_profile(
+		sender: T::AccountId,
+		biz_hash: T::Hash,
+		mut business: BusinessOf<T>,
+		profile: Option<T::Hash>,
+		category: u16,
+	) -> Result {
+		ensure!(business.profile != profile || business.category != category, "Same value");
+		if business.category != category {
+			Self::unindex_category(biz_hash, business.category);
+			Self::index_category(biz_hash, category);
+		}
+		business.profile = profile;
+		business.category = category;
+		<Businesses<T>>::insert(biz_hash, business);
+		Self::touch_business(biz_hash);
+
+		Self::deposit_event(RawEvent::BusinessProfileChanged(sender, biz_hash, profile, category));
+		Ok(())
+	}
+
+	/// Pre-approve or revoke the transfers from a source business to a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @to_biz	the business hash
+	/// @from_biz	the source business hash
+	/// @approved	whether transfers from the source complete without acceptance
+	fn do_set_transfer_approval(sender: T::AccountId, to_biz: T::Hash, from_biz: T::Hash, approved: bool) -> Result {
+		ensure!(Self::transfer_approval((to_biz, from_biz)).is_some() != approved, "Same value");
+		if approved {
+			<TransferApprovals<T>>::insert((to_biz, from_biz), sender.clone());
+		} else {
+			<TransferApprovals<T>>::remove((to_biz, from_biz));
+		}
+
+		Self::deposit_event(RawEvent::TransferApprovalChanged(sender, to_biz, from_biz, approved));
+		Ok(())
+	}
+
+	/// Set the ancestor levels whose owners may act for a name in the calls on a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @depth	the number of ancestor levels
+	fn do_set_authorization_depth(sender: T::AccountId, biz_hash: T::Hash, depth: u32) -> Result {
+		ensure!(depth <= T::MaxAuthorizationDepth::get(), "Exceeds max authorization depth");
+		ensure!(Self::authorization_depth(biz_hash) != depth, "Same value");
+		if depth == 0 {
+			<AuthorizationDepth<T>>::remove(biz_hash);
+		} else {
+			<AuthorizationDepth<T>>::insert(biz_hash, depth);
+		}
+
+		Self::deposit_event(RawEvent::BusinessAuthorizationDepthChanged(sender, biz_hash, depth));
+		Ok(())
+	}
+
+	/// Set what the business does when an info repeats a data hash of its product
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @policy	the deduplication policy
+	fn do_set_dedup_policy(sender: T::AccountId, biz_hash: T::Hash, policy: DedupPolicy) -> Result {
+		ensure!(Self::dedup_policy(biz_hash) != policy, "Same value");
+		if policy == DedupPolicy::default() {
+			<DedupPolicies<T>>::remove(biz_hash);
+		} else {
+			<DedupPolicies<T>>::insert(biz_hash, policy);
+		}
+
+		Self::deposit_event(RawEvent::DedupPolicyChanged(sender, biz_hash, policy));
+		Ok(())
+	}
+
+	/// Set whether the operators provide the sequence ids or the chain assigns them
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @mode	the sequence id mode
+	fn do_set_seq_mode(sender: T::AccountId, biz_hash: T::Hash, mode: SeqMode) -> Result {
+		ensure!(Self::seq_mode(biz_hash) != mode, "Same value");
+		if mode == SeqMode::default() {
+			<SeqModes<T>>::remove(biz_hash);
+		} else {
+			<SeqModes<T>>::insert(biz_hash, mode);
+		}
+		if mode == SeqMode::AutoIncrement {
+			// Move on past the ids provided while the operators numbered the products
+			let next = Self::next_seq_id(biz_hash);
+			let (skipped, _) = Self::skip_taken_seq_ids(biz_hash, next);
+			if skipped != next {
+				<NextSeqIds<T>>::insert(biz_hash, skipped);
+			}
+		}
+
+		Self::deposit_event(RawEvent::SeqModeChanged(sender, biz_hash, mode));
+		Ok(())
+	}
+
+	/// Set the key ids the structured extra of the infos of the business may use
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @allowed_keys	the allowed key ids, none removing the schema
+	fn do_set_extra_schema(sender: T::AccountId, biz_hash: T::Hash, allowed_keys: Vec<u16>) -> Result {
+		ensure!(allowed_keys.len() <= MAX_EXTRA_SCHEMA_KEYS, "Too many extra keys");
+		let mut keys = allowed_keys;
+		keys.sort();
+		keys.dedup();
+		if keys.is_empty() {
+			ensure!(<ExtraSchemas<T>>::exists(biz_hash), "Same value");
+			<ExtraSchemas<T>>::remove(biz_hash);
+		} else {
+			ensure!(Self::extra_schema(biz_hash).as_ref() != Some(&keys), "Same value");
+			<ExtraSchemas<T>>::insert(biz_hash, &keys);
+		}
+
+		Self::deposit_event(RawEvent::ExtraSchemaChanged(sender, biz_hash, keys));
+		Ok(())
+	}
+
+	/// Redact the extra of a product info, keeping the hash of the original info as a tombstone
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	/// @index	the index of the info
+	fn do_redact_product_info(biz_hash: T::Hash, seq_id: Vec<u8>, index: u32) -> Result {
+		let seq_id = BoundedSeqId::try_from(seq_id)?;
+		let product_hash = Self::product_hash_of((biz_hash, seq_id.to_vec())).ok_or("Product does not exist")?;
+		ensure!(<Products<T>>::exists(product_hash), "Product does not exist");
+		ensure!(!<RedactedInfos<T>>::exists(product_hash, index), "Info already redacted");
+		let mut product = Self::product_of(product_hash);
+		let info = product.infos.get_mut(index as usize).ok_or("Info does not exist")?;
+		ensure!(!info.extra.is_empty(), "Extra info is empty");
+
+		let tombstone = merkle::leaf_of(info);
+		info.extra = Default::default();
+		info.extra_version = EXTRA_VERSION_RAW;
+		<Products<T>>::insert(product_hash, product);
+		<RedactedInfos<T>>::insert(product_hash, index, tombstone);
+		Self::touch_product(product_hash);
+
+		Self::deposit_event(RawEvent::ProductInfoRedacted(biz_hash, seq_id, index, tombstone));
+		Ok(())
+	}
+
+	/// Recall a product, a lot or all the products of a business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @scope	what the recall covers
+	/// @reason_hash	the hash of the recall notice
+	fn do_issue_recall(sender: T::AccountId, biz_hash: T::Hash, scope: RecallScope, reason_hash: T::Hash) -> Result {
+		let hash = Self::recall_subject(biz_hash, &scope)?;
+		ensure!(!<ActiveRecalls<T>>::exists(hash), "Already recalled");
+		let recall = RecallOf::<T> {
+			biz_hash: biz_hash,
+			issuer: sender.clone(),
+			issued_at: Self::block_number(),
+			timestamp: <timestamp::Module<T>>::get(),
+			reason_hash: reason_hash,
+		};
+
+		<ActiveRecalls<T>>::insert(hash, recall);
+		Self::touch_recall(&scope, hash);
+		Self::deposit_event(RawEvent::RecallIssued(sender, biz_hash, scope, hash, reason_hash));
+		Ok(())
+	}
+
+	/// Lift a recall of the business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @scope	what the recall covers
+	fn do_lift_recall(sender: T::AccountId, biz_hash: T::Hash, scope: RecallScope) -> Result {
+		let hash = Self::recall_subject(biz_hash, &scope)?;
+		let recall = Self::active_recall(hash).ok_or("Not recalled")?;
+		ensure!(recall.biz_hash == biz_hash, "Recalled by another business");
+
+		<ActiveRecalls<T>>::remove(hash);
+		Self::touch_recall(&scope, hash);
+		Self::deposit_event(RawEvent::RecallLifted(sender, biz_hash, scope, hash));
+		Ok(())
+	}
+
+	/// Appeal the suspension of a business, once and within the appeal period
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @appeal_hash	the hash of the appeal
+	fn do_appeal_suspension(sender: T::AccountId, biz_hash: T::Hash, appeal_hash: T::Hash) -> Result {
+		let mut suspension = Self::suspension_of(biz_hash).ok_or("Business is not suspended")?;
+		ensure!(suspension.appeal.is_none(), "Suspension already appealed");
+		ensure!(
+			Self::block_number() < suspension.suspended_at.saturating_add(T::AppealPeriod::get()),
+			"Appeal period is over"
+		);
+
+		suspension.appeal = Some(appeal_hash);
+		<Suspensions<T>>::insert(biz_hash, suspension);
+		Self::deposit_event(RawEvent::SuspensionAppealed(sender, biz_hash, appeal_hash));
+		Ok(())
+	}
+
+	/// Remove a lot without products and return its deposit to the pool of the business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @lot_id	the lot id
+	fn do_remove_lot(sender: T::AccountId, biz_hash: T::Hash, lot_id: Vec<u8>) -> Result {
+		Self::validate_not_suspended(biz_hash)?;
+
+		let lot_hash = Self::lot_hash(biz_hash, lot_id.clone());
+		ensure!(<Lots<T>>::exists(lot_hash), "Lot does not exist");
+		ensure!(Self::lot_product_count(lot_hash) == 0, "Lot has products");
+
+		Self::delete_lot(lot_hash);
+		<BusinessLots<T>>::mutate(biz_hash, |lots| lots.retain(|h| *h != lot_hash));
+		let deposit = <LotDeposits<T>>::take(lot_hash);
+		if !deposit.is_zero() {
+			<DepositPools<T>>::mutate(biz_hash, |pool| if let Some(pool) = pool {
+				pool.held = pool.held.saturating_sub(deposit);
+				pool.available = pool.available.saturating_add(deposit);
+			});
+		}
+		Self::deposit_event(RawEvent::LotRemoved(sender, biz_hash, lot_id, lot_hash));
+		Ok(())
+	}
+
+	/// Remove a product with its infos and return its deposit to the pool of the business
+	///
+	/// @sender	the owner or the council member applying the change
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id of the product
+	fn do_remove_product(sender: T::AccountId, biz_hash: T::Hash, seq_id: Vec<u8>) -> Result {
+		Self::validate_not_suspended(biz_hash)?;
+
+		let product_hash = Self::product_hash_of((biz_hash, seq_id.clone())).ok_or("Product does not exist")?;
+		ensure!(Self::product_custodian(product_hash).is_none(), "Product is in custody of another business");
+		ensure!(!<PendingTransfers<T>>::exists(product_hash), "Transfer already pending");
+
+		Self::delete_product(biz_hash, product_hash);
+		Self::deposit_event(RawEvent::ProductRemoved(sender, biz_hash, seq_id, product_hash));
+		Ok(())
+	}
+
+	/// Verify an operator payload signed off-chain and return the operator account. The signer
+	/// must be authorized for the operator name, as the sender of a direct write would be.
+	///
+	/// @context	the signing context of the call
+	/// @payload	the operator payload
+	/// @signature	the signature of the payload
+	pub fn verify_operator_payload(context: &[u8], payload: &OperatorPayloadOf<T>, signature: &T::OperatorSignature) -> rstd::result::Result<T::AccountId, &'static str> {
+		Self::validate_business_authorization(&payload.signer, payload.biz_hash, payload.name_hash)?;
+		ensure!(payload.nonce == Self::operator_nonce(payload.name_hash), "Invalid nonce");
+
+		let message = Self::operator_payload_message(context, payload);
+		ensure!(signature.verify(&message[..], &payload.signer), "Invalid signature");
+		Ok(payload.signer.clone())
+	}
+
+	/// Get the message an operator signs for a payload, bound to the genesis hash of the chain
+	///
+	/// @context	the signing context of the call
+	/// @payload	the operator payload
+	pub fn operator_payload_message(context: &[u8], payload: &OperatorPayloadOf<T>) -> Vec<u8> {
+		let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+		(context, genesis_hash, payload).encode()
+	}
+
+	/// Create a product on behalf of an authorized operator
+	///
+	/// @sender	the operator account
+	/// @name_hash	the name hash of the operator
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	/// @data_hash	the data hash
+	/// @extra	the extra information
+	fn do_create_product(sender: T::AccountId, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, data_hash: DataHash, extra: Vec<u8>) -> Result {
+		ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+		let business = Self::business_of(biz_hash);
+		Self::validate_whitelist(biz_hash, &business, &name_hash)?;
+		
+		Self::validate_expiration(business.expiration)?;
+		Self::validate_not_suspended(biz_hash)?;
+		Self::validate_write_rate(biz_hash, &name_hash)?;
+		let (seq_id, next_seq_id) = Self::assign_seq_id(biz_hash, seq_id)?;
+		ensure!(seq_id.len() <= T::MaxSeqIDLength::get(), "Sequence ID too long");
+		ensure!(extra.len() <= Self::max_extra_length(biz_hash), "Extra info too long");
+		Self::validate_extra(biz_hash, &extra)?;
+		let (seq_id, extra) = (BoundedSeqId::try_from(seq_id)?, BoundedExtra::try_from(extra)?);
+		data_hash.validate()?;
+		ensure!(!<ProductHashes<T>>::exists((biz_hash, seq_id.to_vec())), Error::DuplicateSeqId.into());
+		ensure!(!<HeldProducts<T>>::exists((biz_hash, seq_id.to_vec())), Error::DuplicateSeqId.into());
+		let product_hash = Self::product_hash(biz_hash, seq_id.to_vec());
+		ensure!(!<Products<T>>::exists(product_hash), Error::ProductHashCollision.into());
+		let deposit = Self::scope_config(biz_hash).product_deposit.unwrap_or_else(T::ProductDeposit::get)
+			.saturating_add(T::StorageFeePolicy::extra_deposit(extra.len()));
+		let pool = Self::take_product_deposit(biz_hash, deposit)?;
+		let anchor = Self::anchor_key(&data_hash);
+		let timestamp = <timestamp::Module<T>>::get();
+		let info = ProductInfoOf::<T> {
+			creator: sender.clone(),
+			created_at: Self::block_number(),
+			timestamp: timestamp,
+			kind: InfoKind::Created,
+			data_hash: data_hash,
+			extra_version: ExtraPayload::from_extra(&extra).version(),
+			extra: extra,
+		};
+
+		let product = ProductOf::<T> {
+			seq_id: seq_id.clone(),
+			infos_root: merkle::leaf_of(&info),
+			infos: vec![info],
+		};
+
+		Self::insert_product(biz_hash, product_hash, &product)?;
+		if let Some(pool) = pool {
+			<DepositPools<T>>::insert(biz_hash, pool);
+			<ProductDeposits<T>>::insert(product_hash, deposit);
+		}
+		if let Some(next) = next_seq_id {
+			<NextSeqIds<T>>::insert(biz_hash, next);
+		}
+		T::Anchors::note_anchor(anchor, &sender);
+		Self::note_operator_write(biz_hash, name_hash, true);
+		Self::deposit_event(RawEvent::ProductCreated(sender, biz_hash, seq_id, product_hash, timestamp));	
+		Self::deposit_info_recorded(product_hash, product.infos.first());
+
+		Ok(())
+	}
+
+	/// Get the sequence id a new product of the business takes, with the next value of the
+	/// counter of the business if the chain assigned it. The counter is stored by the caller
+	/// once the product is created. The write fails past `MAX_SEQ_ID_SKIPS` taken ids.
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id given by the operator
+	fn assign_seq_id(biz_hash: T::Hash, seq_id: Vec<u8>) -> rstd::result::Result<(Vec<u8>, Option<u64>), &'static str> {
+		match Self::seq_mode(biz_hash) {
+			SeqMode::Provided => Ok((seq_id, None)),
+			SeqMode::AutoIncrement => {
+				ensure!(seq_id.is_empty(), "Sequence ID is assigned");
+				let (number, free) = Self::skip_taken_seq_ids(biz_hash, Self::next_seq_id(biz_hash));
+				ensure!(free, "Too many sequence IDs taken");
+				let next = number.checked_add(1).ok_or("Sequence IDs exhausted")?;
+				Ok((Self::seq_id_of_number(number), Some(next)))
+			},
+		}
+	}
+
+	/// Skip at most `MAX_SEQ_ID_SKIPS` counter values whose sequence ids are taken, by the
+	/// products or the held products of the business. Returns the counter value reached and
+	/// whether its sequence id is free.
+	///
+	/// @biz_hash	the business hash
+	/// @number	the counter value to start from
+	fn skip_taken_seq_ids(biz_hash: T::Hash, mut number: u64) -> (u64, bool) {
+		let is_taken = |number: u64| {
+			let seq_id = Self::seq_id_of_number(number);
+			<ProductHashes<T>>::exists((biz_hash, seq_id.clone())) || <HeldProducts<T>>::exists((biz_hash, seq_id))
+		};
+		for _ in 0..MAX_SEQ_ID_SKIPS {
+			if !is_taken(number) {
+				return (number, true);
+			}
+			number = match number.checked_add(1) {
+				Some(next) => next,
+				None => return (number, false),
+			};
+		}
+		(number, !is_taken(number))
+	}
+
+	/// Format a counter value as a sequence id, in decimal
+	///
+	/// @n	the counter value
+	fn seq_id_of_number(mut n: u64) -> Vec<u8> {
+		let mut seq_id = Vec::new();
+		loop {
+			seq_id.push(b'0' + (n % 10) as u8);
+			n /= 10;
+			if n == 0 {
+				break;
+			}
+		}
+		seq_id.reverse();
+		seq_id
+	}
+
+	/// Append a product info on behalf of an authorized operator
+	///
+	/// @sender	the operator account
+	/// @name_hash	the name hash of the operator
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	/// @kind	the kind of the info
+	/// @data_hash	the data hash
+	/// @extra	the extra information
+	fn do_add_product_info(sender: T::AccountId, name_hash: NameHash<T>, biz_hash: T::Hash, seq_id: Vec<u8>, kind: InfoKind, data_hash: DataHash, extra: Vec<u8>) -> Result {
+		ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+		let business = Self::business_of(biz_hash);
+		Self::validate_whitelist(biz_hash, &business, &name_hash)?;
+		
+		Self::validate_expiration(business.expiration)?;
+		Self::validate_not_suspended(biz_hash)?;
+		Self::validate_write_rate(biz_hash, &name_hash)?;
+		ensure!(seq_id.len() <= T::MaxSeqIDLength::get(), "Sequence ID too long");
+		ensure!(extra.len() <= Self::max_extra_length(biz_hash), "Extra info too long");
+		Self::validate_extra(biz_hash, &extra)?;
+		let (seq_id, extra) = (BoundedSeqId::try_from(seq_id)?, BoundedExtra::try_from(extra)?);
+		data_hash.validate()?;
+		let product_hash = Self::held_product_hash(biz_hash, &seq_id).ok_or("Product does not exist")?;
+		let infos = Self::product_of(product_hash).infos;
+		ensure!(infos.len() < Self::max_product_info_count(biz_hash), "Exceeds max product info limit");
+		let policy = Self::dedup_policy(biz_hash);
+		let duplicate = policy != DedupPolicy::Allow
+			&& infos.iter().any(|info| info.data_hash == data_hash);
+		ensure!(!(duplicate && policy == DedupPolicy::Reject), Error::DuplicateDataHash.into());
+		// The deposits of a product go back to the business which created it, its custodians
+		// append free of the extra deposit
+		let created = Self::product_hash_of((biz_hash, seq_id.to_vec())) == Some(product_hash);
+		let deposit = if created { T::StorageFeePolicy::extra_deposit(extra.len()) } else { Zero::zero() };
+		let pool = Self::take_product_deposit(biz_hash, deposit)?;
+		let anchor = Self::anchor_key(&data_hash);
+		let timestamp = <timestamp::Module<T>>::get();
+		let info = ProductInfoOf::<T> {
+			creator: sender.clone(),
+			created_at: Self::block_number(),
+			timestamp: timestamp,
+			kind: kind,
+			data_hash: data_hash,
+			extra_version: ExtraPayload::from_extra(&extra).version(),
+			extra: extra,
+		};
+
+		Self::append_product_info(product_hash, &seq_id, info.clone())?;
+		if let Some(pool) = pool {
+			<DepositPools<T>>::insert(biz_hash, pool);
+			<ProductDeposits<T>>::mutate(product_hash, |held| *held = held.saturating_add(deposit));
+		}
+		T::Anchors::note_anchor(anchor, &sender);
+		Self::note_operator_write(biz_hash, name_hash, false);
+		if duplicate {
+			Self::deposit_event(RawEvent::DuplicateDataHashAppended(sender.clone(), biz_hash, product_hash));
+		}
+		Self::deposit_event(RawEvent::ProductInfoAppended(sender, biz_hash, seq_id, product_hash, kind, timestamp));	
+		Self::deposit_info_recorded(product_hash, Some(&info));
+
+		Ok(())
+	}
+
+	/// Note a business changed in the block
+	///
+	/// @biz_hash	the business hash
+	fn touch_business(biz_hash: T::Hash) {
+		Self::touch(|digest| &mut digest.businesses, biz_hash);
+	}
+
+	/// Note a product changed in the block
+	///
+	/// @product_hash	the product hash
+	fn touch_product(product_hash: T::Hash) {
+		Self::touch(|digest| &mut digest.products, product_hash);
+	}
+
+	/// Note a lot changed in the block
+	///
+	/// @lot_hash	the lot hash
+	fn touch_lot(lot_hash: T::Hash) {
+		Self::touch(|digest| &mut digest.lots, lot_hash);
+	}
+
+	/// Add a hash to a list of the block digest once, or mark the digest truncated when
+	/// it is full
+	///
+	/// @list	the list of the digest the hash goes to
+	/// @hash	the changed hash
+	fn touch(list: impl FnOnce(&mut RegistryDigest<T::Hash>) -> &mut Vec<T::Hash>, hash: T::Hash) {
+		<Digest<T>>::mutate(|digest| {
+			let full = digest.len() >= T::MaxDigestEntries::get() as usize;
+			let list = list(digest);
+			if !list.contains(&hash) {
+				if full {
+					digest.truncated = true;
+				} else {
+					list.push(hash);
+				}
+			}
+		});
+	}
+
+	/// Log an info in full if the runtime enables `RichEvents`
+	///
+	/// @hash	the product or lot hash
+	/// @info	the info just recorded
+	fn deposit_info_recorded(hash: T::Hash, info: Option<&ProductInfoOf<T>>) {
+		if let Some(info) = info.filter(|_| T::RichEvents::get()) {
+			Self::deposit_event(RawEvent::InfoRecorded(hash, info.clone()));
+		}
+	}
+
+	/// Ensure an operator may write a lot info to a business
+	///
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of the operator
+	/// @lot_id	the lot id
+	/// @data_hash	the data hash
+	/// @extra	the extra information
+	fn validate_operator_write(biz_hash: T::Hash, name_hash: &NameHash<T>, lot_id: &Vec<u8>, data_hash: &DataHash, extra: &Vec<u8>) -> Result {
+		ensure!(<Businesses<T>>::exists(biz_hash), "Business does not exist");
+		let business = Self::business_of(biz_hash);
+		Self::validate_whitelist(biz_hash, &business, name_hash)?;
+
+		Self::validate_expiration(business.expiration)?;
+		Self::validate_not_suspended(biz_hash)?;
+		Self::validate_write_rate(biz_hash, name_hash)?;
+		ensure!(lot_id.len() <= T::MaxSeqIDLength::get(), "Lot ID too long");
+		ensure!(extra.len() <= Self::max_extra_length(biz_hash), "Extra info too long");
+		Self::validate_extra(biz_hash, extra)?;
+		data_hash.validate()
+	}
+
+	/// Build an info recorded by an operator in the current block
+	///
+	/// @sender	the operator account
+	/// @kind	the kind of the info
+	/// @data_hash	the data hash
+	/// @extra	the extra information
+	fn new_info(sender: T::AccountId, kind: InfoKind, data_hash: DataHash, extra: BoundedExtra) -> ProductInfoOf<T> {
+		ProductInfoOf::<T> {
+			creator: sender,
+			created_at: Self::block_number(),
+			timestamp: <timestamp::Module<T>>::get(),
+			kind: kind,
+			data_hash: data_hash,
+			extra_version: ExtraPayload::from_extra(&extra).version(),
+			extra: extra,
+		}
+	}
+
+	/// Get the history of a product, the infos of its lot merged in by block. At the same
+	/// block the lot infos come first.
+	///
+	/// @product_hash	the product hash
+	pub fn product_history(product_hash: T::Hash) -> Vec<ProductInfoOf<T>> {
+		if !<Products<T>>::exists(product_hash) {
+			return Vec::new();
+		}
+		let items = Self::product_of(product_hash).infos;
+		let lot = match Self::product_lot(product_hash).and_then(|lot_hash| Self::lot_of(lot_hash)) {
+			Some(lot) => lot.infos,
+			None => return items,
+		};
+
+		let mut history = Vec::with_capacity(lot.len() + items.len());
+		let mut items = items.into_iter().peekable();
+		for info in lot {
+			while items.peek().map_or(false, |item| item.created_at < info.created_at) {
+				history.extend(items.next());
+			}
+			history.push(info);
+		}
+		history.extend(items);
+		history
+	}
+
+	/// Check if a product held by a business was recalled, by an info on the product or its
+	/// lot, or by a recall in force on the product, its lot or the business
+	///
+	/// @biz_hash	the business hash
+	/// @product_hash	the product hash
+	pub fn is_recalled(biz_hash: T::Hash, product_hash: T::Hash) -> bool {
+		Self::active_recall_of(biz_hash, product_hash).is_some()
+			|| Self::product_history(product_hash).iter().any(|info| info.kind == InfoKind::Recalled)
+	}
+
+	/// Get the recall in force on a product held by a business, on the product itself, its
+	/// lot or the business
+	///
+	/// @biz_hash	the business hash
+	/// @product_hash	the product hash
+	pub fn active_recall_of(biz_hash: T::Hash, product_hash: T::Hash) -> Option<RecallOf<T>> {
+		Self::active_recall(product_hash)
+			.or_else(|| Self::product_lot(product_hash).and_then(Self::active_recall))
+			.or_else(|| Self::active_recall(biz_hash))
+	}
+
+	/// Get the hash a recall of a business is stored under
+	///
+	/// @biz_hash	the business hash
+	/// @scope	what the recall covers
+	fn recall_subject(biz_hash: T::Hash, scope: &RecallScope) -> rstd::result::Result<T::Hash, &'static str> {
+		match scope {
+			RecallScope::Product(seq_id) => Self::held_product_hash(biz_hash, seq_id).ok_or("Product does not exist"),
+			RecallScope::Lot(lot_id) => {
+				let lot_hash = Self::lot_hash(biz_hash, lot_id.clone());
+				ensure!(<Lots<T>>::exists(lot_hash), "Lot does not exist");
+				Ok(lot_hash)
+			},
+			RecallScope::Business => Ok(biz_hash),
+		}
+	}
+
+	/// Note the record a recall covers changed in the block
+	///
+	/// @scope	what the recall covers
+	/// @hash	the recalled hash
+	fn touch_recall(scope: &RecallScope, hash: T::Hash) {
+		match scope {
+			RecallScope::Product(_) => Self::touch_product(hash),
+			RecallScope::Lot(_) => Self::touch_lot(hash),
+			RecallScope::Business => Self::touch_business(hash),
+		}
+	}
+
+	/// Check a one-time code against the Merkle root of the codes of a product. The leaves
+	/// are `code_leaf` of the codes, a node `code_node` of its left and right children, and
+	/// the bits of the index from the lowest tell whether each level is a right child. The
+	/// proof has a sibling for each of the `ceil(log2(count))` levels of the tree.
+	///
+	/// @root	the Merkle root
+	/// @count	the number of codes
+	/// @index	the index of the code
+	/// @code	the code
+	/// @proof	the sibling hashes from the leaf up to the root
+	pub fn verify_code_proof(root: T::Hash, count: u32, index: u32, code: &[u8], proof: &[T::Hash]) -> bool {
+		let depth = (32 - count.saturating_sub(1).leading_zeros()) as usize;
+		if index >= count || proof.len() != depth {
+			return false;
+		}
+		let mut node = Self::code_leaf(code);
+		for (level, sibling) in proof.iter().enumerate() {
+			node = if (index >> level) & 1 == 0 {
+				Self::code_node(node, *sibling)
+			} else {
+				Self::code_node(*sibling, node)
+			};
+		}
+		node == root
+	}
+
+	/// Get the leaf of a one-time code in the tree of the codes of a product
+	///
+	/// @code	the code
+	pub fn code_leaf(code: &[u8]) -> T::Hash {
+		(CODE_LEAF_DOMAIN, code).using_encoded(<T as system::Trait>::Hashing::hash)
+	}
+
+	/// Get an inner node of the tree of the codes of a product
+	///
+	/// @left	the left child
+	/// @right	the right child
+	pub fn code_node(left: T::Hash, right: T::Hash) -> T::Hash {
+		(CODE_NODE_DOMAIN, left, right).using_encoded(<T as system::Trait>::Hashing::hash)
+	}
+
+	/// Check if a one-time code of a product was redeemed
+	///
+	/// @product_hash	the product hash
+	/// @index	the index of the code
+	pub fn is_code_spent(product_hash: T::Hash, index: u32) -> bool {
+		Self::spent_codes(&product_hash, &(index / 64)) & (1u64 << (index % 64)) != 0
+	}
+
+	/// Get the infos of a product with the given kind
+	///
+	/// @product_hash	the product hash
+	/// @kind	the kind of the infos
+	pub fn product_infos_of_kind(product_hash: T::Hash, kind: InfoKind) -> Vec<ProductInfoOf<T>> {
+		Self::product_of(product_hash).infos.into_iter().filter(|info| info.kind == kind).collect()
+	}
+
+	/// Get the key under which the data hash of a product info is anchored: the digest
+	/// itself if it is a hash of the runtime hashing, else the runtime hash of the data hash
+	/// with its algorithm, so equal digests of different algorithms do not share an anchor
+	///
+	/// @data_hash	the data hash
+	pub fn anchor_key(data_hash: &DataHash) -> T::Hash {
+		data_hash.as_runtime_hash().unwrap_or_else(|| T::Hashing::hash_of(data_hash))
+	}
+
+	/// Get a value stored in an older layout, only if it decodes from the whole stored
+	/// value, so a record already in a later layout is not mistaken for it
+	///
+	/// @key	the storage key
+	fn legacy_get<V: Decode>(key: &[u8]) -> Option<V> {
+		let raw = unhashed::get_raw(key)?;
+		let mut input = &raw[..];
+		let value = V::decode(&mut input).ok()?;
+		if input.is_empty() {
+			Some(value)
+		} else {
+			None
+		}
+	}
+
+	/// Upgrade a business of the first layout to the current one and record it in the
+	/// indices added since. Its whitelist becomes entries without expiration, indexed in
+	/// `BusinessesByOperator`; it gets no profile, no category and no parent, and is put in
+	/// the default scope. It is indexed by its creator: its hash was derived from the former
+	/// nonce and is only indexed to be found, the indices of the next businesses follow it.
+	///
+	/// @biz_hash	the business hash
+	/// @old	the business in the first layout
+	fn migrate_legacy_business_record(biz_hash: T::Hash, old: BusinessV1Of<T>) -> BusinessOf<T> {
+		for name_hash in old.whitelist.iter() {
+			Self::index_operator(biz_hash, *name_hash);
+		}
+		let creator_index = Self::creator_business_count(&old.creator);
+		<BusinessesByCreator<T>>::insert(&old.creator, creator_index, biz_hash);
+		<CreatorBusinessCount<T>>::insert(&old.creator, creator_index + 1);
+
+		let business = BusinessOf::<T> {
+			creator: old.creator,
+			owner: old.owner,
+			name: BoundedName::truncating_from(old.name),
+			whitelist: old.whitelist.into_iter().map(|name_hash| WhitelistEntryOf::<T> {
+				name_hash: name_hash,
+				valid_until: None,
+			}).collect(),
+			expiration: old.expiration,
+			profile: None,
+			category: 0,
+			parent: None,
+			scope: Self::default_scope_hash(),
+		};
+		<Businesses<T>>::insert(biz_hash, &business);
+		business
+	}
+
+	/// Migrate a product of a business created before the enumeration indices, indexing it
+	/// in the business product index under the double map key, by its sequence id and by
+	/// its creator. The product keeps the hash it was derived with. Its infos are tagged
+	/// `Custom(0)`, their data hashes as runtime hashes, they get a zero timestamp, the block
+	/// number staying their only time, and the `extra_version` read from their `extra`, and
+	/// they are committed with a Merkle root.
+	///
+	/// @biz_hash	the business hash
+	/// @position	the position of the product in the business product index
+	fn migrate_legacy_product(biz_hash: T::Hash, position: u64) {
+		let product_hash = match unhashed::take::<T::Hash>(&Self::legacy_product_index_key(biz_hash, position)) {
+			Some(product_hash) => product_hash,
+			None => return,
+		};
+		<BusinessProductIndex<T>>::insert(biz_hash, position, product_hash);
+		let old = match Self::legacy_get::<ProductV0Of<T>>(&<Products<T>>::hashed_key_for(product_hash)) {
+			Some(old) => old,
+			None => return,
+		};
+
+		let infos = old.infos.into_iter().map(|info| {
+			let extra = BoundedExtra::truncating_from(info.extra);
+			ProductInfoOf::<T> {
+				creator: info.creator,
+				created_at: info.created_at,
+				timestamp: Zero::zero(),
+				kind: InfoKind::default(),
+				data_hash: DataHash::from_runtime_hash(&info.data_hash),
+				extra_version: ExtraPayload::from_extra(&extra).version(),
+				extra,
+			}
+		}).collect::<Vec<_>>();
+		let mut creator_position = 0;
+		if let Some(creator) = infos.first().map(|info| info.creator.clone()) {
+			creator_position = Self::creator_product_count(&creator);
+			<ProductsByCreator<T>>::insert((creator.clone(), creator_position), product_hash);
+			<CreatorProductCount<T>>::insert(&creator, creator_position + 1);
+		}
+		<ProductPositions<T>>::insert(product_hash, (position, creator_position));
+		<ProductHashes<T>>::insert((biz_hash, old.seq_id.clone()), product_hash);
+
+		let leaves = infos.iter().map(merkle::leaf_of).collect::<Vec<_>>();
+		let peaks = merkle::peaks_of(&leaves);
+		<Products<T>>::insert(product_hash, ProductOf::<T> {
+			seq_id: BoundedSeqId::truncating_from(old.seq_id),
+			infos,
+			infos_root: merkle::root_of_peaks(&peaks),
+		});
+		<InfoPeaks<T>>::insert(product_hash, peaks);
+	}
+
+	/// Register the default scope the businesses created before scopes are put in,
+	/// administered by the name of its hash, as the scope admin was before scopes
+	fn ensure_default_scope() {
+		let scope = Self::default_scope_hash();
+		if !<Scopes<T>>::exists(scope) {
+			Self::insert_scope(scope, scope);
+		}
+	}
+
+	/// Get the storage key of a business product index entry of the first layout, under the
+	/// `(business, index)` tuple
+	///
+	/// @biz_hash	the business hash
+	/// @index	the product index
+	fn legacy_product_index_key(biz_hash: T::Hash, index: u64) -> Vec<u8> {
+		let mut key = runtime_io::hashing::twox_128(b"BusinessModule").to_vec();
+		key.extend_from_slice(&runtime_io::hashing::twox_128(b"BusinessProductIndex"));
+		key.extend_from_slice(&(biz_hash, index).using_encoded(runtime_io::hashing::blake2_256));
+		key
+	}
+
+	/// Ensure an operator is in the whitelist of a business, its engagement is not over and
+	/// its entry is not suspended
+	///
+	/// @biz_hash	the business hash
+	/// @business	the business
+	/// @name_hash	the name hash of the operator
+	pub fn validate_whitelist(biz_hash: T::Hash, business: &BusinessOf<T>, name_hash: &NameHash<T>) -> Result {
+		let entry = business.whitelist.iter().find(|e| e.name_hash == *name_hash).ok_or("Not in the whitelist")?;
+		if let Some(until) = entry.valid_until {
+			ensure!(Self::block_number() < until, "Whitelist entry expired");
+		}
+		ensure!(!Self::is_suspended((biz_hash, *name_hash)), "Whitelist entry suspended");
+		Ok(())
+	}
+
+	/// Ensure an operator has not reached its write limit on a business for the block
+	///
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of the operator
+	fn validate_write_rate(biz_hash: T::Hash, name_hash: &NameHash<T>) -> Result {
+		ensure!(
+			Self::operator_writes(name_hash, &biz_hash) < T::MaxWritesPerOperatorPerBlock::get(),
+			Error::RateLimited.into()
+		);
+		Ok(())
+	}
+
+	/// Ensure neither the block nor the sender reached the verification limits
+	///
+	/// @sender	the sender
+	fn validate_verification_rate(sender: &T::AccountId) -> Result {
+		ensure!(
+			Self::block_verifications() < T::MaxVerificationsPerBlock::get(),
+			"Block verification limit reached"
+		);
+		ensure!(
+			Self::account_verifications(sender) < T::MaxVerificationsPerAccount::get(),
+			Error::RateLimited.into()
+		);
+		Ok(())
+	}
+
+	/// Count a verification of an account in the block
+	///
+	/// @sender	the sender
+	fn note_verification(sender: T::AccountId) {
+		let verifications = Self::account_verifications(&sender).saturating_add(1);
+		if verifications == 1 {
+			<VerifyingAccounts<T>>::append_or_put(&[sender.clone()]);
+		}
+		<AccountVerifications<T>>::insert(&sender, verifications);
+		<BlockVerifications>::mutate(|n| *n = n.saturating_add(1));
+	}
+
+	/// Count a write of an operator to a business, logging when it reaches the limit, and
+	/// add it to the stats of the operator
+	///
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of the operator
+	/// @created	whether the write created a product rather than appended an info
+	fn note_operator_write(biz_hash: T::Hash, name_hash: NameHash<T>, created: bool) {
+		<OperatorStats<T>>::mutate(&biz_hash, &name_hash, |stats| {
+			if created {
+				stats.products_created = stats.products_created.saturating_add(1);
+			} else {
+				stats.infos_appended = stats.infos_appended.saturating_add(1);
+			}
+			stats.last_active = Self::block_number();
+		});
+
+		let writes = Self::operator_writes(&name_hash, &biz_hash).saturating_add(1);
+		if writes == 1 {
+			// an operator writing to several businesses is listed once per business
+			<WritingOperators<T>>::append_or_put(&[name_hash]);
+		}
+		<OperatorWrites<T>>::insert(&name_hash, &biz_hash, writes);
+		if writes == T::MaxWritesPerOperatorPerBlock::get() {
+			Self::deposit_event(RawEvent::OperatorWriteLimitReached(biz_hash, name_hash));
+		}
+	}
+
+	/// Ensure a name may operate for more businesses without exceeding `MaxBusinessesPerName`
+	///
+	/// @name_hash	the name hash of the operator
+	/// @more	the number of businesses to add
+	fn validate_operator_capacity(name_hash: &NameHash<T>, more: usize) -> Result {
+		ensure!(
+			Self::businesses_by_operator(name_hash).len().saturating_add(more) <= T::MaxBusinessesPerName::get() as usize,
+			"Too many businesses for the name"
+		);
+		Ok(())
+	}
+
+	/// Record that the whitelist of a business holds an operator
+	///
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of the operator
+	fn index_operator(biz_hash: T::Hash, name_hash: NameHash<T>) {
+		<BusinessesByOperator<T>>::mutate(name_hash, |list| if !list.contains(&biz_hash) {
+			list.push(biz_hash)
+		});
+	}
+
+	/// Drop an operator removed from the whitelist of a business from the index
+	///
+	/// @biz_hash	the business hash
+	/// @name_hash	the name hash of the operator
+	fn unindex_operator(biz_hash: T::Hash, name_hash: NameHash<T>) {
+		<BusinessesByOperator<T>>::mutate(name_hash, |list| list.retain(|h| *h != biz_hash));
+		<SuspendedOperators<T>>::remove((biz_hash, name_hash));
+	}
+
+	/// Add a business to the index of its category, uncategorized businesses left out
+	///
+	/// @biz_hash	the business hash
+	/// @category	the category
+	fn index_category(biz_hash: T::Hash, category: u16) {
+		if category != 0 {
+			let position = Self::category_business_count(category);
+			<CategoryBusinesses<T>>::insert(category, position, biz_hash);
+			CategoryBusinessCount::insert(category, position + 1);
+			<CategoryPositions<T>>::insert(biz_hash, position);
+		}
+	}
+
+	/// Drop a business from the index of its category, the last business of the category
+	/// taking its position
+	///
+	/// @biz_hash	the business hash
+	/// @category	the category
+	fn unindex_category(biz_hash: T::Hash, category: u16) {
+		if category == 0 {
+			return;
+		}
+		let position = <CategoryPositions<T>>::take(biz_hash);
+		let last = Self::category_business_count(category).saturating_sub(1);
+		if position != last {
+			let moved = <CategoryBusinesses<T>>::get(category, last);
+			<CategoryBusinesses<T>>::insert(category, position, moved);
+			<CategoryPositions<T>>::insert(moved, position);
+		}
+		<CategoryBusinesses<T>>::remove(category, last);
+		CategoryBusinessCount::insert(category, last);
+	}
+
+	/// Get a page of the hashes of the businesses not archived under a category
+	///
+	/// @category	the category
+	/// @start	the position of the first business
+	/// @limit	the maximum number of businesses, capped by `MAX_BUSINESS_PAGE`
+	pub fn businesses_by_category(category: u16, start: u64, limit: u64) -> Vec<T::Hash> {
+		let end = start.saturating_add(limit.min(MAX_BUSINESS_PAGE)).min(Self::category_business_count(category));
+		(start..end).map(|position| Self::category_business(category, position)).collect()
+	}
+
+	/// Get the name hashes in the whitelist of a business
+	///
+	/// @business	the business
+	fn whitelist_names(business: &BusinessOf<T>) -> Vec<NameHash<T>> {
+		business.whitelist.iter().map(|e| e.name_hash).collect()
+	}
+
+	/// Get the weight of creating a product, for the longest whitelist
+	///
+	/// @seq_id	the sequence id
+	/// @extra	the extra information
+	fn create_product_weight(seq_id: &Vec<u8>, extra: &Vec<u8>) -> Weight {
+		T::WeightInfo::create_product(seq_id.len() as u32, extra.len() as u32, T::MaxWhitelistSize::get() as u32)
+	}
+
+	/// Get the weight of appending a product info, for the longest whitelist and a product
+	/// holding the most infos
+	///
+	/// @seq_id	the sequence id
+	/// @extra	the extra information
+	fn add_product_info_weight(seq_id: &Vec<u8>, extra: &Vec<u8>) -> Weight {
+		T::WeightInfo::add_product_info(
+			seq_id.len() as u32,
+			extra.len() as u32,
+			T::MaxWhitelistSize::get() as u32,
+			T::MaxProductInfoCount::get() as u32,
+		)
+	}
+
+	/// Get the deposit pool of a business with a product deposit moved from the available
+	/// to the held balance, none if products take no deposit. The pool is not written back.
+	///
+	/// @biz_hash	the business hash
+	/// @deposit	the product deposit
+	fn take_product_deposit(biz_hash: T::Hash, deposit: BalanceOf<T>) -> rstd::result::Result<Option<DepositPoolOf<T>>, &'static str> {
+		if deposit.is_zero() {
+			return Ok(None);
+		}
+		let mut pool = Self::deposit_pool(biz_hash).ok_or("Insufficient pool funds")?;
+		ensure!(deposit <= pool.available, "Insufficient pool funds");
+		pool.available -= deposit;
+		pool.held += deposit;
+		Ok(Some(pool))
+	}
+
+	/// Record the deposit an info of a lot took from the pool of its business
+	///
+	/// @biz_hash	the business hash
+	/// @lot_hash	the lot hash
+	/// @pool	the pool the deposit was taken from, none for no deposit
+	/// @deposit	the deposit
+	fn hold_lot_deposit(biz_hash: T::Hash, lot_hash: T::Hash, pool: Option<DepositPoolOf<T>>, deposit: BalanceOf<T>) {
+		if let Some(pool) = pool {
+			<DepositPools<T>>::insert(biz_hash, pool);
+			<LotDeposits<T>>::mutate(lot_hash, |held| *held = held.saturating_add(deposit));
+		}
+	}
+
+	/// Get the hash of the product a business holds under a sequence id, either created by
+	/// the business and never transferred away, or received by transfer
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	pub fn held_product_hash(biz_hash: T::Hash, seq_id: &[u8]) -> Option<T::Hash> {
+		if let Some(product_hash) = Self::held_products((biz_hash, seq_id.to_vec())) {
+			return Some(product_hash);
+		}
+		let product_hash = Self::product_hash_of((biz_hash, seq_id.to_vec()))?;
+		let held = Self::product_custodian(product_hash).map_or(true, |custodian| custodian == biz_hash);
+		if held { Some(product_hash) } else { None }
+	}
+
+	/// Ensure a business may hold a product under its sequence id
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	/// @product_hash	the product hash
+	fn validate_custody_slot(biz_hash: T::Hash, seq_id: &Vec<u8>, product_hash: T::Hash) -> Result {
+		let own_hash = Self::product_hash_of((biz_hash, seq_id.clone()));
+		// A product returning to its creating business takes its own slot back
+		if own_hash != Some(product_hash) {
+			ensure!(own_hash.is_none(), "Sequence ID already used by the destination");
+			ensure!(!<HeldProducts<T>>::exists((biz_hash, seq_id.clone())), "Sequence ID already used by the destination");
+		}
 		Ok(())
 	}
 
+	/// Move the custody of a product and record the change in its history
+	///
+	/// @product_hash	the product hash
+	/// @seq_id	the sequence id of the product
+	/// @transfer	the transfer to complete
+	/// @accepted_by	the account accepting the product
+	fn complete_transfer(product_hash: T::Hash, seq_id: Vec<u8>, transfer: PendingTransferOf<T>, accepted_by: T::AccountId) {
+		<HeldProducts<T>>::remove((transfer.from_biz, seq_id.clone()));
+		Self::touch_product(product_hash);
+		if Self::product_hash_of((transfer.to_biz, seq_id.clone())) == Some(product_hash) {
+			<ProductCustodian<T>>::remove(product_hash);
+		} else {
+			<HeldProducts<T>>::insert((transfer.to_biz, seq_id), product_hash);
+			<ProductCustodian<T>>::insert(product_hash, transfer.to_biz);
+		}
+		<PendingTransfers<T>>::remove(product_hash);
+
+		let record = CustodyRecordOf::<T> {
+			from_biz: transfer.from_biz,
+			to_biz: transfer.to_biz,
+			released_by: transfer.released_by,
+			accepted_by: accepted_by.clone(),
+			transferred_at: Self::block_number(),
+		};
+		<ProductCustody<T>>::mutate(product_hash, |history| history.push(record));
+		Self::deposit_event(RawEvent::ProductTransferred(accepted_by, product_hash, transfer.from_biz, transfer.to_biz));
+	}
+
+	/// Check if a business exists
+	///
+	/// @biz_hash	the business hash
+	pub fn business_exists(biz_hash: T::Hash) -> bool {
+		<Businesses<T>>::exists(biz_hash)
+	}
+
 	/// Validate expiration 
 	/// 
 	/// @expiration	the expiration height at which business is expired
@@ -341,13 +4003,78 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
+	/// Validate the business is not suspended
+	///
+	/// @biz_hash	the business hash
+	pub fn validate_not_suspended(biz_hash: T::Hash) -> Result {
+		ensure!(!<Suspensions<T>>::exists(biz_hash), "Business is suspended");
+		Ok(())
+	}
+
+	/// Create a business after checking its name and expiration
+	///
+	/// @sender	the creator
+	/// @owner	the hash of the owner name
+	/// @name	the business name in utf8
+	/// @expiration	the expiration height
+	/// @parent	the parent business of a sub-business
+	fn do_create_business(
+		sender: T::AccountId,
+		scope: T::Hash,
+		owner: NameHash<T>,
+		name: Vec<u8>,
+		expiration: T::BlockNumber,
+		parent: Option<T::Hash>,
+	) -> rstd::result::Result<T::Hash, &'static str> {
+		let name = T::NameServiceResolver::normalize_name(&name)?;
+		ensure!(name.len() >= T::MinNameLength::get(), "Name too short");
+		ensure!(name.len() <= T::MaxNameLength::get(), "Name too long");
+		ensure!(!T::NameServiceResolver::is_reserved(&name), "Name is reserved");
+		let name = BoundedName::try_from(name)?;
+
+		Self::validate_expiration(expiration)?;
+
+		let index = Self::creator_business_count(&sender);
+		let biz_hash = Self::business_hash(sender.clone(), owner, name.to_vec(), index);
+		let business = BusinessOf::<T> {
+			creator: sender.clone(),
+			owner: owner,
+			name: name,
+			whitelist: Vec::new(),
+			expiration: expiration,
+			profile: None,
+			category: 0,
+			parent: parent,
+			scope: scope,
+		};
+
+		Self::insert_business(biz_hash, &business)?;
+		<CreatorBusinessCount<T>>::insert(&sender, index + 1);
+		<BusinessesByCreator<T>>::insert(&sender, index, biz_hash);
+		Self::deposit_event(RawEvent::BusinessCreated(sender, biz_hash));
+		Ok(biz_hash)
+	}
+
 	/// Insert business to the lookup table
 	/// 
 	/// @hash	the business hash
 	/// @business	the business object
 	pub fn insert_business(hash: T::Hash, business: &BusinessOf<T>) -> Result {
 		ensure!(!<Businesses<T>>::exists(hash), "Business already exists");
+		ensure!(
+			Self::businesses_by_owner(business.owner).len() < T::MaxBusinessesPerName::get() as usize,
+			"Too many businesses for the name"
+		);
+		let count = Self::business_count();
+		let new_count = count
+			.checked_add(1)
+			.ok_or("Overflow adding a new business")?;
+
 		<Businesses<T>>::insert(hash, business);
+		Self::touch_business(hash);
+		<BusinessesByOwner<T>>::mutate(business.owner, |list| list.push(hash));
+		<BusinessIndex<T>>::insert(count, hash);
+		BusinessCount::put(new_count);
 			
 		Ok(())
 	}
@@ -365,71 +4092,590 @@ impl<T: Trait> Module<T> {
             .checked_add(1)
             .ok_or("Overflow adding a new product")?;
 
-		ensure!(!<BusinessProductIndex<T>>::exists((biz_hash, info_count)), "Business product hash collides???");
+		let creator = info.infos.first().map(|i| i.creator.clone()).ok_or("Product has no info")?;
+		let creator_count = Self::creator_product_count(&creator);
+		let new_creator_count = creator_count
+			.checked_add(1)
+			.ok_or("Overflow adding a new product")?;
+
+		ensure!(!<BusinessProductIndex<T>>::exists(biz_hash, info_count), "Business product hash collides???");
 		<Products<T>>::insert(product_hash, info);
-		<BusinessProductIndex<T>>::insert((biz_hash, info_count), product_hash);
+		Self::touch_product(product_hash);
+		<BusinessProductIndex<T>>::insert(biz_hash, info_count, product_hash);
 		<ProductCount<T>>::insert(biz_hash, new_info_count);
+		<ProductsByCreator<T>>::insert((creator.clone(), creator_count), product_hash);
+		<CreatorProductCount<T>>::insert(creator, new_creator_count);
+		<ProductPositions<T>>::insert(product_hash, (info_count, creator_count));
+		<ProductHashes<T>>::insert((biz_hash, info.seq_id.to_vec()), product_hash);
+		<InfoPeaks<T>>::insert(product_hash, merkle::peaks_of(&info.infos.iter().map(merkle::leaf_of).collect::<Vec<_>>()));
+		Self::deposit_event(RawEvent::ProductInfosRootChanged(product_hash, info.infos_root));
 		
 		Ok(())
 	}
 
+	/// Delete a product, swap-removing it from the business and creator product indices,
+	/// and return its deposit to the pool of the business
+	///
+	/// @biz_hash	the business hash
+	/// @product_hash	the product hash
+	fn delete_product(biz_hash: T::Hash, product_hash: T::Hash) {
+		let product = <Products<T>>::take(product_hash);
+		Self::touch_product(product_hash);
+		if let Some(lot_hash) = <ProductLot<T>>::take(product_hash) {
+			<LotProductCount<T>>::mutate(lot_hash, |count| *count = count.saturating_sub(1));
+		}
+		<ActiveRecalls<T>>::remove(product_hash);
+		<InfoPeaks<T>>::remove(product_hash);
+		<RedactedInfos<T>>::remove_prefix(&product_hash);
+		<ProductCodes<T>>::remove(product_hash);
+		<SpentCodes<T>>::remove_prefix(&product_hash);
+		<ReplayedCodes<T>>::remove(product_hash);
+		let (biz_position, creator_position) = <ProductPositions<T>>::take(product_hash);
+		<ProductHashes<T>>::remove((biz_hash, product.seq_id.to_vec()));
+
+		let last = Self::product_count(biz_hash).saturating_sub(1);
+		if biz_position != last {
+			let moved = <BusinessProductIndex<T>>::get(biz_hash, last);
+			<BusinessProductIndex<T>>::insert(biz_hash, biz_position, moved);
+			<ProductPositions<T>>::mutate(moved, |p| p.0 = biz_position);
+		}
+		<BusinessProductIndex<T>>::remove(biz_hash, last);
+		<ProductCount<T>>::insert(biz_hash, last);
+
+		if let Some(creator) = product.infos.first().map(|i| i.creator.clone()) {
+			let last = Self::creator_product_count(&creator).saturating_sub(1);
+			if creator_position != last {
+				let moved = <ProductsByCreator<T>>::get((creator.clone(), last));
+				<ProductsByCreator<T>>::insert((creator.clone(), creator_position), moved);
+				<ProductPositions<T>>::mutate(moved, |p| p.1 = creator_position);
+			}
+			<ProductsByCreator<T>>::remove((creator.clone(), last));
+			<CreatorProductCount<T>>::insert(creator, last);
+		}
+
+		let deposit = <ProductDeposits<T>>::take(product_hash);
+		if !deposit.is_zero() {
+			<DepositPools<T>>::mutate(biz_hash, |pool| if let Some(pool) = pool {
+				pool.held = pool.held.saturating_sub(deposit);
+				pool.available = pool.available.saturating_add(deposit);
+			});
+		}
+		<ProductCustody<T>>::remove(product_hash);
+	}
+
+	/// Delete a lot with its recall, leaving its deposits to the caller
+	///
+	/// @lot_hash	the lot hash
+	fn delete_lot(lot_hash: T::Hash) {
+		<Lots<T>>::remove(lot_hash);
+		<LotProductCount<T>>::remove(lot_hash);
+		<ActiveRecalls<T>>::remove(lot_hash);
+		Self::touch_lot(lot_hash);
+	}
+
 	/// Append product info to an existing product
 	/// 
 	/// @product_hash	the product hash
 	/// @seq_id	the sequence id
 	/// @info	the product info
-	pub fn append_product_info(product_hash: T::Hash, seq_id: &Vec<u8>, info: ProductInfoOf<T>) -> Result {
+	pub fn append_product_info(product_hash: T::Hash, seq_id: &[u8], info: ProductInfoOf<T>) -> Result {
 		ensure!(<Products<T>>::exists(product_hash), "Product does not exist");
 
 		let mut product = Self::product_of(product_hash);
-		ensure!(product.seq_id == *seq_id, "Product sequence id not match, should not happen");
+		ensure!(product.seq_id[..] == *seq_id, "Product sequence id not match, should not happen");
 		ensure!(product.infos.len() < T::MaxProductInfoCount::get(), "Exceeds max product info limit");
-		// Append the record to the end of collection
+		// Append the record to the end of collection, and its leaf to the Merkle tree
+		let mut peaks = Self::info_peaks(product_hash);
+		merkle::append(&mut peaks, product.infos.len() as u64, merkle::leaf_of(&info));
 		product.infos.push(info);
+		product.infos_root = merkle::root_of_peaks(&peaks);
+		let root = product.infos_root;
 
 		<Products<T>>::insert(product_hash, product);
+		<InfoPeaks<T>>::insert(product_hash, peaks);
+		Self::touch_product(product_hash);
+		Self::deposit_event(RawEvent::ProductInfosRootChanged(product_hash, root));
 
 		Ok(())
 	}
 
+	/// Check if a business is expired for longer than the grace period
+	///
+	/// @biz_hash	the business hash
+	/// @now	the current block number
+	pub fn is_archivable(biz_hash: T::Hash, now: T::BlockNumber) -> bool {
+		if !<Businesses<T>>::exists(biz_hash) {
+			return false;
+		}
+		let business = Self::business_of(biz_hash);
+		business.expiration.saturating_add(T::ArchiveGracePeriod::get()) <= now
+	}
+
+	/// Move the business to the archive and delete its products and lots, releasing the
+	/// products held in custody of other businesses
+	///
+	/// @biz_hash	the business hash
+	fn archive_business(biz_hash: T::Hash) {
+		let business = <Businesses<T>>::take(biz_hash);
+		Self::touch_business(biz_hash);
+		<BusinessesByOwner<T>>::mutate(business.owner, |list| list.retain(|h| *h != biz_hash));
+		for name_hash in Self::whitelist_names(&business) {
+			Self::unindex_operator(biz_hash, name_hash);
+		}
+		Self::unindex_category(biz_hash, business.category);
+		if let Some(parent) = business.parent {
+			<SubBusinesses<T>>::mutate(parent, |list| list.retain(|h| *h != biz_hash));
+		}
+		<SubBusinesses<T>>::remove(biz_hash);
+
+		// From the last product, so no other product moves in the business index
+		for index in (0..Self::product_count(biz_hash)).rev() {
+			let product_hash = Self::business_product_index(biz_hash, index);
+			if let Some(custodian) = <ProductCustodian<T>>::take(product_hash) {
+				<HeldProducts<T>>::remove((custodian, Self::product_of(product_hash).seq_id.to_vec()));
+			}
+			<PendingTransfers<T>>::remove(product_hash);
+			Self::delete_product(biz_hash, product_hash);
+		}
+		<ProductCount<T>>::remove(biz_hash);
+		<BusinessProductIndex<T>>::remove_prefix(biz_hash);
+		for lot_hash in <BusinessLots<T>>::take(biz_hash) {
+			Self::delete_lot(lot_hash);
+			<LotDeposits<T>>::remove(lot_hash);
+		}
+		<OwnerCouncils<T>>::remove(biz_hash);
+		<CouncilApprovals<T>>::remove_prefix(biz_hash);
+		<Suspensions<T>>::remove(biz_hash);
+
+		// The deposits of the products and lots are refunded along with the rest of the pool
+		if let Some(pool) = <DepositPools<T>>::take(biz_hash) {
+			T::Currency::unreserve(&pool.funder, pool.available.saturating_add(pool.held));
+		}
+
+		<ArchivedBusinesses<T>>::insert(biz_hash, business);
+	}
+
+	/// Scan for archivable businesses and submit an archive transaction
+	///
+	/// @now	the current block number
+	fn offchain_archive(now: T::BlockNumber) {
+		let count = Self::business_count();
+		if count == 0 {
+			return;
+		}
+
+		// Pick the first local key which is allowed to archive
+		let archivers = Self::archivers();
+		let key = match T::ArchiverId::all().into_iter().find(|k| archivers.binary_search(k).is_ok()) {
+			Some(key) => key,
+			None => return,
+		};
+
+		let cursor = runtime_io::offchain::local_storage_get(StorageKind::PERSISTENT, ARCHIVE_CURSOR_KEY)
+			.and_then(|raw| u64::decode(&mut &raw[..]).ok())
+			.unwrap_or(0);
+
+		let scan = rstd::cmp::min(T::MaxArchiveScan::get() as u64, count);
+		let mut biz_hashes = Vec::new();
+		let mut products = 0u32;
+		let mut lots = 0u32;
+		for i in 0..scan {
+			let biz_hash = Self::business_index((cursor + i) % count);
+			if Self::is_archivable(biz_hash, now) {
+				biz_hashes.push(biz_hash);
+				products = products.saturating_add(Self::product_count(biz_hash).saturated_into());
+				lots = lots.saturating_add(Self::business_lots(biz_hash).len() as u32);
+			}
+		}
+		let next_cursor = (cursor + scan) % count;
+		runtime_io::offchain::local_storage_set(StorageKind::PERSISTENT, ARCHIVE_CURSOR_KEY, &next_cursor.encode());
+
+		if biz_hashes.is_empty() {
+			return;
+		}
+
+		let payload = ArchivePayloadOf::<T> {
+			block_number: now,
+			biz_hashes: biz_hashes,
+			products: products,
+			lots: lots,
+			public: key.clone(),
+		};
+		let signature = match key.sign(&payload.encode()) {
+			Some(signature) => signature,
+			None => {
+				debug::warn!(target: "business", "Failed to sign the archive payload at {:?}", now);
+				return;
+			}
+		};
+
+		if T::SubmitTransaction::submit_unsigned(Call::archive_businesses(payload, signature)).is_err() {
+			debug::warn!(target: "business", "Failed to submit the archive transaction at {:?}", now);
+		}
+	}
+
+	/// Get the account and block which first anchored the data hash of a product info
+	///
+	/// @product_hash	the product hash
+	/// @index	the index of the info
+	pub fn info_anchor(product_hash: T::Hash, index: usize) -> Option<(T::AccountId, T::BlockNumber)> {
+		let product = Self::product_of(product_hash);
+		product.infos.get(index).and_then(|info| T::Anchors::anchor_of(Self::anchor_key(&info.data_hash)))
+	}
+
+	/// Get a business with its owner and whitelist resolved through the name service
+	///
+	/// @biz_hash	the business hash
+	pub fn business_info(biz_hash: T::Hash) -> Option<BusinessDetailsOf<T>> {
+		if !<Businesses<T>>::exists(biz_hash) {
+			return None;
+		}
+		let business = Self::business_of(biz_hash);
+		let whitelist = business.whitelist.into_iter().map(|entry| ResolvedWhitelistEntry {
+			name: T::NameServiceResolver::resolve_name(entry.name_hash),
+			account: T::NameServiceResolver::resolve_addr(entry.name_hash),
+			name_hash: entry.name_hash,
+			valid_until: entry.valid_until,
+		}).collect();
+		Some(BusinessDetails {
+			creator: business.creator,
+			owner: business.owner,
+			owner_name: T::NameServiceResolver::resolve_name(business.owner),
+			owner_account: T::NameServiceResolver::resolve_addr(business.owner),
+			name: business.name.into_inner(),
+			whitelist,
+			expiration: business.expiration,
+			profile: business.profile,
+			category: business.category,
+			parent: business.parent,
+			scope: business.scope,
+		})
+	}
+
+	/// Get a product by the sequence id its business created it with, a `Recalled` info
+	/// appended while a recall of the product, its lot or the business is in force
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	pub fn product_by_seq_id(biz_hash: T::Hash, seq_id: Vec<u8>) -> Option<ProductOf<T>> {
+		let product_hash = Self::product_hash_of((biz_hash, seq_id))?;
+		if !<Products<T>>::exists(product_hash) {
+			return None;
+		}
+		let mut product = Self::product_of(product_hash);
+		if let Some(recall) = Self::active_recall_of(biz_hash, product_hash) {
+			product.infos.push(ProductInfoOf::<T> {
+				creator: recall.issuer,
+				created_at: recall.issued_at,
+				timestamp: recall.timestamp,
+				kind: InfoKind::Recalled,
+				data_hash: DataHash::from_runtime_hash(&recall.reason_hash),
+				extra: Default::default(),
+				extra_version: EXTRA_VERSION_RAW,
+			});
+		}
+		Some(product)
+	}
+
+	/// Get the storage keys of a product created by a business: the key mapping its sequence
+	/// id to the product hash, then the key of the product under that hash, or under its
+	/// legacy hash if the sequence id is not recorded. A read proof of these keys lets a light
+	/// client check the product against a header with `proof::verify_product_proof`.
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	pub fn product_storage_keys(biz_hash: T::Hash, seq_id: Vec<u8>) -> Vec<Vec<u8>> {
+		let product_hash = Self::product_hash_of((biz_hash, seq_id.clone()))
+			.unwrap_or_else(|| Self::legacy_product_hash(biz_hash, &seq_id));
+		vec![
+			<ProductHashes<T>>::hashed_key_for((biz_hash, seq_id)),
+			<Products<T>>::hashed_key_for(product_hash),
+		]
+	}
+
+	/// Get the proof of an info of a product created by a business against the Merkle root
+	/// of its infos. The recall infos `product_by_seq_id` appends are not committed.
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	/// @index	the index of the info
+	pub fn product_info_proof(biz_hash: T::Hash, seq_id: Vec<u8>, index: u32) -> Option<InfoProof> {
+		let product_hash = Self::product_hash_of((biz_hash, seq_id))?;
+		if !<Products<T>>::exists(product_hash) {
+			return None;
+		}
+		let product = Self::product_of(product_hash);
+		let leaves = Self::info_leaves(product_hash, &product);
+		Some(InfoProof {
+			root: product.infos_root,
+			index: index,
+			count: leaves.len() as u32,
+			proof: merkle::proof(&leaves, index)?,
+		})
+	}
+
+	/// Get the commitment to the provenance of a product created by a business, the
+	/// archived businesses included, to relay to other chains
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	pub fn provenance_commitment(biz_hash: T::Hash, seq_id: Vec<u8>) -> Option<ProvenanceCommitmentOf<T>> {
+		let business = if <Businesses<T>>::exists(biz_hash) {
+			Self::business_of(biz_hash)
+		} else {
+			Self::archived_business_of(biz_hash)?
+		};
+		let product_hash = Self::product_hash_of((biz_hash, seq_id.clone()))?;
+		if !<Products<T>>::exists(product_hash) {
+			return None;
+		}
+		let product = Self::product_of(product_hash);
+		Some(VersionedProvenanceCommitment::V1(ProvenanceCommitmentV1 {
+			business: biz_hash,
+			business_name: business.name.into_inner(),
+			owner: business.owner,
+			seq_id,
+			product: product_hash,
+			infos_root: product.infos_root,
+			info_count: product.infos.len() as u32,
+			block_number: <system::Module<T>>::block_number(),
+			parent_hash: <system::Module<T>>::parent_hash(),
+		}))
+	}
+
+	/// Get the Merkle leaves of the infos of a product, the tombstones standing for the
+	/// redacted infos
+	///
+	/// @product_hash	the product hash
+	/// @product	the product
+	fn info_leaves(product_hash: T::Hash, product: &ProductOf<T>) -> Vec<H256> {
+		product.infos.iter().enumerate()
+			.map(|(index, info)| Self::redacted_info(product_hash, index as u32)
+				.unwrap_or_else(|| merkle::leaf_of(info)))
+			.collect()
+	}
+
+	/// Get a page of the product hashes of a business, in creation order
+	///
+	/// @biz_hash	the business hash
+	/// @start	the index of the first product
+	/// @limit	the maximum number of products, capped by `MAX_PRODUCT_PAGE`
+	pub fn list_products(biz_hash: T::Hash, start: u64, limit: u64) -> Vec<T::Hash> {
+		let end = start.saturating_add(limit.min(MAX_PRODUCT_PAGE)).min(Self::product_count(biz_hash));
+		(start..end).map(|index| Self::business_product_index(biz_hash, index)).collect()
+	}
+
 	/// Get current block number
     fn block_number() -> T::BlockNumber {
         <system::Module<T>>::block_number()
 	}
 
-	/// Get business hash
-	/// 
-	/// @sender	the sender
+	/// Get the hash of a business, which clients can derive before creating it. The
+	/// businesses created before the domain separation keep their hashes.
+	///
+	/// @creator	the account creating the business
 	/// @owner	the owner's name hash
-	fn business_hash(sender: T::AccountId, owner: NameHash<T>) -> T::Hash {
-		let nonce = Nonce::get();
-		// TODO: use u64 as business id? 
+	/// @name	the normalized business name
+	/// @index	the number of businesses the creator created before
+	pub fn business_hash(creator: T::AccountId, owner: NameHash<T>, name: Vec<u8>, index: u64) -> T::Hash {
 		(
-			// <randomness_collective_flip::Module<T>>::random_seed(),
-			sender,
-			owner, // TODO: add other fields
-			nonce,
+			BUSINESS_HASH_DOMAIN,
+			creator,
+			owner,
+			runtime_io::hashing::blake2_256(&name),
+			index,
 		).using_encoded(<T as system::Trait>::Hashing::hash)
 	}
 
-	/// Get product hash
+	/// Get the hash the next business of the creator will be stored under
+	///
+	/// @creator	the account creating the business
+	/// @owner	the owner's name hash
+	/// @name	the normalized business name
+	pub fn next_business_hash(creator: T::AccountId, owner: NameHash<T>, name: Vec<u8>) -> T::Hash {
+		let index = Self::creator_business_count(&creator);
+		Self::business_hash(creator, owner, name, index)
+	}
+
+	/// Get the hash a new product is stored under. The products created before the domain
+	/// separation keep their hashes, look them up with `product_hash_of`.
 	/// 
 	/// @biz_hash	the business hash
 	/// @seq_id	the sequence id
-	fn product_hash(biz_hash: T::Hash, seq_id: Vec<u8>) -> T::Hash {
-		// (
-		// 	biz_hash,
-		// 	seq_id.clone(),
-		// ).using_encoded(<T as system::Trait>::Hashing::hash)
-
-		let mut data = biz_hash.as_ref().to_vec();
-		data.append(&mut seq_id.clone());
-		<T as system::Trait>::Hashing::hash(data.as_slice())
+	pub fn product_hash(biz_hash: T::Hash, seq_id: Vec<u8>) -> T::Hash {
+		(
+			PRODUCT_HASH_DOMAIN,
+			biz_hash,
+			runtime_io::hashing::blake2_256(&seq_id),
+		).using_encoded(<T as system::Trait>::Hashing::hash)
 	}
 	
-	/// Get scope name hash
-	fn scope_name_hash() -> T::Hash {
+	/// Get the hash a product created before the sequence ids were recorded is stored under,
+	/// until the migration records its sequence id
+	///
+	/// @biz_hash	the business hash
+	/// @seq_id	the sequence id
+	pub fn legacy_product_hash(biz_hash: T::Hash, seq_id: &[u8]) -> T::Hash {
+		let mut data = biz_hash.as_ref().to_vec();
+		data.extend_from_slice(seq_id);
+		<T as system::Trait>::Hashing::hash(&data)
+	}
+
+	/// Get the hash of a lot
+	///
+	/// @biz_hash	the business hash
+	/// @lot_id	the lot id
+	pub fn lot_hash(biz_hash: T::Hash, lot_id: Vec<u8>) -> T::Hash {
+		(
+			LOT_HASH_DOMAIN,
+			biz_hash,
+			runtime_io::hashing::blake2_256(&lot_id),
+		).using_encoded(<T as system::Trait>::Hashing::hash)
+	}
+
+	/// Get the hash of the default `pistis` scope, also the name hash of its admin, which
+	/// the businesses created before scopes belong to
+	pub fn default_scope_hash() -> T::Hash {
 		// TODO: calculate name hash recursively
 		(b"pistis").using_encoded(<T as system::Trait>::Hashing::hash)
 	}
+
+	/// Check the storage invariants of the businesses and their product indices. Walks all
+	/// the businesses, so it is meant for tests and tooling, never for the runtime.
+	///
+	/// - the `ProductCount` of a business matches its `BusinessProductIndex` rows, each
+	///   pointing at a product whose positions and sequence id point back at the row, and
+	///   whose infos match its Merkle root and peaks. The rows are only pushed and popped at
+	///   the tail, so a row left by a missed count update sits at `ProductCount`, the only
+	///   position probed past the count as the double map can not be iterated.
+	/// - archived businesses keep no `ProductCount` nor `BusinessProductIndex` rows, which
+	///   are removed by prefix, so only the first row is probed
+	/// - every whitelist entry is unique and its business is listed under its name hash in
+	///   `BusinessesByOperator`. The entry need not resolve through the name service, as a
+	///   name may be whitelisted before it resolves to an account.
+	/// - a business not archived is listed under its owner and its parent, and belongs to a
+	///   registered scope listed under its admin
+	#[cfg(feature = "std")]
+	pub fn do_try_state() -> Result {
+		for index in 0..Self::business_count() {
+			ensure!(<BusinessIndex<T>>::exists(index), "Business index has a gap");
+			let biz_hash = Self::business_index(index);
+
+			if !<Businesses<T>>::exists(biz_hash) {
+				ensure!(Self::archived_business_of(biz_hash).is_some(), "Indexed business does not exist");
+				ensure!(!<ProductCount<T>>::exists(biz_hash), "Archived business has a product count");
+				ensure!(!<BusinessProductIndex<T>>::exists(biz_hash, 0), "Archived business has product index rows");
+				continue;
+			}
+			ensure!(Self::archived_business_of(biz_hash).is_none(), "Business is both live and archived");
+
+			let count = Self::product_count(biz_hash);
+			for position in 0..count {
+				ensure!(<BusinessProductIndex<T>>::exists(biz_hash, position), "Product count exceeds the index rows");
+				let product_hash = Self::business_product_index(biz_hash, position);
+				ensure!(<Products<T>>::exists(product_hash), "Indexed product does not exist");
+				let product = Self::product_of(product_hash);
+				let (biz_position, creator_position) = Self::product_positions(product_hash);
+				ensure!(biz_position == position, "Product position does not match its index row");
+				ensure!(Self::product_hash_of((biz_hash, product.seq_id.to_vec())) == Some(product_hash), "Product sequence id does not resolve");
+				if let Some(creator) = product.infos.first().map(|i| i.creator.clone()) {
+					ensure!(Self::products_by_creator((creator, creator_position)) == product_hash, "Product creator position does not match");
+				}
+				let peaks = merkle::peaks_of(&Self::info_leaves(product_hash, &product));
+				ensure!(Self::info_peaks(product_hash) == peaks, "Product infos peaks do not match");
+				ensure!(product.infos_root == merkle::root_of_peaks(&peaks), "Product infos root does not match");
+			}
+			ensure!(!<BusinessProductIndex<T>>::exists(biz_hash, count), "Product index row past the product count");
+
+			let business = Self::business_of(biz_hash);
+			for (i, entry) in business.whitelist.iter().enumerate() {
+				ensure!(!business.whitelist[..i].iter().any(|e| e.name_hash == entry.name_hash), "Duplicate whitelist entry");
+				ensure!(Self::businesses_by_operator(entry.name_hash).contains(&biz_hash), "Whitelist entry is not indexed");
+			}
+			ensure!(Self::businesses_by_owner(business.owner).contains(&biz_hash), "Business is not indexed under its owner");
+			let scope = Self::scope_of(business.scope).ok_or("Business scope does not exist")?;
+			ensure!(Self::scopes_by_admin(scope.admin).contains(&business.scope), "Scope is not indexed under its admin");
+			if let Some(parent) = business.parent.filter(|parent| <Businesses<T>>::exists(parent)) {
+				ensure!(Self::sub_businesses(parent).contains(&biz_hash), "Sub-business is not indexed under its parent");
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<T: Trait> name_service::NodeReferences<T::Hash> for Module<T> {
+	/// The scope admins, and the owners and operators of the businesses not archived are
+	/// referenced
+	///
+	/// @node_hash	the node hash
+	fn is_referenced(node_hash: &T::Hash) -> bool {
+		!Self::scopes_by_admin(node_hash).is_empty()
+			|| !Self::businesses_by_owner(node_hash).is_empty()
+			|| !Self::businesses_by_operator(node_hash).is_empty()
+	}
+}
+
+impl<T: Trait> name_service::OnResolveChanged<T> for Module<T> {
+	/// Warn the businesses owned by the name, and warn or suspend the whitelist entries of the
+	/// businesses it operates for, at most `MaxBusinessesPerName` of each
+	///
+	/// @node_hash	the node hash
+	fn on_resolve_changed(node_hash: T::Hash, _old: Option<T::AccountId>, _new: Option<T::AccountId>) {
+		// the lists indexed by the migrations may exceed the bound
+		let max = T::MaxBusinessesPerName::get() as usize;
+		for biz_hash in Self::businesses_by_owner(node_hash).into_iter().take(max) {
+			Self::deposit_event(RawEvent::OwnerResolveChanged(biz_hash, node_hash));
+		}
+		for biz_hash in Self::businesses_by_operator(node_hash).into_iter().take(max) {
+			match T::OnOperatorResolveChanged::get() {
+				ResolveChangeAction::Warn => {
+					Self::deposit_event(RawEvent::OperatorResolveChanged(biz_hash, node_hash));
+				},
+				ResolveChangeAction::Suspend => {
+					<SuspendedOperators<T>>::insert((biz_hash, node_hash), true);
+					Self::deposit_event(RawEvent::WhitelistMemberSuspended(biz_hash, node_hash));
+				},
+			}
+		}
+	}
+
+	fn max_weight() -> Weight {
+		T::WeightInfo::on_resolve_changed(T::MaxBusinessesPerName::get())
+	}
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::archive_businesses(payload, signature) = call {
+			// Only the configured archivers can submit
+			if Self::archivers().binary_search(&payload.public).is_err() {
+				return InvalidTransaction::BadProof.into();
+			}
+
+			let now = Self::block_number();
+			if payload.block_number > now {
+				return InvalidTransaction::Future.into();
+			}
+			if payload.biz_hashes.is_empty() || !payload.biz_hashes.iter().any(|h| Self::is_archivable(*h, now)) {
+				return InvalidTransaction::Stale.into();
+			}
+
+			let signature_valid = payload.using_encoded(|encoded| payload.public.verify(&encoded, signature));
+			if !signature_valid {
+				return InvalidTransaction::BadProof.into();
+			}
+
+			Ok(ValidTransaction {
+				priority: TransactionPriority::max_value(),
+				requires: vec![],
+				provides: payload.biz_hashes.iter().map(|h| (&b"archive"[..], h).encode()).collect(),
+				longevity: 64,
+				propagate: true,
+			})
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
 }
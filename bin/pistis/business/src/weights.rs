@@ -0,0 +1,259 @@
+//! # Business Weights
+//!
+//! The weights of the business calls, as functions of the input sizes they read and write.
+//! Lists held in storage are weighed at the bounds the calls enforce on them, never read while
+//! weighing. The `()` implementation prices each call by the storage reads and writes it makes,
+//! at the per-operation figures of `pistis_primitives::weights`; `benches/bench.rs` measures the
+//! calls on the reference hardware, so a runtime can replace the figures with its own.
+
+use pistis_primitives::weights::{db, linear, BYTE, READ, WRITE};
+use support::weights::Weight;
+use crate::MAX_SEQ_ID_SKIPS;
+
+/// The weight of decoding and comparing an entry of a stored list, about 40 bytes
+const ENTRY: Weight = 40 * BYTE;
+
+/// The weight of decoding and hashing a product info into its Merkle leaf
+const INFO: Weight = 4 * ENTRY;
+
+/// The weight of verifying a signature
+const SIGNATURE: Weight = 4 * WRITE;
+
+/// The weight of authorizing the sender for a name, reading its resolve record
+const AUTHORIZE: Weight = READ;
+
+/// Weight functions of the business calls
+pub trait WeightInfo {
+	/// @n	the length of the business name
+	fn create_business(n: u32) -> Weight;
+	/// @n	the length of the business name
+	/// @c	the maximum number of sub-businesses
+	fn create_sub_business(n: u32, c: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	/// @c	the maximum number of sub-businesses
+	fn set_business_expiration(w: u32, c: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	fn add_business_whitelist(w: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	fn remove_business_whitelist(w: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	/// @n	the length of the new whitelist
+	fn set_business_whitelist(w: u32, n: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	fn set_whitelist_expiration(w: u32) -> Weight;
+	fn set_required_attestation() -> Weight;
+	fn set_business_profile() -> Weight;
+	fn fund_business() -> Weight;
+	fn withdraw_business_funds() -> Weight;
+	/// @s	the length of the sequence id
+	/// @e	the length of the extra information
+	/// @w	the maximum length of a whitelist
+	fn create_product(s: u32, e: u32, w: u32) -> Weight;
+	/// @s	the length of the sequence id
+	/// @e	the length of the extra information
+	/// @w	the maximum length of a whitelist
+	/// @i	the maximum info entries of a product
+	fn add_product_info(s: u32, e: u32, w: u32, i: u32) -> Weight;
+	/// @p	the length of the signed payload
+	fn verify_operator_payload(p: u32) -> Weight;
+	fn remove_product() -> Weight;
+	/// @l	the length of the lot id
+	/// @e	the length of the extra information
+	/// @w	the maximum length of a whitelist
+	fn create_lot(l: u32, e: u32, w: u32) -> Weight;
+	/// @l	the length of the lot id
+	/// @e	the length of the extra information
+	/// @w	the maximum length of a whitelist
+	/// @i	the maximum info entries of a lot
+	fn add_lot_info(l: u32, e: u32, w: u32, i: u32) -> Weight;
+	/// @s	the length of the sequence and lot ids
+	/// @w	the maximum length of a whitelist
+	fn set_product_lot(s: u32, w: u32) -> Weight;
+	/// @l	the length of the lot id
+	/// @n	the maximum number of lots of a business
+	fn remove_lot(l: u32, n: u32) -> Weight;
+	/// @s	the length of the sequence id
+	/// @w	the maximum length of a whitelist
+	fn commit_codes(s: u32, w: u32) -> Weight;
+	/// @c	the length of the code
+	/// @p	the depth of the code proof
+	fn redeem_code(c: u32, p: u32) -> Weight;
+	/// @s	the length of the sequence id
+	/// @i	the maximum info entries of a product
+	fn verify_product(s: u32, i: u32) -> Weight;
+	/// @s	the length of the sequence id
+	/// @w	the maximum length of a whitelist
+	fn transfer_product(s: u32, w: u32) -> Weight;
+	/// @w	the maximum length of a whitelist
+	fn accept_product_transfer(w: u32) -> Weight;
+	fn cancel_product_transfer() -> Weight;
+	fn set_transfer_approval() -> Weight;
+	fn set_authorization_depth() -> Weight;
+	fn set_dedup_policy() -> Weight;
+	fn set_seq_mode() -> Weight;
+	/// @k	the number of allowed keys
+	fn set_extra_schema(k: u32) -> Weight;
+	fn redact_product_info() -> Weight;
+	fn set_admin_delegate() -> Weight;
+	fn remove_admin_delegate() -> Weight;
+	fn register_scope() -> Weight;
+	fn retire_scope() -> Weight;
+	fn set_scope_config() -> Weight;
+	/// @s	the length of the sequence or lot id
+	fn issue_recall(s: u32) -> Weight;
+	/// @s	the length of the sequence or lot id
+	fn lift_recall(s: u32) -> Weight;
+	fn suspend_business() -> Weight;
+	fn appeal_suspension() -> Weight;
+	fn reinstate_business() -> Weight;
+	/// @m	the number of council members
+	fn set_owner_council(m: u32) -> Weight;
+	/// @m	the maximum number of council members
+	/// @w	the maximum length of a whitelist
+	fn approve_council_action(m: u32, w: u32) -> Weight;
+	fn confirm_whitelist_member() -> Weight;
+	/// @b	the number of businesses
+	/// @w	the maximum length of a whitelist
+	fn rotate_operator(b: u32, w: u32) -> Weight;
+	/// @a	the number of archiver keys
+	fn set_archivers(a: u32) -> Weight;
+	/// @b	the number of businesses to archive
+	/// @p	the number of their products
+	/// @l	the number of their lots
+	fn archive_businesses(b: u32, p: u32, l: u32) -> Weight;
+	/// @b	the maximum businesses a name may own or operate for
+	fn on_resolve_changed(b: u32) -> Weight;
+	/// @p	the maximum number of products to migrate
+	fn migrate_legacy_business(p: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+	// The scope and its delegates, the nonce, the business and its owner, creator and enumeration
+	// indices, the digest, the event. The sender is authorized for the admin, or its delegation
+	fn create_business(n: u32) -> Weight {
+		linear(db(8, 9).saturating_add(2 * AUTHORIZE), &[(BYTE, n)])
+	}
+	fn create_sub_business(n: u32, c: u32) -> Weight {
+		linear(db(12, 10).saturating_add(AUTHORIZE), &[(BYTE, n), (ENTRY, c)])
+	}
+	fn set_business_expiration(w: u32, c: u32) -> Weight {
+		// Capping a sub-business rewrites it and notes it in the digest
+		linear(db(8, 5).saturating_add(AUTHORIZE), &[(ENTRY, w), (db(1, 3), c)])
+	}
+	fn add_business_whitelist(w: u32) -> Weight {
+		linear(db(7, 5).saturating_add(AUTHORIZE), &[(ENTRY, w)])
+	}
+	fn remove_business_whitelist(w: u32) -> Weight {
+		linear(db(6, 5).saturating_add(AUTHORIZE), &[(ENTRY, w)])
+	}
+	fn set_business_whitelist(w: u32, n: u32) -> Weight {
+		// An added operator is checked for its attestation and indexed, a removed one
+		// unindexed, each with its event; diffing the lists is quadratic
+		linear(db(7, 4).saturating_add(AUTHORIZE), &[(db(0, 2), w), (db(1, 2), n), (BYTE, w.saturating_mul(n))])
+	}
+	fn set_whitelist_expiration(w: u32) -> Weight {
+		linear(db(6, 3).saturating_add(AUTHORIZE), &[(ENTRY, w)])
+	}
+	fn set_required_attestation() -> Weight { db(5, 2).saturating_add(AUTHORIZE) }
+	// Moving the business between categories swaps the last business of the old one in
+	fn set_business_profile() -> Weight { db(10, 11).saturating_add(AUTHORIZE) }
+	fn fund_business() -> Weight { db(6, 3).saturating_add(AUTHORIZE) }
+	fn withdraw_business_funds() -> Weight { db(2, 3) }
+	// The operator checks, the sequence and product indices, the deposit, the anchor and the
+	// digest, and the taken sequence ids skipped in `AutoIncrement` mode
+	fn create_product(s: u32, e: u32, w: u32) -> Weight {
+		linear(db(14, 12).saturating_add(AUTHORIZE), &[(BYTE, s), (BYTE, e), (ENTRY, w), (db(2, 0), MAX_SEQ_ID_SKIPS)])
+	}
+	fn add_product_info(s: u32, e: u32, w: u32, i: u32) -> Weight {
+		linear(db(12, 8).saturating_add(AUTHORIZE), &[(BYTE, s), (BYTE, e), (ENTRY, w), (INFO, i)])
+	}
+	fn verify_operator_payload(p: u32) -> Weight {
+		linear(db(2, 1).saturating_add(SIGNATURE), &[(BYTE, p)])
+	}
+	// Leaving its lot counts one product less
+	fn remove_product() -> Weight { db(11, 13).saturating_add(AUTHORIZE) }
+	fn create_lot(l: u32, e: u32, w: u32) -> Weight {
+		linear(db(11, 9).saturating_add(AUTHORIZE), &[(BYTE, l), (BYTE, e), (ENTRY, w)])
+	}
+	fn add_lot_info(l: u32, e: u32, w: u32, i: u32) -> Weight {
+		linear(db(10, 7).saturating_add(AUTHORIZE), &[(BYTE, l), (BYTE, e), (ENTRY, w), (INFO, i)])
+	}
+	fn set_product_lot(s: u32, w: u32) -> Weight {
+		linear(db(11, 4).saturating_add(AUTHORIZE), &[(BYTE, s), (ENTRY, w)])
+	}
+	fn remove_lot(l: u32, n: u32) -> Weight {
+		// The lot, its product count, recall and deposit, the lots of the business and the pool
+		linear(db(8, 8).saturating_add(AUTHORIZE), &[(BYTE, l), (ENTRY, n)])
+	}
+	fn commit_codes(s: u32, w: u32) -> Weight {
+		linear(db(10, 3).saturating_add(AUTHORIZE), &[(BYTE, s), (ENTRY, w)])
+	}
+	fn redeem_code(c: u32, p: u32) -> Weight {
+		// A hash of two nodes per level of the proof
+		linear(db(3, 3), &[(BYTE, c), (2 * ENTRY, p)])
+	}
+	fn verify_product(s: u32, i: u32) -> Weight {
+		// Withdrawing the fee of a mismatch, counting the verification of the block and the sender
+		linear(db(9, 6), &[(BYTE, s), (INFO, i)])
+	}
+	fn transfer_product(s: u32, w: u32) -> Weight {
+		linear(db(16, 8).saturating_add(AUTHORIZE), &[(BYTE, s), (ENTRY, w)])
+	}
+	fn accept_product_transfer(w: u32) -> Weight {
+		linear(db(14, 8).saturating_add(AUTHORIZE), &[(ENTRY, w)])
+	}
+	fn cancel_product_transfer() -> Weight { db(5, 2).saturating_add(AUTHORIZE) }
+	fn set_transfer_approval() -> Weight { db(6, 2).saturating_add(AUTHORIZE) }
+	fn set_authorization_depth() -> Weight { db(5, 2).saturating_add(AUTHORIZE) }
+	fn set_dedup_policy() -> Weight { db(6, 2).saturating_add(AUTHORIZE) }
+	// Switching to `AutoIncrement` skips the taken sequence ids
+	fn set_seq_mode() -> Weight {
+		linear(db(6, 3).saturating_add(AUTHORIZE), &[(db(2, 0), MAX_SEQ_ID_SKIPS)])
+	}
+	fn set_extra_schema(k: u32) -> Weight {
+		linear(db(6, 2).saturating_add(AUTHORIZE), &[(ENTRY, k)])
+	}
+	fn redact_product_info() -> Weight { db(8, 4).saturating_add(AUTHORIZE) }
+	fn set_admin_delegate() -> Weight { db(4, 3).saturating_add(AUTHORIZE) }
+	fn remove_admin_delegate() -> Weight { db(4, 3).saturating_add(AUTHORIZE) }
+	fn register_scope() -> Weight { db(1, 3) }
+	fn retire_scope() -> Weight { db(1, 2) }
+	fn set_scope_config() -> Weight { db(4, 2).saturating_add(AUTHORIZE) }
+	fn issue_recall(s: u32) -> Weight {
+		linear(db(7, 4).saturating_add(AUTHORIZE), &[(BYTE, s)])
+	}
+	fn lift_recall(s: u32) -> Weight {
+		linear(db(7, 4).saturating_add(AUTHORIZE), &[(BYTE, s)])
+	}
+	fn suspend_business() -> Weight { db(3, 3) }
+	fn appeal_suspension() -> Weight { db(6, 2).saturating_add(AUTHORIZE) }
+	fn reinstate_business() -> Weight { db(2, 3) }
+	fn set_owner_council(m: u32) -> Weight {
+		linear(db(6, 3).saturating_add(AUTHORIZE), &[(ENTRY, m)])
+	}
+	fn approve_council_action(m: u32, w: u32) -> Weight {
+		// The last approval applies a whitelist or council change
+		linear(db(10, 7).saturating_add(AUTHORIZE), &[(ENTRY, m), (ENTRY, w)])
+	}
+	fn confirm_whitelist_member() -> Weight { db(6, 2).saturating_add(AUTHORIZE) }
+	fn rotate_operator(b: u32, w: u32) -> Weight {
+		// Each business is rewritten, reindexed and noted in the digest
+		linear(db(3, 0).saturating_add(AUTHORIZE), &[(db(4, 6), b), (ENTRY, b.saturating_mul(w))])
+	}
+	fn set_archivers(a: u32) -> Weight {
+		linear(db(0, 1), &[(ENTRY, a)])
+	}
+	fn archive_businesses(b: u32, p: u32, l: u32) -> Weight {
+		// Deleting a product costs about as much as `remove_product`, a lot its entries
+		linear(db(1, 0), &[(db(13, 15), b), (db(11, 13), p), (db(1, 5), l)])
+	}
+	// The owner and operator indices of the name, an event per business owned and a
+	// suspension with its event per business operated for
+	fn on_resolve_changed(b: u32) -> Weight {
+		linear(db(2, 0), &[(WRITE, b), (db(0, 2), b)])
+	}
+	fn migrate_legacy_business(p: u32) -> Weight {
+		// The business and its indices, and per product its record and indices
+		linear(db(5, 7), &[(db(3, 8), p)])
+	}
+}
@@ -0,0 +1,142 @@
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use crate::*;
+	use super::*;
+
+	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use primitives::H256;
+	// The testing primitives are very useful for avoiding having to work with signatures
+	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
+	use sp_runtime::{
+		Perbill, testing::Header, traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Call = ();
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const MaxDidLength: usize = 64;
+		pub const MaxKeyLength: usize = 32;
+		pub const MaxKeys: usize = 2;
+		pub const MaxServiceLength: usize = 64;
+		pub const MaxServices: usize = 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type MaxDidLength = MaxDidLength;
+		type MaxKeyLength = MaxKeyLength;
+		type MaxKeys = MaxKeys;
+		type MaxServiceLength = MaxServiceLength;
+		type MaxServices = MaxServices;
+	}
+
+	type System = system::Module<Test>;
+	type Did = Module<Test>;
+
+	const DID: &str = "did:pistis:v0:1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg-1";
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	#[test]
+	fn create_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Did::create(Origin::signed(1), "did:example:123".into(), vec![1; 32]), "Invalid DID method");
+			assert_noop!(Did::create(Origin::signed(1), "did:pistis:".into(), vec![1; 32]), "DID too short");
+			assert_noop!(Did::create(Origin::signed(1), DID.into(), vec![]), "Key is empty");
+			assert_noop!(Did::create(Origin::signed(1), DID.into(), vec![1; 33]), "Key too long");
+
+			System::set_block_number(5);
+			assert_ok!(Did::create(Origin::signed(1), DID.into(), vec![1; 32]));
+			assert_noop!(Did::create(Origin::signed(2), DID.into(), vec![2; 32]), "DID already exists");
+
+			let did_hash = Did::did_hash(DID.as_bytes());
+			let document = Did::document_of(did_hash).unwrap();
+			assert_eq!(document.controller, 1);
+			assert_eq!(document.public_keys, vec![vec![1; 32]]);
+			assert_eq!(document.created_at, 5);
+			assert!(Did::is_valid_profile(&DataHash::from_runtime_hash(&did_hash)));
+		});
+	}
+
+	#[test]
+	fn update_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Did::create(Origin::signed(1), DID.into(), vec![1; 32]));
+			let did_hash = Did::did_hash(DID.as_bytes());
+			let service = ServiceEndpoint { id: "hub".into(), endpoint: "https://example.com/hub".into() };
+
+			assert_noop!(Did::update(Origin::signed(2), did_hash, vec![service.clone()]), "Sender is not controller");
+			assert_noop!(Did::update(Origin::signed(1), did_hash, vec![service.clone(); 3]), "Too many services");
+			assert_noop!(Did::update(Origin::signed(1), did_hash, vec![service.clone(); 2]), "Duplicate service id");
+
+			System::set_block_number(3);
+			assert_ok!(Did::update(Origin::signed(1), did_hash, vec![service.clone()]));
+			assert_eq!(Did::document_of(did_hash).unwrap().services, vec![service]);
+			assert_eq!(Did::document_of(did_hash).unwrap().updated_at, 3);
+		});
+	}
+
+	#[test]
+	fn rotate_key_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Did::create(Origin::signed(1), DID.into(), vec![1; 32]));
+			let did_hash = Did::did_hash(DID.as_bytes());
+
+			assert_noop!(Did::rotate_key(Origin::signed(1), did_hash, vec![9; 32], vec![2; 32]), "Key does not exist");
+			assert_noop!(Did::rotate_key(Origin::signed(1), did_hash, vec![1; 32], vec![1; 32]), "Key already exists");
+			assert_ok!(Did::rotate_key(Origin::signed(1), did_hash, vec![1; 32], vec![2; 32]));
+			assert_eq!(Did::document_of(did_hash).unwrap().public_keys, vec![vec![2; 32]]);
+
+			assert_ok!(Did::rotate_key(Origin::signed(1), did_hash, vec![], vec![3; 32]));
+			assert_noop!(Did::rotate_key(Origin::signed(1), did_hash, vec![], vec![4; 32]), "Too many keys");
+			assert_eq!(Did::document_of(did_hash).unwrap().public_keys, vec![vec![2; 32], vec![3; 32]]);
+		});
+	}
+
+	#[test]
+	fn deactivate_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Did::create(Origin::signed(1), DID.into(), vec![1; 32]));
+			let did_hash = Did::did_hash(DID.as_bytes());
+
+			assert_noop!(Did::deactivate(Origin::signed(2), did_hash), "Sender is not controller");
+			assert_ok!(Did::deactivate(Origin::signed(1), did_hash));
+			assert_noop!(Did::deactivate(Origin::signed(1), did_hash), "DID is deactivated");
+			assert!(!Did::is_valid_profile(&DataHash::from_runtime_hash(&did_hash)));
+		});
+	}
+}
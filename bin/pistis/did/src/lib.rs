@@ -0,0 +1,289 @@
+//! # DID Module
+//!
+//! - [`did::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module is a registry of `did:pistis` identifiers. Each DID document records its
+//! controller, the public keys and the service endpoints of the identifier, and can be
+//! deactivated by the controller. Documents are keyed by the hash of the DID string, so a
+//! name service profile can point at an on-chain document.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `create` - Register a new DID with an initial public key.
+//! * `update` - Replace the service endpoints of a DID.
+//! * `rotate_key` - Replace one public key of a DID with another.
+//! * `deactivate` - Deactivate a DID; it can not be updated afterwards.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sp_runtime::{RuntimeDebug, traits::Hash};
+use support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::Result,
+	ensure,
+	traits::Get,
+	weights::SimpleDispatchInfo,
+};
+use system::ensure_signed;
+use name_service::{DataHash, ProfileResolver};
+
+#[cfg(test)]
+mod did_test;
+
+/// The method prefix every DID of this registry must start with
+pub const DID_PREFIX: &[u8] = b"did:pistis:";
+
+/// A service endpoint of a DID document
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ServiceEndpoint {
+	/// The id of the service, unique within the document
+	pub id: Vec<u8>,
+	/// The url of the service
+	pub endpoint: Vec<u8>,
+}
+
+/// The DID document
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct DidDocument<AccountId, BlockNumber> {
+	/// The account controlling the document
+	pub controller: AccountId,
+	/// The DID string
+	pub did: Vec<u8>,
+	/// The public keys of the identifier
+	pub public_keys: Vec<Vec<u8>>,
+	/// The service endpoints of the identifier
+	pub services: Vec<ServiceEndpoint>,
+	/// Creation time
+	pub created_at: BlockNumber,
+	/// Last modification time
+	pub updated_at: BlockNumber,
+	/// Whether the document is deactivated
+	pub deactivated: bool,
+}
+
+type DidDocumentOf<T> = DidDocument<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The maximum length a DID may be.
+	type MaxDidLength: Get<usize>;
+
+	/// The maximum length a public key may be.
+	type MaxKeyLength: Get<usize>;
+
+	/// The maximum public keys a document may have.
+	type MaxKeys: Get<usize>;
+
+	/// The maximum length a service id or endpoint may be.
+	type MaxServiceLength: Get<usize>;
+
+	/// The maximum service endpoints a document may have.
+	type MaxServices: Get<usize>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as DidModule {
+		/// The lookup table for DID documents
+		Documents get(document_of): map T::Hash => Option<DidDocumentOf<T>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Hash = <T as system::Trait>::Hash,
+		AccountId = <T as system::Trait>::AccountId,
+	{
+		/// DID created
+		DidCreated(AccountId, Hash),
+		/// DID service endpoints updated
+		DidUpdated(AccountId, Hash),
+		/// DID public key rotated
+		DidKeyRotated(AccountId, Hash),
+		/// DID deactivated
+		DidDeactivated(AccountId, Hash),
+	}
+);
+
+decl_module! {
+	// Simple declaration of the `Module` type. Lets the macro know what it's working on.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The maximum length a DID may be.
+		const MaxDidLength: u32 = T::MaxDidLength::get() as u32;
+
+		/// The maximum length a public key may be.
+		const MaxKeyLength: u32 = T::MaxKeyLength::get() as u32;
+
+		/// The maximum public keys a document may have.
+		const MaxKeys: u32 = T::MaxKeys::get() as u32;
+
+		/// The maximum length a service id or endpoint may be.
+		const MaxServiceLength: u32 = T::MaxServiceLength::get() as u32;
+
+		/// The maximum service endpoints a document may have.
+		const MaxServices: u32 = T::MaxServices::get() as u32;
+
+		/// Create a DID document controlled by the sender
+		///
+		/// @origin	the sender
+		/// @did	the DID string, e.g. did:pistis:v0:...
+		/// @public_key	the initial public key
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn create(origin, did: Vec<u8>, public_key: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(did.starts_with(DID_PREFIX), "Invalid DID method");
+			ensure!(did.len() > DID_PREFIX.len(), "DID too short");
+			ensure!(did.len() <= T::MaxDidLength::get(), "DID too long");
+			Self::validate_key(&public_key)?;
+
+			let did_hash = Self::did_hash(&did);
+			ensure!(!<Documents<T>>::exists(did_hash), "DID already exists");
+
+			let now = <system::Module<T>>::block_number();
+			let document = DidDocumentOf::<T> {
+				controller: sender.clone(),
+				did: did,
+				public_keys: vec![public_key],
+				services: Vec::new(),
+				created_at: now,
+				updated_at: now,
+				deactivated: false,
+			};
+
+			<Documents<T>>::insert(did_hash, document);
+			Self::deposit_event(RawEvent::DidCreated(sender, did_hash));
+			Ok(())
+		}
+
+		/// Replace the service endpoints of a DID document
+		///
+		/// @origin	the controller
+		/// @did_hash	the DID hash
+		/// @services	the new service endpoints
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn update(origin, did_hash: T::Hash, services: Vec<ServiceEndpoint>) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut document = Self::only_controller(did_hash, &sender)?;
+
+			ensure!(services.len() <= T::MaxServices::get(), "Too many services");
+			for (i, service) in services.iter().enumerate() {
+				ensure!(!service.id.is_empty(), "Service id is empty");
+				ensure!(service.id.len() <= T::MaxServiceLength::get(), "Service id too long");
+				ensure!(service.endpoint.len() <= T::MaxServiceLength::get(), "Service endpoint too long");
+				ensure!(!services[..i].iter().any(|s| s.id == service.id), "Duplicate service id");
+			}
+
+			document.services = services;
+			document.updated_at = <system::Module<T>>::block_number();
+			<Documents<T>>::insert(did_hash, document);
+			Self::deposit_event(RawEvent::DidUpdated(sender, did_hash));
+			Ok(())
+		}
+
+		/// Replace a public key of a DID document, or add a new one if `old_key` is empty
+		///
+		/// @origin	the controller
+		/// @did_hash	the DID hash
+		/// @old_key	the public key to be replaced
+		/// @new_key	the new public key
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn rotate_key(origin, did_hash: T::Hash, old_key: Vec<u8>, new_key: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut document = Self::only_controller(did_hash, &sender)?;
+
+			Self::validate_key(&new_key)?;
+			ensure!(!document.public_keys.contains(&new_key), "Key already exists");
+			if old_key.is_empty() {
+				ensure!(document.public_keys.len() < T::MaxKeys::get(), "Too many keys");
+				document.public_keys.push(new_key);
+			} else {
+				let pos = document.public_keys.iter().position(|k| *k == old_key).ok_or("Key does not exist")?;
+				document.public_keys[pos] = new_key;
+			}
+
+			document.updated_at = <system::Module<T>>::block_number();
+			<Documents<T>>::insert(did_hash, document);
+			Self::deposit_event(RawEvent::DidKeyRotated(sender, did_hash));
+			Ok(())
+		}
+
+		/// Deactivate a DID document
+		///
+		/// @origin	the controller
+		/// @did_hash	the DID hash
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn deactivate(origin, did_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut document = Self::only_controller(did_hash, &sender)?;
+
+			document.deactivated = true;
+			document.updated_at = <system::Module<T>>::block_number();
+			<Documents<T>>::insert(did_hash, document);
+			Self::deposit_event(RawEvent::DidDeactivated(sender, did_hash));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Get the hash of a DID string
+	///
+	/// @did	the DID string
+	pub fn did_hash(did: &[u8]) -> T::Hash {
+		<T as system::Trait>::Hashing::hash(did)
+	}
+
+	/// Check if the DID is registered and not deactivated
+	///
+	/// @did_hash	the DID hash
+	pub fn is_active(did_hash: T::Hash) -> bool {
+		match Self::document_of(did_hash) {
+			Some(document) => !document.deactivated,
+			None => false,
+		}
+	}
+
+	/// Check if the sender controls an active DID document and return it
+	///
+	/// @did_hash	the DID hash
+	/// @sender	the sender
+	fn only_controller(did_hash: T::Hash, sender: &T::AccountId) -> rstd::result::Result<DidDocumentOf<T>, &'static str> {
+		let document = Self::document_of(did_hash).ok_or("DID does not exist")?;
+		ensure!(document.controller == *sender, "Sender is not controller");
+		ensure!(!document.deactivated, "DID is deactivated");
+		Ok(document)
+	}
+
+	/// Validate a public key
+	///
+	/// @key	the public key
+	fn validate_key(key: &Vec<u8>) -> Result {
+		ensure!(!key.is_empty(), "Key is empty");
+		ensure!(key.len() <= T::MaxKeyLength::get(), "Key too long");
+		Ok(())
+	}
+}
+
+impl<T: Trait> ProfileResolver for Module<T> {
+	/// A profile is valid if it is the runtime hash of an active DID
+	///
+	/// @profile	the DID hash
+	fn is_valid_profile(profile: &DataHash) -> bool {
+		profile.as_runtime_hash().map_or(false, Self::is_active)
+	}
+}
@@ -0,0 +1,103 @@
+/// tests for the modules wired together
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	use support::{assert_noop, assert_ok};
+	use name_service::NameServiceResolver;
+	use business::DataHash;
+	use sp_runtime::traits::Hash;
+
+	const ACME: u8 = 3;
+	const ALICE: u8 = 4;
+
+	/// Register a top level name owned by and resolving to an account
+	///
+	/// @label	the label in utf8
+	/// @owner	the seed of the owner account
+	fn register(label: &str, owner: u8) -> H256 {
+		let root = H256::default();
+		assert_ok!(NameService::set_subnode_label_owner(Origin::signed(account(ROOT_OWNER)), root, label.into(), account(owner)));
+		let node_hash = NameService::subnode_hash(root, BlakeTwo256::hash(label.as_bytes()));
+		assert_ok!(NameService::set_resolve_addr(Origin::signed(account(owner)), node_hash, account(owner)));
+		node_hash
+	}
+
+	/// A data hash of the runtime hashing
+	fn data_hash(data: &[u8]) -> DataHash {
+		DataHash::from_runtime_hash(&BlakeTwo256::hash(data))
+	}
+
+	#[test]
+	fn business_flow_should_work() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			Timestamp::set_timestamp(1_000);
+
+			// Names registered in the name service resolve to their owners
+			let acme = register("acme", ACME);
+			let alice = register("alice", ALICE);
+			assert_eq!(NameService::resolve_addr(acme), Some(account(ACME)));
+			assert_eq!(NameService::resolve_addr(alice), Some(account(ALICE)));
+
+			// Only the account the scope resolves to creates businesses
+			assert_noop!(BusinessModule::create_business(Origin::signed(account(ACME)), BusinessModule::default_scope_hash(), acme, "crab".into(), 20), "Not authorized");
+			let biz_hash = BusinessModule::next_business_hash(account(SCOPE_ADMIN), acme, "crab".into());
+			assert_ok!(BusinessModule::create_business(Origin::signed(account(SCOPE_ADMIN)), BusinessModule::default_scope_hash(), acme, "crab".into(), 20));
+			assert_eq!(BusinessModule::business_info(biz_hash).and_then(|details| details.owner_account), Some(account(ACME)));
+
+			// The account the owner name resolves to manages the whitelist
+			assert_noop!(BusinessModule::add_business_whitelist(Origin::signed(account(ALICE)), biz_hash, alice, None), "Not authorized");
+			assert_ok!(BusinessModule::add_business_whitelist(Origin::signed(account(ACME)), biz_hash, alice, None));
+
+			// The account the operator name resolves to writes products
+			assert_noop!(
+				BusinessModule::create_product(Origin::signed(account(ACME)), alice, biz_hash, "1".into(), data_hash(b"batch"), Vec::new()),
+				"Not authorized"
+			);
+			assert_ok!(BusinessModule::create_product(Origin::signed(account(ALICE)), alice, biz_hash, "1".into(), data_hash(b"batch"), Vec::new()));
+			let product = BusinessModule::product_by_seq_id(biz_hash, "1".into()).unwrap();
+			assert_eq!(product.infos[0].creator, account(ALICE));
+			assert_eq!(product.infos[0].timestamp, 1_000);
+
+			// The business refers to the owner name, which then can not be cleared
+			assert_noop!(NameService::clear_node(Origin::signed(account(ACME)), acme), "Node is referenced");
+
+			// Writes stop once the business expires, and resume when the scope admin renews it
+			System::set_block_number(20);
+			assert_noop!(
+				BusinessModule::create_product(Origin::signed(account(ALICE)), alice, biz_hash, "2".into(), data_hash(b"batch"), Vec::new()),
+				"Expired"
+			);
+			assert_ok!(BusinessModule::set_business_expiration(Origin::signed(account(SCOPE_ADMIN)), biz_hash, 40));
+			assert_ok!(BusinessModule::create_product(Origin::signed(account(ALICE)), alice, biz_hash, "2".into(), data_hash(b"batch"), Vec::new()));
+			assert_eq!(BusinessModule::list_products(biz_hash, 0, 10).len(), 2);
+		});
+	}
+
+	#[test]
+	fn resolve_change_should_suspend_operator() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let acme = register("acme", ACME);
+			let alice = register("alice", ALICE);
+			let biz_hash = BusinessModule::next_business_hash(account(SCOPE_ADMIN), acme, "crab".into());
+			assert_ok!(BusinessModule::create_business(Origin::signed(account(SCOPE_ADMIN)), BusinessModule::default_scope_hash(), acme, "crab".into(), 20));
+			assert_ok!(BusinessModule::add_business_whitelist(Origin::signed(account(ACME)), biz_hash, alice, None));
+
+			// The operator name moves to another account, which waits for the owner
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(account(ALICE)), alice, account(6)));
+			assert_noop!(
+				BusinessModule::create_product(Origin::signed(account(ALICE)), alice, biz_hash, "1".into(), data_hash(b"batch"), Vec::new()),
+				"Not authorized"
+			);
+			assert_noop!(
+				BusinessModule::create_product(Origin::signed(account(6)), alice, biz_hash, "1".into(), data_hash(b"batch"), Vec::new()),
+				"Whitelist entry suspended"
+			);
+
+			assert_ok!(BusinessModule::confirm_whitelist_member(Origin::signed(account(ACME)), biz_hash, alice));
+			assert_ok!(BusinessModule::create_product(Origin::signed(account(6)), alice, biz_hash, "1".into(), data_hash(b"batch"), Vec::new()));
+		});
+	}
+}
@@ -0,0 +1,194 @@
+/// tests for the storage invariants under random call sequences
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	use proptest::prelude::*;
+	use business::{ArchivePayload, DataHash};
+	use name_service::NameServiceResolver;
+	use sp_runtime::traits::Hash;
+
+	/// The names the harness registers under the root, with the seed of their owners
+	const NAMES: [(&str, u8); 3] = [("acme", 3), ("bolt", 4), ("cork", 5)];
+
+	/// The blocks a business is created for
+	const BUSINESS_PERIOD: u64 = 20;
+
+	/// A call of the harness, its indices wrapping around the names and businesses
+	#[derive(Clone, Debug)]
+	enum Action {
+		Register { name: usize },
+		Resolve { name: usize, seed: u8 },
+		ClearNode { name: usize },
+		Publish { name: usize, publish: bool },
+		CreateBusiness { owner: usize, label: u8 },
+		AddWhitelist { biz: usize, operator: usize },
+		RemoveWhitelist { biz: usize, operator: usize },
+		ConfirmWhitelist { biz: usize, operator: usize },
+		RotateOperator { operator: usize, to: usize },
+		CreateProduct { biz: usize, operator: usize, seq_id: u8 },
+		RemoveProduct { biz: usize, seq_id: u8 },
+		Renew { biz: usize },
+		Archive { biz: usize },
+		AdvanceBlocks(u64),
+	}
+
+	fn action() -> impl Strategy<Value = Action> {
+		prop_oneof![
+			(0..3usize).prop_map(|name| Action::Register { name }),
+			(0..3usize, 3..10u8).prop_map(|(name, seed)| Action::Resolve { name, seed }),
+			(0..3usize).prop_map(|name| Action::ClearNode { name }),
+			(0..3usize, any::<bool>()).prop_map(|(name, publish)| Action::Publish { name, publish }),
+			(0..3usize, 0..3u8).prop_map(|(owner, label)| Action::CreateBusiness { owner, label }),
+			(0..4usize, 0..3usize).prop_map(|(biz, operator)| Action::AddWhitelist { biz, operator }),
+			(0..4usize, 0..3usize).prop_map(|(biz, operator)| Action::RemoveWhitelist { biz, operator }),
+			(0..4usize, 0..3usize).prop_map(|(biz, operator)| Action::ConfirmWhitelist { biz, operator }),
+			(0..3usize, 0..3usize).prop_map(|(operator, to)| Action::RotateOperator { operator, to }),
+			(0..4usize, 0..3usize, 0..4u8).prop_map(|(biz, operator, seq_id)| Action::CreateProduct { biz, operator, seq_id }),
+			(0..4usize, 0..4u8).prop_map(|(biz, seq_id)| Action::RemoveProduct { biz, seq_id }),
+			(0..4usize).prop_map(|biz| Action::Renew { biz }),
+			(0..4usize).prop_map(|biz| Action::Archive { biz }),
+			(1..15u64).prop_map(Action::AdvanceBlocks),
+		]
+	}
+
+	/// The node hash of a harness name
+	fn name_hash(name: usize) -> H256 {
+		NameService::subnode_hash(H256::default(), BlakeTwo256::hash(NAMES[name].0.as_bytes()))
+	}
+
+	/// The owner of a harness name
+	fn owner(name: usize) -> AccountId32 {
+		account(NAMES[name].1)
+	}
+
+	/// The account a harness name resolves to, its owner if it does not resolve
+	fn resolved(name: usize) -> AccountId32 {
+		NameService::resolve_addr(name_hash(name)).unwrap_or_else(|| owner(name))
+	}
+
+	/// A business ever created, archived ones included
+	fn business(biz: usize) -> Option<H256> {
+		match BusinessModule::business_count() {
+			0 => None,
+			count => Some(BusinessModule::business_index(biz as u64 % count)),
+		}
+	}
+
+	/// The account the owner name of a business resolves to
+	fn business_owner(biz_hash: H256) -> AccountId32 {
+		let owner = BusinessModule::business_of(biz_hash).owner;
+		NameService::resolve_addr(owner).unwrap_or_else(|| account(0))
+	}
+
+	/// Dispatch an action, its result ignored: a failed call must leave the storage
+	/// consistent as well
+	fn apply(action: Action) {
+		let now = System::block_number();
+		let _ = match action {
+			Action::Register { name } => NameService::set_subnode_label_owner(
+				Origin::signed(account(ROOT_OWNER)), H256::default(), NAMES[name].0.into(), owner(name),
+			),
+			Action::Resolve { name, seed } => NameService::set_resolve_addr(
+				Origin::signed(owner(name)), name_hash(name), account(seed),
+			),
+			Action::ClearNode { name } => NameService::clear_node(Origin::signed(owner(name)), name_hash(name)),
+			Action::Publish { name, publish } => {
+				let domain = if publish { format!("{}.example.com", NAMES[name].0).into_bytes() } else { Vec::new() };
+				NameService::set_text(Origin::signed(owner(name)), name_hash(name), b"publish_dns".to_vec(), domain)
+			},
+			Action::CreateBusiness { owner, label } => BusinessModule::create_business(
+				Origin::signed(account(SCOPE_ADMIN)), BusinessModule::default_scope_hash(), name_hash(owner), vec![b'a' + label; 4], now + BUSINESS_PERIOD,
+			),
+			Action::AddWhitelist { biz, operator } => match business(biz) {
+				Some(biz_hash) => BusinessModule::add_business_whitelist(
+					Origin::signed(business_owner(biz_hash)), biz_hash, name_hash(operator), None,
+				),
+				None => Ok(()),
+			},
+			Action::RemoveWhitelist { biz, operator } => match business(biz) {
+				Some(biz_hash) => BusinessModule::remove_business_whitelist(
+					Origin::signed(business_owner(biz_hash)), biz_hash, name_hash(operator),
+				),
+				None => Ok(()),
+			},
+			Action::ConfirmWhitelist { biz, operator } => match business(biz) {
+				Some(biz_hash) => BusinessModule::confirm_whitelist_member(
+					Origin::signed(business_owner(biz_hash)), biz_hash, name_hash(operator),
+				),
+				None => Ok(()),
+			},
+			Action::RotateOperator { operator, to } => BusinessModule::rotate_operator(
+				Origin::signed(resolved(operator)),
+				name_hash(operator),
+				name_hash(to),
+				BusinessModule::businesses_by_operator(name_hash(operator)),
+			),
+			Action::CreateProduct { biz, operator, seq_id } => match business(biz) {
+				Some(biz_hash) => BusinessModule::create_product(
+					Origin::signed(resolved(operator)),
+					name_hash(operator),
+					biz_hash,
+					vec![b'0' + seq_id],
+					DataHash::from_runtime_hash(&BlakeTwo256::hash(&[seq_id])),
+					Vec::new(),
+				),
+				None => Ok(()),
+			},
+			Action::RemoveProduct { biz, seq_id } => match business(biz) {
+				Some(biz_hash) => BusinessModule::remove_product(
+					Origin::signed(business_owner(biz_hash)),
+					biz_hash,
+					vec![b'0' + seq_id],
+				),
+				None => Ok(()),
+			},
+			Action::Renew { biz } => match business(biz) {
+				Some(biz_hash) => BusinessModule::set_business_expiration(
+					Origin::signed(account(SCOPE_ADMIN)), biz_hash, now + BUSINESS_PERIOD,
+				),
+				None => Ok(()),
+			},
+			Action::Archive { biz } => match business(biz) {
+				Some(biz_hash) => BusinessModule::archive_businesses(
+					Origin::NONE,
+					ArchivePayload {
+						block_number: now,
+						biz_hashes: vec![biz_hash],
+						products: BusinessModule::product_count(biz_hash) as u32,
+						lots: BusinessModule::business_lots(biz_hash).len() as u32,
+						public: UintAuthorityId(1),
+					},
+					0,
+				),
+				None => Ok(()),
+			},
+			Action::AdvanceBlocks(blocks) => {
+				System::set_block_number(now + blocks);
+				Ok(())
+			},
+		};
+	}
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(64))]
+
+		#[test]
+		fn random_calls_should_keep_invariants(actions in prop::collection::vec(action(), 1..64)) {
+			new_test_ext().execute_with(|| -> Result<(), TestCaseError> {
+				System::set_block_number(1);
+				for name in 0..NAMES.len() {
+					apply(Action::Register { name });
+					apply(Action::Resolve { name, seed: NAMES[name].1 });
+				}
+
+				for action in actions {
+					apply(action.clone());
+					prop_assert_eq!(NameService::do_try_state(), Ok(()), "name service after {:?}", action);
+					prop_assert_eq!(BusinessModule::do_try_state(), Ok(()), "business after {:?}", action);
+				}
+				Ok(())
+			})?;
+		}
+	}
+}
@@ -0,0 +1,211 @@
+//! # Pistis Integration Tests
+//!
+//! The module tests run against mocks, the business tests resolving names with a fixed
+//! table. This crate wires the real name service, business, balances and timestamp
+//! modules together as the runtime does, so the flows in `flow_test` go through the name
+//! service for every authorization of a business call.
+//!
+//! `invariant_test` dispatches random call sequences and checks the `do_try_state`
+//! invariants of both modules after each call, so a change of the storage layout which
+//! leaves them inconsistent fails here before it reaches a chain.
+
+use support::{impl_outer_origin, parameter_types, weights::{DispatchClass, Weight}};
+use primitives::H256;
+use sp_runtime::{
+	AccountId32, MultiSignature, MultiSigner, Perbill,
+	testing::{Header, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+#[cfg(test)]
+mod flow_test;
+#[cfg(test)]
+mod invariant_test;
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+/// The runtime of the integration tests
+#[derive(Clone, Eq, PartialEq)]
+pub struct Runtime;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 4 * 1024 * 1024;
+	pub const MaximumBlockLength: u32 = 4 * 1024 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = ();
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
+}
+impl balances::Trait for Runtime {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = ();
+	type TransferPayment = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl timestamp::Trait for Runtime {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+parameter_types! {
+	pub const MinNameLength: usize = 3;
+	pub const MaxNameLength: usize = 16;
+	pub const MaxZoneLength: usize = 1024;
+	pub const AllowMixedScripts: bool = false;
+	pub const MaxDnsPublished: u32 = 16;
+	pub const MaxNamesPerAccount: u32 = 16;
+}
+impl name_service::Trait for Runtime {
+	type Event = ();
+	type ForceOrigin = system::EnsureRoot<AccountId32>;
+	type MinNameLength = MinNameLength;
+	type MaxNameLength = MaxNameLength;
+	type MaxZoneLength = MaxZoneLength;
+	type ProfileResolver = ();
+	type WeightInfo = ();
+	type NameNormalizer = name_service::StandardNormalizer<AllowMixedScripts>;
+	type NodeReferences = BusinessModule;
+	type OnResolveChanged = BusinessModule;
+	type DnsPublisherId = UintAuthorityId;
+	type MaxDnsPublished = MaxDnsPublished;
+	type MaxNamesPerAccount = MaxNamesPerAccount;
+}
+
+parameter_types! {
+	pub const MaxSeqIDLength: usize = 64;
+	pub const MaxExtraLength: usize = 1024;
+	pub const MaxProductInfoCount: usize = 16;
+	pub const MaxWhitelistSize: usize = 8;
+	pub const ProductDeposit: u64 = 0;
+	pub const RenewalPricePerBlock: u64 = 0;
+	pub const VerificationFee: u64 = 1;
+	pub const ArchiveGracePeriod: u64 = 10;
+	pub const MaxArchiveScan: u32 = 10;
+	pub const MaxAuthorizationDepth: u32 = 2;
+	pub const OnOperatorResolveChanged: business::ResolveChangeAction = business::ResolveChangeAction::Suspend;
+	pub const MaxWritesPerOperatorPerBlock: u32 = 16;
+	pub const MaxVerificationsPerBlock: u32 = 64;
+	pub const MaxVerificationsPerAccount: u32 = 8;
+	pub const AdminDispatchClass: DispatchClass = DispatchClass::Operational;
+	pub const AdminPaysFee: bool = false;
+	pub const MaxSubBusinesses: u32 = 4;
+	pub const MaxBusinessesPerName: u32 = 16;
+	pub const MaxLotsPerBusiness: u32 = 16;
+	pub const RichEvents: bool = false;
+	pub const MaxDigestEntries: u32 = 16;
+	pub const AppealPeriod: u64 = 10;
+}
+type Extrinsic = TestXt<business::Call<Runtime>, ()>;
+type SubmitTransaction = system::offchain::TransactionSubmitter<(), business::Call<Runtime>, Extrinsic>;
+impl business::Trait for Runtime {
+	type Event = ();
+	type ForceOrigin = system::EnsureRoot<AccountId32>;
+	type MinNameLength = MinNameLength;
+	type MaxNameLength = MaxNameLength;
+	type MaxSeqIDLength = MaxSeqIDLength;
+	type MaxExtraLength = MaxExtraLength;
+	type MaxProductInfoCount = MaxProductInfoCount;
+	type MaxWhitelistSize = MaxWhitelistSize;
+	type Currency = Balances;
+	type ProductDeposit = ProductDeposit;
+	type StorageFeePolicy = ();
+	type RenewalPricePerBlock = RenewalPricePerBlock;
+	type RenewalFees = ();
+	type VerificationFee = VerificationFee;
+	type VerificationFees = ();
+	type NameServiceResolver = NameService;
+	type AuthorizationProvider = business::NameServiceAuthorization<NameService>;
+	type ArchiverId = UintAuthorityId;
+	type Call = business::Call<Runtime>;
+	type SubmitTransaction = SubmitTransaction;
+	type ArchiveGracePeriod = ArchiveGracePeriod;
+	type MaxArchiveScan = MaxArchiveScan;
+	type OperatorSignature = MultiSignature;
+	type OperatorSigner = MultiSigner;
+	type WeightInfo = ();
+	type AttestationVerifier = ();
+	type Anchors = ();
+	type MaxAuthorizationDepth = MaxAuthorizationDepth;
+	type OnOperatorResolveChanged = OnOperatorResolveChanged;
+	type MaxWritesPerOperatorPerBlock = MaxWritesPerOperatorPerBlock;
+	type MaxVerificationsPerBlock = MaxVerificationsPerBlock;
+	type MaxVerificationsPerAccount = MaxVerificationsPerAccount;
+	type AdminDispatchClass = AdminDispatchClass;
+	type AdminPaysFee = AdminPaysFee;
+	type MaxSubBusinesses = MaxSubBusinesses;
+	type MaxBusinessesPerName = MaxBusinessesPerName;
+	type MaxLotsPerBusiness = MaxLotsPerBusiness;
+	type RichEvents = RichEvents;
+	type MaxDigestEntries = MaxDigestEntries;
+	type AppealPeriod = AppealPeriod;
+}
+
+pub type System = system::Module<Runtime>;
+pub type Balances = balances::Module<Runtime>;
+pub type Timestamp = timestamp::Module<Runtime>;
+pub type NameService = name_service::Module<Runtime>;
+pub type BusinessModule = business::Module<Runtime>;
+
+/// The owner of the name service root
+pub const ROOT_OWNER: u8 = 1;
+
+/// The admin of the default scope, which the `pistis` scope node resolves to
+pub const SCOPE_ADMIN: u8 = 2;
+
+/// Get the test account of a seed
+///
+/// @seed	the byte the account id repeats
+pub fn account(seed: u8) -> AccountId32 {
+	AccountId32::from([seed; 32])
+}
+
+/// Build the genesis storage: the root owned by `ROOT_OWNER`, the default scope registered
+/// with its node resolving to `SCOPE_ADMIN` and the accounts up to seed 9 funded
+pub fn new_test_ext() -> runtime_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	balances::GenesisConfig::<Runtime> {
+		balances: (1..10).map(|seed| (account(seed), 1_000)).collect(),
+		vesting: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	name_service::GenesisConfig::<Runtime> {
+		root_owner: Some(account(ROOT_OWNER)),
+		nodes: vec![(BusinessModule::default_scope_hash(), account(SCOPE_ADMIN), account(SCOPE_ADMIN))],
+	}.assimilate_storage(&mut t).unwrap();
+	business::GenesisConfig::<Runtime> {
+		scopes: vec![(BusinessModule::default_scope_hash(), BusinessModule::default_scope_hash())],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
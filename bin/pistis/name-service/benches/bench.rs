@@ -0,0 +1,108 @@
+//! Benchmarks of the name service calls whose weights depend on their input sizes.
+//!
+//! Run with `cargo bench` and fit the `WeightInfo` figures to the measured times.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use name_service_module::{Call, StandardNormalizer, Trait};
+use support::{impl_outer_origin, parameter_types, dispatch::Dispatchable, weights::Weight};
+use primitives::H256;
+use sp_runtime::{Perbill, traits::{BlakeTwo256, Hash, IdentityLookup}, testing::{Header, UintAuthorityId}};
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 4 * 1024 * 1024;
+	pub const MaximumBlockLength: u32 = 4 * 1024 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+#[derive(Clone, Eq, PartialEq)]
+pub struct Runtime;
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+parameter_types! {
+	pub const MinNameLength: usize = 3;
+	pub const MaxNameLength: usize = 256;
+	pub const MaxZoneLength: usize = 1024;
+	pub const AllowMixedScripts: bool = false;
+	pub const MaxDnsPublished: u32 = 256;
+	pub const MaxNamesPerAccount: u32 = 256;
+}
+impl Trait for Runtime {
+	type Event = ();
+	type ForceOrigin = system::EnsureRoot<u64>;
+	type MinNameLength = MinNameLength;
+	type MaxNameLength = MaxNameLength;
+	type MaxZoneLength = MaxZoneLength;
+	type ProfileResolver = ();
+	type WeightInfo = ();
+	type NameNormalizer = StandardNormalizer<AllowMixedScripts>;
+	type NodeReferences = ();
+	type OnResolveChanged = ();
+	type DnsPublisherId = UintAuthorityId;
+	type MaxDnsPublished = MaxDnsPublished;
+	type MaxNamesPerAccount = MaxNamesPerAccount;
+}
+
+const OWNER: u64 = 1;
+
+/// The node `eth` under the root, owned by `OWNER`
+fn node_hash() -> H256 {
+	let mut node = H256::default().as_ref().to_vec();
+	node.extend_from_slice(BlakeTwo256::hash(b"eth").as_ref());
+	BlakeTwo256::hash(&node)
+}
+
+fn new_test_ext() -> runtime_io::TestExternalities {
+	let mut t: runtime_io::TestExternalities = system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into();
+	t.execute_with(|| {
+		assert!(Call::<Runtime>::set_root_owner(OWNER).dispatch(system::RawOrigin::Root.into()).is_ok());
+		assert!(Call::<Runtime>::set_subnode_owner(H256::default(), BlakeTwo256::hash(b"eth"), OWNER).dispatch(Origin::signed(OWNER)).is_ok());
+	});
+	t
+}
+
+fn set_resolve_name(c: &mut Criterion) {
+	c.bench_function_over_inputs("set_resolve_name", |b, &n| {
+		b.iter_with_setup(new_test_ext, |mut t| t.execute_with(|| {
+			assert!(Call::<Runtime>::set_resolve_name(node_hash(), vec![b'n'; n]).dispatch(Origin::signed(OWNER)).is_ok());
+		}))
+	}, vec![3usize, 64, 256]);
+}
+
+fn set_zone_storage(c: &mut Criterion) {
+	c.bench_function_over_inputs("set_zone_storage", |b, &z| {
+		b.iter_with_setup(new_test_ext, |mut t| t.execute_with(|| {
+			assert!(Call::<Runtime>::set_zone_storage(node_hash(), vec![b'z'; z]).dispatch(Origin::signed(OWNER)).is_ok());
+		}))
+	}, vec![1usize, 256, 1024]);
+}
+
+fn set_zone_urls(c: &mut Criterion) {
+	c.bench_function_over_inputs("set_zone_urls", |b, &z| {
+		b.iter_with_setup(new_test_ext, |mut t| t.execute_with(|| {
+			assert!(Call::<Runtime>::set_zone_urls(node_hash(), vec![b'r'; z], vec![b'w'; z]).dispatch(Origin::signed(OWNER)).is_ok());
+		}))
+	}, vec![1usize, 256, 1024]);
+}
+
+criterion_group!(benches, set_resolve_name, set_zone_storage, set_zone_urls);
+criterion_main!(benches);
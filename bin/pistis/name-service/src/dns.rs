@@ -0,0 +1,199 @@
+//! # DNS Bridge
+//!
+//! A node whose `publish_dns` text record holds its own name as a domain name is bridged to
+//! the DNS. The labels of the domain have to hash to the label path of the node, so an owner
+//! can only publish the name it was given. The offchain worker renders its resolve record
+//! into zone file lines owned by that domain, with the TTL of the node, and pushes them to
+//! the `DnsEndpoint` of the chain. The body is signed with a local publisher key together
+//! with the genesis hash of the chain and the time of the push, see `signing_payload`, so
+//! the endpoint feeding the real resolvers can accept the pushes of the publishers it trusts
+//! only.
+//!
+//! A signature does not expire by itself. The endpoint must reject a push whose genesis
+//! hash is not that of its chain, whose timestamp is stale by its own clock, or whose
+//! timestamp is not newer than that of the last push it accepted from the publisher, so a
+//! captured push can not be replayed to roll a zone back.
+//!
+//! A node is pushed again once its TTL elapsed, or at once when its lines changed.
+
+use codec::Encode;
+use rstd::prelude::*;
+use pistis_primitives::{HashAlgo, ResolveRecord};
+
+/// The key type of the publisher keys
+pub const KEY_TYPE: app_crypto::KeyTypeId = app_crypto::KeyTypeId(*b"pdns");
+
+pub mod sr25519 {
+	mod app_sr25519 {
+		use app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, super::super::KEY_TYPE);
+	}
+
+	/// A publisher keypair using sr25519 as its crypto.
+	#[cfg(feature = "std")]
+	pub type PublisherPair = app_sr25519::Pair;
+
+	/// A publisher signature using sr25519 as its crypto.
+	pub type PublisherSignature = app_sr25519::Signature;
+
+	/// A publisher identifier using sr25519 as its crypto.
+	pub type PublisherId = app_sr25519::Public;
+}
+
+/// The text record holding the domain name a node is published as
+pub const PUBLISH_DNS_KEY: &[u8] = b"publish_dns";
+
+/// The TTL in seconds of the nodes without one
+pub const DEFAULT_DNS_TTL: u64 = 3600;
+
+/// The milliseconds the offchain worker waits for the endpoint
+pub const DNS_PUSH_TIMEOUT: u64 = 5_000;
+
+/// The header carrying the public key of the publisher
+pub const PUBLIC_KEY_HEADER: &str = "X-Pistis-Public-Key";
+
+/// The header carrying the signature of the `signing_payload`
+pub const SIGNATURE_HEADER: &str = "X-Pistis-Signature";
+
+/// The header carrying the unix time in milliseconds of the push, in decimal
+pub const TIMESTAMP_HEADER: &str = "X-Pistis-Timestamp";
+
+/// The header carrying the genesis hash of the chain, in hex
+pub const GENESIS_HASH_HEADER: &str = "X-Pistis-Genesis-Hash";
+
+/// The maximum length of a domain name
+const MAX_DNS_NAME_LENGTH: usize = 253;
+
+/// The maximum length of a label of a domain name
+const MAX_DNS_LABEL_LENGTH: usize = 63;
+
+/// Check if a name is a domain name made of letters, digits and hyphens, without the
+/// trailing dot
+///
+/// @name	the domain name
+pub fn is_dns_name(name: &[u8]) -> bool {
+	!name.is_empty()
+		&& name.len() <= MAX_DNS_NAME_LENGTH
+		&& name.split(|c| *c == b'.').all(|label| {
+			!label.is_empty()
+				&& label.len() <= MAX_DNS_LABEL_LENGTH
+				&& label[0] != b'-'
+				&& label[label.len() - 1] != b'-'
+				&& label.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'-')
+		})
+}
+
+/// Render a resolve record into zone file lines: TXT records of the address, the name,
+/// the profile and the zone read url, the empty fields left out
+///
+/// @domain	the domain name the lines are owned by
+/// @ttl	the TTL in seconds
+/// @record	the resolve record
+pub fn render_zone<AccountId: Encode>(domain: &[u8], ttl: u64, record: &ResolveRecord<AccountId>) -> Vec<u8> {
+	let mut lines = Vec::new();
+
+	let mut addr = b"pistis-addr=".to_vec();
+	push_hex(&mut addr, &record.addr.encode());
+	push_line(&mut lines, domain, ttl, &addr);
+
+	if !record.name.is_empty() {
+		push_line(&mut lines, domain, ttl, &[&b"pistis-name="[..], &record.name[..]].concat());
+	}
+	if !record.profile.digest.is_empty() {
+		let mut profile = b"pistis-profile=".to_vec();
+		profile.extend_from_slice(algo_name(record.profile.algo));
+		profile.push(b':');
+		push_hex(&mut profile, &record.profile.digest);
+		push_line(&mut lines, domain, ttl, &profile);
+	}
+	if !record.zone.read_url.is_empty() {
+		push_line(&mut lines, domain, ttl, &[&b"pistis-zone="[..], &record.zone.read_url[..]].concat());
+	}
+	lines
+}
+
+/// Get the bytes a push is signed over: the genesis hash, the timestamp as a little endian
+/// `u64`, then the body
+///
+/// @genesis_hash	the genesis hash of the chain
+/// @timestamp	the unix time in milliseconds of the push
+/// @body	the zone lines
+pub fn signing_payload(genesis_hash: &[u8], timestamp: u64, body: &[u8]) -> Vec<u8> {
+	let mut payload = Vec::with_capacity(genesis_hash.len() + 8 + body.len());
+	payload.extend_from_slice(genesis_hash);
+	payload.extend_from_slice(&timestamp.to_le_bytes());
+	payload.extend_from_slice(body);
+	payload
+}
+
+/// Encode a number in decimal
+///
+/// @n	the number
+pub fn to_decimal(n: u64) -> Vec<u8> {
+	let mut decimal = Vec::new();
+	push_decimal(&mut decimal, n);
+	decimal
+}
+
+/// Encode bytes as `0x` prefixed hex
+///
+/// @bytes	the bytes
+pub fn to_hex(bytes: &[u8]) -> Vec<u8> {
+	let mut hex = Vec::with_capacity(2 + bytes.len() * 2);
+	push_hex(&mut hex, bytes);
+	hex
+}
+
+/// The name of a hash algorithm in the profile records
+fn algo_name(algo: HashAlgo) -> &'static [u8] {
+	match algo {
+		HashAlgo::Blake2_256 => b"blake2-256",
+		HashAlgo::Sha2_256 => b"sha2-256",
+		HashAlgo::Sha2_512 => b"sha2-512",
+		HashAlgo::Sha3_256 => b"sha3-256",
+		HashAlgo::Keccak256 => b"keccak-256",
+	}
+}
+
+/// Append a TXT line, `<domain>. <ttl> IN TXT "<text>"`
+fn push_line(lines: &mut Vec<u8>, domain: &[u8], ttl: u64, text: &[u8]) {
+	lines.extend_from_slice(domain);
+	lines.extend_from_slice(b". ");
+	push_decimal(lines, ttl);
+	lines.extend_from_slice(b" IN TXT \"");
+	for c in text {
+		match *c {
+			b'"' | b'\\' => lines.extend_from_slice(&[b'\\', *c]),
+			0x20..=0x7e => lines.push(*c),
+			// the other bytes as `\DDD`
+			_ => {
+				lines.push(b'\\');
+				lines.extend_from_slice(&[b'0' + c / 100, b'0' + c / 10 % 10, b'0' + c % 10]);
+			},
+		}
+	}
+	lines.extend_from_slice(b"\"\n");
+}
+
+/// Append `0x` prefixed hex
+fn push_hex(out: &mut Vec<u8>, bytes: &[u8]) {
+	const DIGITS: &[u8; 16] = b"0123456789abcdef";
+	out.extend_from_slice(b"0x");
+	for byte in bytes {
+		out.push(DIGITS[(byte >> 4) as usize]);
+		out.push(DIGITS[(byte & 0xf) as usize]);
+	}
+}
+
+/// Append a number in decimal
+fn push_decimal(out: &mut Vec<u8>, mut n: u64) {
+	let start = out.len();
+	loop {
+		out.push(b'0' + (n % 10) as u8);
+		n /= 10;
+		if n == 0 {
+			break;
+		}
+	}
+	out[start..].reverse();
+}
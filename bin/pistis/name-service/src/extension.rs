@@ -0,0 +1,36 @@
+//! # Name Service Extension
+//!
+//! The interface through which smart contracts resolve names. A runtime routes the chain
+//! extension calls of its contracts to [`call`], so contracts read the registry directly
+//! instead of trusting an oracle.
+//!
+//! Every function takes the SCALE encoded node hash as input and returns the SCALE encoded
+//! `Option` of the resolved value, `None` if the node does not resolve.
+
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use crate::NameServiceResolver;
+
+/// Resolve a node hash to an `Option<AccountId>`
+pub const RESOLVE_ADDR: u32 = 0x0100;
+/// Resolve a node hash to an `Option<Vec<u8>>` name
+pub const RESOLVE_NAME: u32 = 0x0101;
+/// Resolve a node hash to an `Option<DataHash>` profile
+pub const RESOLVE_PROFILE: u32 = 0x0102;
+
+/// Call a resolver function on behalf of a contract
+///
+/// Returns `None` if the function does not exist or the input is not a node hash.
+///
+/// @func_id	the id of the resolver function
+/// @input	the SCALE encoded node hash
+pub fn call<T: system::Trait, R: NameServiceResolver<T>>(func_id: u32, input: &[u8]) -> Option<Vec<u8>> {
+	let mut input = input;
+	let node_hash = T::Hash::decode(&mut input).ok()?;
+	match func_id {
+		RESOLVE_ADDR => Some(R::resolve_addr(node_hash).encode()),
+		RESOLVE_NAME => Some(R::resolve_name(node_hash).encode()),
+		RESOLVE_PROFILE => Some(R::resolve_profile(node_hash).encode()),
+		_ => None,
+	}
+}
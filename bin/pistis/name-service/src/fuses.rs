@@ -0,0 +1,34 @@
+//! # Node Fuses
+//!
+//! The fuses of a node are the bits of `NodeRecord::fuses`, each taking a permission away
+//! from the node for good once burned with `burn_fuses`. Relying parties check them to
+//! know which records of a name can no longer change, whoever owns it.
+//!
+//! The owner of a subnode burns its fuses only once the owner of the parent burned
+//! `PARENT_CANNOT_CONTROL`, giving up reassigning the subnode; otherwise the parent, a
+//! registrar say, could not take back a name whose registration ended. The root and the
+//! nodes set up at genesis have no parent and burn theirs freely.
+
+/// The node may not be handed to another owner
+pub const CANNOT_TRANSFER: u32 = 1;
+/// The resolve record of the node may not change or be removed
+pub const CANNOT_SET_RESOLVER: u32 = 1 << 1;
+/// No new subnode may be registered under the node
+pub const CANNOT_CREATE_SUBNODE: u32 = 1 << 2;
+/// The text records of the node may not change
+pub const CANNOT_SET_TEXT: u32 = 1 << 3;
+/// The TTL of the node may not change
+pub const CANNOT_SET_TTL: u32 = 1 << 4;
+/// No further fuse of the node may be burned
+pub const CANNOT_BURN_FUSES: u32 = 1 << 5;
+/// The owner of the parent may not reassign the node, burned by the owner of the parent
+pub const PARENT_CANNOT_CONTROL: u32 = 1 << 6;
+
+/// All the fuses a node has
+pub const ALL: u32 = CANNOT_TRANSFER
+	| CANNOT_SET_RESOLVER
+	| CANNOT_CREATE_SUBNODE
+	| CANNOT_SET_TEXT
+	| CANNOT_SET_TTL
+	| CANNOT_BURN_FUSES
+	| PARENT_CANNOT_CONTROL;
@@ -16,24 +16,76 @@
 //!   taken.
 //! * `clear_name` - Remove an account's associated name; the deposit is returned.
 //! * `kill_name` - Forcibly remove the associated name; the deposit is lost.
+//! * `reserve_name` - Block a label from resolve names, subnode registrations and business names.
+//! * `unreserve_name` - Release a reserved label.
+//! * `set_subnode_label_owner` - Like `set_subnode_owner`, taking the label in utf8 so it is logged.
+//! * `propose_owner` - Offer a node to a new owner, which takes it over with `accept_ownership`.
+//! * `cancel_ownership_transfer` - Withdraw the pending offer of a node.
+//! * `claim_node` - List a node handed by another account among the names of its owner.
+//! * `migrate_owned_node` - List a node stored before the index among the names of its owner.
+//! * `force_set_owner` - Hand a node over at once, for the force origin.
+//! * `burn_fuses` - Take permissions away from a node for good, see [`fuses`](./fuses/index.html).
+//! * `clear_resolve` - Remove the resolve record of a node which no business refers to.
+//! * `clear_node` - Remove a node without subnodes which no business refers to, with its resolve record.
+//! * `force_clear_resolve`, `force_clear_node` - The same for the force origin, whoever owns the node.
+//! * `migrate_subnode` - Count a subnode registered before the subnode counts under its parent.
+//! * `approve` - Let an account manage a node on behalf of its owner, until the node is transferred.
+//! * `set_approval_for_all` - Let an operator manage all the nodes of the sender.
+//! * `set_text` - Set or remove a text record of a node.
+//! * `set_dns_endpoint` - Set the HTTP endpoint the DNS bridge pushes to.
+//!
+//! Names and reserved labels are normalized by the `NameNormalizer` of the runtime before
+//! they are checked, see [`normalizer`](./normalizer/index.html).
+//!
+//! A node changes hands in two steps, so a mistyped account does not lose the name: the
+//! owner proposes the new owner, which accepts. The offer is dropped when the node changes
+//! hands otherwise, like the approval of the node.
+//!
+//! The nodes of each owner are indexed in `OwnedNodes`, for wallets to list the names of an
+//! account. An account lists at most `MaxNamesPerAccount` names, and only those it took
+//! itself: registered for itself, accepted or handed by the force origin. A subnode handed
+//! to it by the owner of the parent is its own at once but listed, and counted, only once
+//! it claims it with `claim_node`, so the owner of a parent can not use up the quota of
+//! another account. The nodes stored before the index are listed by `migrate_owned_node`,
+//! which anyone may call.
+//!
+//! The fuses burned on a node bind the force origin as well: a node whose transfer fuse is
+//! burned is not handed over by `force_set_owner`, and a node with any fuse burned is not
+//! cleared.
+//!
+//! A resolve record carries the block it last changed at. Client modules resolving with
+//! `resolve_with_ttl` get the TTL of the node as well, counted in blocks, and check with
+//! [`is_stale`](./fn.is_stale.html) whether the record outlived it; the DNS bridge publishes
+//! the same TTL in seconds.
+//!
+//! A node with a `publish_dns` text record is published to the DNS by the offchain
+//! worker, see [`dns`](./dns/index.html).
+//!
+//! Records stored in the first layout, `NodeOf` and `ResolveOf`, are converted when read and
+//! moved when written again. The root records are moved once in `on_initialize`, tracked by
+//! the `StorageVersion` a new chain starts at. The nodes of the first layout have no parent
+//! link; a subnode gets one when the owner of its parent sets it again, and anyone then
+//! counts it under its parent with `migrate_subnode`, so the parent is not cleared while
+//! the subnode remains.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use app_crypto::RuntimeAppPublic;
 use codec::{Decode, Encode};
-use primitives::H256;
-use rstd::prelude::*;
-use sp_runtime::traits::{EnsureOrigin, Hash, StaticLookup, Zero};
+use primitives::{H256, offchain::{Duration, StorageKind}};
+use rstd::{convert::TryFrom, prelude::*};
+use sp_runtime::{offchain::http, traits::{EnsureOrigin, Hash, SaturatedConversion, StaticLookup, Zero}};
 use support::{
-	decl_event, decl_module, decl_storage,
+	debug, decl_event, decl_module, decl_storage,
 	dispatch::Result,
 	ensure,
 	traits::{Currency, Get, OnUnbalanced, ReservableCurrency},
-	weights::SimpleDispatchInfo,
+	weights::{DispatchClass, SimpleDispatchInfo, Weight},
 };
-use system::{ensure_root, ensure_signed};
+use system::{ensure_signed};
 // use serde::{Serialize, Deserialize, de::DeserializeOwned};
 // #[cfg(std)]
 // use serde_json::Value;
@@ -41,62 +93,167 @@ use system::{ensure_root, ensure_signed};
 // #[cfg(no_std)]
 // use serde_json_core::Value;
 
+pub mod dns;
+pub mod extension;
+pub mod fuses;
+pub mod normalizer;
+pub mod weights;
+pub use normalizer::{NameNormalizer, StandardNormalizer};
+pub use pistis_primitives::{BoundedName, BoundedZone, DataHash, HashAlgo, NodeRecord, ResolveRecord, ZoneFile};
+pub use weights::WeightInfo;
+use pistis_primitives::weights::FunctionOf;
+
 #[cfg(test)]
 mod name_service_test;
 
-/// The node record
+/// The storage version the module migrates to. Chains built from a genesis start at it,
+/// upgraded chains run the migrations in `on_initialize` once.
+const STORAGE_VERSION: u32 = 1;
+
+/// The prefix of the local storage keys under which the offchain worker keeps when it last
+/// pushed a node to the DNS endpoint and the hash of the lines it pushed
+const DNS_PUSH_KEY_PREFIX: &[u8] = b"pistis/dns-pushed/";
+
+/// The node record of the first layout, read until the node is written again
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct NodeRecord<AccountId> {
+pub struct LegacyNodeRecord<AccountId> {
 	/// The owner of the node
 	pub owner: AccountId,
 	/// The ttl of the record
 	pub ttl: u64,
 }
 
-/// The resolve record
+/// The resolve record of the first layout, read until the record is written again
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct ResolveRecord<Hash, AccountId> {
+pub struct LegacyResolveRecord<Hash, AccountId> {
 	/// The resolved address
 	pub addr: AccountId,
 	/// The resolved name
 	pub name: Vec<u8>,
 	/// The resolved profile
 	pub profile: Hash,
-	/// The zone file
+	/// The raw zone content
 	pub zone: Vec<u8>,
 }
 
-// #[derive(Encode, Decode, Default, Clone, PartialEq)]
-// pub struct ZoneFile {
-// 	pub storage: Vec<u8>,
-// 	pub read_url: Vec<u8>,
-// 	pub write_url: Vec<u8>,
-// }
+impl<Hash: AsRef<[u8]> + Default + PartialEq, AccountId> From<LegacyResolveRecord<Hash, AccountId>> for ResolveRecord<AccountId> {
+	/// The raw zone content is kept as the storage descriptor
+	fn from(legacy: LegacyResolveRecord<Hash, AccountId>) -> Self {
+		ResolveRecord {
+			addr: legacy.addr,
+			name: BoundedName::truncating_from(legacy.name),
+			profile: profile_of_hash(legacy.profile),
+			zone: ZoneFile {
+				storage: BoundedZone::truncating_from(legacy.zone),
+				read_url: Default::default(),
+				write_url: Default::default(),
+			},
+			last_updated: 0,
+		}
+	}
+}
 
+/// Convert a legacy profile hash, the default hash standing for no profile
+fn profile_of_hash<Hash: AsRef<[u8]> + Default + PartialEq>(profile: Hash) -> DataHash {
+	if profile == Hash::default() {
+		DataHash::default()
+	} else {
+		DataHash::from_runtime_hash(&profile)
+	}
+}
 
 pub trait Trait: system::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
-	/// The origin which may forcibly set or remove a name. Root can always do this.
+	/// The origin which may forcibly set or remove a name. Root is not accepted
+	/// unless this origin accepts it.
 	type ForceOrigin: EnsureOrigin<Self::Origin>;
 
 	/// The minimum length a name may be.
 	type MinNameLength: Get<usize>;
 
-	/// The maximum length a name may be.
+	/// The maximum length a name may be, at most the bound of `BoundedName`.
 	type MaxNameLength: Get<usize>;
 
-	/// The maxinum length a zone may be
+	/// The maxinum length a zone file field may be, at most the bound of `BoundedZone`
 	type MaxZoneLength: Get<usize>;
+
+	/// The registry which resolve profiles must point at
+	type ProfileResolver: ProfileResolver;
+
+	/// The weight functions of the calls
+	type WeightInfo: WeightInfo;
+
+	/// The policy names are normalized with
+	type NameNormalizer: NameNormalizer;
+
+	/// The client modules which may refer to nodes, which then can not be cleared
+	type NodeReferences: NodeReferences<Self::Hash>;
+
+	/// The client modules notified when the address a node resolves to changes
+	type OnResolveChanged: OnResolveChanged<Self>;
+
+	/// The local key the offchain worker signs the DNS pushes with
+	type DnsPublisherId: RuntimeAppPublic + Encode;
+
+	/// The maximum nodes which may be published to the DNS
+	type MaxDnsPublished: Get<u32>;
+
+	/// The maximum nodes an account may list in `OwnedNodes`
+	type MaxNamesPerAccount: Get<u32>;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as NameServiceModule {
+		/// The lookup table for node records without parent links, migrated lazily into `Nodes`
+		NodeOf get(legacy_node_of): map T::Hash => Option<LegacyNodeRecord<T::AccountId>>;
 		/// The lookup table for node records
-		NodeOf get(node_of): map T::Hash => Option<NodeRecord<T::AccountId>>;
+		Nodes get(node_record_of): map T::Hash => Option<NodeRecord<T::Hash, T::AccountId>>;
+		/// The number of subnodes registered under a node with parent links
+		ChildCount get(child_count): map T::Hash => u32;
+		/// The subnodes counted in the `ChildCount` of their parent
+		CountedSubnodes get(is_counted): map T::Hash => bool;
+		/// The account approved to manage a node on behalf of its owner
+		Approvals get(approved): map T::Hash => Option<T::AccountId>;
+		/// The account a node is offered to, until it accepts
+		PendingOwners get(pending_owner): map T::Hash => Option<T::AccountId>;
+		/// The nodes an owner took, accepted or claimed, the legacy nodes entering it once
+		/// migrated
+		OwnedNodes get(owned_nodes): map T::AccountId => Vec<T::Hash>;
+		/// The operators allowed to manage all the nodes of an owner
+		OperatorApprovals get(is_approved_for_all): map (T::AccountId, T::AccountId) => bool;
+		/// The label hashes which may not be registered or used as names
+		ReservedNames: map T::Hash => ();
+		/// The lookup table for resolve records with raw zones, migrated lazily into `ResolveRecords`
+		ResolveOf get(legacy_resolve_of): map T::Hash => Option<LegacyResolveRecord<T::Hash, T::AccountId>>;
 		/// The lookup table for resolve records
-		ResolveOf get(resolve_of): map T::Hash => Option<ResolveRecord<T::Hash, T::AccountId>>;
+		ResolveRecords get(resolve_record_of): map T::Hash => Option<ResolveRecord<T::AccountId>>;
+		/// The version of the storage layout, bumped by the migrations in `on_initialize`
+		StorageVersion get(storage_version) build(|_| STORAGE_VERSION): u32;
+		/// The text records of a node by key
+		TextRecords get(text_of): double_map T::Hash, blake2_256(Vec<u8>) => Option<Vec<u8>>;
+		/// The nodes with a `publish_dns` text record, pushed to the DNS by the offchain worker
+		DnsPublished get(dns_published): Vec<T::Hash>;
+		/// The HTTP endpoint the offchain worker pushes the zone lines to, empty for none
+		DnsEndpoint get(dns_endpoint): Vec<u8>;
+	}
+	add_extra_genesis {
+		/// The owner of the root node
+		config(root_owner): Option<T::AccountId>;
+		/// The nodes set up at genesis: the node hash, its owner and the address it resolves to
+		config(nodes): Vec<(T::Hash, T::AccountId, T::AccountId)>;
+		build(|config: &GenesisConfig<T>| {
+			if let Some(owner) = &config.root_owner {
+				<Nodes<T>>::insert(T::Hash::default(), NodeRecord { owner: owner.clone(), ..Default::default() });
+				<OwnedNodes<T>>::mutate(owner, |nodes| nodes.push(T::Hash::default()));
+			}
+			for (node_hash, owner, addr) in &config.nodes {
+				<Nodes<T>>::insert(node_hash, NodeRecord { owner: owner.clone(), ..Default::default() });
+				<OwnedNodes<T>>::mutate(owner, |nodes| nodes.push(*node_hash));
+				<ResolveRecords<T>>::insert(node_hash, ResolveRecord { addr: addr.clone(), ..Default::default() });
+			}
+		})
 	}
 }
 
@@ -108,10 +265,17 @@ decl_event!(
 	{
 		/// Logged when root is changed
 		RootChanged(AccountId),
-		/// Logged when the owner of a node assigns a new owner to a subnode.
-		NewOwner(Hash, Hash, AccountId),
+		/// Logged when the owner of a node assigns a new owner to a subnode
+		/// (parent node, label hash, subnode, owner, label in utf8 when given)
+		NewOwner(Hash, Hash, Hash, AccountId, Option<Vec<u8>>),
 		/// Logged when the owner of a node transfers ownership to a new account.
 		Transfer(Hash, AccountId),
+		/// Logged when the owner of a node offers it to a new account (node, new owner)
+		OwnershipProposed(Hash, AccountId),
+		/// Logged when the offer of a node is withdrawn
+		OwnershipTransferCancelled(Hash),
+		/// Logged when fuses of a node are burned (node, all the fuses burned)
+		FusesBurned(Hash, u32),
 		/// Logged when the resolver for a node changes.
 		ResolveSet(Hash, AccountId),
 		/// Logged when the TTL of a node changes
@@ -119,11 +283,29 @@ decl_event!(
 		/// Logged when addr of resolve record changed
 		ResolveAddrChanged(Hash, AccountId),
 		/// Logged when name of resolve record changed
-		ResolveNameChanged(Hash, Vec<u8>),
+		ResolveNameChanged(Hash, BoundedName),
 		/// Logged when profile of resolve record changed
-		ResolveProfileChanged(Hash, Hash),
+		ResolveProfileChanged(Hash, DataHash),
 		/// Logged when zone of resolve record changed
-		ResolveZoneChanged(Hash, Vec<u8>),
+		ResolveZoneChanged(Hash, ZoneFile),
+		/// Logged when a label is reserved
+		NameReserved(Hash, Vec<u8>),
+		/// Logged when a label is released
+		NameUnreserved(Hash, Vec<u8>),
+		/// Logged when the resolve record of a node is removed
+		ResolveCleared(Hash),
+		/// Logged when a node is removed
+		NodeCleared(Hash),
+		/// Logged when the account approved to manage a node changes (node, owner, approved)
+		Approval(Hash, AccountId, Option<AccountId>),
+		/// Logged when an operator is allowed or disallowed to manage the nodes of an owner
+		/// (owner, operator, approved)
+		ApprovalForAll(AccountId, AccountId, bool),
+		/// Logged when a text record of a node changes, an empty value removing it
+		/// (node, key, value)
+		TextChanged(Hash, Vec<u8>, Vec<u8>),
+		/// Logged when the HTTP endpoint of the DNS bridge changes
+		DnsEndpointChanged(Vec<u8>),
 	}
 );
 
@@ -138,81 +320,209 @@ decl_module! {
 		/// The maximum length a name may be.
 		const MaxNameLength: u32 = T::MaxNameLength::get() as u32;
 
-		/// The maximum length a zone may be.
+		/// The maximum length a zone file field may be.
 		const MaxZoneLength: u32 = T::MaxZoneLength::get() as u32;
 
+		/// The maximum nodes which may be published to the DNS.
+		const MaxDnsPublished: u32 = T::MaxDnsPublished::get();
+
+		/// The maximum nodes an account may list.
+		const MaxNamesPerAccount: u32 = T::MaxNamesPerAccount::get();
+
 		/// Set admin owner for this module
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_root_owner())]
 		fn set_root_owner(origin, owner: T::AccountId) -> Result {
-			T::ForceOrigin::try_origin(origin)
-				.map(|_| ())
-				.or_else(ensure_root)
-				.map_err(|_| "Bad origin")?;
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
 
 			let node_hash: T::Hash = T::Hash::default();
-			Self::do_set_owner(node_hash, &owner)?;
+			Self::ensure_fuse_intact(node_hash, fuses::CANNOT_TRANSFER, "Transfer fuse burned")?;
+			Self::do_set_owner(node_hash, &owner, None, true)?;
 			Self::deposit_event(RawEvent::RootChanged(owner));
 
 			Ok(())
 		}
 
-		/// Transfer ownership of a node to a new address. May only be called
+		/// Offer a node to a new owner, replacing any pending offer. May only be called
 		/// by the current owner of the node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn set_owner(origin, node_hash: T::Hash, owner: T::AccountId) -> Result {
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		/// @owner	the new owner, which has to accept
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::propose_owner())]
+		fn propose_owner(origin, node_hash: T::Hash, owner: T::AccountId) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
 
-			let mut record = Self::node_of(node_hash).unwrap();
+			let record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+			ensure!(record.fuses & fuses::CANNOT_TRANSFER == 0, "Transfer fuse burned");
 			ensure!(record.owner != owner, "Owner is the same account");
-			record.owner = owner.clone();
+			ensure!(Self::pending_owner(node_hash).as_ref() != Some(&owner), "Owner is already proposed");
+			<PendingOwners<T>>::insert(node_hash, &owner);
 
-			<NodeOf<T>>::insert(node_hash, record);
-			Self::deposit_event(RawEvent::Transfer(node_hash, owner));
+			Self::deposit_event(RawEvent::OwnershipProposed(node_hash, owner));
 			Ok(())
 		}
 
-		/// Transfer ownership of a subnode sha3(node, label) to a new address. May only be called
-		/// by the current owner of the parent node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn set_subnode_owner(origin, node_hash: T::Hash, label: T::Hash, owner: T::AccountId) -> Result {
+		/// Take over a node offered to the sender
+		///
+		/// @origin	the proposed owner
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::accept_ownership())]
+		fn accept_ownership(origin, node_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::pending_owner(node_hash).as_ref() == Some(&sender), "Not the proposed owner");
+
+			Self::ensure_fuse_intact(node_hash, fuses::CANNOT_TRANSFER, "Transfer fuse burned")?;
+			Self::do_set_owner(node_hash, &sender, None, true)?;
+			Self::deposit_event(RawEvent::Transfer(node_hash, sender));
+			Ok(())
+		}
+
+		/// List a node of the sender among its names, such as a subnode handed to it by the
+		/// owner of the parent
+		///
+		/// @origin	the owner of the node
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::claim_node())]
+		fn claim_node(origin, node_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			let record = Self::node_record_of(node_hash).ok_or("Node does not exist")?;
+			ensure!(record.owner == sender, "Sender is not owner");
+
+			Self::list_owned_node(&sender, node_hash)
+		}
+
+		/// List a node stored before the index among the names of its owner, moving it to
+		/// the current layout. May be called by anyone.
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::migrate_owned_node())]
+		fn migrate_owned_node(origin, node_hash: T::Hash) -> Result {
+			ensure_signed(origin)?;
+			ensure!(Self::node_record_of(node_hash).is_none(), "Node is not a legacy node");
+			let record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+
+			Self::list_owned_node(&record.owner, node_hash)?;
+			Self::insert_node(node_hash, record);
+			Ok(())
+		}
+
+		/// Withdraw the pending offer of a node. May only be called by the current owner
+		/// of the node
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::cancel_ownership_transfer())]
+		fn cancel_ownership_transfer(origin, node_hash: T::Hash) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
 
-			// let subnode_hash = (
-			// 	node_hash,
-			// 	label,
-			// ).using_encoded(<T as system::Trait>::Hashing::hash);
-			// TODO: move to a common place
+			ensure!(<PendingOwners<T>>::exists(node_hash), "No pending transfer");
+			<PendingOwners<T>>::remove(node_hash);
 
-			let mut node = node_hash.as_ref().to_vec();
-			node.append(&mut label.as_ref().to_vec());
+			Self::deposit_event(RawEvent::OwnershipTransferCancelled(node_hash));
+			Ok(())
+		}
 
-			let subnode_hash = <T as system::Trait>::Hashing::hash(node.as_slice()); 
+		/// Hand a node over to a new owner at once
+		///
+		/// @origin	the force origin
+		/// @node_hash	the node hash
+		/// @owner	the new owner
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::force_set_owner())]
+		fn force_set_owner(origin, node_hash: T::Hash, owner: T::AccountId) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(Self::node_of(node_hash).is_some(), "Node does not exist");
+			Self::ensure_fuse_intact(node_hash, fuses::CANNOT_TRANSFER, "Transfer fuse burned")?;
+			Self::do_set_owner(node_hash, &owner, None, true)?;
+			Self::deposit_event(RawEvent::Transfer(node_hash, owner));
+			Ok(())
+		}
 
-			Self::do_set_owner(subnode_hash, &owner)?;
-			Self::deposit_event(RawEvent::NewOwner(node_hash, label, owner));
+		/// Burn fuses of a node, irreversibly. `PARENT_CANNOT_CONTROL` may only be burned by
+		/// the owner of the parent, the other fuses by the owner of the node, once the parent
+		/// gave up control of a subnode
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		/// @fuses	the fuses to burn, see [`fuses`](./fuses/index.html)
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::burn_fuses())]
+		fn burn_fuses(origin, node_hash: T::Hash, fuses: u32) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+			ensure!(fuses & !fuses::ALL == 0, "Unknown fuses");
+			ensure!(record.fuses & fuses::CANNOT_BURN_FUSES == 0, "Fuses are frozen");
+			ensure!(record.fuses | fuses != record.fuses, "Fuses already burned");
+
+			if fuses & fuses::PARENT_CANNOT_CONTROL != 0 {
+				let parent = record.parent.and_then(Self::node_of).ok_or("Node has no parent")?;
+				ensure!(parent.owner == sender, "Sender is not parent owner");
+			}
+			if fuses & !fuses::PARENT_CANNOT_CONTROL != 0 {
+				ensure!(record.owner == sender, "Sender is not owner");
+				ensure!(
+					record.parent.is_none() || (record.fuses | fuses) & fuses::PARENT_CANNOT_CONTROL != 0,
+					"Parent controls the node"
+				);
+			}
+			record.fuses |= fuses;
+			let burned = record.fuses;
+			Self::insert_node(node_hash, record);
+
+			Self::deposit_event(RawEvent::FusesBurned(node_hash, burned));
 			Ok(())
 		}
 
+		/// Transfer ownership of a subnode sha3(node, label) to a new address. May only be called
+		/// by the current owner of the parent node
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_subnode_owner())]
+		fn set_subnode_owner(origin, node_hash: T::Hash, label: T::Hash, owner: T::AccountId) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_subnode_owner(&sender, node_hash, label, owner, None)
+		}
+
+		/// Transfer ownership of a subnode to a new address, with the label in utf8 so
+		/// indexers can rebuild the name from the events. May only be called by the current
+		/// owner of the parent node
+		///
+		/// @origin	the sender
+		/// @node_hash	the parent node hash
+		/// @label	the label in utf8, hashed as raw bytes
+		/// @owner	the owner of the subnode
+		#[weight = FunctionOf(
+			|(_, label, _): (&T::Hash, &Vec<u8>, &T::AccountId)| T::WeightInfo::set_subnode_label_owner(label.len() as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_subnode_label_owner(origin, node_hash: T::Hash, label: Vec<u8>, owner: T::AccountId) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let label = Self::normalize_label(&label)?;
+			let label_hash = T::Hashing::hash(&label);
+			Self::do_set_subnode_owner(&sender, node_hash, label_hash, owner, Some(label))
+		}
+
 		/// Set the TTL for the specified node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_ttl())]
 		fn set_ttl(origin, node_hash: T::Hash, ttl: u64) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
 
 			let mut record = Self::node_of(node_hash).unwrap();
+			ensure!(record.fuses & fuses::CANNOT_SET_TTL == 0, "TTL fuse burned");
 			ensure!(record.ttl != ttl, "TTL is the same value");
 			record.ttl = ttl;
 
-			<NodeOf<T>>::insert(node_hash, record);
+			Self::insert_node(node_hash, record);
 			Self::deposit_event(RawEvent::NewTTL(node_hash, ttl));
 
 			Ok(())
 		}
 
 		/// Set the resolve addr for the node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_resolve_addr().saturating_add(T::OnResolveChanged::max_weight()))]
 		fn set_resolve_addr(origin, node_hash: T::Hash, addr: T::AccountId) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
@@ -224,13 +534,16 @@ decl_module! {
 		}	
 
 		/// Set the resolve name for the node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		#[weight = FunctionOf(|(_, name): (&T::Hash, &Vec<u8>)| T::WeightInfo::set_resolve_name(name.len() as u32), DispatchClass::Normal, true)]
 		fn set_resolve_name(origin, node_hash: T::Hash, name: Vec<u8>) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
 
+			let name = T::NameNormalizer::normalize(&name)?;
 			ensure!(name.len() >= T::MinNameLength::get(), "Name too short");
 			ensure!(name.len() <= T::MaxNameLength::get(), "Name too long");
+			ensure!(!Self::is_reserved_name(&name), "Name is reserved");
+			let name = BoundedName::try_from(name)?;
 			
 			Self::do_set_resolve_name(node_hash, &name)?;
 			Self::deposit_event(RawEvent::ResolveNameChanged(node_hash, name));
@@ -239,60 +552,800 @@ decl_module! {
 		}
 
 		/// Set the resolve profile for the node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn set_resolve_profile(origin, node_hash: T::Hash, profile: T::Hash) -> Result {
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_resolve_profile())]
+		fn set_resolve_profile(origin, node_hash: T::Hash, profile: DataHash) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
-			
-			Self::do_set_resolve_profile(node_hash, profile)?;
+
+			profile.validate()?;
+			ensure!(T::ProfileResolver::is_valid_profile(&profile), "Profile is not valid");
+			Self::do_set_resolve_profile(node_hash, profile.clone())?;
 			Self::deposit_event(RawEvent::ResolveProfileChanged(node_hash, profile));
 
 			Ok(())
 		}	
 
-		/// Set the resolve zone content for the node
-		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
-		fn set_resolve_zone(origin, node_hash: T::Hash, zone: Vec<u8>) -> Result {
+		/// Set the zone storage descriptor for the node
+		#[weight = FunctionOf(|(_, storage): (&T::Hash, &Vec<u8>)| T::WeightInfo::set_zone_storage(storage.len() as u32), DispatchClass::Normal, true)]
+		fn set_zone_storage(origin, node_hash: T::Hash, storage: Vec<u8>) -> Result {
 			let sender = ensure_signed(origin)?;
 			Self::only_owner(node_hash, &sender)?;
 
-			ensure!(zone.len() <= T::MaxZoneLength::get(), "Zone content too long");
-			Self::do_set_resolve_zone(node_hash, &zone)?;
+			ensure!(storage.len() <= T::MaxZoneLength::get(), "Zone storage too long");
+			let storage = BoundedZone::try_from(storage)?;
+			let zone = Self::do_set_zone_storage(node_hash, &storage)?;
 			Self::deposit_event(RawEvent::ResolveZoneChanged(node_hash, zone));
 
-			Ok(())	
+			Ok(())
+		}
+
+		/// Set the zone read and write urls for the node
+		#[weight = FunctionOf(
+			|(_, read_url, write_url): (&T::Hash, &Vec<u8>, &Vec<u8>)| T::WeightInfo::set_zone_urls((read_url.len() + write_url.len()) as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_zone_urls(origin, node_hash: T::Hash, read_url: Vec<u8>, write_url: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::only_owner(node_hash, &sender)?;
+
+			ensure!(read_url.len() <= T::MaxZoneLength::get(), "Zone read url too long");
+			ensure!(write_url.len() <= T::MaxZoneLength::get(), "Zone write url too long");
+			let (read_url, write_url) = (BoundedZone::try_from(read_url)?, BoundedZone::try_from(write_url)?);
+			let zone = Self::do_set_zone_urls(node_hash, &read_url, &write_url)?;
+			Self::deposit_event(RawEvent::ResolveZoneChanged(node_hash, zone));
+
+			Ok(())
+		}
+
+		/// Set a text record of the node, an empty value removing it. The name of the node as a
+		/// domain name under the `publish_dns` key publishes the node to the DNS
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		/// @key	the key of the record
+		/// @value	the value of the record
+		#[weight = FunctionOf(
+			|(_, key, value): (&T::Hash, &Vec<u8>, &Vec<u8>)| T::WeightInfo::set_text((key.len() + value.len()) as u32),
+			DispatchClass::Normal,
+			true
+		)]
+		fn set_text(origin, node_hash: T::Hash, key: Vec<u8>, value: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::only_owner(node_hash, &sender)?;
+
+			Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_TEXT, "Text fuse burned")?;
+			ensure!(!key.is_empty(), "Text key is empty");
+			ensure!(key.len() <= T::MaxNameLength::get(), "Text key too long");
+			ensure!(value.len() <= T::MaxZoneLength::get(), "Text value too long");
+			ensure!(Self::text_of(node_hash, &key).unwrap_or_default() != value, "Text is the same value");
+			if key == dns::PUBLISH_DNS_KEY {
+				ensure!(value.is_empty() || dns::is_dns_name(&value), "Invalid domain name");
+				ensure!(value.is_empty() || Self::is_domain_of(node_hash, &value), "Domain is not the name of the node");
+				Self::set_dns_published(node_hash, !value.is_empty())?;
+			}
+
+			if value.is_empty() {
+				<TextRecords<T>>::remove(node_hash, &key);
+			} else {
+				<TextRecords<T>>::insert(node_hash, &key, &value);
+			}
+			Self::deposit_event(RawEvent::TextChanged(node_hash, key, value));
+
+			Ok(())
+		}
+
+		/// Set the HTTP endpoint the offchain worker pushes the DNS zone lines to, empty to
+		/// stop publishing
+		///
+		/// @origin	the force origin
+		/// @endpoint	the url of the endpoint
+		#[weight = FunctionOf(|(endpoint,): (&Vec<u8>,)| T::WeightInfo::set_dns_endpoint(endpoint.len() as u32), DispatchClass::Normal, true)]
+		fn set_dns_endpoint(origin, endpoint: Vec<u8>) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(endpoint.len() <= T::MaxZoneLength::get(), "Endpoint too long");
+			ensure!(rstd::str::from_utf8(&endpoint).is_ok(), "Endpoint is not utf8");
+
+			DnsEndpoint::put(&endpoint);
+			Self::deposit_event(RawEvent::DnsEndpointChanged(endpoint));
+
+			Ok(())
+		}
+
+		/// Remove the resolve record of a node. May only be called by the owner of the node
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clear_resolve().saturating_add(T::OnResolveChanged::max_weight()))]
+		fn clear_resolve(origin, node_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::only_owner(node_hash, &sender)?;
+
+			Self::do_clear_resolve(node_hash)?;
+			Self::deposit_event(RawEvent::ResolveCleared(node_hash));
+
+			Ok(())
+		}
+
+		/// Remove a node and its resolve record. May only be called by the owner of the node
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clear_node().saturating_add(T::OnResolveChanged::max_weight()))]
+		fn clear_node(origin, node_hash: T::Hash) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::only_owner(node_hash, &sender)?;
+
+			Self::do_clear_node(node_hash)?;
+			Self::deposit_event(RawEvent::NodeCleared(node_hash));
+
+			Ok(())
+		}
+
+		/// Count a subnode registered before the subnode counts in the `ChildCount` of its
+		/// parent. May be called by anyone, once per subnode.
+		///
+		/// @origin	the sender
+		/// @node_hash	the subnode hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::migrate_subnode())]
+		fn migrate_subnode(origin, node_hash: T::Hash) -> Result {
+			ensure_signed(origin)?;
+			let parent = Self::parent_of(node_hash).ok_or("Node has no parent")?;
+			ensure!(!Self::is_counted(node_hash), "Subnode already counted");
+
+			<ChildCount<T>>::mutate(parent, |count| *count = count.saturating_add(1));
+			<CountedSubnodes<T>>::insert(node_hash, true);
+
+			Ok(())
+		}
+
+		/// Forcibly remove the resolve record of a node
+		///
+		/// @origin	the force origin
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clear_resolve().saturating_add(T::OnResolveChanged::max_weight()))]
+		fn force_clear_resolve(origin, node_hash: T::Hash) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			Self::do_clear_resolve(node_hash)?;
+			Self::deposit_event(RawEvent::ResolveCleared(node_hash));
+
+			Ok(())
+		}
+
+		/// Forcibly remove a node and its resolve record
+		///
+		/// @origin	the force origin
+		/// @node_hash	the node hash
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clear_node().saturating_add(T::OnResolveChanged::max_weight()))]
+		fn force_clear_node(origin, node_hash: T::Hash) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			Self::do_clear_node(node_hash)?;
+			Self::deposit_event(RawEvent::NodeCleared(node_hash));
+
+			Ok(())
+		}
+
+		/// Approve an account to manage a node on behalf of its owner, or clear the approval.
+		/// May only be called by the owner of the node or one of its operators.
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash
+		/// @delegate	the approved account, none to clear the approval
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::approve())]
+		fn approve(origin, node_hash: T::Hash, delegate: Option<T::AccountId>) -> Result {
+			let sender = ensure_signed(origin)?;
+			let record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+			ensure!(
+				record.owner == sender || Self::is_approved_for_all((record.owner.clone(), sender)),
+				"Sender is not owner"
+			);
+			ensure!(delegate.as_ref() != Some(&record.owner), "Approval to the owner");
+			ensure!(Self::approved(node_hash) != delegate, "Approval is the same value");
+
+			match &delegate {
+				Some(delegate) => <Approvals<T>>::insert(node_hash, delegate),
+				None => <Approvals<T>>::remove(node_hash),
+			}
+			Self::deposit_event(RawEvent::Approval(node_hash, record.owner, delegate));
+
+			Ok(())
+		}
+
+		/// Allow or disallow an operator to manage all the nodes of the sender
+		///
+		/// @origin	the sender
+		/// @operator	the operator account
+		/// @approved	whether the operator may manage the nodes
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::set_approval_for_all())]
+		fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> Result {
+			let sender = ensure_signed(origin)?;
+			ensure!(operator != sender, "Approval to the owner");
+			ensure!(Self::is_approved_for_all((sender.clone(), operator.clone())) != approved, "Approval is the same value");
+
+			if approved {
+				<OperatorApprovals<T>>::insert((sender.clone(), operator.clone()), true);
+			} else {
+				<OperatorApprovals<T>>::remove((sender.clone(), operator.clone()));
+			}
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+
+			Ok(())
+		}
+
+		/// Reserve a label, so it can not be registered or used as a name
+		///
+		/// @origin	the force origin
+		/// @label	the label in utf8
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::reserve_name())]
+		fn reserve_name(origin, label: Vec<u8>) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			let label = T::NameNormalizer::normalize(&label)?;
+			ensure!(!label.is_empty(), "Name too short");
+			ensure!(label.len() <= T::MaxNameLength::get(), "Name too long");
+			let label_hash = T::Hashing::hash(&label);
+			ensure!(!<ReservedNames<T>>::exists(label_hash), "Name already reserved");
+
+			<ReservedNames<T>>::insert(label_hash, ());
+			Self::deposit_event(RawEvent::NameReserved(label_hash, label));
+
+			Ok(())
+		}
+
+		/// Release a reserved label
+		///
+		/// @origin	the force origin
+		/// @label	the label in utf8
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::unreserve_name())]
+		fn unreserve_name(origin, label: Vec<u8>) -> Result {
+			T::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			let label = T::NameNormalizer::normalize(&label)?;
+			let label_hash = T::Hashing::hash(&label);
+			ensure!(<ReservedNames<T>>::exists(label_hash), "Name is not reserved");
+
+			<ReservedNames<T>>::remove(label_hash);
+			Self::deposit_event(RawEvent::NameUnreserved(label_hash, label));
+
+			Ok(())
+		}
+
+		fn on_initialize(_now: T::BlockNumber) {
+			let version = Self::storage_version();
+			if version < STORAGE_VERSION {
+				// the root records are moved out of the first layout
+				if version < 1 {
+					Self::migrate_root_records();
+				}
+				StorageVersion::put(STORAGE_VERSION);
+			}
+		}
+
+		// Runs after every block.
+		fn offchain_worker(now: T::BlockNumber) {
+			debug::RuntimeLogger::init();
+			Self::offchain_publish_dns(now);
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
-	/// Check if the sender is the current owner of the node
+	/// Get the node record, converting a legacy record on the fly. A legacy node has no
+	/// parent link, its label is unknown and left as the default hash, and no fuse burned.
+	///
+	/// @node_hash	the node hash
+	pub fn node_of(node_hash: T::Hash) -> Option<NodeRecord<T::Hash, T::AccountId>> {
+		Self::node_record_of(node_hash)
+			.or_else(|| Self::legacy_node_of(node_hash).map(|legacy| NodeRecord {
+				owner: legacy.owner,
+				ttl: legacy.ttl,
+				parent: None,
+				label: T::Hash::default(),
+				fuses: 0,
+			}))
+	}
+
+	/// Check a fuse of a node is not burned
+	///
+	/// @node_hash	the node hash
+	/// @fuse	the fuse
+	/// @error	the error if it is burned
+	fn ensure_fuse_intact(node_hash: T::Hash, fuse: u32, error: &'static str) -> Result {
+		let burned = Self::node_of(node_hash).map_or(0, |record| record.fuses);
+		ensure!(burned & fuse == 0, error);
+		Ok(())
+	}
+
+	/// Get the parent of the node
+	///
+	/// @node_hash	the node hash
+	pub fn parent_of(node_hash: T::Hash) -> Option<T::Hash> {
+		Self::node_of(node_hash).and_then(|record| record.parent)
+	}
+
+	/// Get the ancestors of the node, from its parent up to the root
+	///
+	/// @node_hash	the node hash
+	pub fn ancestors(node_hash: T::Hash) -> Vec<T::Hash> {
+		let mut ancestors = Vec::new();
+		let mut node = node_hash;
+		while let Some(parent) = Self::parent_of(node) {
+			// guard against a cycle in records written by hand, e.g. at genesis
+			if parent == node_hash || ancestors.contains(&parent) {
+				break;
+			}
+			ancestors.push(parent);
+			node = parent;
+		}
+		ancestors
+	}
+
+	/// Get the label hashes of the node from the top level down, as the labels of its
+	/// dotted name read from right to left
+	///
+	/// @node_hash	the node hash
+	pub fn label_path(node_hash: T::Hash) -> Vec<T::Hash> {
+		let mut path: Vec<T::Hash> = rstd::iter::once(node_hash)
+			.chain(Self::ancestors(node_hash))
+			.filter_map(|node| Self::node_of(node).filter(|record| record.parent.is_some()).map(|record| record.label))
+			.collect();
+		path.reverse();
+		path
+	}
+
+	/// Get the resolve record of the node, converting a legacy record on the fly. A legacy
+	/// record changed at block zero.
+	///
+	/// @node_hash	the node hash
+	pub fn resolve_of(node_hash: T::Hash) -> Option<ResolveRecord<T::AccountId>> {
+		Self::resolve_record_of(node_hash)
+			.or_else(|| Self::legacy_resolve_of(node_hash).map(Into::into))
+	}
+
+	/// Check if the name or any of its dot separated labels is reserved
+	///
+	/// @name	the name in utf8
+	pub fn is_reserved_name(name: &[u8]) -> bool {
+		<ReservedNames<T>>::exists(T::Hashing::hash(name))
+			|| name.split(|c| *c == b'.').any(|label| <ReservedNames<T>>::exists(T::Hashing::hash(label)))
+	}
+
+	/// Check if the account is the resolved address of the node or owns one of its ancestors,
+	/// up to `max_depth` levels above it. The root node never authorizes its owner.
+	///
+	/// @node_hash	the node hash
+	/// @account	the account to be authorized
+	/// @max_depth	the number of ancestors to check
+	pub fn is_authorized_by_hierarchy(node_hash: T::Hash, account: &T::AccountId, max_depth: u32) -> bool {
+		if Self::resolve_of(node_hash).map_or(false, |record| record.addr == *account) {
+			return true;
+		}
+
+		let mut node = node_hash;
+		for _ in 0..max_depth {
+			node = match Self::parent_of(node) {
+				Some(parent) if parent != T::Hash::default() => parent,
+				_ => return false,
+			};
+			if Self::node_of(node).map_or(false, |record| record.owner == *account) {
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Get the TTL in seconds a node is published to the DNS with
+	///
+	/// @node_hash	the node hash
+	pub fn dns_ttl(node_hash: T::Hash) -> u64 {
+		Self::node_of(node_hash)
+			.map(|record| record.ttl)
+			.filter(|ttl| *ttl > 0)
+			.unwrap_or(dns::DEFAULT_DNS_TTL)
+	}
+
+	/// Get the zone file lines a node is published to the DNS with, none if it has no
+	/// `publish_dns` text record holding its name, or no resolve record
+	///
+	/// @node_hash	the node hash
+	pub fn dns_zone_of(node_hash: T::Hash) -> Option<Vec<u8>> {
+		let domain = Self::text_of(node_hash, dns::PUBLISH_DNS_KEY.to_vec())
+			.filter(|domain| Self::is_domain_of(node_hash, domain))?;
+		let record = Self::resolve_of(node_hash)?;
+		Some(dns::render_zone(&domain, Self::dns_ttl(node_hash), &record))
+	}
+
+	/// Check if a domain name is the name of the node, its labels read from right to left
+	/// hashing to the label path of the node as subnode labels do
+	///
+	/// @node_hash	the node hash
+	/// @domain	the domain name
+	pub fn is_domain_of(node_hash: T::Hash, domain: &[u8]) -> bool {
+		domain.rsplit(|c| *c == b'.')
+			.map(|label| Self::normalize_label(label).map(|label| T::Hashing::hash(&label)))
+			.collect::<rstd::result::Result<Vec<T::Hash>, _>>()
+			.map_or(false, |labels| !labels.is_empty() && labels == Self::label_path(node_hash))
+	}
+
+	/// Add a node to the nodes published to the DNS, or remove it
+	///
+	/// @node_hash	the node hash
+	/// @published	whether the node is published
+	fn set_dns_published(node_hash: T::Hash, published: bool) -> Result {
+		let mut nodes = Self::dns_published();
+		let position = nodes.iter().position(|node| *node == node_hash);
+		match (position, published) {
+			(None, true) => {
+				ensure!(nodes.len() < T::MaxDnsPublished::get() as usize, "Too many published nodes");
+				nodes.push(node_hash);
+			},
+			(Some(index), false) => {
+				nodes.swap_remove(index);
+			},
+			_ => return Ok(()),
+		}
+		<DnsPublished<T>>::put(nodes);
+		Ok(())
+	}
+
+	/// Push the zone file lines of the published nodes which changed or whose TTL elapsed
+	/// to the DNS endpoint, signed with a local publisher key
+	///
+	/// @now	the current block number
+	fn offchain_publish_dns(now: T::BlockNumber) {
+		let endpoint = Self::dns_endpoint();
+		let nodes = Self::dns_published();
+		if endpoint.is_empty() || nodes.is_empty() {
+			return;
+		}
+		let url = match rstd::str::from_utf8(&endpoint) {
+			Ok(url) => url,
+			Err(_) => return,
+		};
+		let key = match T::DnsPublisherId::all().into_iter().next() {
+			Some(key) => key,
+			None => return,
+		};
+
+		let timestamp = runtime_io::offchain::timestamp();
+		let mut body = Vec::new();
+		let mut pushed = Vec::new();
+		for node_hash in nodes {
+			let lines = match Self::dns_zone_of(node_hash) {
+				Some(lines) => lines,
+				None => continue,
+			};
+			let digest = T::Hashing::hash(&lines);
+			let push_key = [DNS_PUSH_KEY_PREFIX, node_hash.as_ref()].concat();
+			let is_due = match runtime_io::offchain::local_storage_get(StorageKind::PERSISTENT, &push_key)
+				.and_then(|raw| <(u64, T::Hash)>::decode(&mut &raw[..]).ok())
+			{
+				Some((pushed_at, pushed_digest)) => pushed_digest != digest
+					|| timestamp.unix_millis().saturating_sub(pushed_at) >= Self::dns_ttl(node_hash).saturating_mul(1000),
+				None => true,
+			};
+			if is_due {
+				body.extend(lines);
+				pushed.push((push_key, digest));
+			}
+		}
+		if pushed.is_empty() {
+			return;
+		}
+
+		// the genesis hash and the time are signed along, so the push is not replayed on
+		// another chain or later
+		let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+		let payload = dns::signing_payload(genesis_hash.as_ref(), timestamp.unix_millis(), &body);
+		let signature = match key.sign(&payload) {
+			Some(signature) => signature,
+			None => {
+				debug::warn!(target: "name-service", "Failed to sign the DNS push at {:?}", now);
+				return;
+			}
+		};
+		let public = dns::to_hex(&key.encode());
+		let signature = dns::to_hex(&signature.encode());
+		let pushed_at = dns::to_decimal(timestamp.unix_millis());
+		let genesis_hash = dns::to_hex(genesis_hash.as_ref());
+		let deadline = timestamp.add(Duration::from_millis(dns::DNS_PUSH_TIMEOUT));
+		let response = http::Request::post(url, vec![body])
+			.add_header("Content-Type", "text/dns")
+			.add_header(dns::PUBLIC_KEY_HEADER, rstd::str::from_utf8(&public).unwrap_or_default())
+			.add_header(dns::SIGNATURE_HEADER, rstd::str::from_utf8(&signature).unwrap_or_default())
+			.add_header(dns::TIMESTAMP_HEADER, rstd::str::from_utf8(&pushed_at).unwrap_or_default())
+			.add_header(dns::GENESIS_HASH_HEADER, rstd::str::from_utf8(&genesis_hash).unwrap_or_default())
+			.deadline(deadline)
+			.send()
+			.ok()
+			.and_then(|pending| pending.try_wait(deadline).ok())
+			.and_then(|result| result.ok());
+
+		match response {
+			Some(response) if response.code >= 200 && response.code < 300 => {
+				for (push_key, digest) in pushed {
+					runtime_io::offchain::local_storage_set(
+						StorageKind::PERSISTENT,
+						&push_key,
+						&(timestamp.unix_millis(), digest).encode(),
+					);
+				}
+			},
+			Some(response) => debug::warn!(
+				target: "name-service",
+				"The DNS endpoint rejected the push at {:?} with status {}",
+				now,
+				response.code,
+			),
+			None => debug::warn!(target: "name-service", "Failed to push the DNS zone at {:?}", now),
+		}
+	}
+
+	/// Move the records of the root node out of the first layout. The other nodes can not
+	/// be enumerated and are migrated when they are written, read in the first layout until
+	/// then.
+	fn migrate_root_records() {
+		let root_hash = T::Hash::default();
+		if Self::node_record_of(root_hash).is_none() {
+			if let Some(record) = Self::node_of(root_hash) {
+				Self::insert_node(root_hash, record);
+			}
+		}
+		if Self::resolve_record_of(root_hash).is_none() {
+			if let Some(record) = Self::resolve_of(root_hash) {
+				Self::insert_resolve(root_hash, record);
+			}
+		}
+	}
+
+	/// Store the node record and drop its legacy record
+	///
+	/// @node_hash	the node hash
+	/// @record	the node record
+	fn insert_node(node_hash: T::Hash, record: NodeRecord<T::Hash, T::AccountId>) {
+		// neither the approval nor the pending offer of a node survives a transfer
+		if let Some(old) = Self::node_of(node_hash).filter(|old| old.owner != record.owner) {
+			<Approvals<T>>::remove(node_hash);
+			<PendingOwners<T>>::remove(node_hash);
+			Self::remove_owned_node(&old.owner, node_hash);
+		}
+		<Nodes<T>>::insert(node_hash, record);
+		<NodeOf<T>>::remove(node_hash);
+	}
+
+	/// List a node among the names of its owner, within `MaxNamesPerAccount`
+	///
+	/// @owner	the owner
+	/// @node_hash	the node hash
+	fn list_owned_node(owner: &T::AccountId, node_hash: T::Hash) -> Result {
+		let mut nodes = Self::owned_nodes(owner);
+		ensure!(!nodes.contains(&node_hash), "Node is already listed");
+		ensure!((nodes.len() as u32) < T::MaxNamesPerAccount::get(), "Too many names");
+		nodes.push(node_hash);
+		<OwnedNodes<T>>::insert(owner, nodes);
+		Ok(())
+	}
+
+	/// Drop a node from the nodes of its former owner
+	///
+	/// @owner	the former owner
+	/// @node_hash	the node hash
+	fn remove_owned_node(owner: &T::AccountId, node_hash: T::Hash) {
+		let mut nodes = Self::owned_nodes(owner);
+		nodes.retain(|node| *node != node_hash);
+		if nodes.is_empty() {
+			<OwnedNodes<T>>::remove(owner);
+		} else {
+			<OwnedNodes<T>>::insert(owner, nodes);
+		}
+	}
+
+	/// Store the resolve record of the node and drop its legacy record
+	///
+	/// @node_hash	the node hash
+	/// @record	the resolve record
+	fn insert_resolve(node_hash: T::Hash, record: ResolveRecord<T::AccountId>) {
+		<ResolveRecords<T>>::insert(node_hash, record);
+		<ResolveOf<T>>::remove(node_hash);
+	}
+
+	/// Remove the resolve record of the node in every layout
+	///
+	/// @node_hash	the node hash
+	fn remove_resolve(node_hash: T::Hash) {
+		<ResolveRecords<T>>::remove(node_hash);
+		<ResolveOf<T>>::remove(node_hash);
+	}
+
+	/// Store a changed resolve record, stamped with the current block
+	///
+	/// @node_hash	the node hash
+	/// @record	the resolve record
+	fn update_resolve(node_hash: T::Hash, mut record: ResolveRecord<T::AccountId>) {
+		record.last_updated = <system::Module<T>>::block_number().saturated_into();
+		Self::insert_resolve(node_hash, record);
+	}
+
+	/// Check if the sender is the current owner of the node, the account approved for the
+	/// node or an operator of the owner
 	///
 	/// @node_hash	the node hash
 	/// @sender	the sender
 	fn only_owner(node_hash: T::Hash, sender: &T::AccountId) -> Result {
 		if let Some(record) = Self::node_of(node_hash) {
-			ensure!(record.owner == *sender, "Sender is not owner");
+			ensure!(
+				record.owner == *sender
+					|| Self::approved(node_hash).as_ref() == Some(sender)
+					|| Self::is_approved_for_all((record.owner, sender.clone())),
+				"Sender is not owner"
+			);
 			Ok(())
 		} else {
 			Err("Node does not exist")
 		}
 	}
 
+	/// Normalize a subnode label and check it is a single label of a valid length
+	///
+	/// @label	the label in utf8
+	pub fn normalize_label(label: &[u8]) -> rstd::result::Result<Vec<u8>, &'static str> {
+		let label = T::NameNormalizer::normalize(label)?;
+		ensure!(!label.is_empty(), "Name too short");
+		ensure!(label.len() <= T::MaxNameLength::get(), "Name too long");
+		ensure!(!label.contains(&b'.'), "Label contains a dot");
+		Ok(label)
+	}
+
+	/// Give a subnode to an owner on behalf of the owner of its parent, and resolve it to
+	/// the new owner. Used by registrar modules holding the parent node, so a name
+	/// registered again by its previous owner is accepted.
+	///
+	/// @registrar	the owner of the parent node
+	/// @node_hash	the parent node hash
+	/// @label	the normalized label in utf8
+	/// @owner	the owner of the subnode
+	pub fn register_subnode(
+		registrar: &T::AccountId,
+		node_hash: T::Hash,
+		label: Vec<u8>,
+		owner: T::AccountId,
+	) -> rstd::result::Result<T::Hash, &'static str> {
+		let label_hash = T::Hashing::hash(&label);
+		let subnode_hash = Self::subnode_hash(node_hash, label_hash);
+		if Self::node_of(subnode_hash).map_or(false, |record| record.owner == owner) {
+			Self::only_owner(node_hash, registrar)?;
+		} else {
+			Self::do_set_subnode_owner(registrar, node_hash, label_hash, owner.clone(), Some(label))?;
+		}
+
+		if Self::resolve_addr(subnode_hash).as_ref() != Some(&owner) {
+			Self::do_set_resolve_addr(subnode_hash, &owner)?;
+			Self::deposit_event(RawEvent::ResolveAddrChanged(subnode_hash, owner));
+		}
+		Ok(subnode_hash)
+	}
+
+	/// Transfer ownership of a subnode sha3(node, label) and log it
+	///
+	/// @sender	the sender
+	/// @node_hash	the parent node hash
+	/// @label	the label hash
+	/// @owner	the owner of the subnode
+	/// @label_name	the label in utf8, if known
+	fn do_set_subnode_owner(
+		sender: &T::AccountId,
+		node_hash: T::Hash,
+		label: T::Hash,
+		owner: T::AccountId,
+		label_name: Option<Vec<u8>>,
+	) -> Result {
+		Self::only_owner(node_hash, sender)?;
+		ensure!(!<ReservedNames<T>>::exists(label), "Name is reserved");
+
+		let subnode_hash = Self::subnode_hash(node_hash, label);
+		let is_new = match Self::node_of(subnode_hash) {
+			Some(record) => {
+				ensure!(record.fuses & fuses::PARENT_CANNOT_CONTROL == 0, "Parent cannot control");
+				false
+			},
+			None => {
+				Self::ensure_fuse_intact(node_hash, fuses::CANNOT_CREATE_SUBNODE, "Subnode fuse burned")?;
+				true
+			},
+		};
+		// a subnode handed to another account is listed once it claims it
+		Self::do_set_owner(subnode_hash, &owner, Some((node_hash, label)), owner == *sender)?;
+		if is_new {
+			<ChildCount<T>>::mutate(node_hash, |count| *count = count.saturating_add(1));
+			<CountedSubnodes<T>>::insert(subnode_hash, true);
+		}
+		Self::deposit_event(RawEvent::NewOwner(node_hash, label, subnode_hash, owner, label_name));
+		Ok(())
+	}
+
+	/// Remove the resolve record of a node which no client module refers to
+	///
+	/// @node_hash	the node hash
+	fn do_clear_resolve(node_hash: T::Hash) -> Result {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
+		ensure!(Self::resolve_of(node_hash).is_some(), "Resolve record does not exist");
+		ensure!(!T::NodeReferences::is_referenced(&node_hash), "Node is referenced");
+
+		let old = Self::resolve_addr(node_hash);
+		Self::remove_resolve(node_hash);
+		if old.is_some() {
+			T::OnResolveChanged::on_resolve_changed(node_hash, old, None);
+		}
+		Ok(())
+	}
+
+	/// Remove a node without subnodes which no client module refers to, with its resolve
+	/// record and approval. Subnodes of the first layout are only counted once migrated, see
+	/// `migrate_subnode`.
+	///
+	/// @node_hash	the node hash
+	fn do_clear_node(node_hash: T::Hash) -> Result {
+		ensure!(node_hash != T::Hash::default(), "Root may not be cleared");
+		let record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+		ensure!(record.fuses == 0, "Node has burned fuses");
+		ensure!(Self::child_count(node_hash) == 0, "Node has subnodes");
+		ensure!(!T::NodeReferences::is_referenced(&node_hash), "Node is referenced");
+
+		if let Some(parent) = record.parent.filter(|_| <CountedSubnodes<T>>::take(node_hash)) {
+			<ChildCount<T>>::mutate(parent, |count| *count = count.saturating_sub(1));
+		}
+		<Nodes<T>>::remove(node_hash);
+		<NodeOf<T>>::remove(node_hash);
+		<ChildCount<T>>::remove(node_hash);
+		<Approvals<T>>::remove(node_hash);
+		<PendingOwners<T>>::remove(node_hash);
+		Self::remove_owned_node(&record.owner, node_hash);
+		<TextRecords<T>>::remove_prefix(node_hash);
+		Self::set_dns_published(node_hash, false)?;
+		let old = Self::resolve_addr(node_hash);
+		Self::remove_resolve(node_hash);
+		if old.is_some() {
+			T::OnResolveChanged::on_resolve_changed(node_hash, old, None);
+		}
+		Ok(())
+	}
+
+	/// Derive the hash of a subnode, sha3(node, label)
+	///
+	/// @node_hash	the parent node hash
+	/// @label	the label hash
+	pub fn subnode_hash(node_hash: T::Hash, label: T::Hash) -> T::Hash {
+		let mut node = node_hash.as_ref().to_vec();
+		node.extend_from_slice(label.as_ref());
+		<T as system::Trait>::Hashing::hash(node.as_slice())
+	}
+
 	/// Set owner of the node
 	///
 	/// @node_hash 	the node hash to be set
 	/// @owner	the owner account
-	fn do_set_owner(node_hash: T::Hash, owner: &T::AccountId) -> Result {
+	/// @parent	the parent node and the label hash of a subnode
+	/// @listed	whether the owner takes the node itself, listing it among its names
+	fn do_set_owner(node_hash: T::Hash, owner: &T::AccountId, parent: Option<(T::Hash, T::Hash)>, listed: bool) -> Result {
 		let mut record = if let Some(record) = Self::node_of(node_hash) {
 			ensure!(record.owner != *owner, "Owner is the same account");
 			record
 		} else {
-			NodeRecord::<T::AccountId>::default()
+			NodeRecord::<T::Hash, T::AccountId>::default()
 		};
+		if listed {
+			Self::list_owned_node(owner, node_hash)?;
+		}
 
 		record.owner = owner.clone();
-		<NodeOf<T>>::insert(node_hash, record);
+		if let Some((parent, label)) = parent {
+			record.parent = Some(parent);
+			record.label = label;
+		}
+		Self::insert_node(node_hash, record);
 
 		Ok(())
 	}
@@ -302,15 +1355,21 @@ impl<T: Trait> Module<T> {
 	/// @node_hash 	the node hash to be set
 	/// @addr	the resolve addr
 	fn do_set_resolve_addr(node_hash: T::Hash, addr: &T::AccountId) -> Result {
-		let mut record = if let Some(record) = Self::resolve_of(node_hash) {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
+		let (mut record, old) = if let Some(record) = Self::resolve_of(node_hash) {
 			ensure!(record.addr != *addr, "Addr is the same value");
-			record
+			let old = record.addr.clone();
+			(record, Some(old))
 		} else {
-			ResolveRecord::<T::Hash, T::AccountId>::default()
+			(ResolveRecord::<T::AccountId>::default(), None)
 		};
 
 		record.addr = addr.clone();
-		<ResolveOf<T>>::insert(node_hash, record);
+		Self::update_resolve(node_hash, record);
+		// a node resolving for the first time moves no address away
+		if old.is_some() {
+			T::OnResolveChanged::on_resolve_changed(node_hash, old, Some(addr.clone()));
+		}
 
 		Ok(())
 	}
@@ -319,16 +1378,17 @@ impl<T: Trait> Module<T> {
 	///
 	/// @node_hash 	the node hash to be set
 	/// @name	the resolve name
-	fn do_set_resolve_name(node_hash: T::Hash, name: &Vec<u8>) -> Result {
+	fn do_set_resolve_name(node_hash: T::Hash, name: &BoundedName) -> Result {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
 		let mut record = if let Some(record) = Self::resolve_of(node_hash) {
 			ensure!(record.name != *name, "Name is the same value");
 			record
 		} else {
-			ResolveRecord::<T::Hash, T::AccountId>::default()
+			ResolveRecord::<T::AccountId>::default()
 		};
 
 		record.name = name.clone();
-		<ResolveOf<T>>::insert(node_hash, record);
+		Self::update_resolve(node_hash, record);
 
 		Ok(())
 	}
@@ -337,35 +1397,102 @@ impl<T: Trait> Module<T> {
 	///
 	/// @node_hash 	the node hash to be set
 	/// @profile	the resolve profile
-	fn do_set_resolve_profile(node_hash: T::Hash, profile: T::Hash) -> Result {
+	fn do_set_resolve_profile(node_hash: T::Hash, profile: DataHash) -> Result {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
 		let mut record = if let Some(record) = Self::resolve_of(node_hash) {
 			ensure!(record.profile != profile, "Profile is the same value");
 			record
 		} else {
-			ResolveRecord::<T::Hash, T::AccountId>::default()
+			ResolveRecord::<T::AccountId>::default()
 		};
 
 		record.profile = profile;
-		<ResolveOf<T>>::insert(node_hash, record);
+		Self::update_resolve(node_hash, record);
 
 		Ok(())
 	}
 
-	/// Set resolve zone content for the node
+	/// Set zone storage descriptor for the node
 	///
 	/// @node_hash 	the node hash to be set
-	/// @zone	the resolve zone content
-	fn do_set_resolve_zone(node_hash: T::Hash, zone: &Vec<u8>) -> Result {
+	/// @storage	the zone storage descriptor
+	fn do_set_zone_storage(node_hash: T::Hash, storage: &BoundedZone) -> rstd::result::Result<ZoneFile, &'static str> {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
 		let mut record = if let Some(record) = Self::resolve_of(node_hash) {
-			ensure!(record.zone != *zone, "Zone is the same value");
+			ensure!(record.zone.storage != *storage, "Zone storage is the same value");
 			record
 		} else {
-			ResolveRecord::<T::Hash, T::AccountId>::default()
+			ResolveRecord::<T::AccountId>::default()
 		};
 
-		record.zone = zone.clone();
-		<ResolveOf<T>>::insert(node_hash, record);
+		record.zone.storage = storage.clone();
+		let zone = record.zone.clone();
+		Self::update_resolve(node_hash, record);
 
+		Ok(zone)
+	}
+
+	/// Set zone urls for the node
+	///
+	/// @node_hash 	the node hash to be set
+	/// @read_url	the zone read url
+	/// @write_url	the zone write url
+	fn do_set_zone_urls(node_hash: T::Hash, read_url: &BoundedZone, write_url: &BoundedZone) -> rstd::result::Result<ZoneFile, &'static str> {
+		Self::ensure_fuse_intact(node_hash, fuses::CANNOT_SET_RESOLVER, "Resolver fuse burned")?;
+		let mut record = if let Some(record) = Self::resolve_of(node_hash) {
+			ensure!(record.zone.read_url != *read_url || record.zone.write_url != *write_url, "Zone urls are the same value");
+			record
+		} else {
+			ResolveRecord::<T::AccountId>::default()
+		};
+
+		record.zone.read_url = read_url.clone();
+		record.zone.write_url = write_url.clone();
+		let zone = record.zone.clone();
+		Self::update_resolve(node_hash, record);
+
+		Ok(zone)
+	}
+
+	/// Check the storage invariants of the name service. The node maps can not be walked, so
+	/// the nodes are reached from `DnsPublished`; meant for tests and tooling, never for the
+	/// runtime.
+	///
+	/// - `DnsPublished` holds at most `MaxDnsPublished` distinct nodes, each existing with a
+	///   `publish_dns` text record holding a domain name
+	/// - the ancestors of these nodes exist, and count them among their subnodes when the
+	///   parent links were recorded
+	#[cfg(feature = "std")]
+	pub fn do_try_state() -> Result {
+		let published = Self::dns_published();
+		ensure!(published.len() <= T::MaxDnsPublished::get() as usize, "Too many published nodes");
+		for (i, node_hash) in published.iter().enumerate() {
+			ensure!(!published[..i].contains(node_hash), "Duplicate published node");
+			let domain = Self::text_of(node_hash, dns::PUBLISH_DNS_KEY.to_vec()).ok_or("Published node has no domain")?;
+			ensure!(dns::is_dns_name(&domain), "Published node has an invalid domain");
+			Self::check_ancestors(*node_hash)?;
+		}
+		Ok(())
+	}
+
+	/// Check that a node and its ancestors exist, each counted by its parent
+	///
+	/// @node_hash	the node hash
+	#[cfg(feature = "std")]
+	pub fn check_ancestors(node_hash: T::Hash) -> Result {
+		let mut node_hash = node_hash;
+		while node_hash != T::Hash::default() {
+			let record = Self::node_of(node_hash).ok_or("Node does not exist")?;
+			let parent = match record.parent {
+				Some(parent) => parent,
+				// nodes of the first layout
+				None => break,
+			};
+			if Self::is_counted(node_hash) {
+				ensure!(Self::child_count(parent) > 0, "Subnode is not counted by its parent");
+			}
+			node_hash = parent;
+		}
 		Ok(())
 	}
 }
@@ -403,28 +1530,110 @@ impl <T: Trait> NameHasher<T> for Module<T> {
 	}
 }
 
+/// Registry of the profiles a resolve record may point at, e.g. a DID registry
+pub trait ProfileResolver {
+	/// Check if the profile exists and is usable
+	fn is_valid_profile(profile: &DataHash) -> bool;
+}
+
+impl ProfileResolver for () {
+	/// Any profile hash is accepted
+	fn is_valid_profile(_profile: &DataHash) -> bool { true }
+}
+
+/// Client modules which refer to nodes, keeping them from being cleared
+pub trait NodeReferences<Hash> {
+	/// Check if a client module refers to the node
+	fn is_referenced(node_hash: &Hash) -> bool;
+}
+
+impl<Hash> NodeReferences<Hash> for () {
+	/// No node is referenced
+	fn is_referenced(_node_hash: &Hash) -> bool { false }
+}
+
+/// Client modules notified when the address a node resolves to changes
+pub trait OnResolveChanged<T: system::Trait> {
+	/// The node resolved to an address and resolves to a new one, or to none once its
+	/// resolve record is removed
+	///
+	/// @node_hash	the node hash
+	/// @old	the address resolved before
+	/// @new	the address resolved now
+	fn on_resolve_changed(node_hash: T::Hash, old: Option<T::AccountId>, new: Option<T::AccountId>);
+
+	/// The maximum weight of a notification, added to the calls which may send one
+	fn max_weight() -> Weight;
+}
+
+impl<T: system::Trait> OnResolveChanged<T> for () {
+	fn on_resolve_changed(_node_hash: T::Hash, _old: Option<T::AccountId>, _new: Option<T::AccountId>) {}
+
+	fn max_weight() -> Weight { 0 }
+}
+
+/// Check if a resolution is stale: a record is good for `ttl` blocks after it last changed,
+/// a client module caching it should resolve it again then. A TTL of zero never expires, nor
+/// do the records which changed before the blocks were recorded.
+///
+/// @record	the resolve record
+/// @ttl	the TTL of the node in blocks
+/// @now	the current block
+pub fn is_stale<AccountId>(record: &ResolveRecord<AccountId>, ttl: u64, now: u64) -> bool {
+	ttl > 0 && record.last_updated > 0 && now > record.last_updated.saturating_add(ttl)
+}
+
 /// Client module should use this trait to communicate with the name service module
 pub trait NameServiceResolver<T: system::Trait> {
 	/// Resolve to record
-	fn resolve(_node_hash: T::Hash) -> Option<ResolveRecord<T::Hash, T::AccountId>> { None }
+	fn resolve(_node_hash: T::Hash) -> Option<ResolveRecord<T::AccountId>> { None }
+	/// Resolve to record with the TTL of the node in blocks, see [`is_stale`](./fn.is_stale.html)
+	fn resolve_with_ttl(_node_hash: T::Hash) -> Option<(ResolveRecord<T::AccountId>, u64)> { None }
 	/// Resolve to addr
 	fn resolve_addr(_node_hash: T::Hash) -> Option<T::AccountId> { None }
 	/// Resolve to name
 	fn resolve_name(_node_hash: T::Hash) -> Option<Vec<u8>> { None }
 	/// Resolve to profile hash
-	fn resolve_profile(_node_hash: T::Hash) -> Option<T::Hash> { None }
-	/// Resolve to zone content
-	fn resolve_zone(_node_hash: T::Hash) -> Option<Vec<u8>> { None }
+	fn resolve_profile(_node_hash: T::Hash) -> Option<DataHash> { None }
+	/// Resolve to zone file
+	fn resolve_zone(_node_hash: T::Hash) -> Option<ZoneFile> { None }
+	/// Resolve to zone storage descriptor
+	fn resolve_zone_storage(node_hash: T::Hash) -> Option<Vec<u8>> {
+		Self::resolve_zone(node_hash).map(|zone| zone.storage.into_inner())
+	}
+	/// Resolve to zone read and write urls
+	fn resolve_zone_urls(node_hash: T::Hash) -> Option<(Vec<u8>, Vec<u8>)> {
+		Self::resolve_zone(node_hash).map(|zone| (zone.read_url.into_inner(), zone.write_url.into_inner()))
+	}
+	/// Check if a name is reserved
+	fn is_reserved(_name: &[u8]) -> bool { false }
+	/// Normalize a name with the policy of the name service
+	fn normalize_name(name: &[u8]) -> rstd::result::Result<Vec<u8>, &'static str> {
+		Ok(name.to_vec())
+	}
+	/// Check if the account is the resolved address of the node or owns one of its ancestors
+	fn is_authorized(node_hash: T::Hash, account: &T::AccountId, _max_depth: u32) -> bool {
+		Self::resolve_addr(node_hash).as_ref() == Some(account)
+	}
 }
 
 impl <T: Trait> NameServiceResolver<T> for Module<T> {
 	/// Resolve name hash to record
 	/// 
 	/// @node_hash	the node hash
-	fn resolve(node_hash: T::Hash) -> Option<ResolveRecord<T::Hash, T::AccountId>> {
+	fn resolve(node_hash: T::Hash) -> Option<ResolveRecord<T::AccountId>> {
 		Self::resolve_of(node_hash)
 	}
 
+	/// Resolve name hash to record with the TTL of the node
+	///
+	/// @node_hash	the node hash
+	fn resolve_with_ttl(node_hash: T::Hash) -> Option<(ResolveRecord<T::AccountId>, u64)> {
+		let record = Self::resolve_of(node_hash)?;
+		let ttl = Self::node_of(node_hash).map_or(0, |node| node.ttl);
+		Some((record, ttl))
+	}
+
 	/// Resolve name hash to addr
 	/// 
 	/// @node_hash	the node hash
@@ -440,7 +1649,7 @@ impl <T: Trait> NameServiceResolver<T> for Module<T> {
 	/// @node_hash	the node hash
 	fn resolve_name(node_hash: T::Hash) -> Option<Vec<u8>> {
 		match Self::resolve_of(node_hash) {
-			Some(record) => Some(record.name),
+			Some(record) => Some(record.name.into_inner()),
 			None => None,
 		}
 	}
@@ -448,21 +1657,44 @@ impl <T: Trait> NameServiceResolver<T> for Module<T> {
 	/// Resolve name hash to profile
 	/// 
 	/// @node_hash	the node hash
-	fn resolve_profile(node_hash: T::Hash) -> Option<T::Hash> {
+	fn resolve_profile(node_hash: T::Hash) -> Option<DataHash> {
 		match Self::resolve_of(node_hash) {
 			Some(record) => Some(record.profile),
 			None => None,
 		}
 	}
 
-	/// Resolve name hash to zone content
+	/// Resolve name hash to zone file
 	/// 
 	/// @node_hash	the node hash
-	fn resolve_zone(node_hash: T::Hash) -> Option<Vec<u8>> {
+	fn resolve_zone(node_hash: T::Hash) -> Option<ZoneFile> {
 		match Self::resolve_of(node_hash) {
 			Some(record) => Some(record.zone),
 			None => None,
 		}
 	}	
+
+	/// Check if a name is reserved
+	///
+	/// @name	the name in utf8
+	fn is_reserved(name: &[u8]) -> bool {
+		Self::is_reserved_name(name)
+	}
+
+	/// Normalize a name with the policy of the name service
+	///
+	/// @name	the name in utf8
+	fn normalize_name(name: &[u8]) -> rstd::result::Result<Vec<u8>, &'static str> {
+		T::NameNormalizer::normalize(name)
+	}
+
+	/// Check if the account is the resolved address of the node or owns one of its ancestors
+	///
+	/// @node_hash	the node hash
+	/// @account	the account to be authorized
+	/// @max_depth	the number of ancestors to check
+	fn is_authorized(node_hash: T::Hash, account: &T::AccountId, max_depth: u32) -> bool {
+		Self::is_authorized_by_hierarchy(node_hash, account, max_depth)
+	}
 }
 
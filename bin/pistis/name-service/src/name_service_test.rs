@@ -4,13 +4,14 @@ mod tests {
     use crate::*;
 	use super::*;
 
-	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, storage::unhashed, weights::{GetDispatchInfo, Weight}};
 	use primitives::H256;
+	use std::cell::RefCell;
 	use system::EnsureSignedBy;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
 	use sp_runtime::{
-		Perbill, testing::Header, traits::{BlakeTwo256, IdentityLookup},
+		Perbill, testing::{Header, UintAuthorityId}, traits::{BlakeTwo256, IdentityLookup, OnInitialize},
 	};
 
 	impl_outer_origin! {
@@ -66,6 +67,9 @@ mod tests {
 		pub const MaxNameLength: usize = 16;
 		pub const MaxZoneLength: usize = 1024;
 		pub const One: u64 = 1;
+		pub const AllowMixedScripts: bool = false;
+		pub const MaxDnsPublished: u32 = 2;
+		pub const MaxNamesPerAccount: u32 = 8;
 	}
 	impl Trait for Test {
 		type Event = ();
@@ -73,6 +77,38 @@ mod tests {
 		type MinNameLength = MinNameLength;
 		type MaxNameLength = MaxNameLength;
 		type MaxZoneLength = MaxZoneLength;
+		type ProfileResolver = ();
+		type WeightInfo = ();
+		type NameNormalizer = StandardNormalizer<AllowMixedScripts>;
+		type NodeReferences = Self;
+		type OnResolveChanged = Self;
+		type DnsPublisherId = UintAuthorityId;
+		type MaxDnsPublished = MaxDnsPublished;
+		type MaxNamesPerAccount = MaxNamesPerAccount;
+	}
+
+	thread_local! {
+		static RESOLVE_CHANGES: RefCell<Vec<(H256, Option<u64>, Option<u64>)>> = RefCell::new(Vec::new());
+	}
+
+	/// The notifications are recorded in `RESOLVE_CHANGES`
+	impl OnResolveChanged<Test> for Test {
+		fn on_resolve_changed(node_hash: H256, old: Option<u64>, new: Option<u64>) {
+			RESOLVE_CHANGES.with(|v| v.borrow_mut().push((node_hash, old, new)));
+		}
+
+		fn max_weight() -> Weight { 100 }
+	}
+
+	fn take_resolve_changes() -> Vec<(H256, Option<u64>, Option<u64>)> {
+		RESOLVE_CHANGES.with(|v| v.borrow_mut().drain(..).collect())
+	}
+
+	/// The top level node `busy` is referenced by a client module
+	impl NodeReferences<H256> for Test {
+		fn is_referenced(node_hash: &H256) -> bool {
+			*node_hash == H256::from_slice(&<NameService as NameHasher<Test>>::namehash("busy"))
+		}
 	}
 
 	type System = system::Module<Test>;
@@ -106,17 +142,110 @@ mod tests {
 	}
 
 	#[test]
-	fn set_owner_should_work() {
+	fn genesis_config_should_work() {
+		let scope = (b"pistis").using_encoded(<Test as system::Trait>::Hashing::hash);
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		GenesisConfig::<Test> {
+			root_owner: Some(1),
+			nodes: vec![(scope, 1, 2)],
+		}.assimilate_storage(&mut t).unwrap();
+
+		runtime_io::TestExternalities::from(t).execute_with(|| {
+			assert_eq!(NameService::node_of(<Test as system::Trait>::Hash::default()).unwrap().owner, 1);
+			assert_eq!(NameService::node_of(scope).unwrap().owner, 1);
+			assert_eq!(NameService::resolve_addr(scope), Some(2));
+			assert_eq!(NameService::storage_version(), STORAGE_VERSION);
+		});
+	}
+
+	#[test]
+	fn ownership_transfer_should_work() {
 		new_test_ext().execute_with(|| {
 			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
-			assert_noop!(NameService::set_owner(Origin::signed(1), [1;32].into(), 4), "Node does not exist");
+			assert_noop!(NameService::propose_owner(Origin::signed(1), [1;32].into(), 4), "Node does not exist");
 			let root_hash = <Test as system::Trait>::Hash::default(); 
-			assert_noop!(NameService::set_owner(Origin::signed(1), root_hash, 4), "Sender is not owner");
-			assert_ok!(NameService::set_owner(Origin::signed(3), root_hash, 4));
+			assert_noop!(NameService::propose_owner(Origin::signed(1), root_hash, 4), "Sender is not owner");
+			assert_noop!(NameService::propose_owner(Origin::signed(3), root_hash, 3), "Owner is the same account");
+
+			// The node stays with its owner until the proposed owner accepts
+			assert_ok!(NameService::propose_owner(Origin::signed(3), root_hash, 5));
+			assert_noop!(NameService::propose_owner(Origin::signed(3), root_hash, 5), "Owner is already proposed");
+			assert_ok!(NameService::propose_owner(Origin::signed(3), root_hash, 4));
+			assert_eq!(NameService::node_of(root_hash).unwrap().owner, 3);
+			assert_noop!(NameService::accept_ownership(Origin::signed(5), root_hash), "Not the proposed owner");
+			assert_ok!(NameService::accept_ownership(Origin::signed(4), root_hash));
 			assert_eq!(NameService::node_of(root_hash).unwrap().owner, 4);
+			assert_eq!(NameService::pending_owner(root_hash), None);
+
+			// The owner withdraws an offer, which a transfer by other means drops too
+			assert_ok!(NameService::propose_owner(Origin::signed(4), root_hash, 5));
+			assert_noop!(NameService::cancel_ownership_transfer(Origin::signed(5), root_hash), "Sender is not owner");
+			assert_ok!(NameService::cancel_ownership_transfer(Origin::signed(4), root_hash));
+			assert_noop!(NameService::cancel_ownership_transfer(Origin::signed(4), root_hash), "No pending transfer");
+			assert_noop!(NameService::accept_ownership(Origin::signed(5), root_hash), "Not the proposed owner");
+			assert_ok!(NameService::propose_owner(Origin::signed(4), root_hash, 5));
+			assert_noop!(NameService::force_set_owner(Origin::signed(4), root_hash, 6), "Bad origin");
+			assert_ok!(NameService::force_set_owner(Origin::signed(1), root_hash, 6));
+			assert_eq!(NameService::node_of(root_hash).unwrap().owner, 6);
+			assert_noop!(NameService::accept_ownership(Origin::signed(5), root_hash), "Not the proposed owner");
 		});	
 	}
 
+	#[test]
+	fn owned_nodes_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			assert_eq!(NameService::owned_nodes(3), vec![root_hash]);
+
+			// A subnode handed to another account is listed once it claims it
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "acme".into(), 4));
+			let acme = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("acme"));
+			assert_eq!(NameService::owned_nodes(4), Vec::<H256>::new());
+			assert_noop!(NameService::claim_node(Origin::signed(5), acme), "Sender is not owner");
+			assert_ok!(NameService::claim_node(Origin::signed(4), acme));
+			assert_noop!(NameService::claim_node(Origin::signed(4), acme), "Node is already listed");
+
+			// The nodes follow their owner through every change of hands
+			assert_eq!(NameService::owned_nodes(4), vec![acme]);
+			assert_ok!(NameService::propose_owner(Origin::signed(4), acme, 5));
+			assert_eq!(NameService::owned_nodes(4), vec![acme]);
+			assert_ok!(NameService::accept_ownership(Origin::signed(5), acme));
+			assert_eq!(NameService::owned_nodes(4), Vec::<H256>::new());
+			assert_eq!(NameService::owned_nodes(5), vec![acme]);
+			assert_ok!(NameService::force_set_owner(Origin::signed(1), acme, 6));
+			assert_eq!(NameService::owned_nodes(5), Vec::<H256>::new());
+			assert_eq!(NameService::owned_nodes(6), vec![acme]);
+			assert_ok!(NameService::clear_node(Origin::signed(6), acme));
+			assert_eq!(NameService::owned_nodes(6), Vec::<H256>::new());
+
+			// The owner of a parent hands nodes beyond the quota of an account without using it up
+			for i in 0..MaxNamesPerAccount::get() {
+				assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, H256::repeat_byte(i as u8), 7));
+			}
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, H256::repeat_byte(0xff), 7));
+			assert_eq!(NameService::owned_nodes(7), Vec::<H256>::new());
+
+			// An account lists at most MaxNamesPerAccount nodes, claimed or taken itself
+			let first = NameService::subnode_hash(root_hash, H256::repeat_byte(0));
+			for i in 0..MaxNamesPerAccount::get() {
+				assert_ok!(NameService::claim_node(Origin::signed(7), NameService::subnode_hash(root_hash, H256::repeat_byte(i as u8))));
+			}
+			assert_eq!(NameService::owned_nodes(7).len(), MaxNamesPerAccount::get() as usize);
+			let last = NameService::subnode_hash(root_hash, H256::repeat_byte(0xff));
+			assert_noop!(NameService::claim_node(Origin::signed(7), last), "Too many names");
+			assert_noop!(NameService::set_subnode_owner(Origin::signed(7), first, H256::repeat_byte(1), 7), "Too many names");
+
+			// The nodes stored before the index are listed by anyone
+			let legacy = H256::repeat_byte(0xee);
+			<NodeOf<Test>>::insert(legacy, LegacyNodeRecord { owner: 8, ttl: 0 });
+			assert_ok!(NameService::migrate_owned_node(Origin::signed(9), legacy));
+			assert_eq!(NameService::owned_nodes(8), vec![legacy]);
+			assert!(NameService::node_record_of(legacy).is_some());
+			assert_noop!(NameService::migrate_owned_node(Origin::signed(9), legacy), "Node is not a legacy node");
+		});
+	}
+
 	#[test]
 	fn set_subnode_owner_should_work() {
 		new_test_ext().execute_with(|| {
@@ -134,12 +263,243 @@ mod tests {
 			let label = ("hsiung").using_encoded(<Test as system::Trait>::Hashing::hash);
 			// set hsiung.eth to account 5
 			assert_ok!(NameService::set_subnode_owner(Origin::signed(4), node_hash, label, 5));
+			let eth = node_hash;
 			let node_hash = (node_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
 			assert_eq!(NameService::node_of(node_hash).unwrap().owner, 5);
 			println!("node_hash={}", node_hash);	
+
+			assert_eq!(NameService::node_of(node_hash).unwrap().parent, Some(eth));
+			assert_eq!(NameService::node_of(node_hash).unwrap().label, label);
+			assert_eq!(NameService::ancestors(node_hash), vec![eth, root_hash]);
+			assert_eq!(NameService::label_path(node_hash), vec![
+				("eth").using_encoded(<Test as system::Trait>::Hashing::hash),
+				label,
+			]);
 		});	
 	}
 
+	#[test]
+	fn set_subnode_label_owner_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			assert_noop!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "ops.acme".into(), 4), "Label contains a dot");
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "Acme".into(), 4));
+			let acme = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("acme"));
+			assert_eq!(NameService::subnode_hash(root_hash, <Test as system::Trait>::Hashing::hash(b"acme")), acme);
+			assert_eq!(NameService::node_of(acme).unwrap().owner, 4);
+
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(4), acme, "ops".into(), 5));
+			let ops = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("ops.acme"));
+			assert_eq!(NameService::node_of(ops).unwrap().owner, 5);
+			assert_eq!(NameService::parent_of(ops), Some(acme));
+		});
+	}
+
+	#[test]
+	fn clear_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "acme".into(), 4));
+			let acme = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("acme"));
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(4), acme, "ops".into(), 5));
+			let ops = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("ops.acme"));
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(5), ops, 6));
+			assert_eq!(NameService::child_count(acme), 1);
+
+			assert_noop!(NameService::clear_resolve(Origin::signed(4), ops), "Sender is not owner");
+			assert_ok!(NameService::clear_resolve(Origin::signed(5), ops));
+			assert!(NameService::resolve_record_of(ops).is_none());
+			assert_noop!(NameService::clear_resolve(Origin::signed(5), ops), "Resolve record does not exist");
+
+			assert_noop!(NameService::clear_node(Origin::signed(4), acme), "Node has subnodes");
+			assert_ok!(NameService::clear_node(Origin::signed(5), ops));
+			assert!(NameService::node_of(ops).is_none());
+			assert_eq!(NameService::child_count(acme), 0);
+			assert_ok!(NameService::clear_node(Origin::signed(4), acme));
+			assert_eq!(NameService::child_count(root_hash), 0);
+
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "busy".into(), 4));
+			let busy = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("busy"));
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), busy, 4));
+			assert_noop!(NameService::clear_resolve(Origin::signed(4), busy), "Node is referenced");
+			assert_noop!(NameService::clear_node(Origin::signed(4), busy), "Node is referenced");
+			assert_noop!(NameService::force_clear_node(Origin::signed(4), busy), "Bad origin");
+			assert_noop!(NameService::force_clear_node(Origin::signed(1), root_hash), "Root may not be cleared");
+			assert_noop!(NameService::force_clear_node(Origin::signed(1), busy), "Node is referenced");
+		});
+	}
+
+	#[test]
+	fn migrate_subnode_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "acme".into(), 4));
+			let acme = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("acme"));
+			assert!(NameService::is_counted(acme));
+			assert_noop!(NameService::migrate_subnode(Origin::signed(9), acme), "Subnode already counted");
+			assert_noop!(NameService::migrate_subnode(Origin::signed(9), root_hash), "Node has no parent");
+
+			// A subnode of the first layout keeps its parent from being cleared once linked and migrated
+			let legacy = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("old.acme"));
+			<NodeOf<Test>>::insert(legacy, LegacyNodeRecord { owner: 5, ttl: 0 });
+			assert_noop!(NameService::migrate_subnode(Origin::signed(9), legacy), "Node has no parent");
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(4), acme, "old".into(), 6));
+			assert_eq!(NameService::parent_of(legacy), Some(acme));
+			assert_eq!(NameService::child_count(acme), 0);
+			assert_ok!(NameService::migrate_subnode(Origin::signed(9), legacy));
+			assert_eq!(NameService::child_count(acme), 1);
+			assert_noop!(NameService::migrate_subnode(Origin::signed(9), legacy), "Subnode already counted");
+			assert_noop!(NameService::clear_node(Origin::signed(4), acme), "Node has subnodes");
+
+			assert_ok!(NameService::clear_node(Origin::signed(6), legacy));
+			assert!(!NameService::is_counted(legacy));
+			assert_eq!(NameService::child_count(acme), 0);
+			assert_ok!(NameService::clear_node(Origin::signed(4), acme));
+		});
+	}
+
+	#[test]
+	fn fuses_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "acme".into(), 4));
+			let acme = H256::from_slice(&<NameService as NameHasher<Test>>::namehash("acme"));
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), acme, 4));
+
+			// The owner of a subnode burns its fuses once the parent gave up control
+			assert_noop!(NameService::burn_fuses(Origin::signed(4), acme, 1 << 10), "Unknown fuses");
+			assert_noop!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_SET_RESOLVER), "Parent controls the node");
+			assert_noop!(NameService::burn_fuses(Origin::signed(4), acme, fuses::PARENT_CANNOT_CONTROL), "Sender is not parent owner");
+			assert_noop!(NameService::burn_fuses(Origin::signed(3), root_hash, fuses::PARENT_CANNOT_CONTROL), "Node has no parent");
+			assert_ok!(NameService::burn_fuses(Origin::signed(3), acme, fuses::PARENT_CANNOT_CONTROL));
+			assert_noop!(NameService::burn_fuses(Origin::signed(3), acme, fuses::CANNOT_SET_RESOLVER), "Sender is not owner");
+			assert_ok!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_SET_RESOLVER | fuses::CANNOT_SET_TEXT));
+			assert_noop!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_SET_TEXT), "Fuses already burned");
+			assert_eq!(NameService::node_of(acme).unwrap().fuses, fuses::PARENT_CANNOT_CONTROL | fuses::CANNOT_SET_RESOLVER | fuses::CANNOT_SET_TEXT);
+
+			// The burned permissions are gone, for the force origin as well
+			assert_noop!(NameService::set_resolve_addr(Origin::signed(4), acme, 5), "Resolver fuse burned");
+			assert_noop!(NameService::clear_resolve(Origin::signed(4), acme), "Resolver fuse burned");
+			assert_noop!(NameService::force_clear_resolve(Origin::signed(1), acme), "Resolver fuse burned");
+			assert_noop!(NameService::set_text(Origin::signed(4), acme, "url".into(), "x".into()), "Text fuse burned");
+			assert_noop!(NameService::force_clear_node(Origin::signed(1), acme), "Node has burned fuses");
+			assert_noop!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "acme".into(), 5), "Parent cannot control");
+			assert_ok!(NameService::set_ttl(Origin::signed(4), acme, 20));
+
+			// Transfers keep the fuses, until the transfer fuse is burned
+			assert_ok!(NameService::propose_owner(Origin::signed(4), acme, 5));
+			assert_ok!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_TRANSFER | fuses::CANNOT_CREATE_SUBNODE));
+			assert_noop!(NameService::accept_ownership(Origin::signed(5), acme), "Transfer fuse burned");
+			assert_noop!(NameService::force_set_owner(Origin::signed(1), acme, 5), "Transfer fuse burned");
+			assert_noop!(NameService::set_subnode_label_owner(Origin::signed(4), acme, "ops".into(), 5), "Subnode fuse burned");
+
+			// Frozen fuses stay as they are
+			assert_ok!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_BURN_FUSES));
+			assert_noop!(NameService::burn_fuses(Origin::signed(4), acme, fuses::CANNOT_SET_TTL), "Fuses are frozen");
+
+			// The nodes of the first layout have none burned
+			let node_hash = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			<NodeOf<Test>>::insert(node_hash, LegacyNodeRecord { owner: 4, ttl: 10 });
+			assert_eq!(NameService::node_of(node_hash), Some(NodeRecord { owner: 4, ttl: 10, parent: None, label: H256::default(), fuses: 0 }));
+		});
+	}
+
+	#[test]
+	fn approvals_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			// account 5 manages eth on behalf of account 4
+			assert_noop!(NameService::set_ttl(Origin::signed(5), node_hash, 10), "Sender is not owner");
+			assert_noop!(NameService::approve(Origin::signed(5), node_hash, Some(5)), "Sender is not owner");
+			assert_noop!(NameService::approve(Origin::signed(4), node_hash, Some(4)), "Approval to the owner");
+			assert_ok!(NameService::approve(Origin::signed(4), node_hash, Some(5)));
+			assert_eq!(NameService::approved(node_hash), Some(5));
+			assert_ok!(NameService::set_ttl(Origin::signed(5), node_hash, 10));
+			assert_eq!(NameService::node_of(node_hash).unwrap().owner, 4);
+
+			// account 6 operates all the nodes of account 4 and may approve
+			assert_noop!(NameService::set_resolve_addr(Origin::signed(6), node_hash, 6), "Sender is not owner");
+			assert_ok!(NameService::set_approval_for_all(Origin::signed(4), 6, true));
+			assert_noop!(NameService::set_approval_for_all(Origin::signed(4), 6, true), "Approval is the same value");
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(6), node_hash, 6));
+			assert_ok!(NameService::approve(Origin::signed(6), node_hash, None));
+			assert_noop!(NameService::set_ttl(Origin::signed(5), node_hash, 20), "Sender is not owner");
+
+			// the approval is cleared by a transfer
+			assert_ok!(NameService::approve(Origin::signed(4), node_hash, Some(5)));
+			assert_ok!(NameService::propose_owner(Origin::signed(4), node_hash, 7));
+			assert_ok!(NameService::accept_ownership(Origin::signed(7), node_hash));
+			assert_eq!(NameService::approved(node_hash), None);
+			assert_noop!(NameService::set_ttl(Origin::signed(5), node_hash, 20), "Sender is not owner");
+			assert_noop!(NameService::set_ttl(Origin::signed(6), node_hash, 20), "Sender is not owner");
+
+			assert_ok!(NameService::set_approval_for_all(Origin::signed(4), 6, false));
+			assert!(!NameService::is_approved_for_all((4, 6)));
+		});
+	}
+
+	#[test]
+	fn is_authorized_should_follow_hierarchy() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let label = ("acme").using_encoded(<Test as system::Trait>::Hashing::hash);
+			// set acme to account 4
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let acme = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+			let label = ("ops").using_encoded(<Test as system::Trait>::Hashing::hash);
+			// set ops.acme to account 5, resolving to account 6
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(4), acme, label, 5));
+			let ops = (acme, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(5), ops, 6));
+			assert_eq!(NameService::parent_of(ops), Some(acme));
+			assert_eq!(NameService::parent_of(acme), Some(root_hash));
+
+			assert!(NameService::is_authorized(ops, &6, 0));
+			assert!(!NameService::is_authorized(ops, &4, 0));
+			assert!(NameService::is_authorized(ops, &4, 1));
+			// neither the owner of the node itself nor the root owner is authorized
+			assert!(!NameService::is_authorized(ops, &5, 1));
+			assert!(!NameService::is_authorized(ops, &3, 8));
+		});
+	}
+
+	#[test]
+	fn reserve_name_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default(); 
+			let label = <Test as system::Trait>::Hashing::hash(b"nike");
+
+			assert_noop!(NameService::reserve_name(Origin::signed(3), "nike".into()), "Bad origin");
+			assert_ok!(NameService::reserve_name(Origin::signed(1), "nike".into()));
+			assert_noop!(NameService::reserve_name(Origin::signed(1), "nike".into()), "Name already reserved");
+
+			assert_noop!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4), "Name is reserved");
+			let eth = <Test as system::Trait>::Hashing::hash(b"eth");
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, eth, 4));
+			let node_hash = (root_hash, eth).using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "nike".into()), "Name is reserved");
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "nike.eth".into()), "Name is reserved");
+			assert!(<NameService as NameServiceResolver<Test>>::is_reserved(b"nike"));
+
+			assert_noop!(NameService::unreserve_name(Origin::signed(3), "nike".into()), "Bad origin");
+			assert_ok!(NameService::unreserve_name(Origin::signed(1), "nike".into()));
+			assert_noop!(NameService::unreserve_name(Origin::signed(1), "nike".into()), "Name is not reserved");
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			assert_ok!(NameService::set_resolve_name(Origin::signed(4), node_hash, "nike.eth".into()));
+		});
+	}
+
 	#[test]
 	fn set_ttl_should_work() {
 		new_test_ext().execute_with(|| {
@@ -166,6 +526,63 @@ mod tests {
 			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), node_hash, addr));
 			assert_noop!(NameService::set_resolve_addr(Origin::signed(4), node_hash, addr), "Addr is the same value");
 			assert_eq!(NameService::resolve_of(node_hash).unwrap().addr, addr);
+
+			// Client modules are notified when an address moves away, not when a node resolves first
+			assert!(take_resolve_changes().is_empty());
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), node_hash, 1005));
+			assert_eq!(take_resolve_changes(), vec![(node_hash, Some(addr), Some(1005))]);
+			assert_ok!(NameService::clear_resolve(Origin::signed(4), node_hash));
+			assert_eq!(take_resolve_changes(), vec![(node_hash, Some(1005), None)]);
+
+			let weight = Call::<Test>::set_resolve_addr(node_hash, addr).get_dispatch_info().weight;
+			assert_eq!(weight, <() as WeightInfo>::set_resolve_addr() + 100);
+		});
+	}
+
+	#[test]
+	fn resolve_with_ttl_should_work() {
+		new_test_ext().execute_with(||{
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_eq!(NameService::resolve_with_ttl(node_hash), None);
+
+			// A record of the first layout never goes stale
+			<ResolveOf<Test>>::insert(node_hash, LegacyResolveRecord { addr: 1004, name: b"eth".to_vec(), ..Default::default() });
+			let (record, ttl) = NameService::resolve_with_ttl(node_hash).unwrap();
+			assert_eq!((record.addr, record.last_updated, ttl), (1004, 0, 0));
+			assert!(!is_stale(&record, 10, 100));
+
+			// The record is good for the TTL of the node after it changed
+			System::set_block_number(5);
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), node_hash, 1005));
+			assert_ok!(NameService::set_ttl(Origin::signed(4), node_hash, 10));
+			let (record, ttl) = NameService::resolve_with_ttl(node_hash).unwrap();
+			assert_eq!((record.addr, record.name.to_vec(), record.last_updated, ttl), (1005, b"eth".to_vec(), 5, 10));
+			assert!(!is_stale(&record, ttl, 15));
+			assert!(is_stale(&record, ttl, 16));
+			assert!(!is_stale(&record, 0, 16));
+		});
+	}
+
+	#[test]
+	fn extension_should_resolve() {
+		new_test_ext().execute_with(||{
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let root_hash = <Test as system::Trait>::Hash::default(); 
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), node_hash, 1004));
+
+			let call = extension::call::<Test, NameService>;
+			assert_eq!(call(extension::RESOLVE_ADDR, &node_hash.encode()), Some(Some(1004u64).encode()));
+			assert_eq!(call(extension::RESOLVE_ADDR, &label.encode()), Some(None::<u64>.encode()));
+			assert_eq!(call(extension::RESOLVE_NAME, &node_hash.encode()), Some(Some(Vec::<u8>::new()).encode()));
+			assert_eq!(call(extension::RESOLVE_ADDR, &[1, 2, 3]), None);
+			assert_eq!(call(0, &node_hash.encode()), None);
 		});
 	}
 
@@ -187,6 +604,42 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn name_should_be_normalized() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 4));
+			let node_hash = <Test as system::Trait>::Hash::default();
+
+			assert_ok!(NameService::set_resolve_name(Origin::signed(4), node_hash, "ETH.Pistis".into()));
+			assert_eq!(NameService::resolve_name(node_hash), Some(b"eth.pistis".to_vec()));
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "eth.pistis".into()), "Name is the same value");
+
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, vec![0xff, 0xfe, 0x65]), "Name is not valid utf8");
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "et\u{0}h".into()), "Name contains control characters");
+			// a Cyrillic `а` in a Latin label
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "p\u{430}ypal".into()), "Name mixes scripts");
+			// labels in different scripts may be joined
+			assert_ok!(NameService::set_resolve_name(Origin::signed(4), node_hash, "\u{43c}\u{438}\u{440}.eth".into()));
+
+			assert_ok!(NameService::reserve_name(Origin::signed(1), "Nike".into()));
+			assert!(NameService::is_reserved(b"nike"));
+			assert_noop!(NameService::set_resolve_name(Origin::signed(4), node_hash, "NIKE".into()), "Name is reserved");
+		});
+	}
+
+	#[test]
+	fn weight_should_follow_input_size() {
+		let node_hash = <Test as system::Trait>::Hash::default();
+		let short = Call::<Test>::set_resolve_name(node_hash, "eth".into()).get_dispatch_info();
+		let long = Call::<Test>::set_resolve_name(node_hash, "e".repeat(16).into()).get_dispatch_info();
+		assert_eq!(short.weight, <() as WeightInfo>::set_resolve_name(3));
+		assert!(long.weight > short.weight);
+		assert_eq!(long.class, DispatchClass::Normal);
+
+		let zone = Call::<Test>::set_zone_urls(node_hash, vec![1; 10], vec![2; 20]).get_dispatch_info();
+		assert_eq!(zone.weight, <() as WeightInfo>::set_zone_urls(30));
+	}
+
 
 	#[test]
 	fn set_resolve_profile_should_work() {
@@ -197,15 +650,21 @@ mod tests {
 			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
 			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
 
-			let profile = ("did:pistis:v0:1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg-1").using_encoded(<Test as system::Trait>::Hashing::hash);
-			assert_ok!(NameService::set_resolve_profile(Origin::signed(4), node_hash, profile));
-			assert_noop!(NameService::set_resolve_profile(Origin::signed(4), node_hash, profile), "Profile is the same value");
+			let did_hash = ("did:pistis:v0:1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg-1").using_encoded(<Test as system::Trait>::Hashing::hash);
+			let profile = DataHash::from_runtime_hash(&did_hash);
+			assert_noop!(
+				NameService::set_resolve_profile(Origin::signed(4), node_hash, DataHash { algo: HashAlgo::Sha2_512, ..profile.clone() }),
+				"Digest length does not match the hash algorithm"
+			);
+			assert_ok!(NameService::set_resolve_profile(Origin::signed(4), node_hash, profile.clone()));
+			assert_noop!(NameService::set_resolve_profile(Origin::signed(4), node_hash, profile.clone()), "Profile is the same value");
 			assert_eq!(NameService::resolve_of(node_hash).unwrap().profile, profile);
+			assert_eq!(NameService::resolve_profile(node_hash).unwrap().as_runtime_hash(), Some(did_hash));
 		});
 	}
 
 	#[test]
-	fn set_resolve_zone_should_work() {
+	fn set_zone_storage_should_work() {
 		new_test_ext().execute_with(||{
 			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
 			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash); 
@@ -213,12 +672,182 @@ mod tests {
 			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
 			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
 
-			let zone = r#"{"compacity":50000000,"class":"normal","storage":"http://example.com/1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg"}"#;
-			assert_noop!(NameService::set_resolve_zone(Origin::signed(4), node_hash, "z".repeat(1025).into()), "Zone content too long");
+			let storage = r#"{"compacity":50000000,"class":"normal"}"#;
+			assert_noop!(NameService::set_zone_storage(Origin::signed(4), node_hash, "z".repeat(1025).into()), "Zone storage too long");
 			
-			assert_ok!(NameService::set_resolve_zone(Origin::signed(4), node_hash, zone.into()));
-			assert_noop!(NameService::set_resolve_zone(Origin::signed(4), node_hash, zone.into()), "Zone is the same value");
-			assert_eq!(NameService::resolve_of(node_hash).unwrap().zone, zone.as_bytes());
+			assert_ok!(NameService::set_zone_storage(Origin::signed(4), node_hash, storage.into()));
+			assert_noop!(NameService::set_zone_storage(Origin::signed(4), node_hash, storage.into()), "Zone storage is the same value");
+			assert_eq!(NameService::resolve_of(node_hash).unwrap().zone.storage[..], *storage.as_bytes());
+			assert_eq!(NameService::resolve_zone_storage(node_hash), Some(storage.as_bytes().to_vec()));
+		});
+	}
+
+	#[test]
+	fn set_zone_urls_should_work() {
+		new_test_ext().execute_with(||{
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash); 
+			let root_hash = <Test as system::Trait>::Hash::default(); 
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			let read_url = "http://example.com/1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg";
+			let write_url = "http://example.com/write";
+			assert_noop!(NameService::set_zone_urls(Origin::signed(4), node_hash, "z".repeat(1025).into(), write_url.into()), "Zone read url too long");
+			assert_noop!(NameService::set_zone_urls(Origin::signed(4), node_hash, read_url.into(), "z".repeat(1025).into()), "Zone write url too long");
+
+			assert_ok!(NameService::set_zone_urls(Origin::signed(4), node_hash, read_url.into(), write_url.into()));
+			assert_noop!(NameService::set_zone_urls(Origin::signed(4), node_hash, read_url.into(), write_url.into()), "Zone urls are the same value");
+			assert_eq!(NameService::resolve_zone_urls(node_hash), Some((read_url.as_bytes().to_vec(), write_url.as_bytes().to_vec())));
+		});
+	}
+
+	#[test]
+	fn set_text_should_work() {
+		new_test_ext().execute_with(||{
+			assert_ok!(NameService::set_root_owner(Origin::signed(1), 3));
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let label = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_ok!(NameService::set_subnode_owner(Origin::signed(3), root_hash, label, 4));
+			let node_hash = (root_hash, label).using_encoded(<Test as system::Trait>::Hashing::hash);
+
+			assert_noop!(NameService::set_text(Origin::signed(3), node_hash, "email".into(), "a@b.c".into()), "Sender is not owner");
+			assert_ok!(NameService::set_text(Origin::signed(4), node_hash, "email".into(), "a@b.c".into()));
+			assert_noop!(NameService::set_text(Origin::signed(4), node_hash, "email".into(), "a@b.c".into()), "Text is the same value");
+			assert_eq!(NameService::text_of(node_hash, b"email".to_vec()), Some(b"a@b.c".to_vec()));
+
+			// The name of the node under `publish_dns` publishes it, another domain does not
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(3), root_hash, "example".into(), 5));
+			let example = NameService::subnode_hash(root_hash, <Test as system::Trait>::Hashing::hash(b"example"));
+			assert_ok!(NameService::set_subnode_label_owner(Origin::signed(5), example, "pay".into(), 4));
+			let pay = NameService::subnode_hash(example, <Test as system::Trait>::Hashing::hash(b"pay"));
+			assert_noop!(NameService::set_text(Origin::signed(4), pay, "publish_dns".into(), "pay..example".into()), "Invalid domain name");
+			assert_noop!(NameService::set_text(Origin::signed(4), pay, "publish_dns".into(), "bank.example".into()), "Domain is not the name of the node");
+			assert_noop!(NameService::set_text(Origin::signed(4), pay, "publish_dns".into(), "pay.example.com".into()), "Domain is not the name of the node");
+			assert_noop!(NameService::set_text(Origin::signed(4), node_hash, "publish_dns".into(), "eth".into()), "Domain is not the name of the node");
+			assert_ok!(NameService::set_text(Origin::signed(4), pay, "publish_dns".into(), "pay.example".into()));
+			assert_eq!(NameService::dns_published(), vec![pay]);
+			assert_eq!(NameService::dns_zone_of(pay), None);
+
+			assert_ok!(NameService::set_ttl(Origin::signed(4), pay, 300));
+			assert_ok!(NameService::set_resolve_addr(Origin::signed(4), pay, 4));
+			assert_ok!(NameService::set_zone_urls(Origin::signed(4), pay, "http://a/\"z\"".into(), Vec::new()));
+			assert_eq!(
+				NameService::dns_zone_of(pay),
+				Some(b"pay.example. 300 IN TXT \"pistis-addr=0x0400000000000000\"\n\
+					pay.example. 300 IN TXT \"pistis-zone=http://a/\\\"z\\\"\"\n".to_vec()),
+			);
+
+			// A domain written before the check is not rendered
+			<TextRecords<Test>>::insert(pay, &dns::PUBLISH_DNS_KEY.to_vec(), &b"bank.example".to_vec());
+			assert_eq!(NameService::dns_zone_of(pay), None);
+			<TextRecords<Test>>::insert(pay, &dns::PUBLISH_DNS_KEY.to_vec(), &b"pay.example".to_vec());
+
+			// Clearing the node unpublishes it
+			assert_ok!(NameService::clear_node(Origin::signed(4), pay));
+			assert_eq!(NameService::text_of(pay, dns::PUBLISH_DNS_KEY.to_vec()), None);
+			assert!(NameService::dns_published().is_empty());
+			assert_ok!(NameService::clear_node(Origin::signed(4), node_hash));
+			assert_eq!(NameService::text_of(node_hash, b"email".to_vec()), None);
+
+			assert_noop!(NameService::set_dns_endpoint(Origin::signed(2), "http://dns".into()), "Bad origin");
+			assert_ok!(NameService::set_dns_endpoint(Origin::signed(1), "http://dns".into()));
+			assert_eq!(NameService::dns_endpoint(), b"http://dns".to_vec());
+		});
+	}
+
+	#[test]
+	fn dns_signing_payload_should_bind_the_chain_and_time() {
+		let genesis_hash = H256::repeat_byte(7);
+		let body = b"pay.example. 300 IN TXT \"pistis-addr=0x04\"\n";
+		let payload = dns::signing_payload(genesis_hash.as_ref(), 1_600_000_000_000, body);
+		assert_eq!(&payload[..32], genesis_hash.as_ref());
+		assert_eq!(&payload[32..40], &1_600_000_000_000u64.to_le_bytes()[..]);
+		assert_eq!(&payload[40..], &body[..]);
+		// A push replayed later or on another chain has another payload
+		assert_ne!(payload, dns::signing_payload(genesis_hash.as_ref(), 1_600_000_000_001, body));
+		assert_ne!(payload, dns::signing_payload(H256::repeat_byte(8).as_ref(), 1_600_000_000_000, body));
+
+		assert_eq!(dns::to_decimal(0), b"0".to_vec());
+		assert_eq!(dns::to_decimal(1_600_000_000_000), b"1600000000000".to_vec());
+	}
+
+	#[test]
+	fn legacy_node_record_should_migrate() {
+		new_test_ext().execute_with(||{
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let node_hash = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			<NodeOf<Test>>::insert(node_hash, LegacyNodeRecord { owner: 4, ttl: 10 });
+
+			// Legacy records are readable before migration
+			let record = NameService::node_of(node_hash).unwrap();
+			assert_eq!(record.owner, 4);
+			assert_eq!(record.parent, None);
+			assert_eq!(record.label, root_hash);
+
+			// Any write migrates the record
+			assert_ok!(NameService::set_ttl(Origin::signed(4), node_hash, 20));
+			assert!(NameService::legacy_node_of(node_hash).is_none());
+			let record = NameService::node_record_of(node_hash).unwrap();
+			assert_eq!(record.owner, 4);
+			assert_eq!(record.ttl, 20);
+			assert_eq!(record.parent, None);
+		});
+	}
+
+	#[test]
+	fn storage_should_migrate() {
+		new_test_ext().execute_with(||{
+			let root_hash = <Test as system::Trait>::Hash::default();
+			let profile = ("profile").using_encoded(<Test as system::Trait>::Hashing::hash);
+			assert_eq!(NameService::storage_version(), 0);
+
+			// Write the root records in the first layout
+			let node: (u64, u64) = (4, 10);
+			unhashed::put(&<NodeOf<Test>>::hashed_key_for(root_hash), &node);
+			let resolve: (u64, Vec<u8>, H256, Vec<u8>) = (5, b"root".to_vec(), profile, b"zone".to_vec());
+			unhashed::put(&<ResolveOf<Test>>::hashed_key_for(root_hash), &resolve);
+
+			NameService::on_initialize(1);
+			assert_eq!(NameService::storage_version(), STORAGE_VERSION);
+			assert!(NameService::legacy_node_of(root_hash).is_none());
+			assert!(NameService::legacy_resolve_of(root_hash).is_none());
+			assert_eq!(NameService::node_record_of(root_hash), Some(NodeRecord { owner: 4, ttl: 10, ..Default::default() }));
+			let record = NameService::resolve_record_of(root_hash).unwrap();
+			assert_eq!(record.addr, 5);
+			assert_eq!(record.profile, DataHash::from_runtime_hash(&profile));
+			assert_eq!(record.zone.storage, b"zone".to_vec());
+
+			// The migrations run once
+			<NodeOf<Test>>::insert(root_hash, LegacyNodeRecord { owner: 6, ttl: 0 });
+			NameService::on_initialize(2);
+			assert_eq!(NameService::node_record_of(root_hash).unwrap().owner, 4);
+		});
+	}
+
+	#[test]
+	fn legacy_resolve_record_should_migrate() {
+		new_test_ext().execute_with(||{
+			let node_hash = ("eth").using_encoded(<Test as system::Trait>::Hashing::hash);
+			let zone = r#"{"storage":"http://example.com/1LrMVQmmEvJXsTmrXuarGrikk5nnB5Cvwg"}"#;
+			<ResolveOf<Test>>::insert(node_hash, LegacyResolveRecord {
+				addr: 5,
+				name: "eth".into(),
+				profile: <Test as system::Trait>::Hash::default(),
+				zone: zone.into(),
+			});
+
+			// Legacy records are readable before migration
+			assert_eq!(NameService::resolve_addr(node_hash), Some(5));
+			assert_eq!(NameService::resolve_zone_storage(node_hash), Some(zone.as_bytes().to_vec()));
+
+			// Any write migrates the record
+			assert_ok!(NameService::do_set_resolve_addr(node_hash, &6));
+			assert!(NameService::legacy_resolve_of(node_hash).is_none());
+			let record = NameService::resolve_record_of(node_hash).unwrap();
+			assert_eq!(record.addr, 6);
+			assert_eq!(record.name[..], *"eth".as_bytes());
+			assert_eq!(record.zone.storage[..], *zone.as_bytes());
 		});
 	}
 
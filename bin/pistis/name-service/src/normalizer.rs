@@ -0,0 +1,75 @@
+//! # Name Normalization
+//!
+//! Names are normalized before they are checked and stored, so visually identical names
+//! with different encodings can not coexist. The policy is chosen by the runtime through
+//! the `NameNormalizer` of the name service, which the client modules reach through
+//! `NameServiceResolver::normalize_name`.
+
+use rstd::{marker::PhantomData, prelude::*};
+use support::traits::Get;
+
+/// Normalize and validate names
+pub trait NameNormalizer {
+	/// Normalize a name, or reject it
+	///
+	/// @name	the name in utf8
+	fn normalize(name: &[u8]) -> Result<Vec<u8>, &'static str>;
+}
+
+impl NameNormalizer for () {
+	/// Any name is accepted as it is
+	fn normalize(name: &[u8]) -> Result<Vec<u8>, &'static str> {
+		Ok(name.to_vec())
+	}
+}
+
+/// The scripts whose letters are commonly confused with each other
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Script {
+	Latin,
+	Greek,
+	Cyrillic,
+}
+
+impl Script {
+	/// Get the script of a letter, none for the characters shared by all scripts
+	fn of(c: char) -> Option<Script> {
+		match c as u32 {
+			0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Some(Script::Latin),
+			0x370..=0x3FF | 0x1F00..=0x1FFF => Some(Script::Greek),
+			0x400..=0x52F => Some(Script::Cyrillic),
+			_ => None,
+		}
+	}
+}
+
+/// The standard policy: names must be valid utf8 without control characters, ASCII letters
+/// are lowercased and, unless `AllowMixedScripts` is set, a dot separated label may not mix
+/// Latin, Greek and Cyrillic letters.
+pub struct StandardNormalizer<AllowMixedScripts>(PhantomData<AllowMixedScripts>);
+
+impl<AllowMixedScripts: Get<bool>> NameNormalizer for StandardNormalizer<AllowMixedScripts> {
+	/// Normalize the name with the standard policy
+	///
+	/// @name	the name in utf8
+	fn normalize(name: &[u8]) -> Result<Vec<u8>, &'static str> {
+		let name = rstd::str::from_utf8(name).map_err(|_| "Name is not valid utf8")?;
+		if name.chars().any(|c| c.is_control()) {
+			return Err("Name contains control characters");
+		}
+		if !AllowMixedScripts::get() {
+			for label in name.split('.') {
+				let mut scripts = label.chars().filter_map(Script::of);
+				if let Some(first) = scripts.next() {
+					if scripts.any(|script| script != first) {
+						return Err("Name mixes scripts");
+					}
+				}
+			}
+		}
+
+		let mut name = name.as_bytes().to_vec();
+		name.make_ascii_lowercase();
+		Ok(name)
+	}
+}
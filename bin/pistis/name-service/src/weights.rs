@@ -0,0 +1,92 @@
+//! # Name Service Weights
+//!
+//! The weights of the name service calls, as functions of the input sizes they read and write.
+//! The `()` implementation prices each call by the storage reads and writes it makes and the
+//! bytes it hashes or copies, at the per-operation figures of `pistis_primitives::weights`.
+//! `benches/bench.rs` measures the calls on the reference hardware, so a runtime can replace
+//! the figures with its own.
+
+use pistis_primitives::weights::{db, linear, BYTE};
+use support::weights::Weight;
+
+/// Weight functions of the name service calls
+pub trait WeightInfo {
+	fn set_root_owner() -> Weight;
+	fn propose_owner() -> Weight;
+	fn accept_ownership() -> Weight;
+	fn cancel_ownership_transfer() -> Weight;
+	fn claim_node() -> Weight;
+	fn migrate_owned_node() -> Weight;
+	fn force_set_owner() -> Weight;
+	fn burn_fuses() -> Weight;
+	fn set_subnode_owner() -> Weight;
+	/// @n	the length of the label
+	fn set_subnode_label_owner(n: u32) -> Weight;
+	fn set_ttl() -> Weight;
+	fn set_resolve_addr() -> Weight;
+	/// @n	the length of the name
+	fn set_resolve_name(n: u32) -> Weight;
+	fn set_resolve_profile() -> Weight;
+	/// @z	the length of the zone storage descriptor
+	fn set_zone_storage(z: u32) -> Weight;
+	/// @z	the total length of the zone urls
+	fn set_zone_urls(z: u32) -> Weight;
+	fn reserve_name() -> Weight;
+	fn unreserve_name() -> Weight;
+	fn clear_resolve() -> Weight;
+	fn clear_node() -> Weight;
+	fn migrate_subnode() -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	/// @n	the total length of the key and the value
+	fn set_text(n: u32) -> Weight;
+	/// @n	the length of the endpoint
+	fn set_dns_endpoint(n: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+	// The fuses, the node, its owned names, the event
+	fn set_root_owner() -> Weight { db(4, 4) }
+	// The node and its approvals, the pending owner, the event
+	fn propose_owner() -> Weight { db(5, 2) }
+	fn accept_ownership() -> Weight { db(5, 5) }
+	fn cancel_ownership_transfer() -> Weight { db(4, 2) }
+	fn claim_node() -> Weight { db(2, 1) }
+	// The node in its layouts, moved to the current one
+	fn migrate_owned_node() -> Weight { db(5, 4) }
+	fn force_set_owner() -> Weight { db(5, 4) }
+	fn burn_fuses() -> Weight { db(2, 3) }
+	// The parent, the subnode, the child count and the owned names of both owners
+	fn set_subnode_owner() -> Weight { db(6, 5) }
+	fn set_subnode_label_owner(n: u32) -> Weight {
+		linear(db(6, 5), &[(BYTE, n)])
+	}
+	fn set_ttl() -> Weight { db(4, 2) }
+	// The node and its approvals, the resolve record, the reverse index, the event
+	fn set_resolve_addr() -> Weight { db(5, 3) }
+	fn set_resolve_name(n: u32) -> Weight {
+		linear(db(5, 3), &[(BYTE, n)])
+	}
+	fn set_resolve_profile() -> Weight { db(5, 2) }
+	fn set_zone_storage(z: u32) -> Weight {
+		linear(db(4, 2), &[(BYTE, z)])
+	}
+	fn set_zone_urls(z: u32) -> Weight {
+		linear(db(4, 2), &[(BYTE, z)])
+	}
+	fn reserve_name() -> Weight { db(1, 2) }
+	fn unreserve_name() -> Weight { db(1, 2) }
+	fn clear_resolve() -> Weight { db(4, 3) }
+	fn clear_node() -> Weight { db(6, 6) }
+	// The node in its layouts, the legacy parent link and the count marker
+	fn migrate_subnode() -> Weight { db(5, 2) }
+	fn approve() -> Weight { db(3, 2) }
+	fn set_approval_for_all() -> Weight { db(1, 2) }
+	// The published count when the key publishes the node to the DNS
+	fn set_text(n: u32) -> Weight {
+		linear(db(6, 4), &[(BYTE, n)])
+	}
+	fn set_dns_endpoint(n: u32) -> Weight {
+		linear(db(0, 2), &[(BYTE, n)])
+	}
+}
@@ -0,0 +1,207 @@
+//! Byte strings whose length is checked whenever they are built or decoded.
+//!
+//! A `BoundedBytes` holds at most the `MAX_LEN` bytes of its bound: `try_from` refuses
+//! longer vectors and decoding fails on them, so a record holding one, read from storage or
+//! from a transaction, never carries more whatever code path produced it. The bounds are
+//! the ceilings of the runtime limits: the modules keep checking their configurable limits,
+//! which may only be lower, on top.
+
+use codec::{Decode, Encode, EncodeLike, Input, Output};
+use rstd::{convert::TryFrom, marker::PhantomData, ops::Deref, prelude::*};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The length bound of a `BoundedBytes`
+pub trait Bound {
+	/// The maximum length
+	const MAX_LEN: usize;
+	/// The error of a longer byte string
+	const ERROR: &'static str;
+}
+
+/// The bound of names and labels
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NameBound;
+
+impl Bound for NameBound {
+	const MAX_LEN: usize = 256;
+	const ERROR: &'static str = "Name too long";
+}
+
+/// The bound of product sequence ids and lot ids
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SeqIdBound;
+
+impl Bound for SeqIdBound {
+	const MAX_LEN: usize = 256;
+	const ERROR: &'static str = "Sequence ID too long";
+}
+
+/// The bound of the extra information of infos
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExtraBound;
+
+impl Bound for ExtraBound {
+	const MAX_LEN: usize = 16 * 1024;
+	const ERROR: &'static str = "Extra info too long";
+}
+
+/// The bound of the fields of zone files
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ZoneBound;
+
+impl Bound for ZoneBound {
+	const MAX_LEN: usize = 4 * 1024;
+	const ERROR: &'static str = "Zone too long";
+}
+
+/// A name or a label
+pub type BoundedName = BoundedBytes<NameBound>;
+/// A product sequence id or a lot id
+pub type BoundedSeqId = BoundedBytes<SeqIdBound>;
+/// The extra information of an info
+pub type BoundedExtra = BoundedBytes<ExtraBound>;
+/// A field of a zone file
+pub type BoundedZone = BoundedBytes<ZoneBound>;
+
+/// A byte string of at most `B::MAX_LEN` bytes, encoded as a plain `Vec<u8>`
+pub struct BoundedBytes<B>(Vec<u8>, PhantomData<B>);
+
+impl<B> BoundedBytes<B> {
+	/// Take the bytes out
+	pub fn into_inner(self) -> Vec<u8> {
+		self.0
+	}
+}
+
+impl<B: Bound> BoundedBytes<B> {
+	/// Bound bytes cut to `MAX_LEN`, for the records stored before the bounds, which the
+	/// lower runtime limits kept within them
+	///
+	/// @bytes	the bytes
+	pub fn truncating_from(mut bytes: Vec<u8>) -> Self {
+		bytes.truncate(B::MAX_LEN);
+		BoundedBytes(bytes, PhantomData)
+	}
+}
+
+impl<B: Bound> TryFrom<Vec<u8>> for BoundedBytes<B> {
+	type Error = &'static str;
+
+	fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+		if bytes.len() > B::MAX_LEN {
+			return Err(B::ERROR);
+		}
+		Ok(BoundedBytes(bytes, PhantomData))
+	}
+}
+
+impl<B> From<BoundedBytes<B>> for Vec<u8> {
+	fn from(bytes: BoundedBytes<B>) -> Self {
+		bytes.0
+	}
+}
+
+impl<B> Deref for BoundedBytes<B> {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl<B> AsRef<[u8]> for BoundedBytes<B> {
+	fn as_ref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl<B> Default for BoundedBytes<B> {
+	fn default() -> Self {
+		BoundedBytes(Vec::new(), PhantomData)
+	}
+}
+
+impl<B> Clone for BoundedBytes<B> {
+	fn clone(&self) -> Self {
+		BoundedBytes(self.0.clone(), PhantomData)
+	}
+}
+
+impl<B> PartialEq for BoundedBytes<B> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<B> Eq for BoundedBytes<B> {}
+
+impl<B> PartialEq<Vec<u8>> for BoundedBytes<B> {
+	fn eq(&self, other: &Vec<u8>) -> bool {
+		self.0 == *other
+	}
+}
+
+impl<B> rstd::fmt::Debug for BoundedBytes<B> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl<B> Encode for BoundedBytes<B> {
+	fn size_hint(&self) -> usize {
+		self.0.size_hint()
+	}
+
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		self.0.encode_to(dest)
+	}
+}
+
+impl<B> EncodeLike for BoundedBytes<B> {}
+
+impl<B> EncodeLike<Vec<u8>> for BoundedBytes<B> {}
+
+impl<B: Bound> Decode for BoundedBytes<B> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let bytes = Vec::<u8>::decode(input)?;
+		Self::try_from(bytes).map_err(Into::into)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<B> Serialize for BoundedBytes<B> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		primitives::bytes::serialize(&self.0, serializer)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de, B: Bound> Deserialize<'de> for BoundedBytes<B> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let bytes = primitives::bytes::deserialize(deserializer)?;
+		Self::try_from(bytes).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bounds_should_hold_on_build_and_decode() {
+		let name = BoundedName::try_from(b"acme".to_vec()).unwrap();
+		assert_eq!(&name[..], b"acme");
+		assert_eq!(name.encode(), b"acme".to_vec().encode());
+		assert_eq!(BoundedName::decode(&mut &name.encode()[..]).ok(), Some(name));
+
+		let long = vec![0u8; NameBound::MAX_LEN + 1];
+		assert_eq!(BoundedName::try_from(long.clone()).err(), Some("Name too long"));
+		assert!(BoundedName::decode(&mut &long.encode()[..]).is_err());
+		assert!(BoundedSeqId::try_from(vec![0u8; SeqIdBound::MAX_LEN]).is_ok());
+
+		let json = serde_json::to_string(&BoundedExtra::try_from(b"{}".to_vec()).unwrap()).unwrap();
+		assert_eq!(json, "\"0x7b7d\"");
+		assert!(serde_json::from_str::<BoundedName>(&format!("\"0x{}\"", "00".repeat(NameBound::MAX_LEN + 1))).is_err());
+	}
+}
@@ -0,0 +1,642 @@
+//! # Pistis Primitives
+//!
+//! The records of the name service and business modules and the `RegistryApi` runtime API
+//! querying them, shared with the node RPC and external tools which should not depend on
+//! the modules themselves. With the `std` feature the records serialize to JSON with
+//! camelCase fields and `0x` prefixed hex for byte vectors, and [`proof`](./proof/index.html)
+//! checks the read proofs of registry entries against block headers. The modules weigh their
+//! calls with the helpers of [`weights`](./weights/index.html).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod bounded;
+pub mod merkle;
+#[cfg(feature = "std")]
+pub mod proof;
+pub mod weights;
+
+use codec::{Codec, Decode, Encode};
+use primitives::H256;
+use rstd::prelude::*;
+use sp_runtime::{RuntimeDebug, traits::Block as BlockT};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub use bounded::{Bound, BoundedBytes, BoundedExtra, BoundedName, BoundedSeqId, BoundedZone};
+
+/// The algorithm a data hash was computed with
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum HashAlgo {
+	/// Blake2b with a 256 bit digest, the hashing of the runtime
+	Blake2_256,
+	/// SHA-256
+	Sha2_256,
+	/// SHA-512
+	Sha2_512,
+	/// SHA3-256
+	Sha3_256,
+	/// Keccak-256 as used by Ethereum
+	Keccak256,
+}
+
+impl HashAlgo {
+	/// The length of the digests of the algorithm in bytes
+	pub fn digest_length(&self) -> usize {
+		match self {
+			HashAlgo::Sha2_512 => 64,
+			_ => 32,
+		}
+	}
+}
+
+impl Default for HashAlgo {
+	fn default() -> Self {
+		HashAlgo::Blake2_256
+	}
+}
+
+/// A digest tagged with the algorithm it was computed with, like a multihash, so verifiers
+/// can reproduce it from the off-chain data
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct DataHash {
+	/// The hash algorithm
+	pub algo: HashAlgo,
+	/// The digest
+	#[cfg_attr(feature = "std", serde(with = "primitives::bytes"))]
+	pub digest: Vec<u8>,
+}
+
+impl DataHash {
+	/// Wrap a hash computed by the runtime hashing
+	pub fn from_runtime_hash<H: AsRef<[u8]>>(hash: &H) -> Self {
+		DataHash {
+			algo: HashAlgo::Blake2_256,
+			digest: hash.as_ref().to_vec(),
+		}
+	}
+
+	/// Get the digest as a hash of the runtime hashing, none for the other algorithms
+	pub fn as_runtime_hash<H: Decode>(&self) -> Option<H> {
+		if self.algo != HashAlgo::Blake2_256 {
+			return None;
+		}
+		let mut digest = &self.digest[..];
+		H::decode(&mut digest).ok().filter(|_| digest.is_empty())
+	}
+
+	/// Ensure the digest has the length of its algorithm
+	pub fn validate(&self) -> Result<(), &'static str> {
+		if self.digest.len() != self.algo.digest_length() {
+			return Err("Digest length does not match the hash algorithm");
+		}
+		Ok(())
+	}
+}
+
+/// The node record
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct NodeRecord<Hash, AccountId> {
+	/// The owner of the node
+	pub owner: AccountId,
+	/// The ttl of the record
+	pub ttl: u64,
+	/// The parent node, none for the root and the nodes set up at genesis
+	pub parent: Option<Hash>,
+	/// The label hash the node was registered with under its parent
+	pub label: Hash,
+	/// The permissions burned on the node, see `name_service::fuses`
+	pub fuses: u32,
+}
+
+/// The resolve record
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ResolveRecord<AccountId> {
+	/// The resolved address
+	pub addr: AccountId,
+	/// The resolved name
+	pub name: BoundedName,
+	/// The resolved profile
+	pub profile: DataHash,
+	/// The zone file
+	pub zone: ZoneFile,
+	/// The block the record last changed at, zero if it changed before the blocks were
+	/// recorded
+	pub last_updated: u64,
+}
+
+/// The zone file
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ZoneFile {
+	/// The storage descriptor of the zone
+	pub storage: BoundedZone,
+	/// The url the zone can be read from
+	pub read_url: BoundedZone,
+	/// The url the zone can be written to
+	pub write_url: BoundedZone,
+}
+
+/// An operator in the whitelist of a business
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WhitelistEntry<NameHash, BlockNumber> {
+	/// The name hash of the operator
+	pub name_hash: NameHash,
+	/// The block from which the operator may no longer write, none for no limit
+	pub valid_until: Option<BlockNumber>,
+}
+
+/// The business struct
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Business<NameHash, AccountId, BlockNumber> {
+	/// The creator
+	pub creator: AccountId,
+	/// The name hash of the owner
+	pub owner: NameHash,
+	/// The name of business
+	pub name: BoundedName,
+	/// The whitelist account
+	pub whitelist: Vec<WhitelistEntry<NameHash, BlockNumber>>,
+	/// The expiration of business
+	pub expiration: BlockNumber,
+	/// The hash of the off-chain profile document of the business
+	pub profile: Option<NameHash>,
+	/// The category marketplaces group the business under, zero for none
+	pub category: u16,
+	/// The parent business of a sub-business
+	pub parent: Option<NameHash>,
+	/// The scope the business was created in, whose admin manages its expiration
+	pub scope: NameHash,
+}
+
+/// A whitelist entry joined with the name service resolution of its operator
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ResolvedWhitelistEntry<NameHash, AccountId, BlockNumber> {
+	/// The name hash of the operator
+	pub name_hash: NameHash,
+	/// The name the operator resolves to
+	#[cfg_attr(feature = "std", serde(with = "option_bytes"))]
+	pub name: Option<Vec<u8>>,
+	/// The account the operator currently resolves to
+	pub account: Option<AccountId>,
+	/// The block from which the operator may no longer write, none for no limit
+	pub valid_until: Option<BlockNumber>,
+}
+
+/// A business joined with the name service resolution of its owner and operators
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BusinessDetails<NameHash, AccountId, BlockNumber> {
+	/// The creator
+	pub creator: AccountId,
+	/// The name hash of the owner
+	pub owner: NameHash,
+	/// The name the owner resolves to
+	#[cfg_attr(feature = "std", serde(with = "option_bytes"))]
+	pub owner_name: Option<Vec<u8>>,
+	/// The account the owner currently resolves to
+	pub owner_account: Option<AccountId>,
+	/// The name of business
+	#[cfg_attr(feature = "std", serde(with = "primitives::bytes"))]
+	pub name: Vec<u8>,
+	/// The resolved whitelist
+	pub whitelist: Vec<ResolvedWhitelistEntry<NameHash, AccountId, BlockNumber>>,
+	/// The expiration of business
+	pub expiration: BlockNumber,
+	/// The hash of the off-chain profile document of the business
+	pub profile: Option<NameHash>,
+	/// The category marketplaces group the business under, zero for none
+	pub category: u16,
+	/// The parent business of a sub-business
+	pub parent: Option<NameHash>,
+	/// The scope the business was created in, whose admin manages its expiration
+	pub scope: NameHash,
+}
+
+/// The kind of a product info, letting consumers filter the history without parsing `extra`
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum InfoKind {
+	/// The first info of a product
+	Created,
+	/// A quality check result
+	QualityCheck,
+	/// The product left a site
+	Shipped,
+	/// The product arrived at a site
+	Received,
+	/// The product was recalled
+	Recalled,
+	/// A kind defined by the business
+	Custom(u16),
+}
+
+impl Default for InfoKind {
+	fn default() -> Self {
+		InfoKind::Custom(0)
+	}
+}
+
+/// The prefix of the `extra` of an info holding a structured payload, which no JSON text
+/// starts with
+pub const STRUCTURED_EXTRA_PREFIX: &[u8] = b"\0pistis:extra:";
+
+/// The `extra_version` of the infos whose `extra` holds raw bytes, the infos recorded before
+/// versions included
+pub const EXTRA_VERSION_RAW: u8 = 0;
+
+/// The `extra_version` of the infos whose `extra` holds a structured payload
+pub const EXTRA_VERSION_STRUCTURED: u8 = 1;
+
+/// The latest `extra_version` the runtime records
+pub const LATEST_EXTRA_VERSION: u8 = EXTRA_VERSION_STRUCTURED;
+
+/// The payload of the `extra` of an info. A structured payload is stored as the prefix
+/// followed by the SCALE encoding of its fields, any other bytes are raw, so the `extra`
+/// recorded before structured payloads keeps its meaning.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ExtraPayload {
+	/// Free-form bytes, usually a JSON string
+	Raw(Vec<u8>),
+	/// Values by key id, the keys strictly increasing
+	Structured(Vec<(u16, Vec<u8>)>),
+}
+
+impl ExtraPayload {
+	/// Read the payload of an `extra`, raw if it is not a well-formed structured payload
+	pub fn from_extra(extra: &[u8]) -> Self {
+		if extra.starts_with(STRUCTURED_EXTRA_PREFIX) {
+			let mut input = &extra[STRUCTURED_EXTRA_PREFIX.len()..];
+			if let Ok(fields) = Vec::<(u16, Vec<u8>)>::decode(&mut input) {
+				if input.is_empty() {
+					return ExtraPayload::Structured(fields);
+				}
+			}
+		}
+		ExtraPayload::Raw(extra.to_vec())
+	}
+
+	/// Get the `extra_version` of the infos holding the payload
+	pub fn version(&self) -> u8 {
+		match self {
+			ExtraPayload::Raw(_) => EXTRA_VERSION_RAW,
+			ExtraPayload::Structured(_) => EXTRA_VERSION_STRUCTURED,
+		}
+	}
+
+	/// Get the `extra` bytes storing the payload
+	pub fn into_extra(self) -> Vec<u8> {
+		match self {
+			ExtraPayload::Raw(bytes) => bytes,
+			ExtraPayload::Structured(fields) => {
+				let mut extra = STRUCTURED_EXTRA_PREFIX.to_vec();
+				fields.encode_to(&mut extra);
+				extra
+			},
+		}
+	}
+}
+
+/// The information of a product
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProductInfo<AccountId, BlockNumber, Moment> {
+	/// Creator account
+	pub creator: AccountId,
+	/// Creation time
+	pub created_at: BlockNumber,
+	/// Creation wall-clock time of the block, zero for the infos recorded before timestamps
+	pub timestamp: Moment,
+	/// The kind of the info
+	pub kind: InfoKind,
+	/// Hash of data
+	pub data_hash: DataHash,
+	/// Extra information, can be a JSON string
+	pub extra: BoundedExtra,
+	/// The format of the extra information, so that readers decode it without guessing
+	pub extra_version: u8,
+}
+
+/// The product information
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Product<AccountId, BlockNumber, Moment> {
+	/// Sequence ID of the record
+	pub seq_id: BoundedSeqId,
+	/// Product info array
+	pub infos: Vec<ProductInfo<AccountId, BlockNumber, Moment>>,
+	/// The Merkle root of the infos, see [`merkle`](./merkle/index.html)
+	pub infos_root: H256,
+}
+
+/// A production lot, whose infos are shared by the products referencing it
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Lot<Hash, AccountId, BlockNumber, Moment> {
+	/// The business which created the lot
+	pub biz_hash: Hash,
+	/// The lot id, unique within the business scope
+	pub lot_id: BoundedSeqId,
+	/// Lot info array
+	pub infos: Vec<ProductInfo<AccountId, BlockNumber, Moment>>,
+}
+
+/// The provenance of a product, in the first layout of `VersionedProvenanceCommitment`
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProvenanceCommitmentV1<Hash, BlockNumber> {
+	/// The business hash
+	pub business: Hash,
+	/// The name of the business
+	#[cfg_attr(feature = "std", serde(with = "primitives::bytes"))]
+	pub business_name: Vec<u8>,
+	/// The name hash of the owner. Its reverse name is not committed, as any account may set
+	/// the reverse name it resolves to.
+	pub owner: Hash,
+	/// Sequence ID of the product
+	#[cfg_attr(feature = "std", serde(with = "primitives::bytes"))]
+	pub seq_id: Vec<u8>,
+	/// The product hash
+	pub product: Hash,
+	/// The Merkle root of the infos of the product
+	pub infos_root: H256,
+	/// The number of infos the root commits
+	pub info_count: u32,
+	/// The block the commitment was read at
+	pub block_number: BlockNumber,
+	/// The hash of the parent of the block the commitment was read at, the runtime not
+	/// knowing the hash of the block itself
+	pub parent_hash: Hash,
+}
+
+/// The provenance of a product as relayed to other chains, SCALE encoded with the index of
+/// its layout first so that receivers decode the layouts they know and reject the others
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum VersionedProvenanceCommitment<Hash, BlockNumber> {
+	/// The first layout
+	V1(ProvenanceCommitmentV1<Hash, BlockNumber>),
+}
+
+/// The registry records changed in a block, for indexers which do not decode the events
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RegistryDigest<Hash> {
+	/// The businesses created, changed or archived
+	pub businesses: Vec<Hash>,
+	/// The products created, changed, transferred or removed
+	pub products: Vec<Hash>,
+	/// The lots created or changed
+	pub lots: Vec<Hash>,
+	/// Whether more records changed than the digest holds, the events then list them all
+	pub truncated: bool,
+}
+
+impl<Hash> RegistryDigest<Hash> {
+	/// The number of hashes in the digest
+	pub fn len(&self) -> usize {
+		self.businesses.len() + self.products.len() + self.lots.len()
+	}
+
+	/// Whether no record changed
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0 && !self.truncated
+	}
+}
+
+/// The writes of an operator to a business, for its owner to audit
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OperatorActivity<BlockNumber> {
+	/// The number of products the operator created
+	pub products_created: u64,
+	/// The number of infos the operator appended to products and lots, lot creations included
+	pub infos_appended: u64,
+	/// The block of the last write of the operator
+	pub last_active: BlockNumber,
+}
+
+/// How an extrinsic would fare if submitted
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum CallOutcome {
+	/// The call would succeed
+	Success,
+	/// The extrinsic would be refused before dispatch without being charged, with the reason
+	Invalid(Vec<u8>),
+	/// The call would fail once the fees are charged
+	Failed {
+		/// The index of the module of the error, none for the errors given as messages
+		module: Option<u8>,
+		/// The index of the error in its module
+		error: u8,
+		/// The message of the error
+		message: Vec<u8>,
+	},
+}
+
+/// The dry run of an extrinsic with the weight and fee it would be charged
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CallValidation<Balance> {
+	/// How the extrinsic would fare
+	pub outcome: CallOutcome,
+	/// The weight of the call
+	pub weight: u32,
+	/// The fee of the extrinsic, its tip excepted
+	pub partial_fee: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the name service and business registries without reading the
+	/// storage directly.
+	pub trait RegistryApi<Hash, AccountId, BlockNumber, Moment> where
+		Hash: Codec,
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+	{
+		/// Get the resolve record of a node.
+		///
+		/// Returns `None` if the node does not resolve.
+		fn resolve(node_hash: Hash) -> Option<ResolveRecord<AccountId>>;
+
+		/// Get the nodes an account owns.
+		///
+		/// The subnodes handed by another account are listed once claimed, the nodes
+		/// stored before the index once migrated.
+		fn names_of(account: AccountId) -> Vec<Hash>;
+
+		/// Get a business with its owner and whitelist resolved through the name service.
+		///
+		/// Returns `None` if the business does not exist.
+		fn business_info(biz_hash: Hash) -> Option<BusinessDetails<Hash, AccountId, BlockNumber>>;
+
+		/// Get a product by the sequence id its business created it with.
+		///
+		/// Returns `None` if the business holds no product under the sequence id.
+		fn product_by_seq_id(biz_hash: Hash, seq_id: Vec<u8>) -> Option<Product<AccountId, BlockNumber, Moment>>;
+
+		/// Get a page of the product hashes of a business, in creation order.
+		///
+		/// The page size is capped by the runtime.
+		fn list_products(biz_hash: Hash, start: u64, limit: u64) -> Vec<Hash>;
+
+		/// Get the history of a product, the infos of its lot merged in by block.
+		///
+		/// Returns an empty history if the product does not exist.
+		fn product_history(product_hash: Hash) -> Vec<ProductInfo<AccountId, BlockNumber, Moment>>;
+
+		/// Get the hash of a business by its creator and its index among the businesses the
+		/// creator created, counting from zero.
+		///
+		/// Returns `None` if the creator did not create that many businesses.
+		fn business_hash_for(creator: AccountId, index: u64) -> Option<Hash>;
+
+		/// Get the businesses, products and lots changed in the block queried at.
+		fn registry_digest() -> RegistryDigest<Hash>;
+
+		/// Get the storage keys of a product created by a business, to prove it with a read
+		/// proof: the key of its product hash by sequence id, then the key of the product under
+		/// the recorded hash, or under its legacy hash if none is recorded.
+		fn product_storage_keys(biz_hash: Hash, seq_id: Vec<u8>) -> Vec<Vec<u8>>;
+
+		/// Get the proof of an info of a product created by a business against the Merkle
+		/// root of its infos, by the index of the info.
+		///
+		/// Returns `None` if the product or the info does not exist.
+		fn product_info_proof(biz_hash: Hash, seq_id: Vec<u8>, index: u32) -> Option<merkle::InfoProof>;
+
+		/// Get the commitment to the provenance of a product created by a business, to relay
+		/// to other chains or a bridge.
+		///
+		/// Returns `None` if the product does not exist.
+		fn provenance_commitment(biz_hash: Hash, seq_id: Vec<u8>) -> Option<VersionedProvenanceCommitment<Hash, BlockNumber>>;
+
+		/// Get the writes of an operator to a business, by the name hash of the operator.
+		///
+		/// Returns zero counts if the operator never wrote to the business.
+		fn operator_stats(biz_hash: Hash, name_hash: Hash) -> OperatorActivity<BlockNumber>;
+	}
+
+	/// The API to dry-run extrinsics before submitting them.
+	pub trait RegistryDryRunApi<Balance> where
+		Balance: Codec,
+	{
+		/// Initialize a block with the header, then apply the extrinsic in it and report how
+		/// it fared. The runtime calls of the client run on a copy of the state which is
+		/// discarded afterwards, so nothing the dry run changes is stored.
+		fn validate_call(header: <Block as BlockT>::Header, uxt: <Block as BlockT>::Extrinsic) -> CallValidation<Balance>;
+	}
+}
+
+/// Serialize optional byte vectors as `0x` prefixed hex or `null`
+#[cfg(feature = "std")]
+mod option_bytes {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+		match bytes {
+			Some(bytes) => primitives::bytes::serialize(bytes, serializer),
+			None => serializer.serialize_none(),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+		#[derive(Deserialize)]
+		struct Bytes(#[serde(with = "primitives::bytes")] Vec<u8>);
+		Ok(Option::<Bytes>::deserialize(deserializer)?.map(|bytes| bytes.0))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstd::convert::TryFrom;
+
+	#[test]
+	fn product_should_serialize_to_json() {
+		let product = Product::<u64, u32, u64> {
+			seq_id: BoundedSeqId::try_from(b"1".to_vec()).unwrap(),
+			infos: vec![ProductInfo {
+				creator: 3,
+				created_at: 10,
+				timestamp: 1_577_836_800_000,
+				kind: InfoKind::QualityCheck,
+				data_hash: DataHash { algo: HashAlgo::Sha2_256, digest: vec![0xab; 32] },
+				extra: BoundedExtra::try_from(b"{}".to_vec()).unwrap(),
+				extra_version: EXTRA_VERSION_RAW,
+			}],
+			infos_root: H256::repeat_byte(1),
+		};
+
+		let json = serde_json::to_value(&product).unwrap();
+		assert_eq!(json["seqId"], "0x31");
+		assert_eq!(json["infos"][0]["createdAt"], 10);
+		assert_eq!(json["infos"][0]["timestamp"], 1_577_836_800_000u64);
+		assert_eq!(json["infos"][0]["kind"], "qualityCheck");
+		assert_eq!(json["infos"][0]["extra"], "0x7b7d");
+		assert_eq!(json["infos"][0]["extraVersion"], 0);
+		assert_eq!(json["infos"][0]["dataHash"]["algo"], "sha2_256");
+		assert_eq!(json["infosRoot"], format!("0x{}", "01".repeat(32)));
+		assert_eq!(serde_json::from_value::<Product<u64, u32, u64>>(json).unwrap(), product);
+	}
+
+	#[test]
+	fn data_hash_should_validate() {
+		let hash = H256::repeat_byte(7);
+		let data_hash = DataHash::from_runtime_hash(&hash);
+		assert_eq!(data_hash.validate(), Ok(()));
+		assert_eq!(data_hash.as_runtime_hash::<H256>(), Some(hash));
+
+		let sha512 = DataHash { algo: HashAlgo::Sha2_512, digest: vec![1; 32] };
+		assert_eq!(sha512.validate(), Err("Digest length does not match the hash algorithm"));
+		assert_eq!(DataHash { digest: vec![1; 64], ..sha512 }.validate(), Ok(()));
+		assert_eq!(DataHash { algo: HashAlgo::Keccak256, ..data_hash }.as_runtime_hash::<H256>(), None);
+	}
+
+	#[test]
+	fn business_details_should_serialize_to_json() {
+		let details = BusinessDetails::<H256, u64, u32> {
+			creator: 1,
+			owner: H256::default(),
+			owner_name: Some(b"acme".to_vec()),
+			owner_account: None,
+			name: b"crab".to_vec(),
+			whitelist: vec![],
+			expiration: 20,
+			profile: None,
+			category: 3,
+			parent: None,
+			scope: H256::default(),
+		};
+
+		let json = serde_json::to_value(&details).unwrap();
+		assert_eq!(json["ownerName"], "0x61636d65");
+		assert!(json["ownerAccount"].is_null());
+		assert_eq!(serde_json::from_value::<BusinessDetails<H256, u64, u32>>(json).unwrap(), details);
+	}
+}
@@ -0,0 +1,175 @@
+//! The Merkle commitment to the infos of a product.
+//!
+//! The leaves are the blake2 hashes of the encoded infos in recording order, and a node is
+//! the hash of its left and right children. Each level pairs its nodes from the left, its
+//! last node moving up unchanged when it has no right sibling. The root of `n` leaves is
+//! thus the fold of the roots of the complete subtrees `n` splits into, the peaks, from the
+//! right, and appending a leaf only merges the peaks of its size: the runtime keeps the
+//! peaks of a product and updates its root in `log n` hashes.
+//!
+//! A verifier holding an info, its index, the info count and the sibling hashes from
+//! [`proof`](./fn.proof.html) checks it against the root with
+//! [`verify_proof`](./fn.verify_proof.html), without the other infos.
+
+use codec::{Decode, Encode};
+use primitives::H256;
+use rstd::prelude::*;
+use sp_runtime::{RuntimeDebug, traits::{BlakeTwo256, Hash}};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The proof of a product info against the Merkle root of the infos of its product
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct InfoProof {
+	/// The Merkle root of the infos
+	pub root: H256,
+	/// The index of the info
+	pub index: u32,
+	/// The number of infos
+	pub count: u32,
+	/// The sibling hashes from the leaf up to the root, the levels where the node moves up
+	/// unchanged left out
+	pub proof: Vec<H256>,
+}
+
+/// Hash an info into a leaf
+///
+/// @info	the product info
+pub fn leaf_of<Info: Encode>(info: &Info) -> H256 {
+	BlakeTwo256::hash_of(info)
+}
+
+/// Hash two children into their parent
+fn parent_of(left: &H256, right: &H256) -> H256 {
+	BlakeTwo256::hash_of(&(left, right))
+}
+
+/// Append a leaf to the peaks of the leaves before it
+///
+/// @peaks	the peaks, the largest subtree first
+/// @count	the number of leaves before
+/// @leaf	the leaf
+pub fn append(peaks: &mut Vec<H256>, count: u64, leaf: H256) {
+	let mut node = leaf;
+	let mut count = count;
+	while count & 1 == 1 {
+		match peaks.pop() {
+			Some(peak) => node = parent_of(&peak, &node),
+			None => break,
+		}
+		count >>= 1;
+	}
+	peaks.push(node);
+}
+
+/// Get the root of the peaks, zero for no leaf
+///
+/// @peaks	the peaks, the largest subtree first
+pub fn root_of_peaks(peaks: &[H256]) -> H256 {
+	let mut nodes = peaks.iter().rev();
+	match nodes.next() {
+		Some(last) => nodes.fold(*last, |node, peak| parent_of(peak, &node)),
+		None => H256::zero(),
+	}
+}
+
+/// Get the peaks of the leaves
+///
+/// @leaves	the leaves
+pub fn peaks_of(leaves: &[H256]) -> Vec<H256> {
+	let mut peaks = Vec::new();
+	for (count, leaf) in leaves.iter().enumerate() {
+		append(&mut peaks, count as u64, *leaf);
+	}
+	peaks
+}
+
+/// Get the sibling hashes proving a leaf, none if the index is out of range
+///
+/// @leaves	the leaves
+/// @index	the index of the leaf
+pub fn proof(leaves: &[H256], index: u32) -> Option<Vec<H256>> {
+	let mut position = index as usize;
+	if position >= leaves.len() {
+		return None;
+	}
+	let mut level = leaves.to_vec();
+	let mut proof = Vec::new();
+	while level.len() > 1 {
+		let sibling = position ^ 1;
+		if sibling < level.len() {
+			proof.push(level[sibling]);
+		}
+		level = level.chunks(2)
+			.map(|pair| if pair.len() == 2 { parent_of(&pair[0], &pair[1]) } else { pair[0] })
+			.collect();
+		position /= 2;
+	}
+	Some(proof)
+}
+
+/// Check a leaf against a root
+///
+/// @root	the Merkle root
+/// @leaf	the leaf
+/// @index	the index of the leaf
+/// @count	the number of leaves
+/// @proof	the sibling hashes from `proof`
+pub fn verify_proof(root: &H256, leaf: H256, index: u32, count: u32, proof: &[H256]) -> bool {
+	if index >= count {
+		return false;
+	}
+	let mut node = leaf;
+	let mut position = index;
+	let mut width = count;
+	let mut siblings = proof.iter();
+	while width > 1 {
+		if position & 1 == 1 {
+			match siblings.next() {
+				Some(sibling) => node = parent_of(sibling, &node),
+				None => return false,
+			}
+		} else if position + 1 < width {
+			match siblings.next() {
+				Some(sibling) => node = parent_of(&node, sibling),
+				None => return false,
+			}
+		}
+		position /= 2;
+		width = (width + 1) / 2;
+	}
+	siblings.next().is_none() && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn proofs_should_verify_against_incremental_root() {
+		let leaves: Vec<H256> = (0..7u8).map(|i| leaf_of(&i)).collect();
+		let mut peaks = Vec::new();
+		for (count, leaf) in leaves.iter().enumerate() {
+			append(&mut peaks, count as u64, *leaf);
+			let root = root_of_peaks(&peaks);
+			let known = &leaves[..=count];
+			assert_eq!(peaks_of(known), peaks);
+			for index in 0..known.len() as u32 {
+				let path = proof(known, index).unwrap();
+				assert!(verify_proof(&root, known[index as usize], index, known.len() as u32, &path));
+				assert!(!verify_proof(&root, leaf_of(&99u8), index, known.len() as u32, &path));
+			}
+		}
+
+		let root = root_of_peaks(&peaks);
+		assert_eq!(root, parent_of(
+			&parent_of(&parent_of(&leaves[0], &leaves[1]), &parent_of(&leaves[2], &leaves[3])),
+			&parent_of(&parent_of(&leaves[4], &leaves[5]), &leaves[6]),
+		));
+		assert_eq!(proof(&leaves, 7), None);
+		assert!(!verify_proof(&root, leaves[6], 6, 8, &proof(&leaves, 6).unwrap()));
+		assert_eq!(root_of_peaks(&[]), H256::zero());
+	}
+}
@@ -0,0 +1,186 @@
+//! # Read proofs
+//!
+//! `registry_proveProduct` returns the storage entries of a product with the trie nodes
+//! proving them. A client holding a header it trusts, from a light client or a finality
+//! proof, checks the product against the state root of the header with
+//! `verify_product_proof`, without trusting the RPC node serving it. The keys are derived
+//! from the business hash and the sequence id the client asks for, never taken from the
+//! node, so the proof of another entry can not pass for the product. `verify_read_proof`
+//! checks entries under keys the client derived itself.
+
+use codec::{Decode, Encode};
+use primitives::hashing::{blake2_256, twox_128};
+use sp_runtime::traits::{Hash as HashT, Header as HeaderT};
+use sp_state_machine::{read_proof_check, StorageProof};
+
+/// The storage prefix of the business module
+const BUSINESS_MODULE: &[u8] = b"BusinessModule";
+
+/// Get the storage key of an entry of a `blake2_256` map of the business module
+///
+/// @storage	the name of the map
+/// @key	the key of the entry
+fn business_map_key(storage: &[u8], key: impl Encode) -> Vec<u8> {
+	let mut final_key = twox_128(BUSINESS_MODULE).to_vec();
+	final_key.extend_from_slice(&twox_128(storage));
+	final_key.extend_from_slice(&key.using_encoded(blake2_256));
+	final_key
+}
+
+/// Get the storage key of the product hash a business created under a sequence id
+///
+/// @biz_hash	the business hash
+/// @seq_id	the sequence id
+pub fn product_hash_key<Hash: Encode>(biz_hash: &Hash, seq_id: &[u8]) -> Vec<u8> {
+	business_map_key(b"ProductHashes", (biz_hash, seq_id))
+}
+
+/// Get the storage key of a product
+///
+/// @product_hash	the product hash
+pub fn product_key<Hash: Encode>(product_hash: &Hash) -> Vec<u8> {
+	business_map_key(b"Products", product_hash)
+}
+
+/// Get the hash a product created before the sequence ids were recorded is stored under,
+/// until the migration records its sequence id
+///
+/// @biz_hash	the business hash
+/// @seq_id	the sequence id
+pub fn legacy_product_hash<Hashing: HashT>(biz_hash: &Hashing::Output, seq_id: &[u8]) -> Hashing::Output {
+	let mut data = biz_hash.as_ref().to_vec();
+	data.extend_from_slice(seq_id);
+	Hashing::hash(&data)
+}
+
+/// Check the proof of a product against the state root of a header and return the SCALE
+/// encoded product, `None` if the proof shows the business created no product under the
+/// sequence id. The keys are derived from the business hash and the sequence id, the
+/// product hash is read from the proof, and a product without a recorded sequence id is
+/// looked up under its legacy hash.
+///
+/// @header	the header of the block the proof was made at
+/// @proof	the trie nodes of the proof
+/// @biz_hash	the business hash
+/// @seq_id	the sequence id
+pub fn verify_product_proof<Header>(
+	header: &Header,
+	proof: Vec<Vec<u8>>,
+	biz_hash: &Header::Hash,
+	seq_id: &[u8],
+) -> Result<Option<Vec<u8>>, &'static str> where
+	Header: HeaderT,
+	Header::Hash: Ord,
+{
+	let proof = StorageProof::new(proof);
+	let read = |key: Vec<u8>| -> Result<Option<Vec<u8>>, &'static str> {
+		let mut proven = read_proof_check::<<Header::Hashing as HashT>::Hasher, _>(
+			*header.state_root(),
+			proof.clone(),
+			Some(&key),
+		).map_err(|_| "Invalid proof")?;
+		Ok(proven.remove(&key).unwrap_or_default())
+	};
+
+	let product_hash = match read(product_hash_key(biz_hash, seq_id))? {
+		Some(value) => Header::Hash::decode(&mut &value[..]).map_err(|_| "Invalid product hash")?,
+		None => legacy_product_hash::<Header::Hashing>(biz_hash, seq_id),
+	};
+	read(product_key(&product_hash))
+}
+
+/// Check storage entries against the state root of a header
+///
+/// @header	the header of the block the proof was made at
+/// @proof	the trie nodes of the proof
+/// @entries	the storage keys with their values, `None` for a key proven absent
+pub fn verify_read_proof<Header>(
+	header: &Header,
+	proof: Vec<Vec<u8>>,
+	entries: &[(Vec<u8>, Option<Vec<u8>>)],
+) -> Result<(), &'static str> where
+	Header: HeaderT,
+	Header::Hash: Ord,
+{
+	let keys = entries.iter().map(|(key, _)| key);
+	let proven = read_proof_check::<<Header::Hashing as HashT>::Hasher, _>(
+		*header.state_root(),
+		StorageProof::new(proof),
+		keys,
+	).map_err(|_| "Invalid proof")?;
+
+	for (key, value) in entries {
+		if proven.get(key) != Some(value) {
+			return Err("Value mismatch");
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::{Blake2Hasher, H256};
+	use sp_runtime::{generic::Digest, testing::Header, traits::BlakeTwo256};
+	use sp_state_machine::{backend::{Backend, InMemory}, prove_read};
+
+	#[test]
+	fn read_proof_should_verify() {
+		let backend = InMemory::<Blake2Hasher>::from(vec![
+			(b"product".to_vec(), b"crab".to_vec()),
+			(b"other".to_vec(), b"clam".to_vec()),
+		].into_iter().collect::<std::collections::HashMap<_, _>>());
+		let (root, _) = backend.storage_root(std::iter::empty());
+		let header = Header::new(1, H256::default(), root, H256::default(), Digest::default());
+		let proof = prove_read(backend, vec![b"product".to_vec(), b"missing".to_vec()])
+			.unwrap()
+			.iter_nodes()
+			.collect::<Vec<_>>();
+
+		assert_eq!(verify_read_proof(&header, proof.clone(), &[
+			(b"product".to_vec(), Some(b"crab".to_vec())),
+			(b"missing".to_vec(), None),
+		]), Ok(()));
+		assert_eq!(verify_read_proof(&header, proof.clone(), &[
+			(b"product".to_vec(), Some(b"squid".to_vec())),
+		]), Err("Value mismatch"));
+		assert_eq!(verify_read_proof(&header, proof.clone(), &[
+			(b"missing".to_vec(), Some(b"crab".to_vec())),
+		]), Err("Value mismatch"));
+
+		let forged = Header::new(1, H256::default(), H256::repeat_byte(1), H256::default(), Digest::default());
+		assert_eq!(verify_read_proof(&forged, proof, &[
+			(b"product".to_vec(), Some(b"crab".to_vec())),
+		]), Err("Invalid proof"));
+	}
+
+	#[test]
+	fn product_proof_should_verify() {
+		let biz_hash = H256::repeat_byte(1);
+		let product_hash = H256::repeat_byte(2);
+		let legacy_hash = legacy_product_hash::<BlakeTwo256>(&biz_hash, b"2");
+		let backend = InMemory::<Blake2Hasher>::from(vec![
+			(product_hash_key(&biz_hash, b"1"), product_hash.encode()),
+			(product_key(&product_hash), b"crab".to_vec()),
+			(product_key(&legacy_hash), b"clam".to_vec()),
+			(product_key(&H256::repeat_byte(3)), b"squid".to_vec()),
+		].into_iter().collect::<std::collections::HashMap<_, _>>());
+		let (root, _) = backend.storage_root(std::iter::empty());
+		let header = Header::new(1, H256::default(), root, H256::default(), Digest::default());
+		let prove = |keys: Vec<Vec<u8>>| prove_read(backend.clone(), keys).unwrap().iter_nodes().collect::<Vec<_>>();
+
+		let proof = prove(vec![product_hash_key(&biz_hash, b"1"), product_key(&product_hash)]);
+		assert_eq!(verify_product_proof(&header, proof.clone(), &biz_hash, b"1"), Ok(Some(b"crab".to_vec())));
+		// The keys are derived from the sequence id asked for, so the proof of another
+		// product does not pass for it
+		assert_ne!(verify_product_proof(&header, proof, &biz_hash, b"3"), Ok(Some(b"crab".to_vec())));
+
+		// A product without a recorded sequence id is found under its legacy hash
+		let proof = prove(vec![product_hash_key(&biz_hash, b"2"), product_key(&legacy_hash)]);
+		assert_eq!(verify_product_proof(&header, proof, &biz_hash, b"2"), Ok(Some(b"clam".to_vec())));
+
+		let missing = legacy_product_hash::<BlakeTwo256>(&biz_hash, b"4");
+		let proof = prove(vec![product_hash_key(&biz_hash, b"4"), product_key(&missing)]);
+		assert_eq!(verify_product_proof(&header, proof, &biz_hash, b"4"), Ok(None));
+	}
+}
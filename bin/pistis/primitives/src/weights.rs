@@ -0,0 +1,52 @@
+//! # Weight Helpers
+//!
+//! The per-operation figures and helpers the Pistis modules weigh their calls with. A call is
+//! priced by the storage reads and writes it makes and the bytes it hashes or copies, and a
+//! weight computed from the call arguments is declared with `FunctionOf`.
+
+use support::weights::{ClassifyDispatch, DispatchClass, PaysFee, WeighData, Weight};
+
+/// The weight of a storage read
+pub const READ: Weight = 1_000;
+
+/// The weight of a storage write, events included
+pub const WRITE: Weight = 4_000;
+
+/// The weight of hashing, decoding or copying a byte of input
+pub const BYTE: Weight = 4;
+
+/// The weight of `reads` storage reads and `writes` storage writes
+pub fn db(reads: Weight, writes: Weight) -> Weight {
+	READ.saturating_mul(reads).saturating_add(WRITE.saturating_mul(writes))
+}
+
+/// The weight of a call with a fixed part and parts linear in its input sizes
+pub fn linear(base: Weight, parts: &[(Weight, u32)]) -> Weight {
+	parts.iter().fold(base, |w, (per_item, n)| w.saturating_add(per_item.saturating_mul(*n)))
+}
+
+/// A weight computed from the arguments of a call, which must not read the storage. The items
+/// are the closure taking the arguments as a tuple of references, the dispatch class and
+/// whether the call pays fee.
+pub struct FunctionOf<F>(pub F, pub DispatchClass, pub bool);
+
+impl<Args, F> WeighData<Args> for FunctionOf<F>
+where
+	F: Fn(Args) -> Weight
+{
+	fn weigh_data(&self, args: Args) -> Weight {
+		(self.0)(args)
+	}
+}
+
+impl<Args, F> ClassifyDispatch<Args> for FunctionOf<F> {
+	fn classify_dispatch(&self, _: Args) -> DispatchClass {
+		self.1
+	}
+}
+
+impl<F> PaysFee for FunctionOf<F> {
+	fn pays_fee(&self) -> bool {
+		self.2
+	}
+}
@@ -0,0 +1,452 @@
+//! # Registrar Module
+//!
+//! - [`registrar::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module opens the registration of names under a top level node of the name service
+//! to anyone. The registrar account owns the node, configured as `Tld`, and gives its
+//! subnodes away for a price per block of the registration. A registered name is owned by
+//! and resolves to the chosen owner. Once the registration ends the name may be registered
+//! again, by anyone; until then it keeps its records.
+//!
+//! The fees are withdrawn from the sender and handed to `RegistrationFees`, the treasury
+//! of the runtime.
+//!
+//! Labels of at most `PremiumLength` bytes are allocated by sealed-bid auctions instead.
+//! Anyone starts an auction for an available premium label; during `BiddingPeriod` the
+//! bidders commit to `bid_commitment` of their bid and a secret, reserving a deposit at
+//! least as large as the bid to hide it; during `RevealPeriod` they reveal. Once the auction
+//! is finalized the highest revealed bid wins the name for `PremiumDuration` blocks and is
+//! paid to `RegistrationFees` like a fee, and the other deposits are returned. The bids start
+//! at the reserve price, the fee of a `PremiumDuration` registration, so a premium name never
+//! goes for less than `register` would charge for it.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `register` - Register a label under the top level node for a number of blocks.
+//! * `start_auction` - Open the auction of an available premium label.
+//! * `bid` - Commit to a sealed bid, reserving a deposit.
+//! * `reveal` - Reveal a bid of the auction.
+//! * `finalize` - Register the name for the highest bid and return the other deposits.
+//! * `set_tld` - Move the registrar to another top level node it owns, for the force origin.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sp_runtime::{
+	ModuleId, RuntimeDebug,
+	traits::{AccountIdConversion, EnsureOrigin, Hash, SaturatedConversion, Saturating, Zero},
+};
+use support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::Result,
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, ReservableCurrency, WithdrawReason},
+	weights::SimpleDispatchInfo,
+};
+use system::{ensure_signed};
+use name_service::OnResolveChanged;
+
+#[cfg(test)]
+mod registrar_test;
+
+/// The id the registrar account is derived from
+const MODULE_ID: ModuleId = ModuleId(*b"pis/rgst");
+
+/// A name registered through the registrar
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Registration<AccountId, BlockNumber> {
+	/// The owner the name was registered for
+	pub owner: AccountId,
+	/// The block from which the name may be registered again
+	pub expires: BlockNumber,
+}
+
+/// The auction of a premium label
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug)]
+pub struct Auction<Hash, AccountId, BlockNumber, Balance> {
+	/// The normalized label
+	pub label: Vec<u8>,
+	/// The top level node the label is auctioned under
+	pub tld: Hash,
+	/// The block from which bids are revealed instead of placed
+	pub bidding_ends: BlockNumber,
+	/// The block from which the auction may be finalized
+	pub reveal_ends: BlockNumber,
+	/// The accounts which placed a bid
+	pub bidders: Vec<AccountId>,
+	/// The highest revealed bid and its bidder
+	pub highest: Option<(AccountId, Balance)>,
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+type RegistrationOf<T> = Registration<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type AuctionOf<T> = Auction<<T as system::Trait>::Hash, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, BalanceOf<T>>;
+type NameService<T> = name_service::Module<T>;
+
+pub trait Trait: name_service::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency the fees are paid and the auction deposits reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The fee for each block of a registration.
+	type PricePerBlock: Get<BalanceOf<Self>>;
+
+	/// The minimum blocks a name may be registered for.
+	type MinDuration: Get<Self::BlockNumber>;
+
+	/// The maximum blocks a name may be registered for.
+	type MaxDuration: Get<Self::BlockNumber>;
+
+	/// The maximum length in bytes of the labels allocated by auctions.
+	type PremiumLength: Get<usize>;
+
+	/// The blocks an auction takes bids for.
+	type BiddingPeriod: Get<Self::BlockNumber>;
+
+	/// The blocks the bids of an auction are revealed in after the bidding.
+	type RevealPeriod: Get<Self::BlockNumber>;
+
+	/// The blocks an auctioned name is registered for.
+	type PremiumDuration: Get<Self::BlockNumber>;
+
+	/// The maximum bids an auction takes.
+	type MaxBidders: Get<u32>;
+
+	/// The handler of the fees, usually the treasury.
+	type RegistrationFees: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The origin which may move the registrar to another top level node. Root is not accepted
+	/// unless this origin accepts it.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as RegistrarModule {
+		/// The top level node the names are registered under, owned by the registrar account
+		Tld get(tld) config(): T::Hash;
+		/// The registrations, by the node hash of the registered name
+		Registrations get(registration_of): map T::Hash => Option<RegistrationOf<T>>;
+		/// The running auctions, by the node hash of the auctioned name
+		Auctions get(auction_of): map T::Hash => Option<AuctionOf<T>>;
+		/// The sealed bids of an auction not revealed yet: the commitment and the deposit
+		Bids get(bid_of): double_map T::Hash, blake2_256(T::AccountId) => Option<(T::Hash, BalanceOf<T>)>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Hash = <T as system::Trait>::Hash,
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber,
+		Balance = BalanceOf<T>,
+	{
+		/// Name registered (sender, node hash, label, owner, expires, fee)
+		Registered(AccountId, Hash, Vec<u8>, AccountId, BlockNumber, Balance),
+		/// Auction started (sender, node hash, label, bidding ends, reveal ends)
+		AuctionStarted(AccountId, Hash, Vec<u8>, BlockNumber, BlockNumber),
+		/// Sealed bid placed (bidder, node hash, deposit)
+		BidPlaced(AccountId, Hash, Balance),
+		/// Bid revealed (bidder, node hash, bid)
+		BidRevealed(AccountId, Hash, Balance),
+		/// Auction finalized (node hash, winner, winning bid), no winner without revealed bids
+		AuctionFinalized(Hash, Option<AccountId>, Balance),
+		/// Registrar moved to another top level node
+		TldChanged(Hash),
+	}
+);
+
+decl_module! {
+	// Simple declaration of the `Module` type. Lets the macro know what it's working on.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The fee for each block of a registration.
+		const PricePerBlock: BalanceOf<T> = T::PricePerBlock::get();
+
+		/// The minimum blocks a name may be registered for.
+		const MinDuration: T::BlockNumber = T::MinDuration::get();
+
+		/// The maximum blocks a name may be registered for.
+		const MaxDuration: T::BlockNumber = T::MaxDuration::get();
+
+		/// The maximum length in bytes of the labels allocated by auctions.
+		const PremiumLength: u32 = T::PremiumLength::get() as u32;
+
+		/// The blocks an auction takes bids for.
+		const BiddingPeriod: T::BlockNumber = T::BiddingPeriod::get();
+
+		/// The blocks the bids of an auction are revealed in after the bidding.
+		const RevealPeriod: T::BlockNumber = T::RevealPeriod::get();
+
+		/// The blocks an auctioned name is registered for.
+		const PremiumDuration: T::BlockNumber = T::PremiumDuration::get();
+
+		/// Register a label under the top level node, paying the price of the duration
+		///
+		/// @origin	the sender, paying the fee
+		/// @label	the label in utf8
+		/// @owner	the owner of the name, which it resolves to
+		/// @duration	the blocks the registration lasts
+		#[weight = SimpleDispatchInfo::FixedNormal(
+			100_000u32.saturating_add(<T as name_service::Trait>::OnResolveChanged::max_weight())
+		)]
+		fn register(origin, label: Vec<u8>, owner: T::AccountId, duration: T::BlockNumber) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(duration >= T::MinDuration::get(), "Duration too short");
+			ensure!(duration <= T::MaxDuration::get(), "Duration too long");
+			let label = NameService::<T>::normalize_label(&label)?;
+			ensure!(label.len() > T::PremiumLength::get(), "Name is auctioned");
+
+			let tld = Self::tld();
+			let node_hash = NameService::<T>::subnode_hash(tld, T::Hashing::hash(&label));
+			let now = <system::Module<T>>::block_number();
+			Self::ensure_available(node_hash, &label, now)?;
+
+			let fee = Self::registration_fee(duration);
+			let imbalance = T::Currency::withdraw(
+				&sender,
+				fee,
+				WithdrawReason::Fee.into(),
+				ExistenceRequirement::KeepAlive,
+			)?;
+			if let Err(e) = NameService::<T>::register_subnode(&Self::account_id(), tld, label.clone(), owner.clone()) {
+				T::Currency::resolve_creating(&sender, imbalance);
+				return Err(e);
+			}
+			T::RegistrationFees::on_unbalanced(imbalance);
+
+			let expires = now.saturating_add(duration);
+			<Registrations<T>>::insert(node_hash, RegistrationOf::<T> {
+				owner: owner.clone(),
+				expires: expires,
+			});
+			Self::deposit_event(RawEvent::Registered(sender, node_hash, label, owner, expires, fee));
+			Ok(())
+		}
+
+		/// Open the auction of an available premium label
+		///
+		/// @origin	the sender
+		/// @label	the label in utf8
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn start_auction(origin, label: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let label = NameService::<T>::normalize_label(&label)?;
+			ensure!(label.len() <= T::PremiumLength::get(), "Name is not premium");
+
+			let tld = Self::tld();
+			let node_hash = NameService::<T>::subnode_hash(tld, T::Hashing::hash(&label));
+			let now = <system::Module<T>>::block_number();
+			Self::ensure_available(node_hash, &label, now)?;
+
+			let bidding_ends = now.saturating_add(T::BiddingPeriod::get());
+			let reveal_ends = bidding_ends.saturating_add(T::RevealPeriod::get());
+			<Auctions<T>>::insert(node_hash, AuctionOf::<T> {
+				label: label.clone(),
+				tld: tld,
+				bidding_ends: bidding_ends,
+				reveal_ends: reveal_ends,
+				bidders: Vec::new(),
+				highest: None,
+			});
+			Self::deposit_event(RawEvent::AuctionStarted(sender, node_hash, label, bidding_ends, reveal_ends));
+			Ok(())
+		}
+
+		/// Commit to a sealed bid, reserving a deposit which must cover the bid and the
+		/// reserve price
+		///
+		/// @origin	the bidder
+		/// @node_hash	the node hash of the auctioned name
+		/// @commitment	the `bid_commitment` of the bid
+		/// @deposit	the deposit, hiding the bid
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn bid(origin, node_hash: T::Hash, commitment: T::Hash, deposit: BalanceOf<T>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::auction_of(node_hash).ok_or("Auction does not exist")?;
+			ensure!(<system::Module<T>>::block_number() < auction.bidding_ends, "Bidding ended");
+			ensure!(!<Bids<T>>::exists(node_hash, &sender), "Already bid");
+			ensure!((auction.bidders.len() as u32) < T::MaxBidders::get(), "Too many bidders");
+			ensure!(deposit >= Self::reserve_price(), "Deposit below the reserve price");
+
+			T::Currency::reserve(&sender, deposit)?;
+			auction.bidders.push(sender.clone());
+			<Auctions<T>>::insert(node_hash, auction);
+			<Bids<T>>::insert(node_hash, &sender, (commitment, deposit));
+			Self::deposit_event(RawEvent::BidPlaced(sender, node_hash, deposit));
+			Ok(())
+		}
+
+		/// Reveal a bid of at least the reserve price. The deposit above the bid is returned,
+		/// and the whole deposit once a higher bid is revealed.
+		///
+		/// @origin	the bidder
+		/// @node_hash	the node hash of the auctioned name
+		/// @bid	the bid
+		/// @secret	the secret the commitment was made with
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn reveal(origin, node_hash: T::Hash, bid: BalanceOf<T>, secret: Vec<u8>) -> Result {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::auction_of(node_hash).ok_or("Auction does not exist")?;
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= auction.bidding_ends && now < auction.reveal_ends, "Not in the reveal period");
+			let (commitment, deposit) = Self::bid_of(node_hash, &sender).ok_or("No bid")?;
+			ensure!(commitment == Self::bid_commitment(node_hash, &sender, bid, &secret), "Commitment does not match");
+			ensure!(bid <= deposit, "Bid exceeds deposit");
+			ensure!(bid >= Self::reserve_price(), "Bid below the reserve price");
+
+			<Bids<T>>::remove(node_hash, &sender);
+			T::Currency::unreserve(&sender, deposit.saturating_sub(bid));
+			match auction.highest.take() {
+				Some((highest, highest_bid)) if highest_bid >= bid => {
+					T::Currency::unreserve(&sender, bid);
+					auction.highest = Some((highest, highest_bid));
+				},
+				outbid => {
+					if let Some((highest, highest_bid)) = outbid {
+						T::Currency::unreserve(&highest, highest_bid);
+					}
+					auction.highest = Some((sender.clone(), bid));
+				},
+			}
+			<Auctions<T>>::insert(node_hash, auction);
+			Self::deposit_event(RawEvent::BidRevealed(sender, node_hash, bid));
+			Ok(())
+		}
+
+		/// Close an auction after the reveal period, registering the name for the highest bid,
+		/// paid to the fee handler, and returning the deposits of the bids never revealed
+		///
+		/// @origin	the sender
+		/// @node_hash	the node hash of the auctioned name
+		#[weight = SimpleDispatchInfo::FixedNormal(
+			100_000u32.saturating_add(<T as name_service::Trait>::OnResolveChanged::max_weight())
+		)]
+		fn finalize(origin, node_hash: T::Hash) -> Result {
+			ensure_signed(origin)?;
+
+			let auction = Self::auction_of(node_hash).ok_or("Auction does not exist")?;
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= auction.reveal_ends, "Auction not ended");
+
+			for bidder in &auction.bidders {
+				if let Some((_, deposit)) = <Bids<T>>::take(node_hash, bidder) {
+					T::Currency::unreserve(bidder, deposit);
+				}
+			}
+			<Auctions<T>>::remove(node_hash);
+
+			let (winner, bid) = match auction.highest {
+				Some((winner, bid)) => (winner, bid),
+				None => {
+					Self::deposit_event(RawEvent::AuctionFinalized(node_hash, None, Zero::zero()));
+					return Ok(());
+				},
+			};
+			// The name goes back to the auctions if the registrar can no longer give it
+			if NameService::<T>::register_subnode(&Self::account_id(), auction.tld, auction.label, winner.clone()).is_err() {
+				T::Currency::unreserve(&winner, bid);
+				Self::deposit_event(RawEvent::AuctionFinalized(node_hash, None, Zero::zero()));
+				return Ok(());
+			}
+
+			let (imbalance, _) = T::Currency::slash_reserved(&winner, bid);
+			T::RegistrationFees::on_unbalanced(imbalance);
+			<Registrations<T>>::insert(node_hash, RegistrationOf::<T> {
+				owner: winner.clone(),
+				expires: now.saturating_add(T::PremiumDuration::get()),
+			});
+			Self::deposit_event(RawEvent::AuctionFinalized(node_hash, Some(winner), bid));
+			Ok(())
+		}
+
+		/// Move the registrar to another top level node, which the registrar account must own
+		///
+		/// @origin	the force origin
+		/// @tld	the node hash of the top level node
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn set_tld(origin, tld: T::Hash) -> Result {
+			<T as Trait>::ForceOrigin::try_origin(origin).map_err(|_| "Bad origin")?;
+
+			ensure!(tld != Self::tld(), "Same value");
+			ensure!(
+				NameService::<T>::node_of(tld).map_or(false, |record| record.owner == Self::account_id()),
+				"Registrar does not own the node"
+			);
+			<Tld<T>>::put(tld);
+			Self::deposit_event(RawEvent::TldChanged(tld));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Get the registrar account, which must own the top level node
+	pub fn account_id() -> T::AccountId {
+		MODULE_ID.into_account()
+	}
+
+	/// Get the node hash of a top level name
+	///
+	/// @label	the normalized label in utf8
+	pub fn tld_hash(label: &[u8]) -> T::Hash {
+		NameService::<T>::subnode_hash(T::Hash::default(), T::Hashing::hash(label))
+	}
+
+	/// Get the commitment of a sealed bid
+	///
+	/// @node_hash	the node hash of the auctioned name
+	/// @bidder	the bidder
+	/// @bid	the bid
+	/// @secret	a secret only the bidder knows until the reveal
+	pub fn bid_commitment(node_hash: T::Hash, bidder: &T::AccountId, bid: BalanceOf<T>, secret: &[u8]) -> T::Hash {
+		(node_hash, bidder, bid, secret).using_encoded(T::Hashing::hash)
+	}
+
+	/// Ensure a name may be registered or auctioned: not reserved, not auctioned, and
+	/// registered by nobody or by a registration which ended
+	///
+	/// @node_hash	the node hash of the name
+	/// @label	the normalized label
+	/// @now	the current block
+	fn ensure_available(node_hash: T::Hash, label: &[u8], now: T::BlockNumber) -> Result {
+		ensure!(!NameService::<T>::is_reserved_name(label), "Name is reserved");
+		ensure!(!<Auctions<T>>::exists(node_hash), "Name is in auction");
+		match Self::registration_of(node_hash) {
+			Some(registration) => ensure!(registration.expires <= now, "Name already registered"),
+			None => ensure!(NameService::<T>::node_of(node_hash).is_none(), "Name already taken"),
+		}
+		Ok(())
+	}
+
+	/// Get the reserve price of an auction, the fee of a registration as long as the
+	/// auctioned one
+	pub fn reserve_price() -> BalanceOf<T> {
+		Self::registration_fee(T::PremiumDuration::get())
+	}
+
+	/// Get the fee of a registration
+	///
+	/// @duration	the blocks the registration lasts
+	pub fn registration_fee(duration: T::BlockNumber) -> BalanceOf<T> {
+		let blocks: u32 = duration.saturated_into();
+		T::PricePerBlock::get().saturating_mul(blocks.into())
+	}
+}
@@ -0,0 +1,223 @@
+/// tests for this module
+#[cfg(test)]
+mod tests {
+    use crate::*;
+	use super::*;
+
+	use support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight};
+	use primitives::H256;
+	use system::EnsureSignedBy;
+	use name_service::{NameServiceResolver, StandardNormalizer};
+	// The testing primitives are very useful for avoiding having to work with signatures
+	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
+	use sp_runtime::{
+		Perbill, testing::{Header, UintAuthorityId}, traits::{BlakeTwo256, Hash, IdentityLookup},
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Call = ();
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+	}
+	parameter_types! {
+		pub const MinNameLength: usize = 3;
+		pub const MaxNameLength: usize = 16;
+		pub const MaxZoneLength: usize = 1024;
+		pub const One: u64 = 1;
+		pub const AllowMixedScripts: bool = false;
+		pub const MaxDnsPublished: u32 = 2;
+		pub const MaxNamesPerAccount: u32 = 16;
+	}
+	impl name_service::Trait for Test {
+		type Event = ();
+		type ForceOrigin = EnsureSignedBy<One, u64>;
+		type MinNameLength = MinNameLength;
+		type MaxNameLength = MaxNameLength;
+		type MaxZoneLength = MaxZoneLength;
+		type ProfileResolver = ();
+		type WeightInfo = ();
+		type NameNormalizer = StandardNormalizer<AllowMixedScripts>;
+		type NodeReferences = ();
+		type OnResolveChanged = ();
+		type DnsPublisherId = UintAuthorityId;
+		type MaxDnsPublished = MaxDnsPublished;
+		type MaxNamesPerAccount = MaxNamesPerAccount;
+	}
+	parameter_types! {
+		pub const PricePerBlock: u64 = 2;
+		pub const MinDuration: u64 = 10;
+		pub const MaxDuration: u64 = 100;
+		pub const PremiumLength: usize = 3;
+		pub const BiddingPeriod: u64 = 5;
+		pub const RevealPeriod: u64 = 5;
+		pub const PremiumDuration: u64 = 10;
+		pub const MaxBidders: u32 = 3;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = Balances;
+		type PricePerBlock = PricePerBlock;
+		type MinDuration = MinDuration;
+		type MaxDuration = MaxDuration;
+		type PremiumLength = PremiumLength;
+		type BiddingPeriod = BiddingPeriod;
+		type RevealPeriod = RevealPeriod;
+		type PremiumDuration = PremiumDuration;
+		type MaxBidders = MaxBidders;
+		type RegistrationFees = ();
+		type ForceOrigin = EnsureSignedBy<One, u64>;
+	}
+
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type NameService = name_service::Module<Test>;
+	type Registrar = Module<Test>;
+
+	/// The top level node `pis`
+	fn tld() -> H256 {
+		Registrar::tld_hash(b"pis")
+	}
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![
+				(2, 100),
+				(3, 100),
+				(4, 100),
+			],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		name_service::GenesisConfig::<Test> {
+			root_owner: Some(1),
+			nodes: vec![(tld(), Registrar::account_id(), Registrar::account_id())],
+		}.assimilate_storage(&mut t).unwrap();
+		GenesisConfig::<Test> {
+			tld: tld(),
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn register_should_work() {
+		new_test_ext().execute_with(|| {
+			let node_hash = NameService::subnode_hash(tld(), BlakeTwo256::hash(b"crab"));
+
+			System::set_block_number(5);
+			assert_noop!(Registrar::register(Origin::signed(2), "crab".into(), 4, 9), "Duration too short");
+			assert_noop!(Registrar::register(Origin::signed(2), "crab".into(), 4, 101), "Duration too long");
+			assert_noop!(Registrar::register(Origin::signed(2), "crab.pis".into(), 4, 10), "Label contains a dot");
+
+			// The sender pays and the owner gets the name, resolving to it
+			assert_ok!(Registrar::register(Origin::signed(2), "Crab".into(), 4, 20));
+			assert_eq!(Balances::free_balance(2), 60);
+			assert_eq!(NameService::node_of(node_hash).map(|record| record.owner), Some(4));
+			assert_eq!(NameService::resolve_addr(node_hash), Some(4));
+			assert_eq!(Registrar::registration_of(node_hash).map(|registration| registration.expires), Some(25));
+			assert_noop!(Registrar::register(Origin::signed(3), "crab".into(), 3, 10), "Name already registered");
+			assert_noop!(Registrar::register(Origin::signed(3), "clam".into(), 3, 100), "too few free funds in account");
+
+			// Anyone may register the name once the registration ends
+			System::set_block_number(25);
+			assert_ok!(Registrar::register(Origin::signed(3), "crab".into(), 3, 10));
+			assert_eq!(NameService::node_of(node_hash).map(|record| record.owner), Some(3));
+			assert_eq!(NameService::resolve_addr(node_hash), Some(3));
+			assert_eq!(Balances::free_balance(3), 80);
+
+			assert_ok!(NameService::reserve_name(Origin::signed(1), "squid".into()));
+			assert_noop!(Registrar::register(Origin::signed(3), "squid".into(), 3, 10), "Name is reserved");
+			assert_noop!(Registrar::register(Origin::signed(3), "cod".into(), 3, 10), "Name is auctioned");
+
+			// The registrar only moves to nodes it owns
+			assert_noop!(Registrar::set_tld(Origin::signed(2), H256::default()), "Bad origin");
+			assert_noop!(Registrar::set_tld(Origin::signed(1), H256::default()), "Registrar does not own the node");
+		});
+	}
+
+	#[test]
+	fn auction_should_work() {
+		new_test_ext().execute_with(|| {
+			let node_hash = NameService::subnode_hash(tld(), BlakeTwo256::hash(b"cod"));
+			let commitment = |bidder, bid| Registrar::bid_commitment(node_hash, &bidder, bid, b"salt");
+
+			System::set_block_number(5);
+			assert_noop!(Registrar::start_auction(Origin::signed(2), "crab".into()), "Name is not premium");
+			assert_ok!(Registrar::start_auction(Origin::signed(2), "cod".into()));
+			assert_noop!(Registrar::start_auction(Origin::signed(2), "cod".into()), "Name is in auction");
+
+			// The deposits hide the bids, and cover at least the reserve price
+			assert_eq!(Registrar::reserve_price(), 20);
+			assert_noop!(Registrar::bid(Origin::signed(2), node_hash, commitment(2, 10), 10), "Deposit below the reserve price");
+			assert_ok!(Registrar::bid(Origin::signed(2), node_hash, commitment(2, 30), 50));
+			assert_noop!(Registrar::bid(Origin::signed(2), node_hash, commitment(2, 30), 50), "Already bid");
+			assert_ok!(Registrar::bid(Origin::signed(3), node_hash, commitment(3, 40), 40));
+			assert_ok!(Registrar::bid(Origin::signed(4), node_hash, commitment(4, 15), 45));
+			assert_noop!(Registrar::bid(Origin::signed(5), node_hash, commitment(5, 45), 45), "Too many bidders");
+			assert_eq!(Balances::reserved_balance(2), 50);
+			assert_noop!(Registrar::reveal(Origin::signed(2), node_hash, 30, b"salt".to_vec()), "Not in the reveal period");
+
+			System::set_block_number(10);
+			assert_noop!(Registrar::bid(Origin::signed(4), node_hash, commitment(4, 45), 45), "Bidding ended");
+			assert_noop!(Registrar::reveal(Origin::signed(2), node_hash, 35, b"salt".to_vec()), "Commitment does not match");
+			assert_ok!(Registrar::reveal(Origin::signed(2), node_hash, 30, b"salt".to_vec()));
+			assert_eq!(Balances::reserved_balance(2), 30);
+			assert_ok!(Registrar::reveal(Origin::signed(3), node_hash, 40, b"salt".to_vec()));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_noop!(Registrar::reveal(Origin::signed(4), node_hash, 15, b"salt".to_vec()), "Bid below the reserve price");
+			assert_noop!(Registrar::finalize(Origin::signed(4), node_hash), "Auction not ended");
+
+			// The highest bid pays for the registration, the bids not revealed are returned
+			System::set_block_number(15);
+			assert_ok!(Registrar::finalize(Origin::signed(4), node_hash));
+			assert_eq!(NameService::resolve_addr(node_hash), Some(3));
+			assert_eq!(Registrar::registration_of(node_hash).map(|registration| registration.expires), Some(25));
+			assert_eq!((Balances::free_balance(3), Balances::reserved_balance(3)), (60, 0));
+			assert_eq!((Balances::free_balance(4), Balances::reserved_balance(4)), (100, 0));
+		});
+	}
+}
@@ -0,0 +1,237 @@
+//! Validation of product writes before they enter a block.
+//!
+//! A product write to a business which does not exist, has expired or is suspended would
+//! only fail at dispatch after charging its sender and taking block space.
+//! `CheckBusinessWrite` rejects such `create_product` and `add_product_info` calls, signed
+//! by the operator or relayed, and such lot writes while the transaction pool validates them.
+//!
+//! The scope admin calls are feeless in this runtime, so it also rejects them from senders
+//! the admin name of their scope does not resolve to, who could otherwise flood the pool
+//! for free. The delegates of the admin are accepted for `create_business`.
+//!
+//! `verify_product` is feeless too, a mismatch being charged at dispatch. It rejects the
+//! verifications of unknown products, the mismatches their sender can not pay for and those
+//! over the per-block and per-account verification limits.
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+	RuntimeDebug,
+	traits::SignedExtension,
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use support::weights::DispatchInfo;
+use crate::{AccountId, BusinessModule, Call, Hash};
+
+/// The custom validity error of a write to a business which does not exist
+pub const UNKNOWN_BUSINESS: u8 = 1;
+/// The custom validity error of a write to an expired business
+pub const EXPIRED_BUSINESS: u8 = 2;
+/// The custom validity error of a scope admin call from another sender
+pub const UNAUTHORIZED_ADMIN: u8 = 3;
+/// The custom validity error of a verification which would not be recorded
+pub const UNRECORDABLE_VERIFICATION: u8 = 4;
+/// The custom validity error of a write to a suspended business
+pub const SUSPENDED_BUSINESS: u8 = 5;
+
+/// Get the message of a custom validity error of `CheckBusinessWrite`
+///
+/// @code	the custom error code
+pub fn custom_error_message(code: u8) -> Option<&'static str> {
+	match code {
+		UNKNOWN_BUSINESS => Some("Business does not exist"),
+		EXPIRED_BUSINESS => Some("Expired"),
+		UNAUTHORIZED_ADMIN => Some("Not the scope admin or one of its delegates"),
+		UNRECORDABLE_VERIFICATION => Some("Verification would not be recorded"),
+		SUSPENDED_BUSINESS => Some("Business is suspended"),
+		_ => None,
+	}
+}
+
+/// `SignedExtension` that checks if the business of a product write accepts writes, if
+/// the sender of a scope admin call is the scope admin, and if a verification would be
+/// recorded.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct CheckBusinessWrite;
+
+impl CheckBusinessWrite {
+	/// Get the business written by a call, none if the call is not a product write
+	///
+	/// @call	the call of the transaction
+	fn written_business(call: &Call) -> Option<Hash> {
+		match call {
+			Call::BusinessModule(business::Call::create_product(_, biz_hash, ..))
+				| Call::BusinessModule(business::Call::add_product_info(_, biz_hash, ..))
+				| Call::BusinessModule(business::Call::create_lot(_, biz_hash, ..))
+				| Call::BusinessModule(business::Call::add_lot_info(_, biz_hash, ..))
+				| Call::BusinessModule(business::Call::set_product_lot(_, biz_hash, ..))
+				| Call::BusinessModule(business::Call::commit_codes(_, biz_hash, ..))
+			=> Some(*biz_hash),
+			Call::BusinessModule(business::Call::create_product_signed(payload, _))
+				| Call::BusinessModule(business::Call::add_product_info_signed(payload, _))
+			=> Some(payload.biz_hash),
+			_ => None,
+		}
+	}
+
+	/// Get the scope whose admin alone may make a call, none if the call is not a scope
+	/// admin call
+	///
+	/// @call	the call of the transaction
+	fn admin_scope(call: &Call) -> Option<Hash> {
+		match call {
+			Call::BusinessModule(business::Call::set_business_expiration(biz_hash, _))
+			=> Some(BusinessModule::business_of(biz_hash).scope),
+			Call::BusinessModule(business::Call::set_admin_delegate(scope_hash, ..))
+				| Call::BusinessModule(business::Call::remove_admin_delegate(scope_hash, ..))
+			=> Some(*scope_hash),
+			_ => None,
+		}
+	}
+}
+
+impl SignedExtension for CheckBusinessWrite {
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	type DispatchInfo = DispatchInfo;
+	type Pre = ();
+
+	fn additional_signed(&self) -> rstd::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_: Self::DispatchInfo,
+		_: usize,
+	) -> TransactionValidity {
+		if let Some(scope_hash) = Self::admin_scope(call) {
+			if BusinessModule::validate_scope_admin(who, scope_hash).is_err() {
+				return InvalidTransaction::Custom(UNAUTHORIZED_ADMIN).into();
+			}
+			return Ok(ValidTransaction::default());
+		}
+		if let Call::BusinessModule(business::Call::create_business(scope_hash, ..)) = call {
+			if BusinessModule::validate_business_creator(who, *scope_hash).is_err() {
+				return InvalidTransaction::Custom(UNAUTHORIZED_ADMIN).into();
+			}
+			return Ok(ValidTransaction::default());
+		}
+		if let Call::BusinessModule(business::Call::verify_product(biz_hash, seq_id, data_hash)) = call {
+			if BusinessModule::validate_verification(who, *biz_hash, seq_id.clone(), data_hash).is_err() {
+				return InvalidTransaction::Custom(UNRECORDABLE_VERIFICATION).into();
+			}
+			return Ok(ValidTransaction::default());
+		}
+
+		let biz_hash = match Self::written_business(call) {
+			Some(biz_hash) => biz_hash,
+			None => return Ok(ValidTransaction::default()),
+		};
+
+		if !BusinessModule::business_exists(biz_hash) {
+			return InvalidTransaction::Custom(UNKNOWN_BUSINESS).into();
+		}
+		// The same check as the dispatch, so a write is never rejected here that would succeed
+		if BusinessModule::validate_expiration(BusinessModule::business_of(biz_hash).expiration).is_err() {
+			return InvalidTransaction::Custom(EXPIRED_BUSINESS).into();
+		}
+		if BusinessModule::validate_not_suspended(biz_hash).is_err() {
+			return InvalidTransaction::Custom(SUSPENDED_BUSINESS).into();
+		}
+		Ok(ValidTransaction::default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use business::Suspension;
+	use pistis_primitives::{Business, DataHash};
+	use primitives::hashing::{blake2_256, twox_128};
+	use crate::{BlockNumber, System};
+
+	/// Put an entry of a `blake2_256` map of the business module
+	fn put_business_entry(storage: &[u8], key: Hash, value: impl Encode) {
+		let mut final_key = twox_128(b"BusinessModule").to_vec();
+		final_key.extend_from_slice(&twox_128(storage));
+		final_key.extend_from_slice(&key.using_encoded(blake2_256));
+		runtime_io::storage::set(&final_key, &value.encode());
+	}
+
+	fn put_business(biz_hash: Hash, expiration: BlockNumber) {
+		put_business_entry(b"Businesses", biz_hash, Business::<Hash, AccountId, BlockNumber> {
+			expiration,
+			..Default::default()
+		});
+	}
+
+	fn create_product(biz_hash: Hash) -> Call {
+		Call::BusinessModule(business::Call::create_product(
+			Hash::repeat_byte(9), biz_hash, b"seq".to_vec(), DataHash::default(), vec![],
+		))
+	}
+
+	fn validate(call: &Call) -> TransactionValidity {
+		CheckBusinessWrite.validate(&AccountId::default(), call, DispatchInfo::default(), 0)
+	}
+
+	fn rejected(code: u8) -> TransactionValidity {
+		InvalidTransaction::Custom(code).into()
+	}
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut ext = runtime_io::TestExternalities::default();
+		ext.execute_with(|| System::set_block_number(10));
+		ext
+	}
+
+	#[test]
+	fn validate_should_accept_writes_to_active_businesses() {
+		new_test_ext().execute_with(|| {
+			let biz_hash = Hash::repeat_byte(1);
+			put_business(biz_hash, 20);
+			assert_eq!(validate(&create_product(biz_hash)), Ok(ValidTransaction::default()));
+		});
+	}
+
+	#[test]
+	fn validate_should_reject_writes_to_unavailable_businesses() {
+		new_test_ext().execute_with(|| {
+			let missing = Hash::repeat_byte(1);
+			assert_eq!(validate(&create_product(missing)), rejected(UNKNOWN_BUSINESS));
+
+			let expired = Hash::repeat_byte(2);
+			put_business(expired, 10);
+			assert_eq!(validate(&create_product(expired)), rejected(EXPIRED_BUSINESS));
+
+			let suspended = Hash::repeat_byte(3);
+			put_business(suspended, 20);
+			put_business_entry(b"Suspensions", suspended, Suspension::<Hash, BlockNumber> {
+				reason_hash: Hash::repeat_byte(7),
+				suspended_at: 5,
+				appeal: None,
+			});
+			assert_eq!(validate(&create_product(suspended)), rejected(SUSPENDED_BUSINESS));
+
+			// An archived business is moved out of the businesses
+			let archived = Hash::repeat_byte(4);
+			put_business_entry(b"ArchivedBusinesses", archived, Business::<Hash, AccountId, BlockNumber>::default());
+			assert_eq!(validate(&create_product(archived)), rejected(UNKNOWN_BUSINESS));
+		});
+	}
+
+	#[test]
+	fn validate_should_ignore_unrelated_calls() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(system::Call::remark(vec![]));
+			assert_eq!(validate(&call), Ok(ValidTransaction::default()));
+
+			// Business calls which do not write products are left to their dispatch
+			let call = Call::BusinessModule(business::Call::set_business_profile(Hash::repeat_byte(1), None, 0));
+			assert_eq!(validate(&call), Ok(ValidTransaction::default()));
+		});
+	}
+}
@@ -9,7 +9,7 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use rstd::prelude::*;
-use primitives::OpaqueMetadata;
+use primitives::{OpaqueMetadata, u32_trait::{_2, _3}};
 use sp_runtime::{
 	ApplyExtrinsicResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
 	impl_opaque_keys, MultiSignature
@@ -22,9 +22,14 @@ use aura_primitives::sr25519::AuthorityId as AuraId;
 use grandpa::AuthorityList as GrandpaAuthorityList;
 use grandpa::fg_primitives;
 use version::RuntimeVersion;
+use system::offchain::TransactionSubmitter;
+use support::weights::DispatchClass;
 #[cfg(feature = "std")]
 use version::NativeVersion;
 
+mod business_write;
+pub use business_write::CheckBusinessWrite;
+
 // A few exports that help ease life for downstream crates.
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -57,16 +62,36 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u64;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
 /// A hash of some data used by the chain.
 pub type Hash = primitives::H256;
 
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// A product record of the business module.
+pub type Product = business::ProductOf<Runtime>;
+
+/// A product info of the business module.
+pub type ProductInfo = business::ProductInfoOf<Runtime>;
+
+/// A business of the business module, resolved through the name service.
+pub type BusinessDetails = business::BusinessDetailsOf<Runtime>;
+
 /// Name service module
 pub use name_service;
 /// Business module
 pub use business;
+/// DID module
+pub use did;
+/// Attestation module
+pub use attestation;
+/// Anchor module
+pub use anchor;
+/// Registrar module
+pub use registrar;
 
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
@@ -97,8 +122,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-pistis"),
 	impl_name: create_runtime_str!("node-pistis"),
 	authoring_version: 1,
-	spec_version: 1,
-	impl_version: 1,
+	// Per convention: if the runtime behavior changes, increment spec_version
+	// and set impl_version to equal spec_version. If only runtime
+	// implementation changes and behavior does not, then leave spec_version as
+	// is and increment impl_version. A change to the storage layout or to the
+	// encoding of the calls or events is a change of behavior.
+	spec_version: 3,
+	impl_version: 3,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -186,8 +216,7 @@ parameter_types! {
 }
 
 impl timestamp::Trait for Runtime {
-	/// A timestamp: milliseconds since the unix epoch.
-	type Moment = u64;
+	type Moment = Moment;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = MinimumPeriod;
 }
@@ -202,7 +231,7 @@ impl balances::Trait for Runtime {
 	/// The type for recording an account's balance.
 	type Balance = Balance;
 	/// What to do if an account's free balance gets zeroed.
-	type OnFreeBalanceZero = ();
+	type OnFreeBalanceZero = Contracts;
 	/// What to do if a new account is created.
 	type OnNewAccount = Indices;
 	/// The ubiquitous event type.
@@ -233,6 +262,63 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+parameter_types! {
+	pub const ContractTransferFee: Balance = 0;
+	pub const ContractCreationFee: Balance = 0;
+	pub const ContractTransactionBaseFee: Balance = 0;
+	pub const ContractTransactionByteFee: Balance = 1;
+	pub const ContractFee: Balance = 1;
+	pub const TombstoneDeposit: Balance = 1 << 16;
+	pub const RentByteFee: Balance = 1 << 4;
+	pub const RentDepositOffset: Balance = 1 << 20;
+	pub const SurchargeReward: Balance = 1 << 24;
+}
+
+impl contracts::Trait for Runtime {
+	type Currency = Balances;
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Call = Call;
+	type Event = Event;
+	type DetermineContractAddress = contracts::SimpleAddressDeterminator<Runtime>;
+	type ComputeDispatchFee = contracts::DefaultDispatchFeeComputor<Runtime>;
+	type TrieIdGenerator = contracts::TrieIdFromParentCounter<Runtime>;
+	type GasPayment = ();
+	type RentPayment = ();
+	type SignedClaimHandicap = contracts::DefaultSignedClaimHandicap;
+	type TombstoneDeposit = TombstoneDeposit;
+	type StorageSizeOffset = contracts::DefaultStorageSizeOffset;
+	type RentByteFee = RentByteFee;
+	type RentDepositOffset = RentDepositOffset;
+	type SurchargeReward = SurchargeReward;
+	type TransferFee = ContractTransferFee;
+	type CreationFee = ContractCreationFee;
+	type TransactionBaseFee = ContractTransactionBaseFee;
+	type TransactionByteFee = ContractTransactionByteFee;
+	type ContractFee = ContractFee;
+	type CallBaseFee = contracts::DefaultCallBaseFee;
+	type InstantiateBaseFee = contracts::DefaultInstantiateBaseFee;
+	type MaxDepth = contracts::DefaultMaxDepth;
+	type MaxValueSize = contracts::DefaultMaxValueSize;
+	type BlockGasLimit = contracts::DefaultBlockGasLimit;
+	type ChainExtension = PistisChainExtension;
+}
+
+/// The chain extension of the contracts, resolving names through the name service.
+/// See `name_service::extension` for the function ids and their encoding.
+pub struct PistisChainExtension;
+
+impl contracts::ChainExtension for PistisChainExtension {
+	fn call(func_id: u32, input: &[u8]) -> Option<Vec<u8>> {
+		name_service::extension::call::<Runtime, NameServiceModule>(func_id, input)
+	}
+
+	fn gas(_func_id: u32) -> contracts::Gas {
+		// A resolve costs about one storage read
+		1_000
+	}
+}
+
 type CouncilCollective = collective::Instance1;
 impl collective::Trait<CouncilCollective> for Runtime {
 	type Origin = Origin;
@@ -240,34 +326,204 @@ impl collective::Trait<CouncilCollective> for Runtime {
 	type Event = Event;
 }
 
+/// The origin of registry council motions approved by at least 2/3 of the council. Forced
+/// registry operations require it and reject root, so neither a single key nor the sudo key
+/// can remove a name or a business.
+pub type EnsureRegistryCouncil = collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+
+impl membership::Trait<membership::Instance1> for Runtime {
+	type Event = Event;
+	type AddOrigin = EnsureRegistryCouncil;
+	type RemoveOrigin = EnsureRegistryCouncil;
+	type SwapOrigin = EnsureRegistryCouncil;
+	type ResetOrigin = EnsureRegistryCouncil;
+	type MembershipInitialized = Council;
+	type MembershipChanged = Council;
+}
+
 parameter_types! {
 	pub const MinNameLength: usize = 3;
 	pub const MaxNameLength: usize = 256;
 	pub const MaxZoneLength: usize = 1024;
+	pub const AllowMixedScripts: bool = false;
+	pub const MaxDnsPublished: u32 = 256;
+	pub const MaxNamesPerAccount: u32 = 256;
 }
 impl name_service::Trait for Runtime {
 	type Event = Event;
-	type ForceOrigin = collective::EnsureMember<AccountId, CouncilCollective>;
+	type ForceOrigin = EnsureRegistryCouncil;
 	type MinNameLength = MinNameLength;
 	type MaxNameLength = MaxNameLength;
 	type MaxZoneLength = MaxZoneLength;
+	type ProfileResolver = DidModule;
+	type WeightInfo = ();
+	type NameNormalizer = name_service::StandardNormalizer<AllowMixedScripts>;
+	type NodeReferences = BusinessModule;
+	type OnResolveChanged = BusinessModule;
+	type DnsPublisherId = name_service::dns::sr25519::PublisherId;
+	type MaxDnsPublished = MaxDnsPublished;
+	type MaxNamesPerAccount = MaxNamesPerAccount;
+}
+
+parameter_types! {
+	pub const MaxDidLength: usize = 256;
+	pub const MaxKeyLength: usize = 64;
+	pub const MaxKeys: usize = 8;
+	pub const MaxServiceLength: usize = 256;
+	pub const MaxServices: usize = 8;
 }
 
+impl did::Trait for Runtime {
+	type Event = Event;
+	type MaxDidLength = MaxDidLength;
+	type MaxKeyLength = MaxKeyLength;
+	type MaxKeys = MaxKeys;
+	type MaxServiceLength = MaxServiceLength;
+	type MaxServices = MaxServices;
+}
+
+type SubmitTransaction = TransactionSubmitter<(), Runtime, UncheckedExtrinsic>;
+
 parameter_types! {
 	pub const MaxSeqIDLength: usize = 64;
 	pub const MaxExtraLength: usize = 1024;
 	pub const MaxProductInfoCount: usize = 10;
+	pub const MaxWhitelistSize: usize = 64;
+	pub const ProductDeposit: Balance = 1 << 10;
+	// The first 128 bytes of an extra cost a fraction of the product deposit, a full 1 KiB
+	// extra about twenty product deposits
+	pub const ExtraByteDeposit: Balance = 1 << 2;
+	pub const CheapExtraLength: u32 = 128;
+	// The scopes price their renewals, if at all
+	pub const RenewalPricePerBlock: Balance = 0;
+	// Only the verifications which do not match pay, against spam
+	pub const VerificationFee: Balance = 1 << 10;
+	pub const ArchiveGracePeriod: BlockNumber = 30 * DAYS;
+	pub const MaxArchiveScan: u32 = 16;
+	pub const MaxAuthorizationDepth: u32 = 4;
+	pub const OnOperatorResolveChanged: business::ResolveChangeAction = business::ResolveChangeAction::Suspend;
+	pub const MaxWritesPerOperatorPerBlock: u32 = 50;
+	pub const MaxVerificationsPerBlock: u32 = 256;
+	pub const MaxVerificationsPerAccount: u32 = 16;
+	// The scope admin is controlled by governance, its calls must not depend on its balance
+	pub const AdminDispatchClass: DispatchClass = DispatchClass::Operational;
+	pub const AdminPaysFee: bool = false;
+	pub const MaxSubBusinesses: u32 = 32;
+	pub const MaxBusinessesPerName: u32 = 64;
+	pub const MaxLotsPerBusiness: u32 = 256;
+	// Indexers read the histories through the registry API, keep the events slim
+	pub const RichEvents: bool = false;
+	pub const MaxDigestEntries: u32 = 1024;
+	pub const AppealPeriod: BlockNumber = 7 * DAYS;
 }
 
 impl business::Trait for Runtime {
 	type Event = Event;
-	type ForceOrigin = collective::EnsureMember<AccountId, CouncilCollective>;
+	type ForceOrigin = EnsureRegistryCouncil;
 	type MinNameLength = MinNameLength;
 	type MaxNameLength = MaxNameLength;
 	type MaxSeqIDLength = MaxSeqIDLength;
 	type MaxExtraLength = MaxExtraLength;
 	type MaxProductInfoCount = MaxProductInfoCount;
+	type MaxWhitelistSize = MaxWhitelistSize;
+	type Currency = Balances;
+	type ProductDeposit = ProductDeposit;
+	type StorageFeePolicy = business::TieredStorageFee<ExtraByteDeposit, CheapExtraLength>;
+	type RenewalPricePerBlock = RenewalPricePerBlock;
+	type RenewalFees = Treasury;
+	type VerificationFee = VerificationFee;
+	type VerificationFees = Treasury;
 	type NameServiceResolver = NameServiceModule;
+	type AuthorizationProvider = business::NameServiceAuthorization<NameServiceModule>;
+	type ArchiverId = business::sr25519::ArchiverId;
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type ArchiveGracePeriod = ArchiveGracePeriod;
+	type MaxArchiveScan = MaxArchiveScan;
+	type OperatorSignature = Signature;
+	type OperatorSigner = <Signature as Verify>::Signer;
+	type WeightInfo = ();
+	type AttestationVerifier = AttestationModule;
+	type Anchors = AnchorModule;
+	type MaxAuthorizationDepth = MaxAuthorizationDepth;
+	type OnOperatorResolveChanged = OnOperatorResolveChanged;
+	type MaxWritesPerOperatorPerBlock = MaxWritesPerOperatorPerBlock;
+	type MaxVerificationsPerBlock = MaxVerificationsPerBlock;
+	type MaxVerificationsPerAccount = MaxVerificationsPerAccount;
+	type AdminDispatchClass = AdminDispatchClass;
+	type AdminPaysFee = AdminPaysFee;
+	type MaxSubBusinesses = MaxSubBusinesses;
+	type MaxBusinessesPerName = MaxBusinessesPerName;
+	type MaxLotsPerBusiness = MaxLotsPerBusiness;
+	type RichEvents = RichEvents;
+	type MaxDigestEntries = MaxDigestEntries;
+	type AppealPeriod = AppealPeriod;
+}
+
+parameter_types! {
+	pub const MaxAttestationsPerSubject: usize = 16;
+}
+
+impl attestation::Trait for Runtime {
+	type Event = Event;
+	type MaxAttestationsPerSubject = MaxAttestationsPerSubject;
+	type NameServiceResolver = NameServiceModule;
+}
+
+parameter_types! {
+	pub const MaxAnchorsPerBlock: u32 = 10;
+	pub const MaxTagLength: usize = 64;
+}
+
+impl anchor::Trait for Runtime {
+	type Event = Event;
+	type MaxAnchorsPerBlock = MaxAnchorsPerBlock;
+	type MaxTagLength = MaxTagLength;
+}
+
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 1 << 20;
+	pub const SpendPeriod: BlockNumber = 7 * DAYS;
+	pub const Burn: Permill = Permill::from_percent(0);
+}
+
+impl treasury::Trait for Runtime {
+	type Currency = Balances;
+	type ApproveOrigin = EnsureRegistryCouncil;
+	type RejectOrigin = EnsureRegistryCouncil;
+	type Event = Event;
+	type ProposalRejection = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+}
+
+parameter_types! {
+	pub const RegistrationPricePerBlock: Balance = 1 << 4;
+	pub const MinRegistrationDuration: BlockNumber = 30 * DAYS;
+	pub const MaxRegistrationDuration: BlockNumber = 5 * 365 * DAYS;
+	pub const PremiumLength: usize = 4;
+	pub const BiddingPeriod: BlockNumber = 3 * DAYS;
+	pub const RevealPeriod: BlockNumber = 2 * DAYS;
+	pub const PremiumDuration: BlockNumber = 365 * DAYS;
+	pub const MaxBidders: u32 = 64;
+}
+
+impl registrar::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type PricePerBlock = RegistrationPricePerBlock;
+	type MinDuration = MinRegistrationDuration;
+	type MaxDuration = MaxRegistrationDuration;
+	type PremiumLength = PremiumLength;
+	type BiddingPeriod = BiddingPeriod;
+	type RevealPeriod = RevealPeriod;
+	type PremiumDuration = PremiumDuration;
+	type MaxBidders = MaxBidders;
+	type RegistrationFees = Treasury;
+	type ForceOrigin = EnsureRegistryCouncil;
 }
 
 construct_runtime!(
@@ -286,8 +542,15 @@ construct_runtime!(
 		Sudo: sudo,
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 		Council: collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
-		NameServiceModule: name_service::{Module, Call, Storage, Event<T>},
-		BusinessModule: business::{Module, Call, Storage, Event<T>},
+		CouncilMembership: membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+		Contracts: contracts,
+		NameServiceModule: name_service::{Module, Call, Storage, Event<T>, Config<T>},
+		BusinessModule: business::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+		DidModule: did::{Module, Call, Storage, Event<T>},
+		AttestationModule: attestation::{Module, Call, Storage, Event<T>},
+		AnchorModule: anchor::{Module, Call, Storage, Event<T>},
+		Treasury: treasury::{Module, Call, Storage, Config, Event<T>},
+		RegistrarModule: registrar::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -308,7 +571,9 @@ pub type SignedExtra = (
 	system::CheckEra<Runtime>,
 	system::CheckNonce<Runtime>,
 	system::CheckWeight<Runtime>,
-	transaction_payment::ChargeTransactionPayment<Runtime>
+	transaction_payment::ChargeTransactionPayment<Runtime>,
+	contracts::CheckBlockGasLimit<Runtime>,
+	CheckBusinessWrite,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -317,6 +582,48 @@ pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExt
 /// Executive: handles dispatch to the various modules.
 pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Runtime, AllModules>;
 
+/// Sign a call for the node tools submitting transactions, immortal and without a tip
+///
+/// @call	the call
+/// @nonce	the nonce of the signer
+/// @signer	the key pair of the signer
+/// @genesis_hash	the genesis hash of the chain
+#[cfg(feature = "std")]
+pub fn signed_extrinsic(call: Call, nonce: Index, signer: &primitives::sr25519::Pair, genesis_hash: Hash) -> UncheckedExtrinsic {
+	use codec::Encode;
+	use primitives::Pair;
+
+	let extra: SignedExtra = (
+		system::CheckVersion::<Runtime>::new(),
+		system::CheckGenesis::<Runtime>::new(),
+		system::CheckEra::<Runtime>::from(generic::Era::Immortal),
+		system::CheckNonce::<Runtime>::from(nonce),
+		system::CheckWeight::<Runtime>::new(),
+		transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+		Default::default(),
+		CheckBusinessWrite,
+	);
+	let payload = generic::SignedPayload::from_raw(
+		call,
+		extra,
+		(VERSION.spec_version, genesis_hash, genesis_hash, (), (), (), (), ()),
+	);
+	let signature = payload.using_encoded(|payload| signer.sign(payload));
+	let account = <Signature as Verify>::Signer::from(signer.public()).into_account();
+	let (call, extra, _) = payload.deconstruct();
+	UncheckedExtrinsic::new_signed(call, account.into(), signature.into(), extra)
+}
+
+/// Get the storage key of the nonce of an account, for the node tools submitting transactions
+///
+/// @account	the account
+#[cfg(feature = "std")]
+pub fn account_nonce_key(account: &AccountId) -> Vec<u8> {
+	use support::storage::StorageMap;
+
+	<system::AccountNonce<Runtime>>::hashed_key_for(account)
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -396,4 +703,127 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 	}
+
+	impl pistis_primitives::RegistryApi<Block, Hash, AccountId, BlockNumber, Moment> for Runtime {
+		fn resolve(node_hash: Hash) -> Option<name_service::ResolveRecord<AccountId>> {
+			NameServiceModule::resolve_of(node_hash)
+		}
+
+		fn names_of(account: AccountId) -> Vec<Hash> {
+			NameServiceModule::owned_nodes(account)
+		}
+
+		fn business_info(biz_hash: Hash) -> Option<BusinessDetails> {
+			BusinessModule::business_info(biz_hash)
+		}
+
+		fn product_by_seq_id(biz_hash: Hash, seq_id: Vec<u8>) -> Option<Product> {
+			BusinessModule::product_by_seq_id(biz_hash, seq_id)
+		}
+
+		fn list_products(biz_hash: Hash, start: u64, limit: u64) -> Vec<Hash> {
+			BusinessModule::list_products(biz_hash, start, limit)
+		}
+
+		fn product_history(product_hash: Hash) -> Vec<ProductInfo> {
+			BusinessModule::product_history(product_hash)
+		}
+
+		fn business_hash_for(creator: AccountId, index: u64) -> Option<Hash> {
+			BusinessModule::business_hash_for(creator, index)
+		}
+
+		fn registry_digest() -> business::RegistryDigest<Hash> {
+			BusinessModule::registry_digest()
+		}
+
+		fn product_storage_keys(biz_hash: Hash, seq_id: Vec<u8>) -> Vec<Vec<u8>> {
+			BusinessModule::product_storage_keys(biz_hash, seq_id)
+		}
+
+		fn product_info_proof(biz_hash: Hash, seq_id: Vec<u8>, index: u32) -> Option<pistis_primitives::merkle::InfoProof> {
+			BusinessModule::product_info_proof(biz_hash, seq_id, index)
+		}
+
+		fn provenance_commitment(biz_hash: Hash, seq_id: Vec<u8>) -> Option<business::VersionedProvenanceCommitment<Hash, BlockNumber>> {
+			BusinessModule::provenance_commitment(biz_hash, seq_id)
+		}
+
+		fn operator_stats(biz_hash: Hash, name_hash: Hash) -> business::OperatorActivity<BlockNumber> {
+			BusinessModule::operator_stats(biz_hash, name_hash)
+		}
+	}
+
+	impl pistis_primitives::RegistryDryRunApi<Block, Balance> for Runtime {
+		fn validate_call(
+			header: <Block as BlockT>::Header,
+			uxt: <Block as BlockT>::Extrinsic,
+		) -> pistis_primitives::CallValidation<Balance> {
+			use codec::Encode;
+			use pistis_primitives::CallOutcome;
+			use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
+
+			Executive::initialize_block(&header);
+			let info = TransactionPayment::query_info(uxt.clone(), uxt.encoded_size() as u32);
+			let outcome = match Executive::apply_extrinsic(uxt) {
+				Ok(Ok(())) => CallOutcome::Success,
+				Ok(Err(e)) => CallOutcome::Failed {
+					module: e.module,
+					error: e.error,
+					message: e.message.unwrap_or_default().as_bytes().to_vec(),
+				},
+				Err(e) => {
+					let reason = match e {
+						TransactionValidityError::Invalid(InvalidTransaction::Custom(code)) =>
+							business_write::custom_error_message(code),
+						_ => None,
+					};
+					CallOutcome::Invalid(reason.unwrap_or_else(|| e.into()).as_bytes().to_vec())
+				},
+			};
+			pistis_primitives::CallValidation {
+				outcome,
+				weight: info.weight,
+				partial_fee: info.partial_fee,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::{DispatchError, traits::Dispatchable};
+
+	fn new_test_ext() -> runtime_io::TestExternalities {
+		let mut ext = runtime_io::TestExternalities::default();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// The forced registry calls, on a node and a business that do not exist
+	fn force_calls() -> Vec<Call> {
+		vec![
+			Call::NameServiceModule(name_service::Call::force_clear_node(Hash::repeat_byte(1))),
+			Call::NameServiceModule(name_service::Call::force_set_owner(Hash::repeat_byte(1), AccountId::default())),
+			Call::BusinessModule(business::Call::suspend_business(Hash::repeat_byte(2), Hash::repeat_byte(3))),
+		]
+	}
+
+	#[test]
+	fn sudo_should_not_force_registry_calls() {
+		new_test_ext().execute_with(|| {
+			for call in force_calls() {
+				// Sudo dispatches its proposals as root, which the registry council origin rejects
+				let error: DispatchError = call.clone().dispatch(Origin::ROOT).unwrap_err().into();
+				assert_eq!(error.message, Some("Bad origin"));
+
+				assert!(Sudo::sudo(Origin::signed(Sudo::key()), Box::new(call)).is_ok());
+				assert_eq!(
+					System::events().pop().map(|record| record.event),
+					Some(Event::sudo(sudo::RawEvent::Sudid(false))),
+				);
+			}
+		});
+	}
 }
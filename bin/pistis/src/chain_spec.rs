@@ -1,8 +1,9 @@
-use primitives::{Pair, Public, sr25519};
+use primitives::{Pair, Public, crypto::Ss58Codec, ed25519, sr25519};
 use runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
 	SudoConfig, IndicesConfig, SystemConfig, WASM_BINARY, Signature,
-	CouncilConfig,
+	CouncilConfig, CouncilMembershipConfig, ContractsConfig, NameServiceModuleConfig,
+	RegistrarConfig, TreasuryConfig, BusinessModuleConfig, Runtime, business, registrar,
 };
 use aura_primitives::sr25519::{AuthorityId as AuraId};
 use grandpa_primitives::{AuthorityId as GrandpaId};
@@ -24,6 +25,21 @@ pub enum Alternative {
 	Development,
 	/// Whatever the current runtime is, with simple Alice/Bob auths.
 	LocalTestnet,
+	/// The shared staging network of the consortium, with three validators.
+	PistisStaging,
+}
+
+/// The public keys of the staging network, as SS58 addresses. The consortium fills them
+/// in before the launch; the `pistis-staging` preset fails to build while any is missing.
+mod staging {
+	/// The aura (sr25519) and grandpa (ed25519) keys of each validator
+	pub const VALIDATORS: &[(&str, &str)] = &[];
+	/// The sudo key, which also owns the name service root
+	pub const ROOT: &str = "";
+	/// The owner of the `pistis` scope node
+	pub const SCOPE_ADMIN: &str = "";
+	/// The initial council
+	pub const COUNCIL: &[&str] = &[];
 }
 
 /// Helper function to generate a crypto pair from seed
@@ -42,6 +58,62 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// Parse an SS58 address into an account ID
+fn account_id_from_ss58(address: &str) -> Result<AccountId, String> {
+	sr25519::Public::from_ss58check(address)
+		.map(|public| AccountPublic::from(public).into_account())
+		.map_err(|e| format!("Invalid account address {}: {:?}", address, e))
+}
+
+/// Parse the SS58 addresses of the aura and grandpa keys of a validator
+fn authority_keys_from_ss58((aura, grandpa): &(&str, &str)) -> Result<(AuraId, GrandpaId), String> {
+	Ok((
+		sr25519::Public::from_ss58check(aura)
+			.map_err(|e| format!("Invalid aura key {}: {:?}", aura, e))?
+			.into(),
+		ed25519::Public::from_ss58check(grandpa)
+			.map_err(|e| format!("Invalid grandpa key {}: {:?}", grandpa, e))?
+			.into(),
+	))
+}
+
+/// The staging network, from the checked-in public keys
+fn staging_spec() -> Result<ChainSpec, String> {
+	if staging::VALIDATORS.is_empty() || staging::ROOT.is_empty()
+		|| staging::SCOPE_ADMIN.is_empty() || staging::COUNCIL.is_empty()
+	{
+		return Err("The pistis-staging keys are not supplied yet, fill them in `chain_spec::staging`".into());
+	}
+	let authorities = staging::VALIDATORS.iter()
+		.map(authority_keys_from_ss58)
+		.collect::<Result<Vec<_>, _>>()?;
+	let root_key = account_id_from_ss58(staging::ROOT)?;
+	let scope_admin = account_id_from_ss58(staging::SCOPE_ADMIN)?;
+	let council = staging::COUNCIL.iter()
+		.map(|address| account_id_from_ss58(address))
+		.collect::<Result<Vec<_>, _>>()?;
+	let mut endowed_accounts = vec![root_key.clone(), scope_admin.clone()];
+	endowed_accounts.extend(council.iter().cloned());
+
+	Ok(ChainSpec::from_genesis(
+		"Pistis Staging",
+		"pistis_staging",
+		move || testnet_genesis(
+			authorities.clone(),
+			root_key.clone(),
+			scope_admin.clone(),
+			council.clone(),
+			endowed_accounts.clone(),
+			false,
+		),
+		vec![],
+		None,
+		Some("pistis-staging"),
+		None,
+		None
+	))
+}
+
 /// Helper function to generate an authority key for Aura
 pub fn get_authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(
@@ -61,6 +133,11 @@ impl Alternative {
 					get_authority_keys_from_seed("Alice"),
 				],
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
@@ -84,6 +161,12 @@ impl Alternative {
 					get_authority_keys_from_seed("Bob"),
 				],
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Ray"),
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
@@ -97,6 +180,7 @@ impl Alternative {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Ray"),
 				],
 				true),
 				vec![],
@@ -105,22 +189,37 @@ impl Alternative {
 				None,
 				None
 			),
+			Alternative::PistisStaging => staging_spec()?,
 		})
 	}
 
 	pub(crate) fn from(s: &str) -> Option<Self> {
 		match s {
 			"dev" => Some(Alternative::Development),
-			"" | "local" => Some(Alternative::LocalTestnet),
+			"" | "local" | "local-testnet" => Some(Alternative::LocalTestnet),
+			"pistis-staging" | "staging" => Some(Alternative::PistisStaging),
 			_ => None,
 		}
 	}
 }
 
+/// The genesis of a permissioned network
+///
+/// @initial_authorities	the aura and grandpa keys of the validators
+/// @root_key	the sudo key, which also owns the name service root
+/// @scope_admin	the owner of the `pistis` scope node, which may create businesses
+/// @council_members	the initial council
+/// @endowed_accounts	the accounts funded at genesis
+/// @enable_println	whether contracts may print, on development chains only
 fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
+	scope_admin: AccountId,
+	council_members: Vec<AccountId>,
 	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool) -> GenesisConfig {
+	enable_println: bool) -> GenesisConfig {
+	// Public names are registered under `pistis`, owned by the registrar account
+	let tld = registrar::Module::<Runtime>::tld_hash(b"pistis");
+	let registrar_account = registrar::Module::<Runtime>::account_id();
 	GenesisConfig {
 		system: Some(SystemConfig {
 			code: WASM_BINARY.to_vec(),
@@ -134,7 +233,7 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 			vesting: vec![],
 		}),
 		sudo: Some(SudoConfig {
-			key: root_key,
+			key: root_key.clone(),
 		}),
 		aura: Some(AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -142,10 +241,43 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
 		}),
+		// The council members are managed by the membership module, which initializes them
 		collective_Instance1: Some(CouncilConfig {
-			members: endowed_accounts.iter().cloned()
-				.collect::<Vec<_>>()[..5].to_vec(),
+			members: vec![],
 			phantom: Default::default(),
 		}),
+		contracts: Some(ContractsConfig {
+			current_schedule: contracts::Schedule {
+				enable_println, // this should only be enabled on development chains
+				..Default::default()
+			},
+			gas_price: 1,
+		}),
+		membership_Instance1: Some(CouncilMembershipConfig {
+			members: council_members,
+			phantom: Default::default(),
+		}),
+		name_service: Some(NameServiceModuleConfig {
+			root_owner: Some(root_key),
+			nodes: vec![(
+				business::Module::<Runtime>::default_scope_hash(),
+				scope_admin.clone(),
+				scope_admin,
+			), (
+				tld,
+				registrar_account.clone(),
+				registrar_account,
+			)],
+		}),
+		business: Some(BusinessModuleConfig {
+			scopes: vec![(
+				business::Module::<Runtime>::default_scope_hash(),
+				business::Module::<Runtime>::default_scope_hash(),
+			)],
+		}),
+		treasury: Some(TreasuryConfig::default()),
+		registrar: Some(RegistrarConfig {
+			tld: tld,
+		}),
 	}
 }
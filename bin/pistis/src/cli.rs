@@ -3,11 +3,54 @@ use futures::{future::{select, Map}, FutureExt, TryFutureExt, channel::oneshot,
 use std::cell::RefCell;
 use tokio::runtime::Runtime;
 pub use sc_cli::{VersionInfo, IntoExit, error};
-use sc_cli::{display_role, informant, parse_and_prepare, ParseAndPrepare, NoCustom};
-use sc_service::{AbstractService, Roles as ServiceRoles, Configuration};
+use sc_cli::{display_role, impl_augment_clap, informant, parse_and_prepare, GetLogFilter, ParseAndPrepare, NoCustom};
+use sc_service::{AbstractService, Roles as ServiceRoles, Configuration, ServiceBuilderCommand};
 use aura_primitives::sr25519::{AuthorityPair as AuraPair};
-use crate::chain_spec;
+use crate::{
+	chain_spec, export::ExportRegistryCmd, inspect::InspectCmd,
+	maintenance::{PurgeRegistryCmd, RevertRegistryCmd}, rpc::RpcMethods, seed::SeedRegistryCmd,
+};
+use sp_runtime::generic::BlockId;
 use log::info;
+use structopt::StructOpt;
+
+/// Custom subcommands.
+#[derive(Clone, Debug, StructOpt)]
+pub enum CustomSubcommands {
+	/// Calculate the hashes the runtime derives on chain.
+	#[structopt(name = "inspect")]
+	Inspect(InspectCmd),
+	/// Export the businesses and products of the local database.
+	#[structopt(name = "export-registry")]
+	ExportRegistry(ExportRegistryCmd),
+	/// Remove the chain data of the local node but its keystore.
+	#[structopt(name = "purge-registry")]
+	PurgeRegistry(PurgeRegistryCmd),
+	/// Revert the last blocks of the local chain, reporting the best block.
+	#[structopt(name = "revert-registry")]
+	RevertRegistry(RevertRegistryCmd),
+	/// Submit demo names, businesses and products to a development node.
+	#[structopt(name = "seed-registry")]
+	SeedRegistry(SeedRegistryCmd),
+}
+
+/// Custom arguments of the run command.
+#[derive(Clone, Debug, Default, StructOpt)]
+pub struct RunArgs {
+	/// The RPC methods to serve: `auto`, `safe` or `unsafe`. The unsafe methods, such as the
+	/// dry runs of `registry_validateCall`, execute at the cost of the node. `auto` serves them
+	/// only while the RPC servers listen on localhost, `unsafe` serves them on any interface.
+	#[structopt(long = "rpc-methods", default_value = "auto")]
+	pub rpc_methods: RpcMethods,
+}
+
+impl_augment_clap!(RunArgs);
+
+impl GetLogFilter for CustomSubcommands {
+	fn get_log_filter(&self) -> Option<String> {
+		None
+	}
+}
 
 /// Parse command line arguments into service configuration.
 pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()> where
@@ -16,9 +59,9 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 	E: IntoExit,
 {
 	type Config<T> = Configuration<(), T>;
-	match parse_and_prepare::<NoCustom, NoCustom, _>(&version, "substrate-node", args) {
+	match parse_and_prepare::<CustomSubcommands, RunArgs, _>(&version, "substrate-node", args) {
 		ParseAndPrepare::Run(cmd) => cmd.run(load_spec, exit,
-		|exit, _cli_args, _custom_args, config: Config<_>| {
+		|exit, _cli_args, run_args: RunArgs, config: Config<_>| {
 			info!("{}", version.name);
 			info!("  version {}", config.full_version());
 			info!("  by {}, 2017, 2018", version.author);
@@ -34,7 +77,7 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 				),
 				_ => run_until_exit(
 					runtime,
-					service::new_full(config)?,
+					service::new_full(config, run_args.rpc_methods)?,
 					exit
 				),
 			}
@@ -49,7 +92,51 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 		ParseAndPrepare::PurgeChain(cmd) => cmd.run(load_spec),
 		ParseAndPrepare::RevertChain(cmd) => cmd.run_with_builder(|config: Config<_>|
 			Ok(new_full_start!(config).0), load_spec),
-		ParseAndPrepare::CustomCommand(_) => Ok(())
+		ParseAndPrepare::CustomCommand(CustomSubcommands::Inspect(cmd)) => cmd.run().map_err(Into::into),
+		ParseAndPrepare::CustomCommand(CustomSubcommands::ExportRegistry(cmd)) => {
+			let config: Config<_> = sc_cli::create_config_with_db_path(
+				load_spec,
+				&cmd.shared_params,
+				&version,
+			)?;
+
+			let builder = new_full_start!(config).0;
+			let client = builder.client();
+			let at = match cmd.at {
+				Some(number) => BlockId::Number(number),
+				None => BlockId::Hash(client.info().chain.best_hash),
+			};
+			let state = client.state_at(&at).map_err(|e| format!("{:?}", e))?;
+			cmd.run(&state).map_err(Into::into)
+		},
+		ParseAndPrepare::CustomCommand(CustomSubcommands::PurgeRegistry(cmd)) => {
+			let config: Config<_> = sc_cli::create_config_with_db_path(
+				load_spec,
+				&cmd.shared_params,
+				&version,
+			)?;
+
+			let chain_dir = config.in_chain_config_dir("").ok_or("The chain has no data directory".to_string())?;
+			cmd.run(&chain_dir).map_err(Into::into)
+		},
+		ParseAndPrepare::CustomCommand(CustomSubcommands::RevertRegistry(cmd)) => {
+			let config: Config<_> = sc_cli::create_config_with_db_path(
+				load_spec,
+				&cmd.shared_params,
+				&version,
+			)?;
+
+			let builder = new_full_start!(config).0;
+			let best = builder.client().info().chain.best_number;
+			if cmd.confirm(best)? {
+				builder.revert_chain(cmd.num)?;
+				println!("Best block #{} reverted to #{}", best, builder.client().info().chain.best_number);
+			} else {
+				println!("Aborted");
+			}
+			Ok(())
+		},
+		ParseAndPrepare::CustomCommand(CustomSubcommands::SeedRegistry(cmd)) => cmd.run().map_err(Into::into),
 	}?;
 
 	Ok(())
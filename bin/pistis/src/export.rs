@@ -0,0 +1,279 @@
+//! The `export-registry` subcommand, dumping the business registry of a local database.
+//!
+//! The registry is read with the storage getters of the runtime, run against the state of
+//! the requested block, so the export follows the storage layout of the native runtime.
+//! Products of archived businesses are not exported, their indices are pruned on archival.
+//! The `extra` of an info is exported as text if it holds raw UTF-8, as `0x` prefixed hex
+//! otherwise, its `extra_encoding` telling which.
+
+use std::{fs, io::{self, Write}, path::PathBuf, str::FromStr};
+
+use primitives::{Blake2Hasher, crypto::Ss58Codec, hexdisplay::HexDisplay};
+use runtime::{BlockNumber, Hash, Moment, Runtime, business::{self, EXTRA_VERSION_RAW}};
+use sc_cli::SharedParams;
+use serde::Serialize;
+use sp_state_machine::{Backend, Ext, InMemoryChangesTrieStorage, OverlayedChanges};
+use structopt::StructOpt;
+
+type Business = business::Module<Runtime>;
+
+/// The formats the registry can be exported to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+	/// A single JSON document
+	Json,
+	/// A directory of CSV tables
+	Csv,
+}
+
+impl FromStr for ExportFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		match s {
+			"json" => Ok(ExportFormat::Json),
+			"csv" => Ok(ExportFormat::Csv),
+			_ => Err(format!("Unknown export format {}, expected json or csv", s)),
+		}
+	}
+}
+
+/// The `export-registry` command used to dump businesses and products.
+#[derive(Clone, Debug, StructOpt)]
+pub struct ExportRegistryCmd {
+	/// The block to export the registry at, the best block if not given
+	#[structopt(long = "at")]
+	pub at: Option<BlockNumber>,
+
+	/// The export format, `json` or `csv`
+	#[structopt(long = "format", default_value = "json")]
+	pub format: ExportFormat,
+
+	/// The file to write the JSON document to, or the directory to write the CSV tables to.
+	/// JSON is written to stdout if not given.
+	#[structopt(long = "output", parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+/// A business of the registry
+#[derive(Serialize)]
+struct BusinessRow {
+	hash: String,
+	creator: String,
+	owner: String,
+	name: String,
+	expiration: BlockNumber,
+	profile: Option<String>,
+	category: u16,
+	parent: Option<String>,
+	scope: String,
+	archived: bool,
+}
+
+/// A whitelist entry of a business
+#[derive(Serialize)]
+struct WhitelistRow {
+	business: String,
+	operator: String,
+	valid_until: Option<BlockNumber>,
+}
+
+/// An info in the history of a product
+#[derive(Serialize)]
+struct ProductInfoRow {
+	business: String,
+	product: String,
+	seq_id: String,
+	index: usize,
+	creator: String,
+	created_at: BlockNumber,
+	timestamp: Moment,
+	kind: String,
+	data_hash_algo: String,
+	data_hash: String,
+	extra_version: u8,
+	extra_encoding: String,
+	extra: String,
+}
+
+/// The exported registry
+#[derive(Serialize)]
+struct Registry {
+	businesses: Vec<BusinessRow>,
+	whitelists: Vec<WhitelistRow>,
+	product_infos: Vec<ProductInfoRow>,
+}
+
+impl ExportRegistryCmd {
+	/// Export the registry stored in a state
+	///
+	/// @state	the state of the block to export
+	pub fn run<S: Backend<Blake2Hasher>>(&self, state: &S) -> Result<(), String> {
+		let mut overlay = OverlayedChanges::default();
+		let mut ext = Ext::new(
+			&mut overlay,
+			state,
+			None::<&InMemoryChangesTrieStorage<Blake2Hasher, BlockNumber>>,
+			None,
+		);
+		let registry = sp_externalities::set_and_run_with_externalities(&mut ext, collect_registry);
+
+		match self.format {
+			ExportFormat::Json => {
+				let json = serde_json::to_string_pretty(&registry).map_err(|e| e.to_string())?;
+				let written = match &self.output {
+					Some(path) => fs::write(path, json),
+					None => writeln!(io::stdout(), "{}", json),
+				};
+				written.map_err(|e| e.to_string())
+			},
+			ExportFormat::Csv => {
+				let dir = self.output.as_ref().ok_or("The CSV export requires an --output directory")?;
+				fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+				write_csv(dir.join("businesses.csv"),
+					&["hash", "creator", "owner", "name", "expiration", "profile", "category", "parent", "scope", "archived"],
+					registry.businesses.iter().map(|b| vec![
+						b.hash.clone(), b.creator.clone(), b.owner.clone(), b.name.clone(),
+						b.expiration.to_string(), b.profile.clone().unwrap_or_default(), b.category.to_string(),
+						b.parent.clone().unwrap_or_default(), b.scope.clone(), b.archived.to_string(),
+					]),
+				)?;
+				write_csv(dir.join("whitelists.csv"),
+					&["business", "operator", "valid_until"],
+					registry.whitelists.iter().map(|w| vec![
+						w.business.clone(), w.operator.clone(),
+						w.valid_until.map(|n| n.to_string()).unwrap_or_default(),
+					]),
+				)?;
+				write_csv(dir.join("product_infos.csv"),
+					&["business", "product", "seq_id", "index", "creator", "created_at", "timestamp", "kind", "data_hash_algo", "data_hash", "extra_version", "extra_encoding", "extra"],
+					registry.product_infos.iter().map(|i| vec![
+						i.business.clone(), i.product.clone(), i.seq_id.clone(), i.index.to_string(),
+						i.creator.clone(), i.created_at.to_string(), i.timestamp.to_string(), i.kind.clone(), i.data_hash_algo.clone(), i.data_hash.clone(),
+						i.extra_version.to_string(), i.extra_encoding.clone(), i.extra.clone(),
+					]),
+				)
+			},
+		}
+	}
+}
+
+/// Walk the business index and collect the registry, with the externalities set
+fn collect_registry() -> Registry {
+	let mut registry = Registry {
+		businesses: Vec::new(),
+		whitelists: Vec::new(),
+		product_infos: Vec::new(),
+	};
+
+	for index in 0..Business::business_count() {
+		let biz_hash = Business::business_index(index);
+		let (business, archived) = match Business::archived_business_of(biz_hash) {
+			Some(business) => (business, true),
+			None => (Business::business_of(biz_hash), false),
+		};
+
+		registry.businesses.push(BusinessRow {
+			hash: hex(&biz_hash),
+			creator: business.creator.to_ss58check(),
+			owner: hex(&business.owner),
+			name: String::from_utf8_lossy(&business.name).into_owned(),
+			expiration: business.expiration,
+			profile: business.profile.as_ref().map(hex),
+			category: business.category,
+			parent: business.parent.as_ref().map(hex),
+			scope: hex(&business.scope),
+			archived,
+		});
+		registry.whitelists.extend(business.whitelist.iter().map(|entry| WhitelistRow {
+			business: hex(&biz_hash),
+			operator: hex(&entry.name_hash),
+			valid_until: entry.valid_until,
+		}));
+
+		for product_index in 0..Business::product_count(biz_hash) {
+			let product_hash = Business::business_product_index(biz_hash, product_index);
+			let product = Business::product_of(product_hash);
+			let seq_id = String::from_utf8_lossy(&product.seq_id).into_owned();
+			registry.product_infos.extend(product.infos.iter().enumerate().map(|(index, info)| {
+				let (extra, extra_encoding) = extra_field(info.extra_version, &info.extra);
+				ProductInfoRow {
+					business: hex(&biz_hash),
+					product: hex(&product_hash),
+					seq_id: seq_id.clone(),
+					index,
+					creator: info.creator.to_ss58check(),
+					created_at: info.created_at,
+					timestamp: info.timestamp,
+					kind: format!("{:?}", info.kind),
+					data_hash_algo: format!("{:?}", info.data_hash.algo),
+					data_hash: format!("0x{}", HexDisplay::from(&info.data_hash.digest)),
+					extra_version: info.extra_version,
+					extra_encoding: extra_encoding.to_string(),
+					extra,
+				}
+			}));
+		}
+	}
+
+	registry
+}
+
+/// Format a hash as `0x` prefixed hex
+fn hex(hash: &Hash) -> String {
+	format!("{:?}", hash)
+}
+
+/// Format the `extra` of an info as text if it holds raw UTF-8, as `0x` prefixed hex
+/// otherwise, with the encoding used
+///
+/// @version	the `extra_version` of the info
+/// @extra	the extra
+fn extra_field(version: u8, extra: &[u8]) -> (String, &'static str) {
+	match std::str::from_utf8(extra) {
+		Ok(text) if version == EXTRA_VERSION_RAW => (text.to_string(), "utf8"),
+		_ => (format!("0x{}", HexDisplay::from(&extra)), "hex"),
+	}
+}
+
+/// Quote a CSV field if it holds a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+	if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+/// Write a CSV table with a header row
+fn write_csv(path: PathBuf, header: &[&str], rows: impl Iterator<Item = Vec<String>>) -> Result<(), String> {
+	let mut out = header.join(",");
+	out.push('\n');
+	for row in rows {
+		let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+		out.push_str(&fields.join(","));
+		out.push('\n');
+	}
+	fs::write(&path, out).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime::business::{ExtraPayload, EXTRA_VERSION_STRUCTURED};
+
+	#[test]
+	fn extra_should_be_hex_unless_raw_text() {
+		assert_eq!(extra_field(EXTRA_VERSION_RAW, b"{\"lot\":7}"), ("{\"lot\":7}".to_string(), "utf8"));
+		assert_eq!(extra_field(EXTRA_VERSION_RAW, &[0xff, 0x00]), ("0xff00".to_string(), "hex"));
+
+		let structured = ExtraPayload::Structured(vec![(1, b"7".to_vec())]).into_extra();
+		let (extra, encoding) = extra_field(EXTRA_VERSION_STRUCTURED, &structured);
+		assert_eq!(encoding, "hex");
+		assert_eq!(extra, format!("0x{}", HexDisplay::from(&structured)));
+	}
+}
@@ -0,0 +1,153 @@
+//! The `inspect` subcommands, calculating the hashes the runtime derives on chain.
+//!
+//! The calculators call the runtime code itself, so their results match the chain:
+//! names are normalized by the name service before hashing, and labels are hashed as raw
+//! bytes, not SCALE encoded strings.
+
+use std::str::FromStr;
+
+use primitives::{H256, crypto::Ss58Codec};
+use runtime::{AccountId, Runtime, business, name_service::{self, NameHasher, NameServiceResolver}};
+use structopt::StructOpt;
+
+/// The `inspect` subcommands.
+#[derive(Clone, Debug, StructOpt)]
+pub enum InspectCmd {
+	/// The name hash of a dotted name, e.g. `alice.pistis`
+	#[structopt(name = "namehash")]
+	Namehash {
+		/// The dotted name, normalized label by label
+		name: String,
+	},
+	/// The hash of a business, derived when it is created
+	#[structopt(name = "business-hash")]
+	BusinessHash {
+		/// The SS58 address of the account creating the business
+		#[structopt(long = "creator")]
+		creator: String,
+		/// The name hash of the owner
+		#[structopt(long = "owner")]
+		owner: String,
+		/// The business name, normalized as on creation
+		#[structopt(long = "name")]
+		name: String,
+		/// The number of businesses the creator created before
+		#[structopt(long = "index", default_value = "0")]
+		index: u64,
+	},
+	/// The hash of a product, derived from its business and sequence id
+	#[structopt(name = "product-hash")]
+	ProductHash {
+		/// The business hash
+		biz_hash: String,
+		/// The sequence id, as printed on the label
+		seq_id: String,
+	},
+}
+
+impl InspectCmd {
+	/// Calculate the hash and print it
+	pub fn run(&self) -> Result<(), String> {
+		println!("{:?}", self.hash()?);
+		Ok(())
+	}
+
+	/// Calculate the hash the runtime derives
+	fn hash(&self) -> Result<H256, String> {
+		Ok(match self {
+			InspectCmd::Namehash { name } => {
+				let node = <name_service::Module<Runtime> as NameHasher<Runtime>>::namehash(&normalize_name(name)?);
+				H256::from_slice(&node)
+			},
+			InspectCmd::BusinessHash { creator, owner, name, index } => {
+				let creator = AccountId::from_ss58check(creator)
+					.map_err(|e| format!("Invalid creator address {}: {:?}", creator, e))?;
+				let name = <name_service::Module<Runtime> as NameServiceResolver<Runtime>>::normalize_name(name.as_bytes())
+					.map_err(|e| format!("Invalid business name {}: {}", name, e))?;
+				business::Module::<Runtime>::business_hash(creator, parse_hash(owner)?, name, *index)
+			},
+			InspectCmd::ProductHash { biz_hash, seq_id } => {
+				business::Module::<Runtime>::product_hash(parse_hash(biz_hash)?, seq_id.as_bytes().to_vec())
+			},
+		})
+	}
+}
+
+/// Normalize each label of a dotted name as the name service does on registration.
+/// The empty name is the root node.
+fn normalize_name(name: &str) -> Result<String, String> {
+	if name.is_empty() {
+		return Ok(String::new());
+	}
+	let labels = name.split('.')
+		.map(|label| {
+			let label = name_service::Module::<Runtime>::normalize_label(label.as_bytes())
+				.map_err(|e| format!("Invalid label {} in {}: {}", label, name, e))?;
+			String::from_utf8(label).map_err(|e| format!("Invalid label in {}: {}", name, e))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+	Ok(labels.join("."))
+}
+
+/// Parse a hex encoded hash, with or without the `0x` prefix
+fn parse_hash(hash: &str) -> Result<H256, String> {
+	H256::from_str(hash.trim_start_matches("0x"))
+		.map_err(|e| format!("Invalid hash {}: {:?}", hash, e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime::{NameServiceModule, NameServiceModuleConfig, Origin};
+	use sp_runtime::{BuildStorage, traits::{BlakeTwo256, Hash as HashT}};
+
+	fn account(seed: u8) -> AccountId {
+		AccountId::from([seed; 32])
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		NameServiceModuleConfig {
+			root_owner: Some(account(1)),
+			nodes: vec![],
+		}.build_storage().unwrap().into()
+	}
+
+	fn namehash(name: &str) -> H256 {
+		InspectCmd::Namehash { name: name.into() }.hash().unwrap()
+	}
+
+	#[test]
+	fn namehash_should_match_registered_nodes() {
+		new_test_ext().execute_with(|| {
+			let root = H256::default();
+			assert_eq!(namehash(""), root);
+
+			assert!(NameServiceModule::set_subnode_label_owner(Origin::signed(account(1)), root, b"Pistis".to_vec(), account(1)).is_ok());
+			let tld = NameServiceModule::subnode_hash(root, BlakeTwo256::hash(b"pistis"));
+			assert!(NameServiceModule::set_subnode_label_owner(Origin::signed(account(1)), tld, b"Acme".to_vec(), account(2)).is_ok());
+
+			for name in &["acme.pistis", "Acme.pistis", "ACME.Pistis"] {
+				let node = NameServiceModule::node_of(namehash(name)).expect("the node is registered");
+				assert_eq!(node.owner, account(2));
+			}
+			assert!(InspectCmd::Namehash { name: "acme..pistis".into() }.hash().is_err());
+		});
+	}
+
+	#[test]
+	fn business_hash_should_use_the_normalized_name() {
+		let owner = H256::repeat_byte(7);
+		let business_hash = |name: &str| InspectCmd::BusinessHash {
+			creator: account(3).to_ss58check(),
+			owner: format!("{:?}", owner),
+			name: name.into(),
+			index: 1,
+		}.hash().unwrap();
+
+		let normalized = <NameServiceModule as NameServiceResolver<Runtime>>::normalize_name(b"Acme Tea").unwrap();
+		assert_eq!(normalized, b"acme tea".to_vec());
+		let expected = business::Module::<Runtime>::business_hash(account(3), owner, normalized, 1);
+		assert_eq!(business_hash("Acme Tea"), expected);
+		assert_eq!(business_hash("acme tea"), expected);
+	}
+}
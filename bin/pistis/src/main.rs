@@ -7,6 +7,11 @@ mod chain_spec;
 #[macro_use]
 mod service;
 mod cli;
+mod export;
+mod inspect;
+mod maintenance;
+mod rpc;
+mod seed;
 
 pub use sc_cli::{VersionInfo, IntoExit, error};
 
@@ -0,0 +1,163 @@
+//! The `purge-registry` and `revert-registry` subcommands, resetting a local node between
+//! development runs.
+//!
+//! Unlike `purge-chain`, which removes the database only, `purge-registry` removes
+//! everything the node stored for the chain but its keystore, so that a validator restarts
+//! from genesis with its session keys. `revert-registry` wraps `revert`, taking the number
+//! of blocks explicitly and printing the best block before and after.
+
+use std::{fs, io::{self, Write}, path::Path};
+
+use runtime::BlockNumber;
+use sc_cli::SharedParams;
+use structopt::StructOpt;
+
+/// The directory of the chain data the purge keeps
+const KEYSTORE_DIR: &str = "keystore";
+
+/// The `purge-registry` command used to remove the chain data but the keystore.
+#[derive(Clone, Debug, StructOpt)]
+pub struct PurgeRegistryCmd {
+	/// Skip interactive prompt by answering yes automatically.
+	#[structopt(short = "y")]
+	pub yes: bool,
+
+	/// Keep the network directory as well, and with it the node key and peer identity.
+	#[structopt(long = "keep-network")]
+	pub keep_network: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl PurgeRegistryCmd {
+	/// Remove the entries of the chain directory but the keystore
+	///
+	/// @chain_dir	the directory of the chain data
+	pub fn run(&self, chain_dir: &Path) -> Result<(), String> {
+		let entries = match fs::read_dir(chain_dir) {
+			Ok(entries) => entries,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+				eprintln!("{:?} did not exist.", chain_dir);
+				return Ok(());
+			},
+			Err(e) => return Err(format!("Failed to read {:?}: {}", chain_dir, e)),
+		};
+		let mut paths = Vec::new();
+		for entry in entries {
+			let entry = entry.map_err(|e| format!("Failed to read {:?}: {}", chain_dir, e))?;
+			let name = entry.file_name();
+			if name == KEYSTORE_DIR || (self.keep_network && name == "network") {
+				continue;
+			}
+			paths.push(entry.path());
+		}
+		if paths.is_empty() {
+			println!("Nothing to remove in {:?}.", chain_dir);
+			return Ok(());
+		}
+
+		if !self.yes && !confirm(&format!("Are you sure to remove {:?}?", paths))? {
+			println!("Aborted");
+			return Ok(());
+		}
+
+		for path in paths {
+			let removed = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+			removed.map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+			println!("{:?} removed.", path);
+		}
+		Ok(())
+	}
+}
+
+/// The `revert-registry` command used to revert the last blocks of the local chain.
+#[derive(Clone, Debug, StructOpt)]
+pub struct RevertRegistryCmd {
+	/// Number of blocks to revert, finalized blocks excepted.
+	pub num: BlockNumber,
+
+	/// Skip interactive prompt by answering yes automatically.
+	#[structopt(short = "y")]
+	pub yes: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl RevertRegistryCmd {
+	/// Ask for confirmation before reverting from the best block
+	///
+	/// @best	the number of the best block
+	pub fn confirm(&self, best: BlockNumber) -> Result<bool, String> {
+		if self.yes || self.num == 0 {
+			return Ok(self.num != 0);
+		}
+		confirm(&format!("Are you sure to revert {} blocks from #{}?", self.num, best))
+	}
+}
+
+/// Ask a yes or no question on stdout, no being the default
+///
+/// @question	the question
+fn confirm(question: &str) -> Result<bool, String> {
+	print!("{} [y/N]: ", question);
+	io::stdout().flush().map_err(|e| e.to_string())?;
+
+	let mut input = String::new();
+	io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+	Ok(match input.trim().chars().nth(0) {
+		Some('y') | Some('Y') => true,
+		_ => false,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Fill a chain directory with the entries a node stores
+	fn chain_dir() -> tempfile::TempDir {
+		let dir = tempfile::tempdir().unwrap();
+		for sub in &["db", "keystore", "network"] {
+			fs::create_dir(dir.path().join(sub)).unwrap();
+			fs::write(dir.path().join(sub).join("data"), b"data").unwrap();
+		}
+		fs::write(dir.path().join("LOCK"), b"").unwrap();
+		dir
+	}
+
+	/// List the entries left in a chain directory
+	fn entries(dir: &Path) -> Vec<String> {
+		let mut names: Vec<String> = fs::read_dir(dir).unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.collect();
+		names.sort();
+		names
+	}
+
+	#[test]
+	fn purge_should_keep_the_keystore() {
+		let dir = chain_dir();
+		PurgeRegistryCmd::from_iter(&["purge-registry", "-y"]).run(dir.path()).unwrap();
+		assert_eq!(entries(dir.path()), vec!["keystore"]);
+		assert_eq!(fs::read(dir.path().join("keystore").join("data")).unwrap(), b"data");
+	}
+
+	#[test]
+	fn purge_should_keep_the_network_if_asked() {
+		let dir = chain_dir();
+		PurgeRegistryCmd::from_iter(&["purge-registry", "-y", "--keep-network"]).run(dir.path()).unwrap();
+		assert_eq!(entries(dir.path()), vec!["keystore", "network"]);
+		assert_eq!(fs::read(dir.path().join("network").join("data")).unwrap(), b"data");
+	}
+
+	#[test]
+	fn purge_should_skip_a_missing_directory() {
+		let dir = tempfile::tempdir().unwrap();
+		let missing = dir.path().join("chains");
+		assert_eq!(PurgeRegistryCmd::from_iter(&["purge-registry", "-y"]).run(&missing), Ok(()));
+	}
+}
@@ -0,0 +1,113 @@
+//! The pistis specific RPC methods, served next to the `sc-rpc` core methods.
+
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
+
+use futures01::{Future, future::{Executor, ExecuteError, ExecuteErrorKind}, sync::mpsc};
+use runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Moment, Runtime};
+use sp_runtime::traits::ProvideRuntimeApi;
+
+/// A task of an RPC subscription
+pub type SubscriptionTask = Box<dyn Future<Item = (), Error = ()> + Send>;
+
+/// Queues the tasks of the RPC subscriptions.
+///
+/// The RPC extensions are built before the service which runs their tasks, so the queued
+/// tasks are spawned once `new_full` started the service.
+#[derive(Clone)]
+pub struct SubscriptionTasks(mpsc::UnboundedSender<SubscriptionTask>);
+
+impl SubscriptionTasks {
+	/// Create the queue and the receiving end the service spawns the tasks from.
+	pub fn new() -> (Self, mpsc::UnboundedReceiver<SubscriptionTask>) {
+		let (tx, rx) = mpsc::unbounded();
+		(SubscriptionTasks(tx), rx)
+	}
+}
+
+impl Executor<SubscriptionTask> for SubscriptionTasks {
+	fn execute(&self, task: SubscriptionTask) -> Result<(), ExecuteError<SubscriptionTask>> {
+		self.0.unbounded_send(task)
+			.map_err(|e| ExecuteError::new(ExecuteErrorKind::Shutdown, e.into_inner()))
+	}
+}
+
+/// The RPC methods the node serves, as chosen with `--rpc-methods`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RpcMethods {
+	/// Serve the unsafe methods only while the HTTP and WebSocket servers listen on localhost
+	Auto,
+	/// Refuse the unsafe methods
+	Safe,
+	/// Serve the unsafe methods whichever interface the servers listen on
+	Unsafe,
+}
+
+impl Default for RpcMethods {
+	fn default() -> Self {
+		RpcMethods::Auto
+	}
+}
+
+impl FromStr for RpcMethods {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		match s.to_ascii_lowercase().as_str() {
+			"auto" => Ok(RpcMethods::Auto),
+			"safe" => Ok(RpcMethods::Safe),
+			"unsafe" => Ok(RpcMethods::Unsafe),
+			_ => Err(format!("Unknown RPC methods {}, expected auto, safe or unsafe", s)),
+		}
+	}
+}
+
+/// Whether the unsafe RPC methods are refused. By default they are as the HTTP or WebSocket
+/// server listens beyond localhost, unless the operator opted in with `--rpc-methods unsafe`.
+pub fn deny_unsafe(
+	rpc_http: Option<SocketAddr>,
+	rpc_ws: Option<SocketAddr>,
+	methods: RpcMethods,
+) -> business_rpc::DenyUnsafe {
+	let external = |addr: Option<SocketAddr>| addr.map_or(false, |addr| !addr.ip().is_loopback());
+	match methods {
+		RpcMethods::Auto if external(rpc_http) || external(rpc_ws) => business_rpc::DenyUnsafe::Yes,
+		RpcMethods::Auto | RpcMethods::Unsafe => business_rpc::DenyUnsafe::No,
+		RpcMethods::Safe => business_rpc::DenyUnsafe::Yes,
+	}
+}
+
+/// Instantiate all RPC extensions.
+pub fn create<C>(
+	client: Arc<C>,
+	tasks: SubscriptionTasks,
+	deny_unsafe: business_rpc::DenyUnsafe,
+) -> jsonrpc_core::IoHandler<sc_rpc::Metadata> where
+	C: ProvideRuntimeApi,
+	C: sc_client::blockchain::HeaderBackend<Block>,
+	C: sc_client::BlockchainEvents<Block>,
+	C: business_rpc::ReadProofProvider<Block>,
+	C: Send + Sync + 'static,
+	C::Api: business_rpc::RegistryRuntimeApi<Block, Hash, AccountId, BlockNumber, Moment>,
+	C::Api: business_rpc::RegistryDryRunRuntimeApi<Block, Balance>,
+{
+	use business_rpc::{
+		Business, BusinessApi, ProductEvents, ProductEventsApi, RegistryDryRun, RegistryDryRunApi,
+		RegistryProof, RegistryProofApi,
+	};
+
+	let subscriptions = sc_rpc::Subscriptions::new(Arc::new(tasks));
+	let mut io = jsonrpc_core::IoHandler::default();
+	io.extend_with(
+		BusinessApi::to_delegate(Business::<_, Block, AccountId, BlockNumber, Moment>::new(client.clone()))
+	);
+	io.extend_with(
+		RegistryProofApi::to_delegate(RegistryProof::<_, Block, AccountId, BlockNumber, Moment>::new(client.clone()))
+	);
+	io.extend_with(
+		RegistryDryRunApi::to_delegate(RegistryDryRun::<_, Block, Balance>::new(client.clone(), deny_unsafe))
+	);
+	io.extend_with(
+		ProductEventsApi::to_delegate(ProductEvents::<_, Block, Runtime>::new(client, subscriptions))
+	);
+	io
+}
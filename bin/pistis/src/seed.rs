@@ -0,0 +1,238 @@
+//! The `seed-registry` subcommand, bootstrapping a demo registry on a development node.
+//!
+//! The calls are signed with the development accounts and submitted over the HTTP RPC of a
+//! running node, through the clients generated for its RPC APIs, each step waiting for its transactions to be included before the next:
+//! Alice, the root owner, registers `acme` and `acme-ops` for Bob, which resolves them to
+//! the Bob account; Alice, the scope admin, creates the `acme` business owned by `acme`;
+//! Bob funds its deposit pool, whitelists `acme-ops` and records products through it.
+//!
+//! The command refuses chains other than the development and local testnet ones, and is
+//! meant for a fresh chain: the names and sequence ids it registers are taken afterwards.
+
+use std::{
+	cell::{Cell, RefCell},
+	thread,
+	time::{Duration, Instant},
+};
+
+use business_rpc::BusinessClient;
+use codec::{Decode, Encode};
+use futures01::Future;
+use jsonrpc_core_client::{RpcChannel, RpcError, transports::http};
+use primitives::{Bytes, H256, Pair, sr25519, storage::StorageKey};
+use runtime::{
+	AccountId, BlockNumber, Call, DAYS, Hash, Header, Index, Runtime, SignedBlock,
+	business::{self, DataHash, InfoKind}, name_service::{self, NameHasher},
+};
+use sc_rpc::{author::AuthorClient, chain::ChainClient, state::StateClient, system::SystemClient};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::{MultiSigner, traits::{BlakeTwo256, Hash as HashT, IdentifyAccount}};
+use structopt::StructOpt;
+use tokio::runtime::Runtime as RpcRuntime;
+
+/// The chains the command may seed
+const DEV_CHAINS: [&str; 2] = ["Development", "Local Testnet"];
+
+/// The business the command creates
+const BUSINESS_NAME: &str = "acme";
+
+/// The name of the operator recording the products
+const OPERATOR_NAME: &str = "acme-ops";
+
+/// The balance Bob reserves into the deposit pool of the business
+const POOL_FUNDS: u128 = 1 << 20;
+
+/// How long a step waits for its transactions to be included
+const STEP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The `seed-registry` command used to bootstrap a demo environment.
+#[derive(Clone, Debug, StructOpt)]
+pub struct SeedRegistryCmd {
+	/// The HTTP RPC endpoint of the node.
+	#[structopt(long = "url", default_value = "http://127.0.0.1:9933")]
+	pub url: String,
+
+	/// The number of products to record.
+	#[structopt(long = "products", default_value = "3")]
+	pub products: u32,
+}
+
+impl SeedRegistryCmd {
+	/// Submit the script and check the registry it left
+	pub fn run(&self) -> Result<(), String> {
+		let node = Node::connect(&self.url)?;
+		let chain = node.call("system_chain", node.system.system_chain())?;
+		if !DEV_CHAINS.contains(&chain.as_str()) {
+			return Err(format!("Refusing to seed the {} chain, a development chain is expected", chain));
+		}
+		let genesis_hash = node.genesis_hash()?;
+
+		let alice = Signer::new(&node, "//Alice", genesis_hash)?;
+		let bob = Signer::new(&node, "//Bob", genesis_hash)?;
+		let owner = name_hash(BUSINESS_NAME);
+		let operator = name_hash(OPERATOR_NAME);
+
+		println!("Registering {} and {} for Bob", BUSINESS_NAME, OPERATOR_NAME);
+		for name in [BUSINESS_NAME, OPERATOR_NAME].iter() {
+			alice.submit(Call::NameServiceModule(name_service::Call::set_subnode_label_owner(
+				H256::default(), name.as_bytes().to_vec(), bob.account.clone(),
+			)))?;
+		}
+		alice.wait()?;
+		for node_hash in [owner, operator].iter() {
+			bob.submit(Call::NameServiceModule(name_service::Call::set_resolve_addr(*node_hash, bob.account.clone())))?;
+		}
+		bob.wait()?;
+
+		println!("Creating the {} business", BUSINESS_NAME);
+		let best = node.call("chain_getHeader", node.chain.header(None))?.ok_or("The node has no best block")?;
+		let expiration = best.number.saturating_add(30 * DAYS);
+		alice.submit(Call::BusinessModule(business::Call::create_business(
+			business::Module::<Runtime>::default_scope_hash(), owner, BUSINESS_NAME.as_bytes().to_vec(), expiration,
+		)))?;
+		alice.wait()?;
+		let biz_hash = node.last_business_of(&alice.account)?
+			.ok_or_else(|| format!("The {} business was not created", BUSINESS_NAME))?;
+		println!("Business {:?}", biz_hash);
+
+		println!("Recording {} products through {}", self.products, OPERATOR_NAME);
+		bob.submit(Call::BusinessModule(business::Call::fund_business(biz_hash, POOL_FUNDS)))?;
+		bob.submit(Call::BusinessModule(business::Call::add_business_whitelist(biz_hash, operator, None)))?;
+		bob.wait()?;
+		for index in 0..self.products {
+			let seq_id = format!("{:04}", index + 1).into_bytes();
+			let data_hash = DataHash::from_runtime_hash(&BlakeTwo256::hash(&seq_id));
+			bob.submit(Call::BusinessModule(business::Call::create_product(
+				operator, biz_hash, seq_id.clone(), data_hash.clone(), br#"{"demo":true}"#.to_vec(),
+			)))?;
+			bob.submit(Call::BusinessModule(business::Call::add_product_info(
+				operator, biz_hash, seq_id, InfoKind::Shipped, data_hash, Vec::new(),
+			)))?;
+		}
+		bob.wait()?;
+
+		for index in 0..self.products {
+			let seq_id = format!("{:04}", index + 1);
+			let product = node.call(
+				"business_productBySeqId",
+				node.business.product_by_seq_id(biz_hash, Bytes(seq_id.clone().into_bytes()), None),
+			)?;
+			if product.is_none() {
+				return Err(format!("Product {} was not recorded", seq_id));
+			}
+		}
+		println!("Seeded {} products", self.products);
+		Ok(())
+	}
+}
+
+/// Get the name hash of a name under the root
+fn name_hash(name: &str) -> H256 {
+	H256::from_slice(&<name_service::Module<Runtime> as NameHasher<Runtime>>::namehash(name))
+}
+
+/// A development account submitting transactions with consecutive nonces
+struct Signer<'a> {
+	node: &'a Node,
+	pair: sr25519::Pair,
+	account: AccountId,
+	genesis_hash: Hash,
+	nonce: Cell<Index>,
+}
+
+impl<'a> Signer<'a> {
+	/// Derive the account from a development seed and fetch its nonce
+	fn new(node: &'a Node, seed: &str, genesis_hash: Hash) -> Result<Self, String> {
+		let pair = sr25519::Pair::from_string(seed, None)
+			.map_err(|e| format!("Invalid seed {}: {:?}", seed, e))?;
+		let account: AccountId = MultiSigner::from(pair.public()).into_account();
+		let nonce = node.account_nonce(&account)?;
+		Ok(Signer { node, pair, account, genesis_hash, nonce: Cell::new(nonce) })
+	}
+
+	/// Sign a call with the next nonce and submit it
+	fn submit(&self, call: Call) -> Result<(), String> {
+		let nonce = self.nonce.get();
+		let extrinsic = runtime::signed_extrinsic(call, nonce, &self.pair, self.genesis_hash);
+		self.node.call("author_submitExtrinsic", self.node.author.submit_extrinsic(Bytes(extrinsic.encode())))?;
+		self.nonce.set(nonce + 1);
+		Ok(())
+	}
+
+	/// Wait for the transactions submitted to be included, failed dispatches included
+	fn wait(&self) -> Result<(), String> {
+		let started = Instant::now();
+		while self.node.account_nonce(&self.account)? < self.nonce.get() {
+			if started.elapsed() > STEP_TIMEOUT {
+				return Err("Timed out waiting for the transactions to be included, is the node producing blocks?".into());
+			}
+			thread::sleep(Duration::from_millis(500));
+		}
+		Ok(())
+	}
+}
+
+/// The clients of the RPC APIs of a node, sharing one HTTP transport
+struct Node {
+	runtime: RefCell<RpcRuntime>,
+	system: SystemClient<Hash, BlockNumber>,
+	chain: ChainClient<BlockNumber, Hash, Header, SignedBlock>,
+	author: AuthorClient<Hash, Hash>,
+	state: StateClient<Hash>,
+	business: BusinessClient<Hash, Hash, AccountId>,
+}
+
+impl Node {
+	/// Connect to an `http://host:port` endpoint
+	fn connect(url: &str) -> Result<Self, String> {
+		let mut runtime = RpcRuntime::new().map_err(|e| format!("Failed to start the RPC client: {}", e))?;
+		let channel: RpcChannel = runtime.block_on(http::connect(url))
+			.map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+		Ok(Node {
+			runtime: RefCell::new(runtime),
+			system: channel.clone().into(),
+			chain: channel.clone().into(),
+			author: channel.clone().into(),
+			state: channel.clone().into(),
+			business: channel.into(),
+		})
+	}
+
+	/// Wait for the result of an RPC call
+	fn call<R: Send + 'static>(
+		&self,
+		method: &str,
+		request: impl Future<Item = R, Error = RpcError> + Send + 'static,
+	) -> Result<R, String> {
+		self.runtime.borrow_mut().block_on(request).map_err(|e| format!("{} failed: {}", method, e))
+	}
+
+	/// Get the hash of the genesis block
+	fn genesis_hash(&self) -> Result<Hash, String> {
+		let request = self.chain.block_hash(Some(ListOrValue::Value(NumberOrHex::Number(0))));
+		match self.call("chain_getBlockHash", request)? {
+			ListOrValue::Value(Some(hash)) => Ok(hash),
+			_ => Err("The node has no genesis block".into()),
+		}
+	}
+
+	/// Get the nonce of an account at the best block
+	fn account_nonce(&self, account: &AccountId) -> Result<Index, String> {
+		let key = StorageKey(runtime::account_nonce_key(account));
+		match self.call("state_getStorage", self.state.storage(key, None))? {
+			Some(value) => Index::decode(&mut &value.0[..]).map_err(|e| format!("Invalid nonce: {:?}", e)),
+			None => Ok(0),
+		}
+	}
+
+	/// Get the last business an account created
+	fn last_business_of(&self, creator: &AccountId) -> Result<Option<Hash>, String> {
+		let mut last = None;
+		let mut index = 0u64;
+		while let Some(biz_hash) = self.call("business_hashFor", self.business.business_hash_for(creator.clone(), index, None))? {
+			last = Some(biz_hash);
+			index += 1;
+		}
+		Ok(last)
+	}
+}
@@ -12,6 +12,7 @@ pub use sc_executor::NativeExecutor;
 use aura_primitives::sr25519::{AuthorityPair as AuraPair};
 use grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider};
 use basic_authorship;
+use futures01::{Stream, future::Executor};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -30,9 +31,15 @@ construct_simple_protocol! {
 /// Use this macro if you don't actually need the full service, but just the builder in order to
 /// be able to perform chain operations.
 macro_rules! new_full_start {
-	($config:expr) => {{
+	($config:expr) => {
+		new_full_start!($config, crate::rpc::RpcMethods::default())
+	};
+	($config:expr, $rpc_methods:expr) => {{
 		let mut import_setup = None;
 		let inherent_data_providers = inherents::InherentDataProviders::new();
+		type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+		let (subscription_tasks, subscription_queue) = crate::rpc::SubscriptionTasks::new();
+		let deny_unsafe = crate::rpc::deny_unsafe($config.rpc_http, $config.rpc_ws, $rpc_methods);
 
 		let builder = sc_service::ServiceBuilder::new_full::<
 			runtime::opaque::Block, runtime::RuntimeApi, crate::service::Executor
@@ -69,14 +76,17 @@ macro_rules! new_full_start {
 				import_setup = Some((grandpa_block_import, grandpa_link));
 
 				Ok(import_queue)
+			})?
+			.with_rpc_extensions(|client, _pool, _backend, _fetcher, _remote_blockchain| -> Result<RpcExtension, _> {
+				Ok(crate::rpc::create(client, subscription_tasks, deny_unsafe))
 			})?;
 
-		(builder, import_setup, inherent_data_providers)
+		(builder, import_setup, inherent_data_providers, subscription_queue)
 	}}
 }
 
-/// Builds a new service for a full client.
-pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisConfig>)
+/// Builds a new service for a full client, serving the RPC methods given.
+pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisConfig>, rpc_methods: crate::rpc::RpcMethods)
 	-> Result<impl AbstractService, ServiceError>
 {
 	let is_authority = config.roles.is_authority();
@@ -89,7 +99,7 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 	// never actively participate in any consensus process.
 	let participates_in_consensus = is_authority && !config.sentry_mode;
 
-	let (builder, mut import_setup, inherent_data_providers) = new_full_start!(config);
+	let (builder, mut import_setup, inherent_data_providers, subscription_queue) = new_full_start!(config, rpc_methods);
 
 	let (block_import, grandpa_link) =
 		import_setup.take()
@@ -101,6 +111,13 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 		)?
 		.build()?;
 
+	// drive the tasks of the RPC subscriptions on the service executor
+	let spawn_handle = service.spawn_task_handle();
+	service.spawn_task(subscription_queue.for_each(move |task| {
+		let _ = spawn_handle.execute(task);
+		Ok(())
+	}));
+
 	if participates_in_consensus {
 		let proposer = basic_authorship::ProposerFactory {
 			client: service.client(),
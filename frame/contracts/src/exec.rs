@@ -15,7 +15,7 @@
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
 use super::{CodeHash, Config, ContractAddressFor, Event, RawEvent, Trait,
-	TrieId, BalanceOf, ContractInfo};
+	TrieId, BalanceOf, ContractInfo, ChainExtension};
 use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::gas::{Gas, GasMeter, Token, approx_gas_for_balance};
 use crate::rent;
@@ -183,6 +183,11 @@ pub trait Ext {
 	///
 	/// Returns `None` if the value doesn't exist.
 	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Calls the function `func_id` of the runtime's chain extension with the given `input`.
+	///
+	/// Returns `None` if the function doesn't exist or failed.
+	fn chain_extension(&self, func_id: u32, input: &[u8]) -> Option<Vec<u8>>;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -797,6 +802,10 @@ where
 	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
 		unhashed::get_raw(&key)
 	}
+
+	fn chain_extension(&self, func_id: u32, input: &[u8]) -> Option<Vec<u8>> {
+		T::ChainExtension::call(func_id, input)
+	}
 }
 
 /// These tests exercise the executive layer.
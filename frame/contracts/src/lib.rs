@@ -416,6 +416,38 @@ pub trait Trait: system::Trait {
 
 	/// The maximum amount of gas that could be expended per block.
 	type BlockGasLimit: Get<Gas>;
+
+	/// Chain specific functionality exposed to contracts through `ext_chain_extension`.
+	type ChainExtension: ChainExtension;
+}
+
+/// Chain specific functionality which contracts call through `ext_chain_extension`.
+///
+/// A runtime exposes the functionality of its modules to contracts by implementing this trait,
+/// e.g. to let contracts query a module without reading its raw storage.
+pub trait ChainExtension {
+	/// Call the function `func_id` of the extension with the given `input`.
+	///
+	/// Returns the output to be copied into the scratch buffer, or `None` if the function
+	/// doesn't exist or failed.
+	fn call(func_id: u32, input: &[u8]) -> Option<Vec<u8>>;
+
+	/// The gas charged for calling the function `func_id`, on top of reading its input.
+	///
+	/// It must cover the storage reads and the computation of the function, as contracts may
+	/// call it as often as their gas allows.
+	fn gas(func_id: u32) -> Gas;
+}
+
+/// No chain extension: every call fails without reading anything.
+impl ChainExtension for () {
+	fn call(_func_id: u32, _input: &[u8]) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn gas(_func_id: u32) -> Gas {
+		0
+	}
 }
 
 /// Simple contract address determiner.
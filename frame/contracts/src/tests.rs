@@ -22,7 +22,8 @@
 use crate::{
 	BalanceOf, ComputeDispatchFee, ContractAddressFor, ContractInfo, ContractInfoOf, GenesisConfig,
 	Module, RawAliveContractInfo, RawEvent, Trait, TrieId, TrieIdFromParentCounter, Schedule,
-	TrieIdGenerator, CheckBlockGasLimit, account_db::{AccountDb, DirectAccountDb, OverlayAccountDb},
+	TrieIdGenerator, CheckBlockGasLimit, ChainExtension, Gas,
+	account_db::{AccountDb, DirectAccountDb, OverlayAccountDb},
 };
 use assert_matches::assert_matches;
 use hex_literal::*;
@@ -176,6 +177,23 @@ impl Trait for Test {
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
 	type BlockGasLimit = BlockGasLimit;
+	type ChainExtension = TestChainExtension;
+}
+
+/// The gas charged for a chain extension call in the tests.
+pub const CHAIN_EXTENSION_GAS: Gas = 10_000;
+
+/// A chain extension charging `CHAIN_EXTENSION_GAS` per call. Its functions are mocked by the
+/// externalities of the wasm tests.
+pub struct TestChainExtension;
+impl ChainExtension for TestChainExtension {
+	fn call(_func_id: u32, _input: &[u8]) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn gas(_func_id: u32) -> Gas {
+		CHAIN_EXTENSION_GAS
+	}
 }
 
 type Balances = balances::Module<Test>;
@@ -155,7 +155,8 @@ mod tests {
 	use primitives::H256;
 	use crate::exec::{Ext, StorageKey, ExecError, ExecReturnValue, STATUS_SUCCESS};
 	use crate::gas::{Gas, GasMeter};
-	use crate::tests::{Test, Call};
+	use crate::tests::{Test, Call, CHAIN_EXTENSION_GAS};
+	use crate::wasm::runtime::RuntimeToken;
 	use crate::wasm::prepare::prepare_contract;
 	use crate::CodeHash;
 	use wabt;
@@ -330,6 +331,13 @@ mod tests {
 				)
 			)
 		}
+		fn chain_extension(&self, func_id: u32, input: &[u8]) -> Option<Vec<u8>> {
+			// The function 1 reverses its input, any other function fails.
+			match func_id {
+				1 => Some(input.iter().rev().cloned().collect()),
+				_ => None,
+			}
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -417,6 +425,9 @@ mod tests {
 		fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
 			(**self).get_runtime_storage(key)
 		}
+		fn chain_extension(&self, func_id: u32, input: &[u8]) -> Option<Vec<u8>> {
+			(**self).chain_extension(func_id, input)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -1761,4 +1772,121 @@ mod tests {
 			&mut gas_meter,
 		).unwrap();
 	}
+
+	const CODE_CHAIN_EXTENSION: &str = r#"
+(module
+	(import "env" "ext_chain_extension"
+		(func $ext_chain_extension (param i32 i32 i32) (result i32))
+	)
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func $call (export "call")
+		;; Call the function 1 and assert that it succeeds.
+		(call $assert
+			(i32.eq
+				(call $ext_chain_extension
+					(i32.const 1)
+					(i32.const 16)
+					(i32.const 4)
+				)
+				(i32.const 0)
+			)
+		)
+
+		;; assert $ext_scratch_size == 4
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 4)
+			)
+		)
+
+		;; copy contents of the scratch buffer into the contract's memory.
+		(call $ext_scratch_read
+			(i32.const 4)		;; Pointer in memory to the place where to copy.
+			(i32.const 0)		;; Offset from the start of the scratch buffer.
+			(i32.const 4)		;; Count of bytes to copy.
+		)
+
+		;; assert that the input came back reversed.
+		(call $assert
+			(i32.eq
+				(i32.load
+					(i32.const 4)
+				)
+				(i32.const 0x01020304)
+			)
+		)
+
+		;; Call the function 2 and assert that it fails with an empty scratch buffer.
+		(call $assert
+			(i32.eq
+				(call $ext_chain_extension
+					(i32.const 2)
+					(i32.const 16)
+					(i32.const 4)
+				)
+				(i32.const 1)
+			)
+		)
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 0)
+			)
+		)
+	)
+
+	;; The input, 4 bytes long.
+	(data (i32.const 16) "\01\02\03\04")
+)
+"#;
+
+	#[test]
+	fn chain_extension() {
+		let mut gas_meter = GasMeter::with_limit(50_000, 1);
+		let _ = execute(
+			CODE_CHAIN_EXTENSION,
+			vec![],
+			MockExt::default(),
+			&mut gas_meter,
+		).unwrap();
+
+		// Both calls are charged the gas of the extension, the failing one too.
+		let charged = gas_meter.tokens().iter()
+			.filter_map(|token| token.token.downcast_ref::<RuntimeToken>())
+			.filter(|token| **token == RuntimeToken::ChainExtension(CHAIN_EXTENSION_GAS))
+			.count();
+		assert_eq!(charged, 2);
+		assert!(gas_meter.gas_left() < 50_000 - 2 * CHAIN_EXTENSION_GAS);
+	}
+
+	#[test]
+	fn chain_extension_out_of_gas() {
+		// Enough gas for the first call only.
+		let mut gas_meter = GasMeter::with_limit(CHAIN_EXTENSION_GAS + 5_000, 1);
+		assert_matches!(
+			execute(
+				CODE_CHAIN_EXTENSION,
+				vec![],
+				MockExt::default(),
+				&mut gas_meter
+			),
+			Err(ExecError { reason: "during execution", buffer: _ })
+		);
+		assert_eq!(gas_meter.gas_left(), 0);
+	}
 }
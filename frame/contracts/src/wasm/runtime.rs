@@ -16,7 +16,7 @@
 
 //! Environment definition of the wasm smart-contract runtime.
 
-use crate::{Schedule, Trait, CodeHash, ComputeDispatchFee, BalanceOf};
+use crate::{Schedule, Trait, CodeHash, ComputeDispatchFee, ChainExtension, BalanceOf};
 use crate::exec::{
 	Ext, ExecResult, ExecError, ExecReturnValue, StorageKey, TopicOf, STATUS_SUCCESS,
 };
@@ -128,6 +128,8 @@ pub enum RuntimeToken {
 	ReturnData(u32),
 	/// Dispatch fee calculated by `T::ComputeDispatchFee`.
 	ComputedDispatchFee(Gas),
+	/// Fee of a chain extension function given by `T::ChainExtension`.
+	ChainExtension(Gas),
 	/// (topic_count, data_bytes): A buffer of the given size is posted as an event indexed with the
 	/// given number of topics.
 	DepositEvent(u32, u32),
@@ -169,6 +171,7 @@ impl<T: Trait> Token<T> for RuntimeToken {
 					)
 			},
 			ComputedDispatchFee(gas) => Some(gas),
+			ChainExtension(gas) => Some(gas),
 		};
 
 		value.unwrap_or_else(|| Bounded::max_value())
@@ -861,6 +864,36 @@ define_env!(Env, <E: Ext>,
 			}
 		}
 	},
+
+	// Calls a function of the runtime's chain extension.
+	//
+	// Returns 0 if the call succeeded and its output is placed into the scratch buffer, or 1 if
+	// the function doesn't exist or failed, in which case the scratch buffer is cleared.
+	//
+	// - func_id: the id of the extension function to call.
+	// - input_ptr: the pointer into the linear memory where the input of the function is placed.
+	// - input_len: the length of the input in bytes.
+	ext_chain_extension(ctx, func_id: u32, input_ptr: u32, input_len: u32) -> u32 => {
+		let fee = <<<E as Ext>::T as Trait>::ChainExtension as ChainExtension>::gas(func_id);
+		charge_gas(&mut ctx.gas_meter, ctx.schedule, RuntimeToken::ChainExtension(fee))?;
+
+		// Steal the scratch buffer so that we hopefully save an allocation for the `input_buf`.
+		read_sandbox_memory_into_scratch(ctx, input_ptr, input_len)?;
+		let input_buf = mem::replace(&mut ctx.scratch_buf, Vec::new());
+
+		match ctx.ext.chain_extension(func_id, &input_buf) {
+			Some(output_buf) => {
+				ctx.scratch_buf = output_buf;
+				Ok(0)
+			}
+			None => {
+				// Put back the `input_buf` and allow its allocation to be reused.
+				ctx.scratch_buf = input_buf;
+				ctx.scratch_buf.clear();
+				Ok(1)
+			}
+		}
+	},
 );
 
 /// Finds duplicates in a given vector.